@@ -35,7 +35,11 @@ pub fn bench_get_entity_type_by_id(
                         link_type_resolve_depth: 0,
                         link_resolve_depth: 0,
                         link_target_entity_resolve_depth: 0,
+                        incoming_link_resolve_depth: 0,
+                        link_source_entity_resolve_depth: 0,
+                        referencing_type_resolve_depth: 0,
                     },
+                    link_type_filter: None,
                 })
                 .await
                 .expect("failed to read entity type from store");
@@ -1,15 +1,31 @@
-use clap::{Args as _, Command, Parser};
+use std::path::PathBuf;
+
+use clap::{Args as _, Command, Parser, Subcommand};
 use clap_complete::Shell;
-use graph::{logging::LoggingArgs, store::DatabaseConnectionInfo};
+use graph::{
+    logging::LoggingArgs,
+    ontology::AccountId,
+    store::{DatabaseConnectionInfo, DatabasePoolConfig, QueryLimits},
+};
 use regex::Regex;
+use uuid::Uuid;
 
 /// Arguments passed to the program.
 #[derive(Debug, Parser)]
 #[clap(version, author, about, long_about = None)]
 pub struct Args {
+    #[clap(subcommand)]
+    pub command: Option<SubCommand>,
+
     #[clap(flatten)]
     pub db_info: DatabaseConnectionInfo,
 
+    #[clap(flatten)]
+    pub db_pool_config: DatabasePoolConfig,
+
+    #[clap(flatten)]
+    pub query_limits: QueryLimits,
+
     #[clap(flatten)]
     pub log_config: LoggingArgs,
 
@@ -41,11 +57,109 @@ pub struct Args {
     )]
     pub allowed_url_domain: Regex,
 
+    /// A directory of ontology type schemas, which is used to resolve types from other domains
+    /// instead of fetching them over the network.
+    #[clap(long, env = "HASH_GRAPH_EXTERNAL_TYPES_DIRECTORY")]
+    pub external_types_directory: Option<PathBuf>,
+
+    /// The domains, from which external types may be fetched. Subdomains are allowed as well.
+    #[clap(
+        long,
+        default_value = "blockprotocol.org",
+        env = "HASH_GRAPH_EXTERNAL_TYPE_DOMAINS",
+        value_delimiter = ','
+    )]
+    pub external_type_domain: Vec<String>,
+
+    /// API keys authenticating requests as the assigned account, in the format
+    /// `<API_KEY>=<ACCOUNT_ID>`.
+    ///
+    /// If neither API keys nor a JWT key are provided, authentication is disabled and the account
+    /// IDs in the requests are trusted.
+    #[clap(
+        long,
+        env = "HASH_GRAPH_API_KEYS",
+        value_delimiter = ',',
+        value_parser = parse_api_key,
+        conflicts_with_all = ["jwt_secret", "jwt_public_key"]
+    )]
+    pub api_key: Vec<(String, AccountId)>,
+
+    /// The secret used to verify JSON Web Tokens signed with HS256.
+    #[clap(long, env = "HASH_GRAPH_JWT_SECRET", conflicts_with = "jwt_public_key")]
+    pub jwt_secret: Option<String>,
+
+    /// The path to a PEM encoded RSA public key used to verify JSON Web Tokens signed with RS256.
+    #[clap(long, env = "HASH_GRAPH_JWT_PUBLIC_KEY")]
+    pub jwt_public_key: Option<PathBuf>,
+
+    /// Accounts, which may act on behalf of other accounts and modify resources of other
+    /// accounts.
+    #[clap(long, env = "HASH_GRAPH_PRIVILEGED_ACCOUNTS", value_delimiter = ',')]
+    pub privileged_account: Vec<Uuid>,
+
+    /// URLs, to which every change of the graph is sent as JSON in a `POST` request.
+    #[clap(long, env = "HASH_GRAPH_WEBHOOK_URLS", value_delimiter = ',')]
+    pub webhook_url: Vec<String>,
+
+    /// The number of times the delivery of a change to a webhook is retried before it is dropped.
+    #[clap(long, default_value_t = 5, env = "HASH_GRAPH_WEBHOOK_MAX_RETRIES")]
+    pub webhook_max_retries: u32,
+
+    /// Apply pending database migrations before serving the REST API.
+    #[clap(long, env = "HASH_GRAPH_AUTO_MIGRATE")]
+    pub auto_migrate: bool,
+
     /// Generate a completion script for the given shell and outputs it to stdout.
     #[clap(long, value_enum, exclusive = true)]
     generate_completion: Option<Shell>,
 }
 
+/// Operations run instead of serving the REST API.
+#[derive(Debug, Subcommand)]
+pub enum SubCommand {
+    /// Write a snapshot of the graph as newline-delimited JSON.
+    Export {
+        /// Only export the records owned by this account.
+        #[clap(long)]
+        account_id: Option<Uuid>,
+
+        /// The file the snapshot is written to. By default, it's written to stdout.
+        #[clap(long)]
+        output: Option<PathBuf>,
+    },
+    /// Restore a snapshot written by `export`. Records, which already exist, are skipped.
+    Import {
+        /// The file the snapshot is read from. By default, it's read from stdin.
+        #[clap(long)]
+        input: Option<PathBuf>,
+    },
+    /// Manage the migrations of the database schema.
+    Migrate {
+        #[clap(subcommand)]
+        command: MigrateCommand,
+    },
+}
+
+#[derive(Debug, Copy, Clone, Subcommand)]
+pub enum MigrateCommand {
+    /// Apply all pending migrations.
+    Up,
+    /// List the embedded and applied migrations.
+    Status,
+    /// Fail if a migration is pending, was modified after it was applied or is unknown.
+    Verify,
+}
+
+fn parse_api_key(value: &str) -> Result<(String, AccountId), String> {
+    let (key, account_id) = value
+        .split_once('=')
+        .ok_or_else(|| "expected `<API_KEY>=<ACCOUNT_ID>`".to_owned())?;
+    let account_id = Uuid::parse_str(account_id).map_err(|error| error.to_string())?;
+
+    Ok((key.to_owned(), AccountId::new(account_id)))
+}
+
 impl Args {
     /// Parse the arguments passed to the program.
     pub fn parse() -> Self {
@@ -2,24 +2,41 @@
 
 mod args;
 
-use std::{collections::HashMap, fmt, net::SocketAddr, sync::Arc};
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    net::SocketAddr,
+    path::PathBuf,
+    sync::Arc,
+};
 
-use error_stack::{Context, IntoReport, Result, ResultExt};
+use error_stack::{Context, IntoReport, Report, Result, ResultExt};
 use graph::{
-    api::rest::rest_api_router,
+    api::rest::{
+        auth::{ApiKeyAuthenticator, Authentication, JwtAuthenticator},
+        rest_api_router,
+    },
     logging::init_logger,
-    ontology::{domain_validator::DomainValidator, AccountId},
-    store::{AccountStore, DataTypeStore, PostgresStorePool, StorePool},
-};
-use serde_json::json;
-use tokio_postgres::NoTls;
-use type_system::{
-    uri::{BaseUri, VersionedUri},
-    DataType,
+    ontology::{
+        domain_validator::DomainValidator,
+        external_types::{
+            ExternalTypeResolver, FixtureFetcher, HttpFetcher, EXTERNAL_TYPES_ACCOUNT_ID,
+        },
+        AccountId,
+    },
+    snapshot::Snapshot,
+    store::{
+        change::{ChangeFeed, WebhookDispatcher},
+        listen_for_changes, AccountStore, DatabasePoolConfig, PostgresStorePool, QueryLimits,
+        StorePool,
+    },
 };
+use tokio_postgres_rustls::MakeRustlsConnect;
+use type_system::uri::{BaseUri, VersionedUri};
 use uuid::Uuid;
 
-use crate::args::Args;
+use crate::args::{Args, MigrateCommand, SubCommand};
 
 #[derive(Debug)]
 pub struct GraphError;
@@ -31,96 +48,24 @@ impl fmt::Display for GraphError {
     }
 }
 
+/// The primitive data types of the Block Protocol, which are required by most ontology types.
+const PRIMITIVE_DATA_TYPES: [&str; 6] = [
+    "https://blockprotocol.org/@blockprotocol/types/data-type/text/",
+    "https://blockprotocol.org/@blockprotocol/types/data-type/number/",
+    "https://blockprotocol.org/@blockprotocol/types/data-type/boolean/",
+    "https://blockprotocol.org/@blockprotocol/types/data-type/empty-list/",
+    "https://blockprotocol.org/@blockprotocol/types/data-type/object/",
+    "https://blockprotocol.org/@blockprotocol/types/data-type/null/",
+];
+
 /// A place to collect temporary implementations that are useful before stabilization of the Graph.
 ///
 /// This will include things that are mocks or stubs to make up for missing pieces of infrastructure
 /// that haven't been created yet.
-async fn stop_gap_setup(pool: &PostgresStorePool<NoTls>) -> Result<(), GraphError> {
-    // TODO: how do we make these URIs compliant
-    let text = DataType::new(
-        VersionedUri::new(
-            BaseUri::new(
-                "https://blockprotocol.org/@blockprotocol/types/data-type/text/".to_owned(),
-            )
-            .expect("failed to construct base URI"),
-            1,
-        ),
-        "Text".to_owned(),
-        Some("An ordered sequence of characters".to_owned()),
-        "string".to_owned(),
-        HashMap::default(),
-    );
-
-    let number = DataType::new(
-        VersionedUri::new(
-            BaseUri::new(
-                "https://blockprotocol.org/@blockprotocol/types/data-type/number/".to_owned(),
-            )
-            .expect("failed to construct base URI"),
-            1,
-        ),
-        "Number".to_owned(),
-        Some("An arithmetical value (in the Real number system)".to_owned()),
-        "number".to_owned(),
-        HashMap::default(),
-    );
-
-    let boolean = DataType::new(
-        VersionedUri::new(
-            BaseUri::new(
-                "https://blockprotocol.org/@blockprotocol/types/data-type/boolean/".to_owned(),
-            )
-            .expect("failed to construct base URI"),
-            1,
-        ),
-        "Boolean".to_owned(),
-        Some("A True or False value".to_owned()),
-        "boolean".to_owned(),
-        HashMap::default(),
-    );
-
-    let null = DataType::new(
-        VersionedUri::new(
-            BaseUri::new(
-                "https://blockprotocol.org/@blockprotocol/types/data-type/null/".to_owned(),
-            )
-            .expect("failed to construct base URI"),
-            1,
-        ),
-        "Null".to_owned(),
-        Some("A placeholder value representing 'nothing'".to_owned()),
-        "null".to_owned(),
-        HashMap::default(),
-    );
-
-    let object = DataType::new(
-        VersionedUri::new(
-            BaseUri::new(
-                "https://blockprotocol.org/@blockprotocol/types/data-type/object/".to_owned(),
-            )
-            .expect("failed to construct base URI"),
-            1,
-        ),
-        "Object".to_owned(),
-        Some("A plain JSON object with no pre-defined structure".to_owned()),
-        "object".to_owned(),
-        HashMap::default(),
-    );
-
-    let empty_list = DataType::new(
-        VersionedUri::new(
-            BaseUri::new(
-                "https://blockprotocol.org/@blockprotocol/types/data-type/empty-list/".to_owned(),
-            )
-            .expect("failed to construct base URI"),
-            1,
-        ),
-        "Empty List".to_owned(),
-        Some("An Empty List".to_owned()),
-        "array".to_owned(),
-        [("const".to_owned(), json!([]))].into_iter().collect(),
-    );
-
+async fn stop_gap_setup(
+    pool: &PostgresStorePool<MakeRustlsConnect>,
+    external_type_resolver: &ExternalTypeResolver,
+) -> Result<(), GraphError> {
     // TODO: Revisit once an authentication and authorization setup is in place
     let root_account_id = AccountId::new(Uuid::nil());
 
@@ -146,22 +91,136 @@ async fn stop_gap_setup(pool: &PostgresStorePool<NoTls>) -> Result<(), GraphErro
         tracing::info!(%root_account_id, "created root account id");
     }
 
-    // Seed the primitive data types if they don't already exist
-    let data_types = [text, number, boolean, empty_list, object, null];
-    for data_type in data_types {
-        let title = data_type.title().to_owned();
-        if connection
-            .create_data_type(data_type, root_account_id, root_account_id)
+    if connection
+        .insert_account_id(EXTERNAL_TYPES_ACCOUNT_ID)
+        .await
+        .change_context(GraphError)
+        .is_err()
+    {
+        tracing::info!(account_id=%EXTERNAL_TYPES_ACCOUNT_ID, "tried to create external types account, but id already exists");
+    } else {
+        tracing::info!(account_id=%EXTERNAL_TYPES_ACCOUNT_ID, "created external types account id");
+    }
+
+    // The primitive data types are external types hosted by the Block Protocol. Resolving them
+    // eagerly makes them available before the first type referencing them is created. If they
+    // can't be fetched now, they are resolved once they are referenced.
+    let primitive_data_types = PRIMITIVE_DATA_TYPES.map(|base_uri| {
+        VersionedUri::new(
+            BaseUri::new(base_uri.to_owned()).expect("failed to construct base URI"),
+            1,
+        )
+    });
+    match external_type_resolver
+        .resolve_uris(&mut connection, primitive_data_types)
+        .await
+    {
+        Ok(resolved) => {
+            for uri in resolved {
+                tracing::info!(%uri, "inserted primitive data type");
+            }
+        }
+        Err(report) => {
+            tracing::warn!(error=?report, "could not resolve the primitive data types");
+        }
+    }
+
+    Ok(())
+}
+
+async fn migrate(
+    args: &Args,
+    tls: MakeRustlsConnect,
+    command: MigrateCommand,
+) -> Result<(), GraphError> {
+    // Migrations, e.g. backfilling a column, may run longer than the statement timeout of the
+    // connections serving requests, so a dedicated connection without the timeout is used.
+    let pool_config = DatabasePoolConfig {
+        max_connections: 1,
+        min_idle_connections: Some(0),
+        statement_timeout: None,
+        ..args.db_pool_config
+    };
+    let pool =
+        PostgresStorePool::with_config(&args.db_info, &pool_config, QueryLimits::unlimited(), tls)
             .await
-            .change_context(GraphError)
-            .is_err()
-        {
-            tracing::info!(%root_account_id, "tried to insert primitive {} data type, but it already exists", title);
-        } else {
-            tracing::info!(%root_account_id, "inserted the primitive {} data type", title);
+            .change_context(GraphError)?;
+    let mut store = pool.acquire().await.change_context(GraphError)?;
+    match command {
+        MigrateCommand::Up => {
+            let applied = store.migrate().await.change_context(GraphError)?;
+            for migration in &applied {
+                tracing::info!(%migration, "applied migration");
+            }
+            tracing::info!(applied = applied.len(), "database schema is up to date");
+        }
+        MigrateCommand::Status => {
+            let status = store.migration_status().await.change_context(GraphError)?;
+            let mut stdout = io::stdout().lock();
+            for migration in status {
+                writeln!(stdout, "{migration}")
+                    .into_report()
+                    .change_context(GraphError)?;
+            }
+        }
+        MigrateCommand::Verify => {
+            store.verify_migrations().await.change_context(GraphError)?;
+            tracing::info!("all migrations are applied");
+        }
+    }
+
+    Ok(())
+}
+
+async fn export_snapshot(
+    pool: &PostgresStorePool<MakeRustlsConnect>,
+    account_id: Option<AccountId>,
+    output: Option<PathBuf>,
+) -> Result<(), GraphError> {
+    let store = pool.acquire().await.change_context(GraphError)?;
+    let snapshot = Snapshot::export(&store, account_id)
+        .await
+        .change_context(GraphError)?;
+
+    match output {
+        Some(path) => {
+            let file = File::create(&path)
+                .into_report()
+                .change_context(GraphError)
+                .attach_printable_lazy(|| path.display().to_string())?;
+            snapshot.write(BufWriter::new(file))
+        }
+        None => snapshot.write(io::stdout().lock()),
+    }
+    .change_context(GraphError)?;
+
+    tracing::info!(records = snapshot.entries().len(), "exported snapshot");
+    Ok(())
+}
+
+async fn import_snapshot(
+    pool: &PostgresStorePool<MakeRustlsConnect>,
+    input: Option<PathBuf>,
+) -> Result<(), GraphError> {
+    let snapshot = match input {
+        Some(path) => {
+            let file = File::open(&path)
+                .into_report()
+                .change_context(GraphError)
+                .attach_printable_lazy(|| path.display().to_string())?;
+            Snapshot::read(BufReader::new(file))
         }
+        None => Snapshot::read(io::stdin().lock()),
     }
+    .change_context(GraphError)?;
 
+    let mut store = pool.acquire().await.change_context(GraphError)?;
+    let summary = snapshot
+        .restore(&mut store)
+        .await
+        .change_context(GraphError)?;
+
+    tracing::info!(?summary, "imported snapshot");
     Ok(())
 }
 
@@ -175,19 +234,85 @@ async fn main() -> Result<(), GraphError> {
         &args.log_config.log_file_prefix,
     );
 
-    let pool = PostgresStorePool::new(&args.db_info, NoTls)
+    let tls = args.db_info.make_tls_connect().change_context(GraphError)?;
+    let pool = PostgresStorePool::with_config(
+        &args.db_info,
+        &args.db_pool_config,
+        args.query_limits,
+        tls.clone(),
+    )
+    .await
+    .change_context(GraphError)
+    .map_err(|err| {
+        tracing::error!("{err:?}");
+        err
+    })?;
+
+    if let Some(SubCommand::Migrate { command }) = &args.command {
+        return migrate(&args, tls, *command).await;
+    }
+    if args.auto_migrate {
+        migrate(&args, tls.clone(), MigrateCommand::Up).await?;
+    }
+
+    let domain_validator = DomainValidator::new(args.allowed_url_domain);
+    let external_type_resolver = match args.external_types_directory {
+        Some(directory) => ExternalTypeResolver::new(
+            domain_validator.clone(),
+            FixtureFetcher::from_directory(directory).change_context(GraphError)?,
+        ),
+        None => ExternalTypeResolver::new(
+            domain_validator.clone(),
+            HttpFetcher::new().change_context(GraphError)?,
+        ),
+    }
+    .with_allowed_domains(args.external_type_domain);
+
+    stop_gap_setup(&pool, &external_type_resolver).await?;
+
+    match args.command {
+        Some(SubCommand::Export { account_id, output }) => {
+            return export_snapshot(&pool, account_id.map(AccountId::new), output).await;
+        }
+        Some(SubCommand::Import { input }) => return import_snapshot(&pool, input).await,
+        Some(SubCommand::Migrate { .. }) | None => {}
+    }
+
+    let change_feed = ChangeFeed::default();
+    let change_listener = listen_for_changes(&args.db_info, tls, change_feed.clone())
         .await
-        .change_context(GraphError)
-        .map_err(|err| {
-            tracing::error!("{err:?}");
-            err
-        })?;
+        .change_context(GraphError)?;
+    if !args.webhook_url.is_empty() {
+        WebhookDispatcher::new(&args.webhook_url)
+            .change_context(GraphError)?
+            .with_max_retries(args.webhook_max_retries)
+            .spawn(&change_feed);
+    }
 
-    stop_gap_setup(&pool).await?;
+    let authentication = if let Some(secret) = args.jwt_secret {
+        Authentication::new(JwtAuthenticator::from_secret(secret.as_bytes()))
+    } else if let Some(path) = args.jwt_public_key {
+        let pem = std::fs::read(&path)
+            .into_report()
+            .change_context(GraphError)
+            .attach_printable_lazy(|| path.display().to_string())?;
+        Authentication::new(JwtAuthenticator::from_rsa_pem(&pem).change_context(GraphError)?)
+    } else if !args.api_key.is_empty() {
+        Authentication::new(ApiKeyAuthenticator::new(args.api_key))
+    } else {
+        tracing::warn!(
+            "Authentication is disabled, the account IDs provided in requests are trusted"
+        );
+        Authentication::default()
+    }
+    .with_privileged_accounts(args.privileged_account.into_iter().map(AccountId::new));
 
     let rest_router = rest_api_router(
         Arc::new(pool),
-        DomainValidator::new(args.allowed_url_domain),
+        domain_validator,
+        external_type_resolver,
+        authentication,
+        change_feed,
     );
     let api_address = format!("{}:{}", args.api_host, args.api_port);
     let addr: SocketAddr = api_address
@@ -197,10 +322,14 @@ async fn main() -> Result<(), GraphError> {
         .attach_printable_lazy(|| api_address.clone())?;
 
     tracing::info!("Listening on {api_address}");
-    axum::Server::bind(&addr)
-        .serve(rest_router.into_make_service())
-        .await
-        .unwrap();
+    let server = axum::Server::bind(&addr).serve(rest_router.into_make_service());
 
-    Ok(())
+    // The change listener reconnects on its own, so it only stops if it failed unexpectedly. In
+    // that case the process exits instead of silently serving without change notifications.
+    tokio::select! {
+        result = server => result.into_report().change_context(GraphError),
+        result = change_listener => Err(Report::new(GraphError)
+            .attach_printable("stopped listening for changes")
+            .attach_printable(format!("{result:?}"))),
+    }
 }
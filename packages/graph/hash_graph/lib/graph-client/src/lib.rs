@@ -0,0 +1,780 @@
+//! A typed client for the REST API of the HASH Graph.
+//!
+//! The requests and responses are the types used by the [`graph`] crate itself, so the client can't
+//! get out of sync with the server.
+//!
+//! ```no_run
+//! use graph::{
+//!     store::query::Expression,
+//!     subgraph::{GraphResolveDepths, StructuralQuery},
+//! };
+//! use graph_client::Client;
+//!
+//! # async fn run() -> error_stack::Result<(), graph_client::ClientError> {
+//! let client = Client::new("http://localhost:4000")?.with_bearer_token("secret");
+//!
+//! let subgraph = client
+//!     .query_entities(&StructuralQuery {
+//!         expression: Expression::for_latest_version(),
+//!         graph_resolve_depths: GraphResolveDepths::zeroed(),
+//!         link_type_filter: None,
+//!     })
+//!     .await?;
+//! # Ok(()) }
+//! ```
+
+#![warn(
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::as_underscore,
+    clippy::clone_on_ref_ptr,
+    clippy::dbg_macro,
+    clippy::get_unwrap,
+    clippy::print_stdout,
+    clippy::print_stderr,
+    clippy::str_to_string,
+    clippy::string_add,
+    clippy::string_slice,
+    clippy::string_to_string,
+    clippy::try_err,
+    clippy::unwrap_used
+)]
+#![forbid(unsafe_code)]
+
+use std::fmt;
+
+use error_stack::{Context, IntoReport, Report, Result, ResultExt};
+use futures::{stream, Stream, StreamExt};
+use graph::{
+    api::rest::{
+        BatchOperationResult, BatchRequest, CreateDataTypeRequest, CreateEntityRequest,
+        CreateEntityTypeRequest, CreateLinkRequest, CreateLinkTypeRequest,
+        CreatePropertyTypeRequest, DeprecateOntologyTypeRequest, RemoveLinkRequest,
+        TransferOntologyTypeOwnershipRequest, UpdateDataTypeRequest, UpdateEntityRequest,
+        UpdateEntityTypeRequest, UpdateLinkTypeRequest, UpdateOntologyTypeResponse,
+        UpdatePropertyTypeRequest,
+    },
+    knowledge::{
+        EntityId, Link, LinkRootedSubgraph, PersistedEntity, PersistedEntityMetadata, PersistedLink,
+    },
+    ontology::{
+        AccountId, PersistedDataType, PersistedEntityType, PersistedLinkType,
+        PersistedOntologyMetadata, PersistedPropertyType,
+    },
+    snapshot::{RestoreSummary, Snapshot},
+    store::{
+        change::ChangeEvent,
+        search::{EntitySearch, EntitySearchHit},
+    },
+    subgraph::{StructuralQuery, Subgraph},
+};
+pub use reqwest::StatusCode;
+use reqwest::{header, Method, RequestBuilder, Response, Url};
+use serde::{de::DeserializeOwned, Serialize};
+use type_system::uri::VersionedUri;
+
+/// A request to the graph failed.
+///
+/// If the server responded with an unsuccessful status, the [`StatusCode`] is attached to the
+/// report and can be retrieved by [`Report::downcast_ref`].
+#[derive(Debug)]
+pub struct ClientError;
+
+impl Context for ClientError {}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("the request to the Graph failed")
+    }
+}
+
+/// A client for the REST API of the HASH Graph.
+///
+/// Cloning the client is cheap, the clones share the same connection pool.
+#[derive(Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: Url,
+    bearer_token: Option<String>,
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The bearer token is omitted on purpose, so it doesn't end up in logs.
+        fmt.debug_struct("Client")
+            .field("base_url", &self.base_url.as_str())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Client {
+    /// Creates a client for the graph served at `base_url`, e.g. `http://localhost:4000`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if `base_url` is not a valid base URL
+    pub fn new(base_url: &str) -> Result<Self, ClientError> {
+        let url = Url::parse(base_url)
+            .into_report()
+            .change_context(ClientError)
+            .attach_printable_lazy(|| base_url.to_owned())?;
+
+        if url.cannot_be_a_base() {
+            return Err(Report::new(ClientError)
+                .attach_printable("the URL of the graph cannot be a base URL")
+                .attach_printable(base_url.to_owned()));
+        }
+
+        Ok(Self {
+            http: reqwest::Client::new(),
+            base_url: url,
+            bearer_token: None,
+        })
+    }
+
+    /// Authenticates all requests with `token`, which is either an API key or a JWT.
+    #[must_use]
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// Sends the requests with `http` instead of a client with the default configuration.
+    #[must_use]
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    fn url(&self, segments: &[&str]) -> Result<Url, ClientError> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .map_err(|()| {
+                Report::new(ClientError)
+                    .attach_printable("the URL of the graph cannot be a base URL")
+            })?
+            .pop_if_empty()
+            .extend(segments);
+        Ok(url)
+    }
+
+    fn request(&self, method: Method, segments: &[&str]) -> Result<RequestBuilder, ClientError> {
+        let request = self.http.request(method, self.url(segments)?);
+        Ok(match &self.bearer_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        })
+    }
+
+    /// Sends `request` and fails, if the server didn't respond successfully.
+    async fn execute(request: RequestBuilder) -> Result<Response, ClientError> {
+        let response = request
+            .send()
+            .await
+            .into_report()
+            .change_context(ClientError)?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let mut report = Report::new(ClientError)
+            .attach(status)
+            .attach_printable(format!("the graph responded with {status}"));
+        match response.text().await {
+            Ok(body) if !body.is_empty() => report = report.attach_printable(body),
+            _ => {}
+        }
+        Err(report)
+    }
+
+    async fn send<T: DeserializeOwned>(request: RequestBuilder) -> Result<T, ClientError> {
+        Self::execute(request)
+            .await?
+            .json()
+            .await
+            .into_report()
+            .change_context(ClientError)
+            .attach_printable("could not decode the response of the graph")
+    }
+
+    async fn get<T: DeserializeOwned>(&self, segments: &[&str]) -> Result<T, ClientError> {
+        Self::send(self.request(Method::GET, segments)?).await
+    }
+
+    async fn post<B, T>(&self, segments: &[&str], body: &B) -> Result<T, ClientError>
+    where
+        B: Serialize + Sync + ?Sized,
+        T: DeserializeOwned,
+    {
+        Self::send(self.request(Method::POST, segments)?.json(body)).await
+    }
+
+    async fn put<B, T>(&self, segments: &[&str], body: &B) -> Result<T, ClientError>
+    where
+        B: Serialize + Sync + ?Sized,
+        T: DeserializeOwned,
+    {
+        Self::send(self.request(Method::PUT, segments)?.json(body)).await
+    }
+
+    /// Creates a new account and returns its ID.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the account could not be created
+    pub async fn create_account_id(&self) -> Result<AccountId, ClientError> {
+        Self::send(self.request(Method::POST, &["accounts"])?).await
+    }
+
+    /// Creates a data type and returns its metadata.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the data type could not be created
+    pub async fn create_data_type(
+        &self,
+        request: &CreateDataTypeRequest,
+    ) -> Result<PersistedOntologyMetadata, ClientError> {
+        self.post(&["data-types"], request).await
+    }
+
+    /// Updates a data type and returns its metadata and the compatibility of the update.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the data type could not be updated
+    pub async fn update_data_type(
+        &self,
+        request: &UpdateDataTypeRequest,
+    ) -> Result<UpdateOntologyTypeResponse, ClientError> {
+        self.put(&["data-types"], request).await
+    }
+
+    /// Returns the latest versions of all data types.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the data types could not be read
+    pub async fn get_latest_data_types(&self) -> Result<Vec<PersistedDataType>, ClientError> {
+        self.get(&["data-types"]).await
+    }
+
+    /// Returns the data type identified by `uri`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the data type could not be read, e.g. if it does not exist
+    pub async fn get_data_type(
+        &self,
+        uri: &VersionedUri,
+    ) -> Result<PersistedDataType, ClientError> {
+        self.get(&["data-types", &uri.to_string()]).await
+    }
+
+    /// Returns the subgraph rooted at the data types matching `query`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the query is invalid or could not be resolved
+    pub async fn query_data_types(&self, query: &StructuralQuery) -> Result<Subgraph, ClientError> {
+        self.post(&["data-types", "query"], query).await
+    }
+
+    /// Creates a property type and returns its metadata.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the property type could not be created
+    pub async fn create_property_type(
+        &self,
+        request: &CreatePropertyTypeRequest,
+    ) -> Result<PersistedOntologyMetadata, ClientError> {
+        self.post(&["property-types"], request).await
+    }
+
+    /// Updates a property type and returns its metadata and the compatibility of the update.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the property type could not be updated
+    pub async fn update_property_type(
+        &self,
+        request: &UpdatePropertyTypeRequest,
+    ) -> Result<UpdateOntologyTypeResponse, ClientError> {
+        self.put(&["property-types"], request).await
+    }
+
+    /// Returns the latest versions of all property types.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the property types could not be read
+    pub async fn get_latest_property_types(
+        &self,
+    ) -> Result<Vec<PersistedPropertyType>, ClientError> {
+        self.get(&["property-types"]).await
+    }
+
+    /// Returns the property type identified by `uri`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the property type could not be read, e.g. if it does not exist
+    pub async fn get_property_type(
+        &self,
+        uri: &VersionedUri,
+    ) -> Result<PersistedPropertyType, ClientError> {
+        self.get(&["property-types", &uri.to_string()]).await
+    }
+
+    /// Returns the subgraph rooted at the property types matching `query`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the query is invalid or could not be resolved
+    pub async fn query_property_types(
+        &self,
+        query: &StructuralQuery,
+    ) -> Result<Subgraph, ClientError> {
+        self.post(&["property-types", "query"], query).await
+    }
+
+    /// Creates a link type and returns its metadata.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the link type could not be created
+    pub async fn create_link_type(
+        &self,
+        request: &CreateLinkTypeRequest,
+    ) -> Result<PersistedOntologyMetadata, ClientError> {
+        self.post(&["link-types"], request).await
+    }
+
+    /// Updates a link type and returns its metadata and the compatibility of the update.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the link type could not be updated
+    pub async fn update_link_type(
+        &self,
+        request: &UpdateLinkTypeRequest,
+    ) -> Result<UpdateOntologyTypeResponse, ClientError> {
+        self.put(&["link-types"], request).await
+    }
+
+    /// Returns the latest versions of all link types.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the link types could not be read
+    pub async fn get_latest_link_types(&self) -> Result<Vec<PersistedLinkType>, ClientError> {
+        self.get(&["link-types"]).await
+    }
+
+    /// Returns the link type identified by `uri`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the link type could not be read, e.g. if it does not exist
+    pub async fn get_link_type(
+        &self,
+        uri: &VersionedUri,
+    ) -> Result<PersistedLinkType, ClientError> {
+        self.get(&["link-types", &uri.to_string()]).await
+    }
+
+    /// Returns the subgraph rooted at the link types matching `query`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the query is invalid or could not be resolved
+    pub async fn query_link_types(&self, query: &StructuralQuery) -> Result<Subgraph, ClientError> {
+        self.post(&["link-types", "query"], query).await
+    }
+
+    /// Creates an entity type and returns its metadata.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the entity type could not be created
+    pub async fn create_entity_type(
+        &self,
+        request: &CreateEntityTypeRequest,
+    ) -> Result<PersistedOntologyMetadata, ClientError> {
+        self.post(&["entity-types"], request).await
+    }
+
+    /// Updates an entity type and returns its metadata and the compatibility of the update.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the entity type could not be updated
+    pub async fn update_entity_type(
+        &self,
+        request: &UpdateEntityTypeRequest,
+    ) -> Result<UpdateOntologyTypeResponse, ClientError> {
+        self.put(&["entity-types"], request).await
+    }
+
+    /// Returns the latest versions of all entity types.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the entity types could not be read
+    pub async fn get_latest_entity_types(&self) -> Result<Vec<PersistedEntityType>, ClientError> {
+        self.get(&["entity-types"]).await
+    }
+
+    /// Returns the entity type identified by `uri`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the entity type could not be read, e.g. if it does not exist
+    pub async fn get_entity_type(
+        &self,
+        uri: &VersionedUri,
+    ) -> Result<PersistedEntityType, ClientError> {
+        self.get(&["entity-types", &uri.to_string()]).await
+    }
+
+    /// Returns the subgraph rooted at the entity types matching `query`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the query is invalid or could not be resolved
+    pub async fn query_entity_types(
+        &self,
+        query: &StructuralQuery,
+    ) -> Result<Subgraph, ClientError> {
+        self.post(&["entity-types", "query"], query).await
+    }
+
+    /// Deprecates an ontology type of any kind and returns its metadata.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the ontology type could not be deprecated, e.g. if the actor does not
+    ///   own it
+    pub async fn deprecate_ontology_type(
+        &self,
+        request: &DeprecateOntologyTypeRequest,
+    ) -> Result<PersistedOntologyMetadata, ClientError> {
+        self.post(&["ontology-types", "deprecation"], request).await
+    }
+
+    /// Transfers the ownership of all versions of an ontology type to another account and returns
+    /// the metadata of the latest version.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the ownership could not be transferred, e.g. if the actor does not own
+    ///   the ontology type
+    pub async fn transfer_ontology_type_ownership(
+        &self,
+        request: &TransferOntologyTypeOwnershipRequest,
+    ) -> Result<PersistedOntologyMetadata, ClientError> {
+        self.put(&["ontology-types", "owner"], request).await
+    }
+
+    /// Creates an entity and returns its metadata.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the entity could not be created
+    pub async fn create_entity(
+        &self,
+        request: &CreateEntityRequest,
+    ) -> Result<PersistedEntityMetadata, ClientError> {
+        self.post(&["entities"], request).await
+    }
+
+    /// Updates an entity and returns the metadata of the new version.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the entity could not be updated
+    pub async fn update_entity(
+        &self,
+        request: &UpdateEntityRequest,
+    ) -> Result<PersistedEntityMetadata, ClientError> {
+        self.put(&["entities"], request).await
+    }
+
+    /// Returns the latest versions of all entities.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the entities could not be read
+    pub async fn get_latest_entities(&self) -> Result<Vec<PersistedEntity>, ClientError> {
+        self.get(&["entities"]).await
+    }
+
+    /// Returns the latest version of the entity identified by `entity_id`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the entity could not be read, e.g. if it does not exist
+    pub async fn get_entity(&self, entity_id: EntityId) -> Result<PersistedEntity, ClientError> {
+        self.get(&["entities", &entity_id.to_string()]).await
+    }
+
+    /// Returns the subgraph rooted at the entities matching `query`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the query is invalid or could not be resolved
+    pub async fn query_entities(&self, query: &StructuralQuery) -> Result<Subgraph, ClientError> {
+        self.post(&["entities", "query"], query).await
+    }
+
+    /// Searches the properties of the latest versions of all entities, the most relevant hits
+    /// first.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the search is invalid or could not be executed
+    pub async fn search_entities(
+        &self,
+        search: &EntitySearch,
+    ) -> Result<Vec<EntitySearchHit>, ClientError> {
+        self.post(&["entities", "search"], search).await
+    }
+
+    /// Creates a link from the entity identified by `source_entity_id`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the link could not be created
+    pub async fn create_link(
+        &self,
+        source_entity_id: EntityId,
+        request: &CreateLinkRequest,
+    ) -> Result<Link, ClientError> {
+        self.post(
+            &["entities", &source_entity_id.to_string(), "links"],
+            request,
+        )
+        .await
+    }
+
+    /// Returns the links from the entity identified by `source_entity_id`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the links could not be read
+    pub async fn get_entity_links(
+        &self,
+        source_entity_id: EntityId,
+    ) -> Result<Vec<PersistedLink>, ClientError> {
+        self.get(&["entities", &source_entity_id.to_string(), "links"])
+            .await
+    }
+
+    /// Removes a link from the entity identified by `source_entity_id`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the link could not be removed, e.g. if it does not exist
+    pub async fn remove_link(
+        &self,
+        source_entity_id: EntityId,
+        request: &RemoveLinkRequest,
+    ) -> Result<(), ClientError> {
+        Self::execute(
+            self.request(Method::DELETE, &[
+                "entities",
+                &source_entity_id.to_string(),
+                "links",
+            ])?
+            .json(request),
+        )
+        .await
+        .map(drop)
+    }
+
+    /// Returns the subgraphs rooted at the links matching `query`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the query is invalid or could not be resolved
+    pub async fn query_links(
+        &self,
+        query: &StructuralQuery,
+    ) -> Result<Vec<LinkRootedSubgraph>, ClientError> {
+        self.post(&["links", "query"], query).await
+    }
+
+    /// Applies the operations of `request` in a single transaction and returns their results in
+    /// the same order.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if any of the operations failed, in which case none of them is applied
+    pub async fn apply_batch(
+        &self,
+        request: &BatchRequest,
+    ) -> Result<Vec<BatchOperationResult>, ClientError> {
+        self.post(&["batch"], request).await
+    }
+
+    /// Exports the records of the graph, optionally only those owned by `account_id`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the snapshot could not be exported or read
+    pub async fn export_snapshot(
+        &self,
+        account_id: Option<AccountId>,
+    ) -> Result<Snapshot, ClientError> {
+        let mut request = self.request(Method::GET, &["snapshot"])?;
+        if let Some(account_id) = account_id {
+            request = request.query(&[("accountId", account_id)]);
+        }
+
+        let body = Self::execute(request)
+            .await?
+            .bytes()
+            .await
+            .into_report()
+            .change_context(ClientError)?;
+        Snapshot::read(body.as_ref()).change_context(ClientError)
+    }
+
+    /// Restores the records of `snapshot` and returns the number of restored records.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the snapshot could not be restored
+    pub async fn import_snapshot(
+        &self,
+        snapshot: &Snapshot,
+    ) -> Result<RestoreSummary, ClientError> {
+        let mut body = Vec::new();
+        snapshot.write(&mut body).change_context(ClientError)?;
+
+        Self::send(
+            self.request(Method::POST, &["snapshot"])?
+                .header(header::CONTENT_TYPE, "application/x-ndjson")
+                .body(body),
+        )
+        .await
+    }
+
+    /// Subscribes to the changes of the graph.
+    ///
+    /// If `resource_types` is provided, only changes of these resource types (e.g. `entity` or
+    /// `link`) are streamed. The stream ends when the connection is closed.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the subscription could not be established
+    pub async fn subscribe_to_changes(
+        &self,
+        resource_types: Option<&[&str]>,
+    ) -> Result<impl Stream<Item = Result<ChangeEvent, ClientError>>, ClientError> {
+        let mut request = self
+            .request(Method::GET, &["changes"])?
+            .header(header::ACCEPT, "text/event-stream");
+        if let Some(resource_types) = resource_types {
+            request = request.query(&[("types", resource_types.join(","))]);
+        }
+
+        Ok(change_events(Self::execute(request).await?.bytes_stream()))
+    }
+
+    /// Succeeds, if the graph is able to handle requests.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the graph is not reachable
+    pub async fn check_liveness(&self) -> Result<(), ClientError> {
+        Self::execute(self.request(Method::GET, &["health", "live"])?)
+            .await
+            .map(drop)
+    }
+
+    /// Succeeds, if the graph is able to connect to its store.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the graph is not reachable or not ready
+    pub async fn check_readiness(&self) -> Result<(), ClientError> {
+        Self::execute(self.request(Method::GET, &["health", "ready"])?)
+            .await
+            .map(drop)
+    }
+
+    /// Returns the metrics of the graph in the Prometheus text format.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError`], if the metrics could not be read
+    pub async fn metrics(&self) -> Result<String, ClientError> {
+        Self::execute(self.request(Method::GET, &["metrics"])?)
+            .await?
+            .text()
+            .await
+            .into_report()
+            .change_context(ClientError)
+    }
+}
+
+/// Splits a stream of server-sent events into the [`ChangeEvent`]s sent as their data.
+fn change_events<B: AsRef<[u8]>>(
+    bytes: impl Stream<Item = reqwest::Result<B>>,
+) -> impl Stream<Item = Result<ChangeEvent, ClientError>> {
+    stream::unfold(
+        (Box::pin(bytes), Vec::new()),
+        |(mut bytes, mut buffer)| async move {
+            loop {
+                // Events are separated by an empty line
+                while let Some(position) = buffer.windows(2).position(|window| window == b"\n\n") {
+                    let event: Vec<u8> = buffer.drain(..position + 2).collect();
+                    if let Some(change_event) = parse_change_event(&event) {
+                        return Some((change_event, (bytes, buffer)));
+                    }
+                }
+
+                match bytes.next().await {
+                    Some(Ok(chunk)) => buffer.extend_from_slice(chunk.as_ref()),
+                    Some(Err(error)) => {
+                        return Some((
+                            Err(Report::new(error).change_context(ClientError)),
+                            (bytes, buffer),
+                        ));
+                    }
+                    None => return None,
+                }
+            }
+        },
+    )
+}
+
+/// Parses the data of a single server-sent event.
+///
+/// Returns `None` for events without data, e.g. the comments sent to keep the connection alive.
+fn parse_change_event(event: &[u8]) -> Option<Result<ChangeEvent, ClientError>> {
+    let event = match std::str::from_utf8(event) {
+        Ok(event) => event,
+        Err(error) => return Some(Err(Report::new(error).change_context(ClientError))),
+    };
+
+    let data: Vec<_> = event
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|data| data.strip_prefix(' ').unwrap_or(data))
+        .collect();
+    if data.is_empty() {
+        return None;
+    }
+
+    Some(
+        serde_json::from_str(&data.join("\n"))
+            .into_report()
+            .change_context(ClientError)
+            .attach_printable("could not decode the change event"),
+    )
+}
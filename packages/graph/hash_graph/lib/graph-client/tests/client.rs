@@ -0,0 +1,563 @@
+use std::{net::TcpListener, sync::Arc};
+
+use futures::StreamExt;
+use graph::{
+    api::rest::{
+        auth::Authentication, rest_api_router, BatchEntityId, BatchOperation, BatchOperationResult,
+        BatchRequest, CreateDataTypeRequest, CreateEntityOperation, CreateEntityRequest,
+        CreateEntityTypeRequest, CreateLinkOperation, CreateLinkRequest, CreateLinkTypeRequest,
+        CreatePropertyTypeRequest, DeprecateOntologyTypeRequest, OperationReference,
+        RemoveLinkRequest, TransferOntologyTypeOwnershipRequest, UpdateEntityRequest,
+        UpdateEntityTypeRequest,
+    },
+    knowledge::{Entity, EntityId},
+    ontology::{
+        domain_validator::DomainValidator,
+        external_types::{ExternalTypeResolver, FixtureFetcher},
+        AccountId,
+    },
+    shared::identifier::GraphElementIdentifier,
+    store::{
+        change::{ChangeFeed, ChangeKind},
+        query::Expression,
+        search::EntitySearch,
+        MemoryStore, MemoryStorePool,
+    },
+    subgraph::{GraphResolveDepths, StructuralQuery},
+};
+use graph_client::{Client, StatusCode};
+use graph_test_data::{data_type, entity, entity_type, link_type, property_type};
+use regex::Regex;
+use type_system::uri::VersionedUri;
+
+const TEXT_V1_ID: &str = "https://blockprotocol.org/@blockprotocol/types/data-type/text/v/1";
+const NAME_V1_ID: &str = "https://blockprotocol.org/@alice/types/property-type/name/v/1";
+const FRIEND_OF_V1_ID: &str = "https://blockprotocol.org/@alice/types/link-type/friend-of/v/1";
+const PERSON_V1_ID: &str = "https://blockprotocol.org/@alice/types/entity-type/person/v/1";
+
+/// Serves the REST API backed by an empty [`MemoryStore`] on a random port and returns a client
+/// connected to it.
+fn start_graph() -> Client {
+    let domain_validator = DomainValidator::new(
+        Regex::new(
+            r"https://blockprotocol.org/@(?P<shortname>[\w-]+)/types/(?P<kind>(?:data-type)|(?:property-type)|(?:entity-type)|(?:link-type))/[\w-]+/",
+        )
+        .expect("invalid domain regex"),
+    );
+    let change_feed = ChangeFeed::default();
+
+    let router = rest_api_router(
+        Arc::new(MemoryStorePool::from_store(
+            MemoryStore::new().with_change_feed(change_feed.clone()),
+        )),
+        domain_validator.clone(),
+        ExternalTypeResolver::new(domain_validator, FixtureFetcher::default()),
+        Authentication::default(),
+        change_feed,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").expect("could not bind to a port");
+    let address = listener
+        .local_addr()
+        .expect("could not read the address of the listener");
+    tokio::spawn(
+        axum::Server::from_tcp(listener)
+            .expect("could not start the server")
+            .serve(router.into_make_service()),
+    );
+
+    Client::new(&format!("http://{address}")).expect("could not create client")
+}
+
+fn uri(uri: &str) -> VersionedUri {
+    serde_json::from_value(serde_json::Value::String(uri.to_owned())).expect("invalid URI")
+}
+
+fn entity(entity: &str) -> Entity {
+    serde_json::from_str(entity).expect("invalid entity")
+}
+
+fn query(expression: Expression, graph_resolve_depths: GraphResolveDepths) -> StructuralQuery {
+    StructuralQuery {
+        expression,
+        graph_resolve_depths,
+        link_type_filter: None,
+    }
+}
+
+/// Creates an account and the types required to create people.
+async fn seed(client: &Client) -> AccountId {
+    let account_id = client
+        .create_account_id()
+        .await
+        .expect("could not create account");
+
+    client
+        .create_data_type(&CreateDataTypeRequest {
+            schema: serde_json::from_str(data_type::TEXT_V1).expect("invalid data type"),
+            owned_by_id: account_id,
+            actor_id: account_id,
+        })
+        .await
+        .expect("could not create data type");
+    client
+        .create_property_type(&CreatePropertyTypeRequest {
+            schema: serde_json::from_str(property_type::NAME_V1).expect("invalid property type"),
+            owned_by_id: account_id,
+            actor_id: account_id,
+        })
+        .await
+        .expect("could not create property type");
+    client
+        .create_link_type(&CreateLinkTypeRequest {
+            schema: serde_json::from_str(link_type::FRIEND_OF_V1).expect("invalid link type"),
+            owned_by_id: account_id,
+            actor_id: account_id,
+        })
+        .await
+        .expect("could not create link type");
+    client
+        .create_entity_type(&CreateEntityTypeRequest {
+            schema: serde_json::from_str(entity_type::PERSON_V1).expect("invalid entity type"),
+            owned_by_id: account_id,
+            actor_id: account_id,
+        })
+        .await
+        .expect("could not create entity type");
+
+    account_id
+}
+
+async fn create_person(client: &Client, account_id: AccountId, person: &str) -> EntityId {
+    client
+        .create_entity(&CreateEntityRequest {
+            entity: entity(person),
+            entity_type_id: uri(PERSON_V1_ID),
+            owned_by_id: account_id,
+            entity_id: None,
+            actor_id: account_id,
+        })
+        .await
+        .expect("could not create entity")
+        .identifier()
+        .entity_id()
+}
+
+#[tokio::test]
+async fn health() {
+    let client = start_graph();
+
+    client.check_liveness().await.expect("graph is not live");
+    client.check_readiness().await.expect("graph is not ready");
+}
+
+#[tokio::test]
+async fn ontology_types() {
+    let client = start_graph();
+    let account_id = seed(&client).await;
+
+    let text = client
+        .get_data_type(&uri(TEXT_V1_ID))
+        .await
+        .expect("could not read data type");
+    assert_eq!(text.metadata().identifier().uri(), &uri(TEXT_V1_ID));
+    assert_eq!(text.metadata().identifier().owned_by_id(), account_id);
+
+    let name = client
+        .get_property_type(&uri(NAME_V1_ID))
+        .await
+        .expect("could not read property type");
+    assert_eq!(name.metadata().identifier().uri(), &uri(NAME_V1_ID));
+
+    let friend_of = client
+        .get_link_type(&uri(FRIEND_OF_V1_ID))
+        .await
+        .expect("could not read link type");
+    assert_eq!(
+        friend_of.metadata().identifier().uri(),
+        &uri(FRIEND_OF_V1_ID)
+    );
+
+    let person = client
+        .get_entity_type(&uri(PERSON_V1_ID))
+        .await
+        .expect("could not read entity type");
+    assert_eq!(person.metadata().identifier().uri(), &uri(PERSON_V1_ID));
+
+    assert_eq!(
+        client
+            .get_latest_data_types()
+            .await
+            .expect("could not read data types")
+            .len(),
+        1
+    );
+    assert_eq!(
+        client
+            .get_latest_property_types()
+            .await
+            .expect("could not read property types")
+            .len(),
+        1
+    );
+    assert_eq!(
+        client
+            .get_latest_link_types()
+            .await
+            .expect("could not read link types")
+            .len(),
+        1
+    );
+    assert_eq!(
+        client
+            .get_latest_entity_types()
+            .await
+            .expect("could not read entity types")
+            .len(),
+        1
+    );
+
+    let subgraph = client
+        .query_entity_types(&query(
+            Expression::for_versioned_uri(&uri(PERSON_V1_ID)),
+            GraphResolveDepths {
+                property_type_resolve_depth: 1,
+                ..GraphResolveDepths::zeroed()
+            },
+        ))
+        .await
+        .expect("could not query entity types");
+    assert_eq!(subgraph.roots, [GraphElementIdentifier::OntologyElementId(
+        uri(PERSON_V1_ID)
+    )]);
+    assert!(
+        subgraph
+            .vertices
+            .contains_key(&GraphElementIdentifier::OntologyElementId(uri(NAME_V1_ID)))
+    );
+
+    let subgraph = client
+        .query_data_types(&query(
+            Expression::for_latest_version(),
+            GraphResolveDepths::zeroed(),
+        ))
+        .await
+        .expect("could not query data types");
+    assert_eq!(subgraph.roots.len(), 1);
+}
+
+#[tokio::test]
+async fn deprecation_and_ownership_transfer() {
+    let client = start_graph();
+    let account_id = seed(&client).await;
+
+    let mut schema: serde_json::Value =
+        serde_json::from_str(entity_type::PERSON_V1).expect("invalid entity type");
+    schema
+        .as_object_mut()
+        .expect("entity type is not an object")
+        .remove("$id");
+    let person_v2 = client
+        .update_entity_type(&UpdateEntityTypeRequest {
+            schema,
+            type_to_update: uri(PERSON_V1_ID),
+            actor_id: account_id,
+            allow_breaking_changes: false,
+        })
+        .await
+        .expect("could not update entity type")
+        .metadata
+        .identifier()
+        .uri()
+        .clone();
+
+    let report = client
+        .deprecate_ontology_type(&DeprecateOntologyTypeRequest {
+            type_id: uri(PERSON_V1_ID),
+            replaced_by: Some(uri(NAME_V1_ID)),
+            actor_id: account_id,
+        })
+        .await
+        .expect_err("a property type cannot replace an entity type");
+    assert_eq!(
+        report.downcast_ref::<StatusCode>(),
+        Some(&StatusCode::UNPROCESSABLE_ENTITY)
+    );
+
+    let metadata = client
+        .deprecate_ontology_type(&DeprecateOntologyTypeRequest {
+            type_id: uri(PERSON_V1_ID),
+            replaced_by: Some(person_v2.clone()),
+            actor_id: account_id,
+        })
+        .await
+        .expect("could not deprecate entity type");
+    let deprecation = metadata
+        .deprecation()
+        .expect("entity type is not deprecated");
+    assert_eq!(deprecation.deprecated_by_id(), account_id);
+    assert_eq!(deprecation.replaced_by(), Some(&person_v2));
+
+    // Deprecated types can still be read and used.
+    let person = client
+        .get_entity_type(&uri(PERSON_V1_ID))
+        .await
+        .expect("could not read entity type");
+    assert_eq!(person.metadata().deprecation(), Some(deprecation));
+    create_person(&client, account_id, entity::PERSON_A_V1).await;
+
+    let new_owner = client
+        .create_account_id()
+        .await
+        .expect("could not create account");
+    let metadata = client
+        .transfer_ontology_type_ownership(&TransferOntologyTypeOwnershipRequest {
+            base_uri: uri(PERSON_V1_ID).base_uri().clone(),
+            owned_by_id: new_owner,
+            actor_id: account_id,
+        })
+        .await
+        .expect("could not transfer ownership");
+    assert_eq!(metadata.identifier().uri(), &person_v2);
+    assert_eq!(metadata.identifier().owned_by_id(), new_owner);
+    let person = client
+        .get_entity_type(&uri(PERSON_V1_ID))
+        .await
+        .expect("could not read entity type");
+    assert_eq!(person.metadata().identifier().owned_by_id(), new_owner);
+}
+
+#[tokio::test]
+async fn entities_and_links() {
+    let client = start_graph();
+    let account_id = seed(&client).await;
+
+    let alice = create_person(&client, account_id, entity::PERSON_A_V1).await;
+    let bob = create_person(&client, account_id, entity::PERSON_B_V1).await;
+
+    assert_eq!(
+        client
+            .get_entity(alice)
+            .await
+            .expect("could not read entity")
+            .inner(),
+        &entity(entity::PERSON_A_V1)
+    );
+
+    client
+        .update_entity(&UpdateEntityRequest {
+            entity: entity(entity::PERSON_C_V1),
+            entity_id: bob,
+            entity_type_id: uri(PERSON_V1_ID),
+            actor_id: account_id,
+        })
+        .await
+        .expect("could not update entity");
+    assert_eq!(
+        client
+            .get_entity(bob)
+            .await
+            .expect("could not read entity")
+            .inner(),
+        &entity(entity::PERSON_C_V1)
+    );
+    assert_eq!(
+        client
+            .get_latest_entities()
+            .await
+            .expect("could not read entities")
+            .len(),
+        2
+    );
+
+    let link = client
+        .create_link(alice, &CreateLinkRequest {
+            target_entity_id: bob,
+            link_type_id: uri(FRIEND_OF_V1_ID),
+            owned_by_id: account_id,
+            actor_id: account_id,
+            index: None,
+        })
+        .await
+        .expect("could not create link");
+    assert_eq!(link.source_entity(), alice);
+    assert_eq!(link.target_entity(), bob);
+
+    let links = client
+        .get_entity_links(alice)
+        .await
+        .expect("could not read links");
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].inner(), &link);
+
+    let link_subgraphs = client
+        .query_links(&query(
+            Expression::for_link_by_source_entity_id(alice),
+            GraphResolveDepths::zeroed(),
+        ))
+        .await
+        .expect("could not query links");
+    assert_eq!(link_subgraphs.len(), 1);
+
+    let subgraph = client
+        .query_entities(&query(
+            Expression::for_latest_entity_id(alice),
+            GraphResolveDepths {
+                link_resolve_depth: 1,
+                link_target_entity_resolve_depth: 1,
+                ..GraphResolveDepths::zeroed()
+            },
+        ))
+        .await
+        .expect("could not query entities");
+    assert_eq!(subgraph.roots, [
+        GraphElementIdentifier::KnowledgeGraphElementId(alice)
+    ]);
+    assert!(
+        subgraph
+            .vertices
+            .contains_key(&GraphElementIdentifier::KnowledgeGraphElementId(bob))
+    );
+
+    let hits = client
+        .search_entities(&EntitySearch::new("Alice"))
+        .await
+        .expect("could not search entities");
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].entity().metadata().identifier().entity_id(), alice);
+
+    client
+        .remove_link(alice, &RemoveLinkRequest {
+            target_entity_id: bob,
+            link_type_id: uri(FRIEND_OF_V1_ID),
+            actor_id: account_id,
+        })
+        .await
+        .expect("could not remove link");
+    assert!(
+        client
+            .get_entity_links(alice)
+            .await
+            .expect("could not read links")
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn batch() {
+    let client = start_graph();
+    let account_id = seed(&client).await;
+
+    let create_person_operation = |person| {
+        BatchOperation::CreateEntity(CreateEntityOperation {
+            entity: entity(person),
+            entity_type_id: uri(PERSON_V1_ID),
+            owned_by_id: account_id,
+            entity_id: None,
+            actor_id: account_id,
+        })
+    };
+
+    let results = client
+        .apply_batch(&BatchRequest {
+            operations: vec![
+                create_person_operation(entity::PERSON_A_V1),
+                create_person_operation(entity::PERSON_B_V1),
+                BatchOperation::CreateLink(CreateLinkOperation {
+                    source_entity_id: BatchEntityId::Reference(OperationReference { result_of: 0 }),
+                    target_entity_id: BatchEntityId::Reference(OperationReference { result_of: 1 }),
+                    link_type_id: uri(FRIEND_OF_V1_ID),
+                    owned_by_id: account_id,
+                    actor_id: account_id,
+                    index: None,
+                }),
+            ],
+        })
+        .await
+        .expect("could not apply batch");
+
+    let (alice, bob) = match results.as_slice() {
+        [
+            BatchOperationResult::CreateEntity(alice),
+            BatchOperationResult::CreateEntity(bob),
+            BatchOperationResult::CreateLink(link),
+        ] => {
+            assert_eq!(link.source_entity(), alice.identifier().entity_id());
+            assert_eq!(link.target_entity(), bob.identifier().entity_id());
+            (alice.identifier().entity_id(), bob.identifier().entity_id())
+        }
+        _ => panic!("unexpected batch results"),
+    };
+
+    let links = client
+        .get_entity_links(alice)
+        .await
+        .expect("could not read links");
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].inner().target_entity(), bob);
+}
+
+#[tokio::test]
+async fn snapshot() {
+    let source = start_graph();
+    let account_id = seed(&source).await;
+    let alice = create_person(&source, account_id, entity::PERSON_A_V1).await;
+
+    let snapshot = source
+        .export_snapshot(Some(account_id))
+        .await
+        .expect("could not export snapshot");
+
+    let target = start_graph();
+    target
+        .import_snapshot(&snapshot)
+        .await
+        .expect("could not import snapshot");
+
+    assert_eq!(
+        target
+            .get_entity(alice)
+            .await
+            .expect("could not read restored entity")
+            .inner(),
+        &entity(entity::PERSON_A_V1)
+    );
+}
+
+#[tokio::test]
+async fn changes() {
+    let client = start_graph();
+    let account_id = seed(&client).await;
+
+    let mut changes = Box::pin(
+        client
+            .subscribe_to_changes(Some(&["entity"]))
+            .await
+            .expect("could not subscribe to changes"),
+    );
+
+    create_person(&client, account_id, entity::PERSON_A_V1).await;
+
+    let change = changes
+        .next()
+        .await
+        .expect("change stream ended")
+        .expect("could not read change event");
+    assert_eq!(change.kind(), ChangeKind::Created);
+    assert_eq!(change.resource().resource_type(), "entity");
+    assert_eq!(change.actor_id(), account_id);
+}
+
+#[tokio::test]
+async fn unsuccessful_status_is_attached() {
+    let client = start_graph();
+
+    let report = client
+        .get_data_type(&uri(TEXT_V1_ID))
+        .await
+        .expect_err("data type should not exist");
+    assert_eq!(
+        report.downcast_ref::<StatusCode>(),
+        Some(&StatusCode::NOT_FOUND)
+    );
+}
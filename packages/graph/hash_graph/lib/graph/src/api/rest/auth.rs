@@ -0,0 +1,457 @@
+//! Authentication and authorization of requests to the REST API.
+//!
+//! When an [`Authenticator`] is configured, every request has to provide credentials as bearer
+//! token in the `Authorization` header. The verified account is made available to the handlers as
+//! [`Actor`], which is used to check that requests only act on behalf of the authenticated account
+//! and only modify resources owned by it. Privileged accounts may act on behalf of any account.
+//!
+//! Without an [`Authenticator`], the account IDs provided in the requests are trusted.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
+
+use axum::{
+    body::Body,
+    http::{header::AUTHORIZATION, HeaderMap, Request, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use error_stack::{Context, IntoReport, Report, Result, ResultExt};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use type_system::uri::VersionedUri;
+use uuid::Uuid;
+
+use crate::{
+    api::rest::report_to_status_code,
+    knowledge::{EntityId, PersistedEntity, PersistedLink},
+    ontology::AccountId,
+    store::{crud::Read, query::Expression, Store, StorePool},
+};
+
+#[derive(Debug)]
+pub struct AuthenticationError;
+
+impl Context for AuthenticationError {}
+
+impl fmt::Display for AuthenticationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("could not authenticate request")
+    }
+}
+
+/// Verifies the credentials of a request.
+pub trait Authenticator: Send + Sync {
+    /// Returns the account, which is authenticated by the credentials in `headers`.
+    ///
+    /// # Errors
+    ///
+    /// - [`AuthenticationError`], if the credentials are missing or invalid
+    fn authenticate(&self, headers: &HeaderMap) -> Result<AccountId, AuthenticationError>;
+}
+
+fn bearer_token(headers: &HeaderMap) -> Result<&str, AuthenticationError> {
+    headers
+        .get(AUTHORIZATION)
+        .ok_or_else(|| {
+            Report::new(AuthenticationError).attach_printable("missing `Authorization` header")
+        })?
+        .to_str()
+        .into_report()
+        .change_context(AuthenticationError)?
+        .strip_prefix("Bearer ")
+        .ok_or_else(|| Report::new(AuthenticationError).attach_printable("expected a bearer token"))
+}
+
+/// Authenticates requests by static API keys, which are assigned to accounts.
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeyAuthenticator {
+    keys: HashMap<String, AccountId>,
+}
+
+impl ApiKeyAuthenticator {
+    #[must_use]
+    pub fn new(keys: impl IntoIterator<Item = (String, AccountId)>) -> Self {
+        Self {
+            keys: keys.into_iter().collect(),
+        }
+    }
+}
+
+impl Authenticator for ApiKeyAuthenticator {
+    fn authenticate(&self, headers: &HeaderMap) -> Result<AccountId, AuthenticationError> {
+        let key = bearer_token(headers)?;
+        self.keys
+            .get(key)
+            .copied()
+            .ok_or_else(|| Report::new(AuthenticationError).attach_printable("unknown API key"))
+    }
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: Uuid,
+}
+
+/// Authenticates requests by JSON Web Tokens signed with a locally configured key.
+///
+/// The subject (`sub`) of the token is the ID of the authenticated account. Tokens are required
+/// to have an expiration time (`exp`).
+#[derive(Clone)]
+pub struct JwtAuthenticator {
+    key: DecodingKey,
+    validation: Validation,
+}
+
+impl JwtAuthenticator {
+    /// Verifies tokens signed with HMAC using SHA-256 and the shared `secret`.
+    #[must_use]
+    pub fn from_secret(secret: &[u8]) -> Self {
+        Self {
+            key: DecodingKey::from_secret(secret),
+            validation: Validation::new(Algorithm::HS256),
+        }
+    }
+
+    /// Verifies tokens signed with RSA using SHA-256 and the PEM encoded public key.
+    ///
+    /// # Errors
+    ///
+    /// - [`AuthenticationError`], if `pem` is not a valid RSA public key
+    pub fn from_rsa_pem(pem: &[u8]) -> Result<Self, AuthenticationError> {
+        Ok(Self {
+            key: DecodingKey::from_rsa_pem(pem)
+                .into_report()
+                .change_context(AuthenticationError)?,
+            validation: Validation::new(Algorithm::RS256),
+        })
+    }
+}
+
+impl Authenticator for JwtAuthenticator {
+    fn authenticate(&self, headers: &HeaderMap) -> Result<AccountId, AuthenticationError> {
+        let token =
+            jsonwebtoken::decode::<Claims>(bearer_token(headers)?, &self.key, &self.validation)
+                .into_report()
+                .change_context(AuthenticationError)?;
+
+        Ok(AccountId::new(token.claims.sub))
+    }
+}
+
+/// Configuration of the authentication layer of the REST API.
+///
+/// By default, authentication is disabled.
+#[derive(Clone, Default)]
+pub struct Authentication {
+    authenticator: Option<Arc<dyn Authenticator>>,
+    privileged_accounts: Arc<HashSet<AccountId>>,
+}
+
+impl Authentication {
+    /// Requires every request to be authenticated by `authenticator`.
+    #[must_use]
+    pub fn new(authenticator: impl Authenticator + 'static) -> Self {
+        Self {
+            authenticator: Some(Arc::new(authenticator)),
+            privileged_accounts: Arc::default(),
+        }
+    }
+
+    /// Allows `accounts` to act on behalf of any account and to modify resources of any account.
+    #[must_use]
+    pub fn with_privileged_accounts(
+        mut self,
+        accounts: impl IntoIterator<Item = AccountId>,
+    ) -> Self {
+        self.privileged_accounts = Arc::new(accounts.into_iter().collect());
+        self
+    }
+}
+
+/// The account making a request, as determined by the authentication layer.
+#[derive(Debug, Copy, Clone)]
+pub struct Actor {
+    account_id: Option<AccountId>,
+    privileged: bool,
+}
+
+impl Actor {
+    /// Returns the authenticated account or `None` if authentication is disabled.
+    #[must_use]
+    pub const fn account_id(&self) -> Option<AccountId> {
+        self.account_id
+    }
+
+    fn may_act_as(&self, account_id: AccountId) -> bool {
+        self.privileged
+            || self
+                .account_id
+                .map_or(true, |actor_id| actor_id == account_id)
+    }
+
+    /// Returns if the actor may observe changes of resources owned by `owned_by_id`.
+    #[must_use]
+    pub fn may_observe(&self, owned_by_id: AccountId) -> bool {
+        self.may_act_as(owned_by_id)
+    }
+
+    /// Returns `account_id` if the actor may act on behalf of it.
+    ///
+    /// # Errors
+    ///
+    /// - [`StatusCode::FORBIDDEN`], if the authenticated account is a different, unprivileged one
+    pub fn act_as(&self, account_id: AccountId) -> std::result::Result<AccountId, StatusCode> {
+        if self.may_act_as(account_id) {
+            Ok(account_id)
+        } else {
+            tracing::error!(actor_id=?self.account_id, %account_id, "Actor tried to act on behalf of another account");
+            Err(StatusCode::FORBIDDEN)
+        }
+    }
+
+    /// Checks that the actor may modify a resource owned by `owned_by_id`.
+    ///
+    /// # Errors
+    ///
+    /// - [`StatusCode::FORBIDDEN`], if the resource is owned by a different account and the actor
+    ///   is not privileged
+    pub fn ensure_may_modify(&self, owned_by_id: AccountId) -> std::result::Result<(), StatusCode> {
+        if self.may_act_as(owned_by_id) {
+            Ok(())
+        } else {
+            tracing::error!(actor_id=?self.account_id, %owned_by_id, "Actor tried to modify a resource of another account");
+            Err(StatusCode::FORBIDDEN)
+        }
+    }
+}
+
+/// Middleware authenticating the request and providing the [`Actor`] to the handlers.
+///
+/// The [`Authentication`] has to be provided as extension. If it's missing, the request is rejected
+/// instead of falling back to disabled authentication.
+pub(super) async fn authenticate(
+    mut request: Request<Body>,
+    next: Next<Body>,
+) -> std::result::Result<Response, StatusCode> {
+    let authentication = request
+        .extensions()
+        .get::<Authentication>()
+        .cloned()
+        .ok_or_else(|| {
+            tracing::error!("Authentication is not configured for the request");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let actor = match authentication.authenticator {
+        Some(authenticator) => {
+            let account_id = authenticator
+                .authenticate(request.headers())
+                .map_err(|report| {
+                    tracing::warn!(error=?report, "Could not authenticate request");
+                    StatusCode::UNAUTHORIZED
+                })?;
+            Actor {
+                account_id: Some(account_id),
+                privileged: authentication.privileged_accounts.contains(&account_id),
+            }
+        }
+        None => Actor {
+            account_id: None,
+            privileged: false,
+        },
+    };
+
+    request.extensions_mut().insert(actor);
+    Ok(next.run(request).await)
+}
+
+/// Returns the owner of the latest version of the entity identified by `entity_id`.
+pub(super) async fn entity_owner<P: StorePool + Send>(
+    pool: &P,
+    entity_id: EntityId,
+) -> std::result::Result<AccountId, StatusCode> {
+    entity_owner_in_store(&acquire_store(pool).await?, entity_id).await
+}
+
+/// Returns the owner of the link between the given entities.
+pub(super) async fn link_owner<P: StorePool + Send>(
+    pool: &P,
+    source_entity_id: EntityId,
+    target_entity_id: EntityId,
+    link_type_id: &VersionedUri,
+) -> std::result::Result<AccountId, StatusCode> {
+    link_owner_in_store(
+        &acquire_store(pool).await?,
+        source_entity_id,
+        target_entity_id,
+        link_type_id,
+    )
+    .await
+}
+
+async fn acquire_store<P: StorePool + Send>(
+    pool: &P,
+) -> std::result::Result<P::Store<'_>, StatusCode> {
+    pool.acquire().await.map_err(|report| {
+        tracing::error!(error=?report, "Could not acquire access to the store");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+/// Returns the owner of the latest version of the entity identified by `entity_id` as seen by
+/// `store`.
+///
+/// In contrast to [`entity_owner`], this also sees entities created in a transaction.
+pub(super) async fn entity_owner_in_store<S: Store>(
+    store: &S,
+    entity_id: EntityId,
+) -> std::result::Result<AccountId, StatusCode> {
+    let mut entities: Vec<PersistedEntity> =
+        read_from(store, &Expression::for_latest_entity_id(entity_id)).await?;
+
+    entities
+        .pop()
+        .map(|entity| entity.metadata().identifier().owned_by_id())
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Returns the owner of the link between the given entities as seen by `store`.
+pub(super) async fn link_owner_in_store<S: Store>(
+    store: &S,
+    source_entity_id: EntityId,
+    target_entity_id: EntityId,
+    link_type_id: &VersionedUri,
+) -> std::result::Result<AccountId, StatusCode> {
+    let links: Vec<PersistedLink> = read_from(
+        store,
+        &Expression::for_link_by_source_entity_id(source_entity_id),
+    )
+    .await?;
+
+    links
+        .into_iter()
+        .find(|link| {
+            link.inner().target_entity() == target_entity_id
+                && link.inner().link_type_id() == link_type_id
+        })
+        .map(|link| link.metadata().owned_by_id())
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn read_from<S, T>(store: &S, query: &Expression) -> std::result::Result<Vec<T>, StatusCode>
+where
+    S: for<'q> Read<T, Query<'q> = Expression>,
+    T: Send,
+{
+    store.read(query).await.map_err(|report| {
+        tracing::error!(error=?report, ?query, "Could not read from the store");
+        report_to_status_code(&report)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+
+    use super::*;
+
+    fn headers(authorization: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(authorization).expect("invalid header value"),
+        );
+        headers
+    }
+
+    #[test]
+    fn api_key() {
+        let account_id = AccountId::new(Uuid::new_v4());
+        let authenticator = ApiKeyAuthenticator::new([("secret-key".to_owned(), account_id)]);
+
+        assert_eq!(
+            authenticator
+                .authenticate(&headers("Bearer secret-key"))
+                .expect("could not authenticate"),
+            account_id
+        );
+        assert!(
+            authenticator
+                .authenticate(&headers("Bearer other-key"))
+                .is_err()
+        );
+        assert!(authenticator.authenticate(&headers("secret-key")).is_err());
+        assert!(authenticator.authenticate(&HeaderMap::new()).is_err());
+    }
+
+    #[test]
+    fn jwt() {
+        let account_id = Uuid::new_v4();
+        let authenticator = JwtAuthenticator::from_secret(b"secret");
+
+        let encode = |secret: &[u8]| {
+            jsonwebtoken::encode(
+                &Header::new(Algorithm::HS256),
+                &json!({ "sub": account_id, "exp": u32::MAX }),
+                &EncodingKey::from_secret(secret),
+            )
+            .expect("could not encode token")
+        };
+
+        assert_eq!(
+            authenticator
+                .authenticate(&headers(&format!("Bearer {}", encode(b"secret"))))
+                .expect("could not authenticate"),
+            AccountId::new(account_id)
+        );
+        assert!(
+            authenticator
+                .authenticate(&headers(&format!("Bearer {}", encode(b"other-secret"))))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn actor() {
+        let account_id = AccountId::new(Uuid::new_v4());
+        let other_account_id = AccountId::new(Uuid::new_v4());
+
+        let unauthenticated = Actor {
+            account_id: None,
+            privileged: false,
+        };
+        assert_eq!(
+            unauthenticated.act_as(other_account_id),
+            Ok(other_account_id)
+        );
+
+        let authenticated = Actor {
+            account_id: Some(account_id),
+            privileged: false,
+        };
+        assert_eq!(authenticated.act_as(account_id), Ok(account_id));
+        assert_eq!(
+            authenticated.act_as(other_account_id),
+            Err(StatusCode::FORBIDDEN)
+        );
+        assert_eq!(
+            authenticated.ensure_may_modify(other_account_id),
+            Err(StatusCode::FORBIDDEN)
+        );
+        assert!(authenticated.may_observe(account_id));
+        assert!(!authenticated.may_observe(other_account_id));
+
+        let privileged = Actor {
+            account_id: Some(account_id),
+            privileged: true,
+        };
+        assert_eq!(privileged.ensure_may_modify(other_account_id), Ok(()));
+        assert!(privileged.may_observe(other_account_id));
+    }
+}
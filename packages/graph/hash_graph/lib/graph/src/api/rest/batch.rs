@@ -0,0 +1,542 @@
+//! Web routes for applying multiple mutations atomically.
+
+use std::sync::Arc;
+
+use axum::{
+    http::{header, HeaderMap, StatusCode},
+    routing::post,
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use type_system::uri::VersionedUri;
+use utoipa::{openapi, OpenApi, ToSchema};
+
+use crate::{
+    api::rest::{
+        api_resource::RoutedResource,
+        auth::{entity_owner_in_store, link_owner_in_store, Actor},
+        data_type::{create_data_type_in_store, update_data_type_in_store},
+        entity::entity_type_deprecation_warning,
+        entity_type::{create_entity_type_in_store, update_entity_type_in_store},
+        link_type::{create_link_type_in_store, update_link_type_in_store},
+        property_type::{create_property_type_in_store, update_property_type_in_store},
+        CreateDataTypeRequest, CreateEntityTypeRequest, CreateLinkTypeRequest,
+        CreatePropertyTypeRequest, UpdateDataTypeRequest, UpdateEntityTypeRequest,
+        UpdateLinkTypeRequest, UpdateOntologyTypeResponse, UpdatePropertyTypeRequest,
+    },
+    knowledge::{Entity, EntityId, Link, PersistedEntityIdentifier, PersistedEntityMetadata},
+    ontology::{
+        domain_validator::DomainValidator, external_types::ExternalTypeResolver, AccountId,
+        PersistedOntologyMetadata,
+    },
+    store::{
+        error::{EntityDoesNotExist, QueryError},
+        Store, StorePool, StoreTransaction, TransactionalStore,
+    },
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        apply_batch
+    ),
+    components(
+        schemas(
+            BatchRequest,
+            BatchOperation,
+            BatchOperationResult,
+            BatchEntityId,
+            OperationReference,
+            CreateEntityOperation,
+            UpdateEntityOperation,
+            CreateLinkOperation,
+            RemoveLinkOperation,
+            CreateDataTypeRequest,
+            UpdateDataTypeRequest,
+            CreatePropertyTypeRequest,
+            UpdatePropertyTypeRequest,
+            CreateLinkTypeRequest,
+            UpdateLinkTypeRequest,
+            CreateEntityTypeRequest,
+            UpdateEntityTypeRequest,
+            UpdateOntologyTypeResponse,
+            PersistedOntologyMetadata,
+            EntityId,
+            Entity,
+            Link,
+            PersistedEntityIdentifier,
+            PersistedEntityMetadata,
+        )
+    ),
+    tags(
+        (name = "Batch", description = "API for applying multiple operations atomically")
+    )
+)]
+pub struct BatchResource;
+
+impl RoutedResource for BatchResource {
+    /// Create routes for applying batches of operations.
+    fn routes<P: StorePool + Send + 'static>() -> Router {
+        Router::new().route("/batch", post(apply_batch::<P>))
+    }
+}
+
+/// Refers to the result of an earlier operation of the same batch.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct OperationReference {
+    /// The index of the operation in the batch.
+    pub result_of: usize,
+}
+
+/// Refers to an entity either by its ID or by the entity created or updated by an earlier
+/// operation of the same batch.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BatchEntityId {
+    Id(EntityId),
+    Reference(OperationReference),
+}
+
+// TODO: We have to do this because utoipa doesn't understand serde untagged
+//  https://github.com/juhaku/utoipa/issues/320
+impl ToSchema for BatchEntityId {
+    fn schema() -> openapi::Schema {
+        openapi::OneOfBuilder::new()
+            .item(EntityId::schema())
+            .item(OperationReference::schema())
+            .into()
+    }
+}
+
+impl BatchEntityId {
+    /// Returns the ID of the referred entity.
+    ///
+    /// `results` are the results of the operations applied so far, so only earlier operations can
+    /// be referenced.
+    fn resolve(self, results: &[BatchOperationResult]) -> Result<EntityId, StatusCode> {
+        match self {
+            Self::Id(entity_id) => Ok(entity_id),
+            Self::Reference(OperationReference { result_of }) => match results.get(result_of) {
+                Some(
+                    BatchOperationResult::CreateEntity(metadata)
+                    | BatchOperationResult::UpdateEntity(metadata),
+                ) => Ok(metadata.identifier().entity_id()),
+                _ => {
+                    tracing::error!(
+                        result_of,
+                        "Referenced operation is not an earlier operation resulting in an entity"
+                    );
+                    Err(StatusCode::UNPROCESSABLE_ENTITY)
+                }
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct CreateEntityOperation {
+    pub entity: Entity,
+    #[schema(value_type = String)]
+    pub entity_type_id: VersionedUri,
+    pub owned_by_id: AccountId,
+    pub entity_id: Option<EntityId>,
+    pub actor_id: AccountId,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct UpdateEntityOperation {
+    pub entity: Entity,
+    pub entity_id: BatchEntityId,
+    #[schema(value_type = String)]
+    pub entity_type_id: VersionedUri,
+    pub actor_id: AccountId,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct CreateLinkOperation {
+    pub source_entity_id: BatchEntityId,
+    pub target_entity_id: BatchEntityId,
+    #[schema(value_type = String)]
+    pub link_type_id: VersionedUri,
+    pub owned_by_id: AccountId,
+    pub actor_id: AccountId,
+    pub index: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct RemoveLinkOperation {
+    pub source_entity_id: BatchEntityId,
+    pub target_entity_id: BatchEntityId,
+    #[schema(value_type = String)]
+    pub link_type_id: VersionedUri,
+    pub actor_id: AccountId,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "kind", content = "inner")]
+pub enum BatchOperation {
+    CreateDataType(CreateDataTypeRequest),
+    UpdateDataType(UpdateDataTypeRequest),
+    CreatePropertyType(CreatePropertyTypeRequest),
+    UpdatePropertyType(UpdatePropertyTypeRequest),
+    CreateLinkType(CreateLinkTypeRequest),
+    UpdateLinkType(UpdateLinkTypeRequest),
+    CreateEntityType(CreateEntityTypeRequest),
+    UpdateEntityType(UpdateEntityTypeRequest),
+    CreateEntity(CreateEntityOperation),
+    UpdateEntity(UpdateEntityOperation),
+    CreateLink(CreateLinkOperation),
+    RemoveLink(RemoveLinkOperation),
+}
+
+// WARNING: This MUST be kept up to date with the enum names and serde attribute, as utoipa does
+// not currently support adjacently tagged enums so we must roll our own:
+// https://github.com/juhaku/utoipa/issues/219
+impl ToSchema for BatchOperation {
+    fn schema() -> openapi::Schema {
+        adjacently_tagged_schema([
+            ("createDataType", CreateDataTypeRequest::schema()),
+            ("updateDataType", UpdateDataTypeRequest::schema()),
+            ("createPropertyType", CreatePropertyTypeRequest::schema()),
+            ("updatePropertyType", UpdatePropertyTypeRequest::schema()),
+            ("createLinkType", CreateLinkTypeRequest::schema()),
+            ("updateLinkType", UpdateLinkTypeRequest::schema()),
+            ("createEntityType", CreateEntityTypeRequest::schema()),
+            ("updateEntityType", UpdateEntityTypeRequest::schema()),
+            ("createEntity", CreateEntityOperation::schema()),
+            ("updateEntity", UpdateEntityOperation::schema()),
+            ("createLink", CreateLinkOperation::schema()),
+            ("removeLink", RemoveLinkOperation::schema()),
+        ])
+    }
+}
+
+/// The result of a single operation of a batch.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "kind", content = "inner")]
+pub enum BatchOperationResult {
+    CreateDataType(PersistedOntologyMetadata),
+    UpdateDataType(UpdateOntologyTypeResponse),
+    CreatePropertyType(PersistedOntologyMetadata),
+    UpdatePropertyType(UpdateOntologyTypeResponse),
+    CreateLinkType(PersistedOntologyMetadata),
+    UpdateLinkType(UpdateOntologyTypeResponse),
+    CreateEntityType(PersistedOntologyMetadata),
+    UpdateEntityType(UpdateOntologyTypeResponse),
+    CreateEntity(PersistedEntityMetadata),
+    UpdateEntity(PersistedEntityMetadata),
+    CreateLink(Link),
+    RemoveLink(Link),
+}
+
+// WARNING: This MUST be kept up to date with the enum names and serde attribute, as utoipa does
+// not currently support adjacently tagged enums so we must roll our own:
+// https://github.com/juhaku/utoipa/issues/219
+impl ToSchema for BatchOperationResult {
+    fn schema() -> openapi::Schema {
+        adjacently_tagged_schema([
+            ("createDataType", PersistedOntologyMetadata::schema()),
+            ("updateDataType", UpdateOntologyTypeResponse::schema()),
+            ("createPropertyType", PersistedOntologyMetadata::schema()),
+            ("updatePropertyType", UpdateOntologyTypeResponse::schema()),
+            ("createLinkType", PersistedOntologyMetadata::schema()),
+            ("updateLinkType", UpdateOntologyTypeResponse::schema()),
+            ("createEntityType", PersistedOntologyMetadata::schema()),
+            ("updateEntityType", UpdateOntologyTypeResponse::schema()),
+            ("createEntity", PersistedEntityMetadata::schema()),
+            ("updateEntity", PersistedEntityMetadata::schema()),
+            ("createLink", Link::schema()),
+            ("removeLink", Link::schema()),
+        ])
+    }
+}
+
+fn adjacently_tagged_schema<const N: usize>(
+    variants: [(&'static str, openapi::Schema); N],
+) -> openapi::Schema {
+    let mut builder =
+        openapi::OneOfBuilder::new().discriminator(Some(openapi::Discriminator::new("kind")));
+
+    for (kind, schema) in variants {
+        builder = builder.item(
+            openapi::ObjectBuilder::new()
+                .property(
+                    "kind",
+                    openapi::Schema::from(openapi::ObjectBuilder::new().enum_values(Some([kind]))),
+                )
+                .required("kind")
+                .property("inner", schema)
+                .required("inner"),
+        );
+    }
+
+    builder.into()
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct BatchRequest {
+    /// The operations to apply, in order.
+    pub operations: Vec<BatchOperation>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/batch",
+    request_body = BatchRequest,
+    tag = "Batch",
+    responses(
+        (status = 200, content_type = "application/json", description = "The results of the operations in the order of the request. A `Warning` header is set for every entity created with a deprecated entity type", body = [BatchOperationResult]),
+        (status = 422, content_type = "text/plain", description = "Provided request body is invalid or an operation refers to an invalid operation"),
+
+        (status = 404, description = "An ontology type, entity or link referred to by an operation was not found"),
+        (status = 403, description = "The actor is not allowed to apply one of the operations"),
+        (status = 409, description = "An ontology type to create already exists or an update contains breaking changes, which were not allowed"),
+        (status = 500, description = "Store error occurred"),
+    ),
+)]
+async fn apply_batch<P: StorePool + Send>(
+    body: Json<BatchRequest>,
+    pool: Extension<Arc<P>>,
+    domain_validator: Extension<DomainValidator>,
+    external_type_resolver: Extension<ExternalTypeResolver>,
+    actor: Extension<Actor>,
+) -> Result<(HeaderMap, Json<Vec<BatchOperationResult>>), StatusCode> {
+    let Json(BatchRequest { operations }) = body;
+
+    let mut store = pool.acquire().await.map_err(|report| {
+        tracing::error!(error=?report, "Could not acquire store");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    // Dropping the transaction on an error rolls back all operations applied so far
+    let mut transaction = store.transaction().await.map_err(|report| {
+        tracing::error!(error=?report, "Could not start transaction");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let context = OperationContext {
+        actor: *actor,
+        domain_validator: &domain_validator,
+        external_type_resolver: &external_type_resolver,
+    };
+    let mut headers = HeaderMap::new();
+    let mut results = Vec::with_capacity(operations.len());
+    for (index, operation) in operations.into_iter().enumerate() {
+        let result = apply_operation(
+            transaction.store(),
+            operation,
+            &results,
+            &context,
+            &mut headers,
+        )
+        .await
+        .map_err(|status_code| {
+            tracing::error!(index, %status_code, "Could not apply operation of batch");
+            status_code
+        })?;
+        results.push(result);
+    }
+
+    transaction.commit().await.map_err(|report| {
+        tracing::error!(error=?report, "Could not commit transaction");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((headers, Json(results)))
+}
+
+/// The state shared by all operations of a batch.
+struct OperationContext<'a> {
+    actor: Actor,
+    domain_validator: &'a DomainValidator,
+    external_type_resolver: &'a ExternalTypeResolver,
+}
+
+/// Applies `operation` to the `store`.
+///
+/// Warnings about the operation are appended to `headers`.
+async fn apply_operation<S: Store + Send>(
+    store: &mut S,
+    operation: BatchOperation,
+    results: &[BatchOperationResult],
+    context: &OperationContext<'_>,
+    headers: &mut HeaderMap,
+) -> Result<BatchOperationResult, StatusCode> {
+    let OperationContext {
+        actor,
+        domain_validator,
+        external_type_resolver,
+    } = *context;
+
+    match operation {
+        BatchOperation::CreateDataType(request) => {
+            create_data_type_in_store(store, request, actor, domain_validator)
+                .await
+                .map(BatchOperationResult::CreateDataType)
+        }
+        BatchOperation::UpdateDataType(request) => {
+            update_data_type_in_store(store, request, actor, external_type_resolver)
+                .await
+                .map(BatchOperationResult::UpdateDataType)
+        }
+        BatchOperation::CreatePropertyType(request) => create_property_type_in_store(
+            store,
+            request,
+            actor,
+            domain_validator,
+            external_type_resolver,
+        )
+        .await
+        .map(BatchOperationResult::CreatePropertyType),
+        BatchOperation::UpdatePropertyType(request) => {
+            update_property_type_in_store(store, request, actor, external_type_resolver)
+                .await
+                .map(BatchOperationResult::UpdatePropertyType)
+        }
+        BatchOperation::CreateLinkType(request) => {
+            create_link_type_in_store(store, request, actor, domain_validator)
+                .await
+                .map(BatchOperationResult::CreateLinkType)
+        }
+        BatchOperation::UpdateLinkType(request) => {
+            update_link_type_in_store(store, request, actor, external_type_resolver)
+                .await
+                .map(BatchOperationResult::UpdateLinkType)
+        }
+        BatchOperation::CreateEntityType(request) => create_entity_type_in_store(
+            store,
+            request,
+            actor,
+            domain_validator,
+            external_type_resolver,
+        )
+        .await
+        .map(BatchOperationResult::CreateEntityType),
+        BatchOperation::UpdateEntityType(request) => {
+            update_entity_type_in_store(store, request, actor, external_type_resolver)
+                .await
+                .map(BatchOperationResult::UpdateEntityType)
+        }
+        BatchOperation::CreateEntity(CreateEntityOperation {
+            entity,
+            entity_type_id,
+            owned_by_id,
+            entity_id,
+            actor_id,
+        }) => {
+            let owned_by_id = actor.act_as(owned_by_id)?;
+            let actor_id = actor.act_as(actor_id)?;
+
+            if let Some(warning) = entity_type_deprecation_warning(&*store, &entity_type_id).await {
+                headers.append(header::WARNING, warning);
+            }
+
+            store
+                .create_entity(entity, entity_type_id, owned_by_id, entity_id, actor_id)
+                .await
+                .map_err(|report| {
+                    tracing::error!(error=?report, "Could not create entity");
+
+                    // Insertion/update errors are considered internal server errors.
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })
+                .map(BatchOperationResult::CreateEntity)
+        }
+        BatchOperation::UpdateEntity(UpdateEntityOperation {
+            entity,
+            entity_id,
+            entity_type_id,
+            actor_id,
+        }) => {
+            let entity_id = entity_id.resolve(results)?;
+            let actor_id = actor.act_as(actor_id)?;
+            actor.ensure_may_modify(entity_owner_in_store(&*store, entity_id).await?)?;
+
+            store
+                .update_entity(entity_id, entity, entity_type_id, actor_id)
+                .await
+                .map_err(|report| {
+                    tracing::error!(error=?report, "Could not update entity");
+
+                    if report.contains::<QueryError>() || report.contains::<EntityDoesNotExist>() {
+                        return StatusCode::NOT_FOUND;
+                    }
+
+                    // Insertion/update errors are considered internal server errors.
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })
+                .map(BatchOperationResult::UpdateEntity)
+        }
+        BatchOperation::CreateLink(CreateLinkOperation {
+            source_entity_id,
+            target_entity_id,
+            link_type_id,
+            owned_by_id,
+            actor_id,
+            index,
+        }) => {
+            let source_entity_id = source_entity_id.resolve(results)?;
+            let target_entity_id = target_entity_id.resolve(results)?;
+            let owned_by_id = actor.act_as(owned_by_id)?;
+            let actor_id = actor.act_as(actor_id)?;
+            // Links are part of their source entity, so only its owner may add links
+            actor.ensure_may_modify(entity_owner_in_store(&*store, source_entity_id).await?)?;
+
+            let link = Link::new(source_entity_id, target_entity_id, link_type_id, index);
+
+            store
+                .create_link(&link, owned_by_id, actor_id)
+                .await
+                .map_err(|report| {
+                    tracing::error!(error=?report, "Could not create link");
+
+                    // when parts of the requested link cannot be found
+                    if report.contains::<QueryError>() {
+                        return StatusCode::NOT_FOUND;
+                    }
+
+                    // Insertion/update errors are considered internal server errors.
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+
+            Ok(BatchOperationResult::CreateLink(link))
+        }
+        BatchOperation::RemoveLink(RemoveLinkOperation {
+            source_entity_id,
+            target_entity_id,
+            link_type_id,
+            actor_id,
+        }) => {
+            let source_entity_id = source_entity_id.resolve(results)?;
+            let target_entity_id = target_entity_id.resolve(results)?;
+            let actor_id = actor.act_as(actor_id)?;
+            actor.ensure_may_modify(
+                link_owner_in_store(&*store, source_entity_id, target_entity_id, &link_type_id)
+                    .await?,
+            )?;
+
+            let link = Link::new(source_entity_id, target_entity_id, link_type_id, None);
+
+            store.remove_link(&link, actor_id).await.map_err(|report| {
+                tracing::error!(error=?report, "Could not remove link");
+
+                if report.contains::<QueryError>() {
+                    return StatusCode::NOT_FOUND;
+                }
+
+                // Insertion/update errors are considered internal server errors.
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+
+            Ok(BatchOperationResult::RemoveLink(link))
+        }
+    }
+}
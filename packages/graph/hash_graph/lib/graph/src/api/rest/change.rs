@@ -0,0 +1,109 @@
+//! Web routes for subscribing to changes of the graph.
+
+use std::{collections::HashSet, convert::Infallible};
+
+use axum::{
+    extract::Query,
+    response::sse::{Event, KeepAlive, Sse},
+    routing::get,
+    Extension, Router,
+};
+use futures::{stream, Stream, StreamExt};
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+use utoipa::OpenApi;
+
+use super::{api_resource::RoutedResource, auth::Actor};
+use crate::store::{
+    change::{ChangeEvent, ChangeFeed},
+    StorePool,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        subscribe_to_changes,
+    ),
+    tags(
+        (name = "Change", description = "Change notification API")
+    )
+)]
+pub struct ChangeResource;
+
+impl RoutedResource for ChangeResource {
+    /// Create routes for subscribing to changes.
+    fn routes<P: StorePool + Send + 'static>() -> Router {
+        // TODO: The URL format here is preliminary and will have to change.
+        Router::new().nest(
+            "/changes",
+            Router::new().route("/", get(subscribe_to_changes)),
+        )
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChangeFilter {
+    /// Comma-separated list of resource types to subscribe to.
+    types: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/changes",
+    tag = "Change",
+    responses(
+        (status = 200, content_type = "text/event-stream", description = "A stream of server-sent events, one for each change of a resource owned by the authenticated account. The event name is the type of the changed resource and the data is the change event encoded as JSON."),
+    ),
+    params(
+        ("types" = Option<String>, Query, description = "Comma-separated list of resource types to subscribe to, e.g. `entity,link`. By default, all changes are sent."),
+    )
+)]
+#[allow(
+    clippy::unused_async,
+    reason = "This route does not need async capabilities, but axum requires it in trait bounds."
+)]
+async fn subscribe_to_changes(
+    Query(filter): Query<ChangeFilter>,
+    change_feed: Extension<ChangeFeed>,
+    actor: Extension<Actor>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let resource_types: Option<HashSet<String>> = filter.types.map(|types| {
+        types
+            .split(',')
+            .map(|resource_type| resource_type.trim().to_owned())
+            .collect()
+    });
+
+    let events = stream::unfold(change_feed.subscribe(), |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => return Some((event, receiver)),
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Change subscriber fell behind, skipped events");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+    .filter(move |event: &ChangeEvent| {
+        // Only changes of resources the caller owns are sent, unless the caller is privileged.
+        let is_subscribed = actor.may_observe(event.owned_by_id())
+            && resource_types.as_ref().map_or(true, |resource_types| {
+                resource_types.contains(event.resource().resource_type())
+            });
+        async move { is_subscribed }
+    })
+    .filter_map(|event| async move {
+        Event::default()
+            .event(event.resource().resource_type())
+            .json_data(&event)
+            .map_err(|error| {
+                tracing::error!(%error, ?event, "Could not serialize change event");
+            })
+            .ok()
+            .map(Ok)
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
@@ -3,7 +3,7 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::Path,
+    extract::{Path, Query},
     http::StatusCode,
     routing::{get, post},
     Extension, Json, Router,
@@ -16,15 +16,21 @@ use utoipa::{OpenApi, ToSchema};
 
 use super::api_resource::RoutedResource;
 use crate::{
-    api::rest::{read_from_store, report_to_status_code},
+    api::rest::{
+        auth::Actor, check_update_compatibility, ensure_complete, ensure_not_external,
+        read_from_read_only_store, read_in_store, report_to_status_code, with_query_timeout,
+        ResolveOptions, UpdateOntologyTypeResponse,
+    },
     ontology::{
         domain_validator::{DomainValidator, ValidateOntologyType},
-        patch_id_and_parse, AccountId, PersistedDataType, PersistedOntologyIdentifier,
-        PersistedOntologyMetadata,
+        external_types::ExternalTypeResolver,
+        patch_id_and_parse, AccountId, BreakingChange, BreakingChangeKind, CompatibilityReport,
+        Deprecation, PersistedDataType, PersistedOntologyIdentifier, PersistedOntologyMetadata,
     },
     shared::identifier::GraphElementIdentifier,
     store::{
-        query::Expression, BaseUriAlreadyExists, BaseUriDoesNotExist, DataTypeStore, StorePool,
+        query::Expression, BaseUriAlreadyExists, BaseUriDoesNotExist, DataTypeStore, Store,
+        StorePool,
     },
     subgraph::{
         EdgeKind, Edges, GraphResolveDepths, OutwardEdge, StructuralQuery, Subgraph, Vertex,
@@ -44,9 +50,14 @@ use crate::{
         schemas(
             CreateDataTypeRequest,
             UpdateDataTypeRequest,
+            UpdateOntologyTypeResponse,
+            CompatibilityReport,
+            BreakingChange,
+            BreakingChangeKind,
             AccountId,
             PersistedOntologyIdentifier,
             PersistedOntologyMetadata,
+            Deprecation,
             PersistedDataType,
             StructuralQuery,
             GraphElementIdentifier,
@@ -85,11 +96,11 @@ impl RoutedResource for DataTypeResource {
 
 #[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
-struct CreateDataTypeRequest {
+pub struct CreateDataTypeRequest {
     #[schema(value_type = VAR_DATA_TYPE)]
-    schema: serde_json::Value,
-    owned_by_id: AccountId,
-    actor_id: AccountId,
+    pub schema: serde_json::Value,
+    pub owned_by_id: AccountId,
+    pub actor_id: AccountId,
 }
 
 #[utoipa::path(
@@ -102,6 +113,7 @@ struct CreateDataTypeRequest {
         (status = 422, content_type = "text/plain", description = "Provided request body is invalid"),
 
         (status = 409, description = "Unable to create data type in the store as the base data type URI already exists"),
+        (status = 403, description = "The actor is not allowed to act on behalf of the given accounts"),
         (status = 500, description = "Store error occurred"),
     ),
     request_body = CreateDataTypeRequest,
@@ -110,12 +122,33 @@ async fn create_data_type<P: StorePool + Send>(
     body: Json<CreateDataTypeRequest>,
     pool: Extension<Arc<P>>,
     domain_validator: Extension<DomainValidator>,
+    actor: Extension<Actor>,
 ) -> Result<Json<PersistedOntologyMetadata>, StatusCode> {
-    let Json(CreateDataTypeRequest {
+    let mut store = pool.acquire().await.map_err(|report| {
+        tracing::error!(error=?report, "Could not acquire store");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    create_data_type_in_store(&mut store, body.0, *actor, &domain_validator)
+        .await
+        .map(Json)
+}
+
+/// Creates the data type described by `request` in the `store` on behalf of `actor`.
+pub(super) async fn create_data_type_in_store<S: Store + Send>(
+    store: &mut S,
+    request: CreateDataTypeRequest,
+    actor: Actor,
+    domain_validator: &DomainValidator,
+) -> Result<PersistedOntologyMetadata, StatusCode> {
+    let CreateDataTypeRequest {
         schema,
         owned_by_id,
         actor_id,
-    }) = body;
+    } = request;
+
+    let owned_by_id = actor.act_as(owned_by_id)?;
+    let actor_id = actor.act_as(actor_id)?;
 
     let data_type: DataType = schema.try_into().into_report().map_err(|report| {
         tracing::error!(error=?report, "Couldn't convert schema to Data Type");
@@ -129,11 +162,6 @@ async fn create_data_type<P: StorePool + Send>(
         StatusCode::UNPROCESSABLE_ENTITY
     })?;
 
-    let mut store = pool.acquire().await.map_err(|report| {
-        tracing::error!(error=?report, "Could not acquire store");
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
-
     store
         .create_data_type(data_type, owned_by_id, actor_id)
         .await
@@ -148,7 +176,6 @@ async fn create_data_type<P: StorePool + Send>(
             // Insertion/update errors are considered internal server errors.
             StatusCode::INTERNAL_SERVER_ERROR
         })
-        .map(Json)
 }
 
 #[utoipa::path(
@@ -159,27 +186,38 @@ async fn create_data_type<P: StorePool + Send>(
     responses(
         (status = 200, content_type = "application/json", body = Subgraph, description = "Gets a subgraph rooted at all data types that satisfy the given query, each resolved to the requested depth."),
 
-        (status = 422, content_type = "text/plain", description = "Provided query is invalid"),
+        (status = 422, content_type = "text/plain", description = "Provided query is invalid or exceeds the limits of the Graph"),
         (status = 500, description = "Store error occurred"),
+        (status = 503, description = "The query did not complete in time"),
+    ),
+    params(
+        ("allowTruncation" = Option<bool>, Query, description = "Return a truncated subgraph instead of failing, if the query exceeds the limits of the Graph"),
     )
 )]
 async fn get_data_types_by_query<P: StorePool + Send>(
     pool: Extension<Arc<P>>,
+    Query(options): Query<ResolveOptions>,
     Json(query): Json<StructuralQuery>,
 ) -> Result<Json<Subgraph>, StatusCode> {
-    pool.acquire()
-        .map_err(|error| {
-            tracing::error!(?error, "Could not acquire access to the store");
-            StatusCode::INTERNAL_SERVER_ERROR
-        })
-        .and_then(|store| async move {
-            store.get_data_type(&query).await.map_err(|report| {
-                tracing::error!(error=?report, ?query, "Could not read data types from the store");
-                report_to_status_code(&report)
+    with_query_timeout(
+        pool.as_ref(),
+        pool.acquire_read_only()
+            .map_err(|error| {
+                tracing::error!(?error, "Could not acquire access to the store");
+                StatusCode::INTERNAL_SERVER_ERROR
             })
-        })
-        .await
-        .map(Json)
+            .and_then(|store| async move {
+                store.get_data_type(&query).await.map_err(|report| {
+                    tracing::error!(error=?report, ?query, "Could not read data types from the store");
+                    report_to_status_code(&report)
+                })
+            }),
+    )
+    .await
+    .and_then(|subgraph| {
+        ensure_complete(subgraph.truncated, &options)?;
+        Ok(Json(subgraph))
+    })
 }
 
 #[utoipa::path(
@@ -190,12 +228,13 @@ async fn get_data_types_by_query<P: StorePool + Send>(
         (status = 200, content_type = "application/json", description = "List of all data types at their latest versions", body = [PersistedDataType]),
 
         (status = 500, description = "Store error occurred"),
+        (status = 503, description = "The query did not complete in time"),
     )
 )]
 async fn get_latest_data_types<P: StorePool + Send>(
     pool: Extension<Arc<P>>,
 ) -> Result<Json<Vec<PersistedDataType>>, StatusCode> {
-    read_from_store(pool.as_ref(), &Expression::for_latest_version())
+    read_from_read_only_store(pool.as_ref(), &Expression::for_latest_version())
         .await
         .map(Json)
 }
@@ -210,6 +249,7 @@ async fn get_latest_data_types<P: StorePool + Send>(
 
         (status = 404, description = "Data type was not found"),
         (status = 500, description = "Store error occurred"),
+        (status = 503, description = "The query did not complete in time"),
     ),
     params(
         ("uri" = String, Path, description = "The URI of the data type"),
@@ -219,7 +259,7 @@ async fn get_data_type<P: StorePool + Send>(
     uri: Path<VersionedUri>,
     pool: Extension<Arc<P>>,
 ) -> Result<Json<PersistedDataType>, StatusCode> {
-    read_from_store(pool.as_ref(), &Expression::for_versioned_uri(&uri.0))
+    read_from_read_only_store(pool.as_ref(), &Expression::for_versioned_uri(&uri.0))
         .await
         .and_then(|mut data_types| data_types.pop().ok_or(StatusCode::NOT_FOUND))
         .map(Json)
@@ -227,12 +267,15 @@ async fn get_data_type<P: StorePool + Send>(
 
 #[derive(ToSchema, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct UpdateDataTypeRequest {
+pub struct UpdateDataTypeRequest {
     #[schema(value_type = VAR_UPDATE_DATA_TYPE)]
-    schema: serde_json::Value,
+    pub schema: serde_json::Value,
     #[schema(value_type = String)]
-    type_to_update: VersionedUri,
-    actor_id: AccountId,
+    pub type_to_update: VersionedUri,
+    pub actor_id: AccountId,
+    /// Allows updates, which may invalidate existing entities.
+    #[serde(default)]
+    pub allow_breaking_changes: bool,
 }
 
 #[utoipa::path(
@@ -240,10 +283,12 @@ struct UpdateDataTypeRequest {
     path = "/data-types",
     tag = "DataType",
     responses(
-        (status = 200, content_type = "application/json", description = "The metadata of the updated data type", body = PersistedOntologyMetadata),
+        (status = 200, content_type = "application/json", description = "The metadata of the updated data type and the compatibility of the update", body = UpdateOntologyTypeResponse),
         (status = 422, content_type = "text/plain", description = "Provided request body is invalid"),
 
         (status = 404, description = "Base data type ID was not found"),
+        (status = 403, description = "The type is an external type or the actor is not allowed to update it"),
+        (status = 409, description = "The update contains breaking changes, which were not allowed"),
         (status = 500, description = "Store error occurred"),
     ),
     request_body = UpdateDataTypeRequest,
@@ -251,12 +296,35 @@ struct UpdateDataTypeRequest {
 async fn update_data_type<P: StorePool + Send>(
     body: Json<UpdateDataTypeRequest>,
     pool: Extension<Arc<P>>,
-) -> Result<Json<PersistedOntologyMetadata>, StatusCode> {
-    let Json(UpdateDataTypeRequest {
+    external_type_resolver: Extension<ExternalTypeResolver>,
+    actor: Extension<Actor>,
+) -> Result<Json<UpdateOntologyTypeResponse>, StatusCode> {
+    let mut store = pool.acquire().await.map_err(|report| {
+        tracing::error!(error=?report, "Could not acquire store");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    update_data_type_in_store(&mut store, body.0, *actor, &external_type_resolver)
+        .await
+        .map(Json)
+}
+
+/// Updates the data type as described by `request` in the `store` on behalf of `actor`.
+pub(super) async fn update_data_type_in_store<S: Store + Send>(
+    store: &mut S,
+    request: UpdateDataTypeRequest,
+    actor: Actor,
+    external_type_resolver: &ExternalTypeResolver,
+) -> Result<UpdateOntologyTypeResponse, StatusCode> {
+    let UpdateDataTypeRequest {
         schema,
         type_to_update,
         actor_id,
-    }) = body;
+        allow_breaking_changes,
+    } = request;
+
+    ensure_not_external(external_type_resolver, &type_to_update)?;
+    let actor_id = actor.act_as(actor_id)?;
 
     let new_type_id = VersionedUri::new(
         type_to_update.base_uri().clone(),
@@ -270,10 +338,24 @@ async fn update_data_type<P: StorePool + Send>(
         //  https://app.asana.com/0/1201095311341924/1202574350052904/f
     })?;
 
-    let mut store = pool.acquire().await.map_err(|report| {
-        tracing::error!(error=?report, "Could not acquire store");
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    let previous_data_type = read_in_store::<_, PersistedDataType>(
+        &*store,
+        &Expression::for_versioned_uri(&type_to_update),
+    )
+    .await?
+    .pop()
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    actor.ensure_may_modify(previous_data_type.metadata().identifier().owned_by_id())?;
+
+    let compatibility = check_update_compatibility(
+        &*store,
+        type_to_update.base_uri(),
+        previous_data_type.inner(),
+        &data_type,
+        allow_breaking_changes,
+    )
+    .await?;
 
     store
         .update_data_type(data_type, actor_id)
@@ -288,5 +370,5 @@ async fn update_data_type<P: StorePool + Send>(
             // Insertion/update errors are considered internal server errors.
             StatusCode::INTERNAL_SERVER_ERROR
         })
-        .map(Json)
+        .map(|metadata| UpdateOntologyTypeResponse::new(metadata, compatibility))
 }
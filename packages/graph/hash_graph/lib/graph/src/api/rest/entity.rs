@@ -3,8 +3,8 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::Path,
-    http::StatusCode,
+    extract::{Path, Query},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
     routing::{get, post},
     Extension, Json, Router,
 };
@@ -14,16 +14,22 @@ use type_system::uri::VersionedUri;
 use utoipa::{OpenApi, ToSchema};
 
 use crate::{
-    api::rest::{api_resource::RoutedResource, read_from_store, report_to_status_code},
+    api::rest::{
+        api_resource::RoutedResource,
+        auth::{entity_owner, Actor},
+        ensure_complete, read_from_read_only_store, report_to_status_code, with_query_timeout,
+        ResolveOptions,
+    },
     knowledge::{
         Entity, EntityId, PersistedEntity, PersistedEntityIdentifier, PersistedEntityMetadata,
     },
-    ontology::AccountId,
+    ontology::{AccountId, Deprecation},
     shared::identifier::GraphElementIdentifier,
     store::{
         error::{EntityDoesNotExist, QueryError},
         query::Expression,
-        EntityStore, StorePool,
+        search::{EntitySearch, EntitySearchHit},
+        EntityStore, Store, StorePool,
     },
     subgraph::{
         EdgeKind, Edges, GraphResolveDepths, OutwardEdge, StructuralQuery, Subgraph, Vertex,
@@ -35,6 +41,7 @@ use crate::{
     paths(
         create_entity,
         get_entities_by_query,
+        search_entities,
         get_entity,
         get_latest_entities,
         update_entity
@@ -49,6 +56,8 @@ use crate::{
             PersistedEntity,
             Entity,
             StructuralQuery,
+            EntitySearch,
+            EntitySearchHit,
             GraphElementIdentifier,
             Vertex,
             EdgeKind,
@@ -78,6 +87,7 @@ impl RoutedResource for EntityResource {
                         .put(update_entity::<P>),
                 )
                 .route("/query", post(get_entities_by_query::<P>))
+                .route("/search", post(search_entities::<P>))
                 .route("/:entity_id", get(get_entity::<P>)),
         )
     }
@@ -85,13 +95,13 @@ impl RoutedResource for EntityResource {
 
 #[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
-struct CreateEntityRequest {
-    entity: Entity,
+pub struct CreateEntityRequest {
+    pub entity: Entity,
     #[schema(value_type = String)]
-    entity_type_id: VersionedUri,
-    owned_by_id: AccountId,
-    entity_id: Option<EntityId>,
-    actor_id: AccountId,
+    pub entity_type_id: VersionedUri,
+    pub owned_by_id: AccountId,
+    pub entity_id: Option<EntityId>,
+    pub actor_id: AccountId,
 }
 
 #[utoipa::path(
@@ -100,10 +110,11 @@ struct CreateEntityRequest {
     request_body = CreateEntityRequest,
     tag = "Entity",
     responses(
-        (status = 201, content_type = "application/json", description = "The metadata of the created entity", body = PersistedEntityMetadata),
+        (status = 201, content_type = "application/json", description = "The metadata of the created entity, a `Warning` header is set if the entity type is deprecated", body = PersistedEntityMetadata),
         (status = 422, content_type = "text/plain", description = "Provided request body is invalid"),
 
         (status = 404, description = "Entity Type URI was not found"),
+        (status = 403, description = "The actor is not allowed to act on behalf of the given accounts"),
         (status = 500, description = "Store error occurred"),
     ),
     request_body = CreateEntityRequest,
@@ -111,7 +122,8 @@ struct CreateEntityRequest {
 async fn create_entity<P: StorePool + Send>(
     body: Json<CreateEntityRequest>,
     pool: Extension<Arc<P>>,
-) -> Result<Json<PersistedEntityMetadata>, StatusCode> {
+    actor: Extension<Actor>,
+) -> Result<(HeaderMap, Json<PersistedEntityMetadata>), StatusCode> {
     let Json(CreateEntityRequest {
         entity,
         entity_type_id,
@@ -120,11 +132,19 @@ async fn create_entity<P: StorePool + Send>(
         actor_id,
     }) = body;
 
+    let owned_by_id = actor.act_as(owned_by_id)?;
+    let actor_id = actor.act_as(actor_id)?;
+
     let mut store = pool.acquire().await.map_err(|report| {
         tracing::error!(error=?report, "Could not acquire store");
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
+    let headers = entity_type_deprecation_warning(&store, &entity_type_id)
+        .await
+        .map(|warning| HeaderMap::from_iter([(header::WARNING, warning)]))
+        .unwrap_or_default();
+
     store
         .create_entity(entity, entity_type_id, owned_by_id, entity_id, actor_id)
         .await
@@ -134,7 +154,36 @@ async fn create_entity<P: StorePool + Send>(
             // Insertion/update errors are considered internal server errors.
             StatusCode::INTERNAL_SERVER_ERROR
         })
-        .map(Json)
+        .map(|metadata| (headers, Json(metadata)))
+}
+
+/// Returns the value of the `Warning` header sent when an entity of the entity type identified by
+/// `entity_type_id` is created, if the entity type is deprecated.
+pub(super) async fn entity_type_deprecation_warning<S: Store>(
+    store: &S,
+    entity_type_id: &VersionedUri,
+) -> Option<HeaderValue> {
+    // A missing entity type is reported when creating the entity.
+    store
+        .get_ontology_type_metadata(entity_type_id)
+        .await
+        .ok()
+        .and_then(|metadata| deprecation_warning(entity_type_id, metadata.deprecation()?))
+}
+
+/// Returns the value of the `Warning` header sent when an entity of a deprecated entity type is
+/// created.
+fn deprecation_warning(
+    entity_type_id: &VersionedUri,
+    deprecation: &Deprecation,
+) -> Option<HeaderValue> {
+    let message = match deprecation.replaced_by() {
+        Some(replaced_by) => {
+            format!("entity type {entity_type_id} is deprecated, use {replaced_by} instead")
+        }
+        None => format!("entity type {entity_type_id} is deprecated"),
+    };
+    HeaderValue::from_str(&format!("299 - \"{message}\"")).ok()
 }
 
 #[utoipa::path(
@@ -144,22 +193,63 @@ async fn create_entity<P: StorePool + Send>(
     tag = "Entity",
     responses(
         (status = 200, content_type = "application/json", body = Subgraph, description = "A subgraph rooted at entities that satisfy the given query, each resolved to the requested depth."),
-        (status = 422, content_type = "text/plain", description = "Provided query is invalid"),
+        (status = 422, content_type = "text/plain", description = "Provided query is invalid or exceeds the limits of the Graph"),
         (status = 500, description = "Store error occurred"),
+        (status = 503, description = "The query did not complete in time"),
+    ),
+    params(
+        ("allowTruncation" = Option<bool>, Query, description = "Return a truncated subgraph instead of failing, if the query exceeds the limits of the Graph"),
     )
 )]
 async fn get_entities_by_query<P: StorePool + Send>(
     pool: Extension<Arc<P>>,
+    Query(options): Query<ResolveOptions>,
     Json(query): Json<StructuralQuery>,
 ) -> Result<Json<Subgraph>, StatusCode> {
-    pool.acquire()
+    with_query_timeout(
+        pool.as_ref(),
+        pool.acquire_read_only()
+            .map_err(|error| {
+                tracing::error!(?error, "Could not acquire access to the store");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })
+            .and_then(|store| async move {
+                store.get_entity(&query).await.map_err(|report| {
+                    tracing::error!(error=?report, ?query, "Could not read entities from the store");
+                    report_to_status_code(&report)
+                })
+            }),
+    )
+    .await
+    .and_then(|subgraph| {
+        ensure_complete(subgraph.truncated, &options)?;
+        Ok(Json(subgraph))
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/entities/search",
+    request_body = EntitySearch,
+    tag = "Entity",
+    responses(
+        (status = 200, content_type = "application/json", body = [EntitySearchHit], description = "The latest versions of the entities matching the search, the most relevant first."),
+        (status = 422, content_type = "text/plain", description = "Provided search is invalid"),
+        (status = 500, description = "Store error occurred"),
+    )
+)]
+async fn search_entities<P: StorePool + Send>(
+    pool: Extension<Arc<P>>,
+    Json(search): Json<EntitySearch>,
+) -> Result<Json<Vec<EntitySearchHit>>, StatusCode> {
+    pool.acquire_read_only()
         .map_err(|error| {
             tracing::error!(?error, "Could not acquire access to the store");
             StatusCode::INTERNAL_SERVER_ERROR
         })
         .and_then(|store| async move {
-            store.get_entity(&query).await.map_err(|report| {
-                tracing::error!(error=?report, ?query, "Could not read entities from the store");
+            store.search_entities(&search).await.map_err(|report| {
+                tracing::error!(error=?report, ?search, "Could not search entities in the store");
                 report_to_status_code(&report)
             })
         })
@@ -175,12 +265,13 @@ async fn get_entities_by_query<P: StorePool + Send>(
         (status = 200, content_type = "application/json", description = "List of all entities", body = [PersistedEntity]),
 
         (status = 500, description = "Store error occurred"),
+        (status = 503, description = "The query did not complete in time"),
     )
 )]
 async fn get_latest_entities<P: StorePool + Send>(
     pool: Extension<Arc<P>>,
 ) -> Result<Json<Vec<PersistedEntity>>, StatusCode> {
-    read_from_store(pool.as_ref(), &Expression::for_latest_version())
+    read_from_read_only_store(pool.as_ref(), &Expression::for_latest_version())
         .await
         .map(Json)
 }
@@ -195,6 +286,7 @@ async fn get_latest_entities<P: StorePool + Send>(
         (status = 400, content_type = "text/plain", description = "Provided entity id is invalid"),
         (status = 404, description = "Entity was not found"),
         (status = 500, description = "Store error occurred"),
+        (status = 503, description = "The query did not complete in time"),
     ),
     params(
         ("entityId" = Uuid, Path, description = "The ID of the entity"),
@@ -204,7 +296,7 @@ async fn get_entity<P: StorePool + Send>(
     Path(entity_id): Path<EntityId>,
     pool: Extension<Arc<P>>,
 ) -> Result<Json<PersistedEntity>, StatusCode> {
-    read_from_store(pool.as_ref(), &Expression::for_latest_entity_id(entity_id))
+    read_from_read_only_store(pool.as_ref(), &Expression::for_latest_entity_id(entity_id))
         .await
         .and_then(|mut entities| entities.pop().ok_or(StatusCode::NOT_FOUND))
         .map(Json)
@@ -212,12 +304,12 @@ async fn get_entity<P: StorePool + Send>(
 
 #[derive(ToSchema, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct UpdateEntityRequest {
-    entity: Entity,
-    entity_id: EntityId,
+pub struct UpdateEntityRequest {
+    pub entity: Entity,
+    pub entity_id: EntityId,
     #[schema(value_type = String)]
-    entity_type_id: VersionedUri,
-    actor_id: AccountId,
+    pub entity_type_id: VersionedUri,
+    pub actor_id: AccountId,
 }
 
 #[utoipa::path(
@@ -229,6 +321,7 @@ struct UpdateEntityRequest {
         (status = 422, content_type = "text/plain", description = "Provided request body is invalid"),
 
         (status = 404, description = "Entity ID or Entity Type URI was not found"),
+        (status = 403, description = "The actor is not allowed to update the entity"),
         (status = 500, description = "Store error occurred"),
     ),
     request_body = UpdateEntityRequest,
@@ -236,6 +329,7 @@ struct UpdateEntityRequest {
 async fn update_entity<P: StorePool + Send>(
     body: Json<UpdateEntityRequest>,
     pool: Extension<Arc<P>>,
+    actor: Extension<Actor>,
 ) -> Result<Json<PersistedEntityMetadata>, StatusCode> {
     let Json(UpdateEntityRequest {
         entity,
@@ -244,6 +338,9 @@ async fn update_entity<P: StorePool + Send>(
         actor_id,
     }) = body;
 
+    let actor_id = actor.act_as(actor_id)?;
+    actor.ensure_may_modify(entity_owner(pool.as_ref(), entity_id).await?)?;
+
     let mut store = pool.acquire().await.map_err(|report| {
         tracing::error!(error=?report, "Could not acquire store");
         StatusCode::INTERNAL_SERVER_ERROR
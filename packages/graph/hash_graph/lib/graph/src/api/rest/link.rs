@@ -2,14 +2,24 @@
 
 use std::sync::Arc;
 
-use axum::{extract::Path, http::StatusCode, routing::post, Extension, Json, Router};
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    routing::post,
+    Extension, Json, Router,
+};
 use futures::TryFutureExt;
 use serde::{Deserialize, Serialize};
 use type_system::uri::VersionedUri;
 use utoipa::{OpenApi, ToSchema};
 
 use crate::{
-    api::rest::{api_resource::RoutedResource, read_from_store, report_to_status_code},
+    api::rest::{
+        api_resource::RoutedResource,
+        auth::{entity_owner, link_owner, Actor},
+        ensure_complete, read_from_read_only_store, report_to_status_code, with_query_timeout,
+        ResolveOptions,
+    },
     knowledge::{EntityId, Link, LinkRootedSubgraph, PersistedLink, PersistedLinkMetadata},
     ontology::AccountId,
     store::{error::QueryError, query::Expression, LinkStore, StorePool},
@@ -63,16 +73,16 @@ impl RoutedResource for LinkResource {
 
 #[derive(Serialize, Deserialize, ToSchema)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
-struct CreateLinkRequest {
-    target_entity_id: EntityId,
+pub struct CreateLinkRequest {
+    pub target_entity_id: EntityId,
     #[schema(value_type = String)]
-    link_type_id: VersionedUri,
-    owned_by_id: AccountId,
-    actor_id: AccountId,
+    pub link_type_id: VersionedUri,
+    pub owned_by_id: AccountId,
+    pub actor_id: AccountId,
     // TODO: Consider if ordering should be exposed on links as they are here. The API consumer
     //   manages indexes currently.
     //   https://app.asana.com/0/1202805690238892/1202937382769278/f
-    index: Option<i32>,
+    pub index: Option<i32>,
 }
 
 #[utoipa::path(
@@ -85,6 +95,7 @@ struct CreateLinkRequest {
         (status = 422, content_type = "text/plain", description = "Provided request body is invalid"),
 
         (status = 404, description = "Source entity, target entity or link type URI was not found"),
+        (status = 403, description = "The actor is not allowed to modify the links of the source entity"),
         (status = 500, description = "Store error occurred"),
     ),
     params(
@@ -95,6 +106,7 @@ async fn create_link<P: StorePool + Send>(
     source_entity_id: Path<EntityId>,
     body: Json<CreateLinkRequest>,
     pool: Extension<Arc<P>>,
+    actor: Extension<Actor>,
 ) -> Result<Json<Link>, StatusCode> {
     let Path(source_entity_id) = source_entity_id;
     let Json(CreateLinkRequest {
@@ -105,6 +117,11 @@ async fn create_link<P: StorePool + Send>(
         index,
     }) = body;
 
+    let owned_by_id = actor.act_as(owned_by_id)?;
+    let actor_id = actor.act_as(actor_id)?;
+    // Links are part of their source entity, so only its owner may add links
+    actor.ensure_may_modify(entity_owner(pool.as_ref(), source_entity_id).await?)?;
+
     let mut store = pool.acquire().await.map_err(|report| {
         tracing::error!(error=?report, "Could not acquire store");
         StatusCode::INTERNAL_SERVER_ERROR
@@ -138,27 +155,41 @@ async fn create_link<P: StorePool + Send>(
     responses(
         (status = 200, content_type = "application/json", body = [LinkRootedSubgraph], description = "A list of subgraphs rooted at links that satisfy the given query, each resolved to the requested depth."),
 
-        (status = 422, content_type = "text/plain", description = "Provided query is invalid"),
+        (status = 422, content_type = "text/plain", description = "Provided query is invalid or exceeds the limits of the Graph"),
         (status = 500, description = "Store error occurred"),
+        (status = 503, description = "The query did not complete in time"),
+    ),
+    params(
+        ("allowTruncation" = Option<bool>, Query, description = "Return a truncated subgraph instead of failing, if the query exceeds the limits of the Graph"),
     )
 )]
 async fn get_links_by_query<P: StorePool + Send>(
     pool: Extension<Arc<P>>,
+    Query(options): Query<ResolveOptions>,
     Json(query): Json<StructuralQuery>,
 ) -> Result<Json<Vec<LinkRootedSubgraph>>, StatusCode> {
-    pool.acquire()
-        .map_err(|error| {
-            tracing::error!(?error, "Could not acquire access to the store");
-            StatusCode::INTERNAL_SERVER_ERROR
-        })
-        .and_then(|store| async move {
-            store.get_links(&query).await.map_err(|report| {
-                tracing::error!(error=?report, ?query, "Could not read links from the store");
-                report_to_status_code(&report)
+    with_query_timeout(
+        pool.as_ref(),
+        pool.acquire_read_only()
+            .map_err(|error| {
+                tracing::error!(?error, "Could not acquire access to the store");
+                StatusCode::INTERNAL_SERVER_ERROR
             })
-        })
-        .await
-        .map(Json)
+            .and_then(|store| async move {
+                store.get_links(&query).await.map_err(|report| {
+                    tracing::error!(error=?report, ?query, "Could not read links from the store");
+                    report_to_status_code(&report)
+                })
+            }),
+    )
+    .await
+    .and_then(|subgraphs| {
+        ensure_complete(
+            subgraphs.iter().any(|subgraph| subgraph.truncated),
+            &options,
+        )?;
+        Ok(Json(subgraphs))
+    })
 }
 
 #[utoipa::path(
@@ -171,6 +202,7 @@ async fn get_links_by_query<P: StorePool + Send>(
 
         (status = 404, description = "No links were found"),
         (status = 500, description = "Store error occurred"),
+        (status = 503, description = "The query did not complete in time"),
     ),
     params(
         ("entityId" = Uuid, Path, description = "The ID of the source entity"),
@@ -180,7 +212,7 @@ async fn get_entity_links<P: StorePool + Send>(
     Path(source_entity_id): Path<EntityId>,
     pool: Extension<Arc<P>>,
 ) -> Result<Json<Vec<PersistedLink>>, StatusCode> {
-    read_from_store(
+    read_from_read_only_store(
         pool.as_ref(),
         &Expression::for_link_by_source_entity_id(source_entity_id),
     )
@@ -190,11 +222,11 @@ async fn get_entity_links<P: StorePool + Send>(
 
 #[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
-struct RemoveLinkRequest {
-    target_entity_id: EntityId,
+pub struct RemoveLinkRequest {
+    pub target_entity_id: EntityId,
     #[schema(value_type = String)]
-    link_type_id: VersionedUri,
-    actor_id: AccountId,
+    pub link_type_id: VersionedUri,
+    pub actor_id: AccountId,
 }
 
 #[utoipa::path(
@@ -206,6 +238,7 @@ struct RemoveLinkRequest {
         (status = 422, content_type = "text/plain", description = "Provided request body is invalid"),
 
         (status = 404, description = "Source entity, target entity or link type URI was not found"),
+        (status = 403, description = "The actor is not allowed to modify the links of the source entity"),
         (status = 500, description = "Store error occurred"),
     ),
     request_body = RemoveLinkRequest,
@@ -217,6 +250,7 @@ async fn remove_link<P: StorePool + Send>(
     source_entity_id: Path<EntityId>,
     body: Json<RemoveLinkRequest>,
     pool: Extension<Arc<P>>,
+    actor: Extension<Actor>,
 ) -> Result<StatusCode, StatusCode> {
     let Path(source_entity_id) = source_entity_id;
     let Json(RemoveLinkRequest {
@@ -225,6 +259,17 @@ async fn remove_link<P: StorePool + Send>(
         actor_id,
     }) = body;
 
+    let actor_id = actor.act_as(actor_id)?;
+    actor.ensure_may_modify(
+        link_owner(
+            pool.as_ref(),
+            source_entity_id,
+            target_entity_id,
+            &link_type_id,
+        )
+        .await?,
+    )?;
+
     let mut store = pool.acquire().await.map_err(|report| {
         tracing::error!(error=?report, "Could not acquire store");
         StatusCode::INTERNAL_SERVER_ERROR
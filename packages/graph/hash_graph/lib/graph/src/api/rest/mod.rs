@@ -4,37 +4,65 @@
 
 mod account;
 mod api_resource;
+pub mod auth;
+mod batch;
+mod change;
 mod data_type;
 mod entity;
 mod entity_type;
 mod link;
 mod link_type;
+mod monitoring;
+mod ontology_type;
 mod property_type;
+mod snapshot;
 
-use std::sync::Arc;
+use std::{collections::HashSet, fmt, future::Future, sync::Arc};
 
 use axum::{
     extract::Path,
     http::StatusCode,
+    middleware,
     response::{IntoResponse, Response},
     routing::get,
     Extension, Json, Router,
 };
 use error_stack::Report;
-use futures::TryFutureExt;
 use include_dir::{include_dir, Dir};
+use serde::{Deserialize, Serialize};
+use type_system::uri::{BaseUri, VersionedUri};
 use utoipa::{
     openapi::{self, schema, schema::RefOr, ObjectBuilder},
-    Modify, OpenApi,
+    Modify, OpenApi, ToSchema,
 };
 
-use self::api_resource::RoutedResource;
+use self::{api_resource::RoutedResource, auth::Authentication};
+pub use self::{
+    batch::{
+        BatchEntityId, BatchOperation, BatchOperationResult, BatchRequest, CreateEntityOperation,
+        CreateLinkOperation, OperationReference, RemoveLinkOperation, UpdateEntityOperation,
+    },
+    data_type::{CreateDataTypeRequest, UpdateDataTypeRequest},
+    entity::{CreateEntityRequest, UpdateEntityRequest},
+    entity_type::{CreateEntityTypeRequest, UpdateEntityTypeRequest},
+    link::{CreateLinkRequest, RemoveLinkRequest},
+    link_type::{CreateLinkTypeRequest, UpdateLinkTypeRequest},
+    ontology_type::{DeprecateOntologyTypeRequest, TransferOntologyTypeOwnershipRequest},
+    property_type::{CreatePropertyTypeRequest, UpdatePropertyTypeRequest},
+};
 use crate::{
-    ontology::domain_validator::DomainValidator,
+    knowledge::PersistedEntity,
+    ontology::{
+        domain_validator::DomainValidator,
+        external_types::{collect_references, ExternalTypeResolver},
+        CompatibilityReport, EntityValidator, PersistedDataType, PersistedEntityType,
+        PersistedOntologyMetadata, PersistedPropertyType, UpdateCompatibility,
+    },
     store::{
+        change::ChangeFeed,
         crud::Read,
         query::{Expression, ExpressionError, ResolveError},
-        StorePool,
+        Store, StorePool,
     },
 };
 
@@ -47,8 +75,12 @@ fn api_resources<P: StorePool + Send + 'static>() -> Vec<Router> {
         property_type::PropertyTypeResource::routes::<P>(),
         link_type::LinkTypeResource::routes::<P>(),
         entity_type::EntityTypeResource::routes::<P>(),
+        ontology_type::OntologyTypeResource::routes::<P>(),
         entity::EntityResource::routes::<P>(),
         link::LinkResource::routes::<P>(),
+        change::ChangeResource::routes::<P>(),
+        snapshot::SnapshotResource::routes::<P>(),
+        batch::BatchResource::routes::<P>(),
     ]
 }
 
@@ -59,8 +91,12 @@ fn api_documentation() -> Vec<openapi::OpenApi> {
         property_type::PropertyTypeResource::documentation(),
         link_type::LinkTypeResource::documentation(),
         entity_type::EntityTypeResource::documentation(),
+        ontology_type::OntologyTypeResource::documentation(),
         entity::EntityResource::documentation(),
         link::LinkResource::documentation(),
+        change::ChangeResource::documentation(),
+        snapshot::SnapshotResource::documentation(),
+        batch::BatchResource::documentation(),
     ]
 }
 
@@ -79,7 +115,10 @@ fn report_to_status_code<C>(report: &Report<C>) -> StatusCode {
     status_code
 }
 
-async fn read_from_store<'pool, P, T>(
+/// Reads from a [`Store`], which is connected to a read replica if one is configured.
+///
+/// [`Store`]: crate::store::Store
+async fn read_from_read_only_store<'pool, P, T>(
     pool: &'pool P,
     query: &<P::Store<'pool> as Read<T>>::Query<'_>,
 ) -> Result<Vec<T>, StatusCode>
@@ -87,30 +126,251 @@ where
     P: StorePool<Store<'pool>: Read<T>>,
     T: Send,
 {
-    pool.acquire()
-        .map_err(|report| {
-            tracing::error!(error=?report, "Could not acquire access to the store");
-            StatusCode::INTERNAL_SERVER_ERROR
-        })
-        .and_then(|store| async move {
-            // TODO: Closure is taking reference of `store` to `read()`, so the read operation
-            //       needs to be awaited on. By passing through a stream we could avoid awaiting and
-            //       remove the `async move` closure
-            //   see https://app.asana.com/0/1202805690238892/1202923536131158/f
-            Read::read(&store, query)
-                .map_err(|report| {
-                    // TODO: Implement `Valuable` for queries and print them here
-                    tracing::error!(error=?report, ?query, "Could not read from the store");
-                    report_to_status_code(&report)
-                })
-                .await
+    with_query_timeout(pool, read(pool.acquire_read_only().await, query)).await
+}
+
+async fn read<S, T, E>(store: Result<S, E>, query: &S::Query<'_>) -> Result<Vec<T>, StatusCode>
+where
+    S: Read<T>,
+    T: Send,
+    E: fmt::Debug,
+{
+    let store = store.map_err(|report| {
+        tracing::error!(error=?report, "Could not acquire access to the store");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    read_in_store(&store, query).await
+}
+
+/// Reads from an already acquired [`Store`], e.g. a transaction.
+///
+/// [`Store`]: crate::store::Store
+async fn read_in_store<S, T>(store: &S, query: &S::Query<'_>) -> Result<Vec<T>, StatusCode>
+where
+    S: Read<T>,
+    T: Send,
+{
+    Read::read(store, query).await.map_err(|report| {
+        // TODO: Implement `Valuable` for queries and print them here
+        tracing::error!(error=?report, ?query, "Could not read from the store");
+        report_to_status_code(&report)
+    })
+}
+
+/// Awaits `query` until the query timeout of the `pool` elapses.
+///
+/// Fails with [`StatusCode::SERVICE_UNAVAILABLE`], if the timeout elapsed before the query
+/// completed. Dropping `query` does not abort a statement, which is already running in the
+/// database, so the pool has to abort statements after the same timeout, e.g. as
+/// [`PostgresStorePool`] does by default.
+///
+/// [`PostgresStorePool`]: crate::store::PostgresStorePool
+async fn with_query_timeout<P, T>(
+    pool: &P,
+    query: impl Future<Output = Result<T, StatusCode>>,
+) -> Result<T, StatusCode>
+where
+    P: StorePool,
+{
+    let Some(timeout) = pool.query_timeout() else {
+        return query.await;
+    };
+
+    tokio::time::timeout(timeout, query)
+        .await
+        .unwrap_or_else(|_| {
+            tracing::error!(?timeout, "Query did not complete in time");
+            Err(StatusCode::SERVICE_UNAVAILABLE)
         })
+}
+
+/// Query parameters of the routes resolving a subgraph.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResolveOptions {
+    /// Return a truncated subgraph instead of failing, if the query exceeds the limits of the
+    /// store.
+    #[serde(default)]
+    allow_truncation: bool,
+}
+
+/// Fails with [`StatusCode::UNPROCESSABLE_ENTITY`], if a subgraph is `truncated` but `options` do
+/// not allow truncation.
+fn ensure_complete(truncated: bool, options: &ResolveOptions) -> Result<(), StatusCode> {
+    if truncated && !options.allow_truncation {
+        tracing::error!("Query exceeds the limits of the store");
+        Err(StatusCode::UNPROCESSABLE_ENTITY)
+    } else {
+        Ok(())
+    }
+}
+
+/// The response to a successful update of an ontology type.
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateOntologyTypeResponse {
+    pub metadata: PersistedOntologyMetadata,
+    pub compatibility: CompatibilityReport,
+}
+
+impl UpdateOntologyTypeResponse {
+    const fn new(metadata: PersistedOntologyMetadata, compatibility: CompatibilityReport) -> Self {
+        Self {
+            metadata,
+            compatibility,
+        }
+    }
+}
+
+/// Checks the update of an ontology type against its previous version.
+///
+/// If the update contains breaking changes, the number of existing entities, which would fail
+/// validation after the update, is determined. Breaking updates are rejected with
+/// [`StatusCode::CONFLICT`] unless `allow_breaking_changes` is set.
+async fn check_update_compatibility<S, T>(
+    store: &S,
+    base_uri: &BaseUri,
+    previous: &T,
+    updated: &T,
+    allow_breaking_changes: bool,
+) -> Result<CompatibilityReport, StatusCode>
+where
+    S: Store,
+    T: UpdateCompatibility + Clone + Into<serde_json::Value> + Sync,
+{
+    let breaking_changes = updated.breaking_changes(previous);
+    if breaking_changes.is_empty() {
+        return Ok(CompatibilityReport::new(breaking_changes, 0));
+    }
+    if !allow_breaking_changes {
+        tracing::error!(
+            ?breaking_changes,
+            "Update contains breaking changes but breaking changes are not allowed"
+        );
+        return Err(StatusCode::CONFLICT);
+    }
+
+    // Only entities of an entity type, which references the updated type, may be affected.
+    let entities = store
+        .get_latest_entities_referencing_type(base_uri)
+        .await
+        .map_err(|report| {
+            tracing::error!(error=?report, %base_uri, "Could not read affected entities");
+            report_to_status_code(&report)
+        })?;
+    if entities.is_empty() {
+        return Ok(CompatibilityReport::new(breaking_changes, 0));
+    }
+
+    let mut validator = entity_validator(store, &entities).await?;
+
+    // Entities, which are already invalid, are not affected by the update.
+    let valid_entities: Vec<_> = entities
+        .iter()
+        .filter(|entity| validator.is_valid(entity.inner(), entity.metadata().entity_type_id()))
+        .collect();
+
+    validator.replace_type(updated.clone());
+    let invalid_entity_count = valid_entities
+        .into_iter()
+        .filter(|entity| !validator.is_valid(entity.inner(), entity.metadata().entity_type_id()))
+        .count();
+
+    Ok(CompatibilityReport::new(
+        breaking_changes,
+        invalid_entity_count,
+    ))
+}
+
+/// Creates an [`EntityValidator`] for `entities`.
+///
+/// Only the entity types of `entities` and the types they reference, directly or transitively, are
+/// read from the `store`, so the cost does not grow with the size of the whole ontology.
+async fn entity_validator<S: Store>(
+    store: &S,
+    entities: &[PersistedEntity],
+) -> Result<EntityValidator, StatusCode> {
+    let mut validator = EntityValidator::new();
+    let mut visited = HashSet::new();
+    let mut pending: Vec<VersionedUri> = entities
+        .iter()
+        .map(|entity| entity.metadata().entity_type_id().clone())
+        .collect();
+
+    // Every pass reads the types referenced by the types of the previous pass, until no new type
+    // is referenced.
+    loop {
+        pending.retain(|uri| visited.insert(uri.clone()));
+        if pending.is_empty() {
+            return Ok(validator);
+        }
+        let expression =
+            Expression::Any(pending.iter().map(Expression::for_versioned_uri).collect());
+
+        let mut schemas: Vec<serde_json::Value> = Vec::new();
+        let data_types: Vec<PersistedDataType> = read_in_store(store, &expression).await?;
+        schemas.extend(
+            data_types
+                .into_iter()
+                .map(|data_type| data_type.inner().clone().into()),
+        );
+        let property_types: Vec<PersistedPropertyType> = read_in_store(store, &expression).await?;
+        schemas.extend(
+            property_types
+                .into_iter()
+                .map(|property_type| property_type.inner().clone().into()),
+        );
+        let entity_types: Vec<PersistedEntityType> = read_in_store(store, &expression).await?;
+        schemas.extend(
+            entity_types
+                .into_iter()
+                .map(|entity_type| entity_type.inner().clone().into()),
+        );
+
+        pending.clear();
+        for schema in schemas {
+            collect_references(&schema, &mut pending);
+            validator.add_type(schema);
+        }
+    }
+}
+
+/// Persists the external types referenced by `ontology_type`, which are not in the `store`, yet.
+async fn resolve_external_types<S: Store + Send>(
+    external_type_resolver: &ExternalTypeResolver,
+    store: &mut S,
+    ontology_type: impl Into<serde_json::Value> + Send,
+) -> Result<(), StatusCode> {
+    external_type_resolver
+        .resolve(store, ontology_type)
         .await
+        .map(drop)
+        .map_err(|report| {
+            tracing::error!(error=?report, "Could not resolve external ontology types");
+            StatusCode::UNPROCESSABLE_ENTITY
+        })
+}
+
+/// External types are read-only, so updating them is forbidden.
+fn ensure_not_external(
+    external_type_resolver: &ExternalTypeResolver,
+    type_to_update: &VersionedUri,
+) -> Result<(), StatusCode> {
+    if external_type_resolver.is_external(type_to_update) {
+        tracing::error!(%type_to_update, "Tried to update an external ontology type");
+        Err(StatusCode::FORBIDDEN)
+    } else {
+        Ok(())
+    }
 }
 
 pub fn rest_api_router<P: StorePool + Send + 'static>(
     store: Arc<P>,
     domain_regex: DomainValidator,
+    external_type_resolver: ExternalTypeResolver,
+    authentication: Authentication,
+    change_feed: ChangeFeed,
 ) -> Router {
     // All api resources are merged together into a super-router.
     let merged_routes = api_resources::<P>()
@@ -121,10 +381,18 @@ pub fn rest_api_router<P: StorePool + Send + 'static>(
     let open_api_doc = OpenApiDocumentation::openapi();
 
     // super-router can then be used as any other router.
-    // Make sure extensions are added at the end so they are made available to merged routers.
+    // Make sure extensions are added at the end so they are made available to merged routers and
+    // the authentication middleware.
+    // The monitoring routes are merged after the authentication middleware, so they are available
+    // without credentials.
     merged_routes
+        .layer(middleware::from_fn(auth::authenticate))
+        .merge(monitoring::routes::<P>())
         .layer(Extension(store))
         .layer(Extension(domain_regex))
+        .layer(Extension(external_type_resolver))
+        .layer(Extension(change_feed))
+        .layer(Extension(authentication))
         .nest(
             "/api-doc",
             Router::new()
@@ -137,6 +405,7 @@ pub fn rest_api_router<P: StorePool + Send + 'static>(
                 )
                 .route("/models/*path", get(serve_static_schema)),
         )
+        .layer(middleware::from_fn(monitoring::track_requests))
 }
 
 #[allow(
@@ -0,0 +1,82 @@
+//! Web routes for monitoring the graph.
+//!
+//! The routes are meant to be used by the infrastructure running the graph rather than by clients
+//! of the API, so they are not part of the OpenAPI specification and don't require authentication.
+
+use std::{sync::Arc, time::Instant};
+
+use axum::{
+    body::Body,
+    extract::MatchedPath,
+    http::{header::CONTENT_TYPE, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+    Extension, Router,
+};
+
+use crate::{metrics, store::StorePool};
+
+/// The content type of the Prometheus text format.
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Creates the routes for the health checks and the metrics.
+pub(super) fn routes<P: StorePool + Send + 'static>() -> Router {
+    Router::new()
+        .route("/health/live", get(live))
+        .route("/health/ready", get(ready::<P>))
+        .route("/metrics", get(render_metrics::<P>))
+}
+
+/// Responds as long as the server is able to handle requests.
+#[expect(
+    clippy::unused_async,
+    reason = "This route does not need async capabilities, but axum requires it in trait bounds."
+)]
+async fn live() -> StatusCode {
+    StatusCode::OK
+}
+
+/// Responds successfully, if a connection to the store can be established.
+async fn ready<P: StorePool + Send>(pool: Extension<Arc<P>>) -> StatusCode {
+    match pool.acquire().await {
+        Ok(_) => StatusCode::OK,
+        Err(report) => {
+            tracing::warn!(error=?report, "Could not acquire store, the graph is not ready");
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    }
+}
+
+#[expect(
+    clippy::unused_async,
+    reason = "This route does not need async capabilities, but axum requires it in trait bounds."
+)]
+async fn render_metrics<P: StorePool + Send>(pool: Extension<Arc<P>>) -> Response {
+    (
+        [(CONTENT_TYPE, METRICS_CONTENT_TYPE)],
+        metrics::global().render(pool.status()),
+    )
+        .into_response()
+}
+
+/// Middleware recording the number and the duration of the handled requests.
+pub(super) async fn track_requests(request: Request<Body>, next: Next<Body>) -> Response {
+    let method = request.method().clone();
+    // Unmatched requests are grouped, so arbitrary paths don't result in new metrics.
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| "unmatched".to_owned(), |path| path.as_str().to_owned());
+
+    let start = Instant::now();
+    let response = next.run(request).await;
+    metrics::global().observe_request(
+        method.as_str(),
+        &route,
+        response.status().as_u16(),
+        start.elapsed(),
+    );
+
+    response
+}
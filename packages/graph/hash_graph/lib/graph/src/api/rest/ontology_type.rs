@@ -0,0 +1,221 @@
+//! Web routes for operations, which apply to ontology types of any kind.
+
+use std::sync::Arc;
+
+use axum::{
+    http::StatusCode,
+    routing::{post, put},
+    Extension, Json, Router,
+};
+use error_stack::Report;
+use serde::{Deserialize, Serialize};
+use type_system::uri::{BaseUri, VersionedUri};
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    api::rest::{api_resource::RoutedResource, auth::Actor, ensure_not_external},
+    ontology::{
+        external_types::ExternalTypeResolver, AccountId, Deprecation, PersistedOntologyIdentifier,
+        PersistedOntologyMetadata,
+    },
+    store::{
+        error::{
+            AccountDoesNotExist, InvalidReplacement, OntologyTypeDoesNotExist, UnexpectedOwner,
+        },
+        BaseUriDoesNotExist, OntologyTypeStore, QueryError, StorePool,
+    },
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(deprecate_ontology_type, transfer_ontology_type_ownership),
+    components(
+        schemas(
+            DeprecateOntologyTypeRequest,
+            TransferOntologyTypeOwnershipRequest,
+            AccountId,
+            Deprecation,
+            PersistedOntologyIdentifier,
+            PersistedOntologyMetadata,
+        )
+    ),
+    tags(
+        (name = "OntologyType", description = "Ontology type lifecycle API")
+    )
+)]
+pub struct OntologyTypeResource;
+
+impl RoutedResource for OntologyTypeResource {
+    /// Create routes for managing ontology types independent of their kind.
+    fn routes<P: StorePool + Send + 'static>() -> Router {
+        Router::new().nest(
+            "/ontology-types",
+            Router::new()
+                .route("/deprecation", post(deprecate_ontology_type::<P>))
+                .route("/owner", put(transfer_ontology_type_ownership::<P>)),
+        )
+    }
+}
+
+/// Maps a failed metadata lookup to [`StatusCode::NOT_FOUND`], if the type does not exist, and to
+/// [`StatusCode::INTERNAL_SERVER_ERROR`] otherwise.
+fn metadata_lookup_status(report: &Report<QueryError>) -> StatusCode {
+    tracing::error!(error=?report, "Could not read ontology type");
+
+    if report.contains::<OntologyTypeDoesNotExist>() {
+        StatusCode::NOT_FOUND
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeprecateOntologyTypeRequest {
+    #[schema(value_type = String)]
+    pub type_id: VersionedUri,
+    /// The type, which should be used instead of the deprecated one.
+    #[schema(value_type = Option<String>)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<VersionedUri>,
+    pub actor_id: AccountId,
+}
+
+#[utoipa::path(
+    post,
+    path = "/ontology-types/deprecation",
+    request_body = DeprecateOntologyTypeRequest,
+    tag = "OntologyType",
+    responses(
+        (status = 200, content_type = "application/json", description = "The metadata of the deprecated ontology type", body = PersistedOntologyMetadata),
+
+        (status = 422, content_type = "text/plain", description = "The replacement does not exist or is of a different kind, or the actor does not exist"),
+        (status = 404, description = "Ontology type was not found"),
+        (status = 403, description = "The actor does not own the ontology type or the ontology type is external"),
+        (status = 500, description = "Store error occurred"),
+    ),
+)]
+async fn deprecate_ontology_type<P: StorePool + Send>(
+    body: Json<DeprecateOntologyTypeRequest>,
+    pool: Extension<Arc<P>>,
+    external_type_resolver: Extension<ExternalTypeResolver>,
+    actor: Extension<Actor>,
+) -> Result<Json<PersistedOntologyMetadata>, StatusCode> {
+    let Json(DeprecateOntologyTypeRequest {
+        type_id,
+        replaced_by,
+        actor_id,
+    }) = body;
+
+    ensure_not_external(&external_type_resolver, &type_id)?;
+    let actor_id = actor.act_as(actor_id)?;
+
+    let mut store = pool.acquire().await.map_err(|report| {
+        tracing::error!(error=?report, "Could not acquire store");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let metadata = store
+        .get_ontology_type_metadata(&type_id)
+        .await
+        .map_err(|report| metadata_lookup_status(&report))?;
+    actor.ensure_may_modify(metadata.identifier().owned_by_id())?;
+
+    store
+        .deprecate_ontology_type(&type_id, replaced_by, actor_id)
+        .await
+        .map_err(|report| {
+            tracing::error!(error=?report, "Could not deprecate ontology type");
+
+            // A missing replacement is reported as invalid replacement, so this is checked first
+            if report.contains::<InvalidReplacement>() || report.contains::<AccountDoesNotExist>() {
+                return StatusCode::UNPROCESSABLE_ENTITY;
+            }
+
+            if report.contains::<OntologyTypeDoesNotExist>() {
+                return StatusCode::NOT_FOUND;
+            }
+
+            // Insertion/update errors are considered internal server errors.
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+        .map(Json)
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferOntologyTypeOwnershipRequest {
+    #[schema(value_type = String)]
+    pub base_uri: BaseUri,
+    /// The account, which owns all versions of the type after the transfer.
+    pub owned_by_id: AccountId,
+    pub actor_id: AccountId,
+}
+
+#[utoipa::path(
+    put,
+    path = "/ontology-types/owner",
+    request_body = TransferOntologyTypeOwnershipRequest,
+    tag = "OntologyType",
+    responses(
+        (status = 200, content_type = "application/json", description = "The metadata of the latest version of the transferred ontology type", body = PersistedOntologyMetadata),
+
+        (status = 422, content_type = "text/plain", description = "Provided request body is invalid or the new owner does not exist"),
+        (status = 404, description = "Base URI was not found"),
+        (status = 409, description = "The ownership of the ontology type was transferred concurrently"),
+        (status = 403, description = "The actor does not own the ontology type or the ontology type is external"),
+        (status = 500, description = "Store error occurred"),
+    ),
+)]
+async fn transfer_ontology_type_ownership<P: StorePool + Send>(
+    body: Json<TransferOntologyTypeOwnershipRequest>,
+    pool: Extension<Arc<P>>,
+    external_type_resolver: Extension<ExternalTypeResolver>,
+    actor: Extension<Actor>,
+) -> Result<Json<PersistedOntologyMetadata>, StatusCode> {
+    let Json(TransferOntologyTypeOwnershipRequest {
+        base_uri,
+        owned_by_id,
+        actor_id,
+    }) = body;
+
+    let actor_id = actor.act_as(actor_id)?;
+
+    let mut store = pool.acquire().await.map_err(|report| {
+        tracing::error!(error=?report, "Could not acquire store");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let metadata = store
+        .get_latest_ontology_type_metadata(&base_uri)
+        .await
+        .map_err(|report| metadata_lookup_status(&report))?;
+    ensure_not_external(&external_type_resolver, metadata.identifier().uri())?;
+    let current_owned_by_id = metadata.identifier().owned_by_id();
+    actor.ensure_may_modify(current_owned_by_id)?;
+
+    // The store only transfers the ownership if the type is still owned by the account checked
+    // above.
+    store
+        .transfer_ontology_type_ownership(&base_uri, current_owned_by_id, owned_by_id, actor_id)
+        .await
+        .map_err(|report| {
+            tracing::error!(error=?report, "Could not transfer ownership of ontology type");
+
+            if report.contains::<BaseUriDoesNotExist>() {
+                return StatusCode::NOT_FOUND;
+            }
+
+            if report.contains::<UnexpectedOwner>() {
+                return StatusCode::CONFLICT;
+            }
+
+            if report.contains::<AccountDoesNotExist>() {
+                return StatusCode::UNPROCESSABLE_ENTITY;
+            }
+
+            // Insertion/update errors are considered internal server errors.
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+        .map(Json)
+}
@@ -3,7 +3,7 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::Path,
+    extract::{Path, Query},
     http::StatusCode,
     routing::{get, post},
     Extension, Json, Router,
@@ -16,15 +16,21 @@ use utoipa::{OpenApi, ToSchema};
 
 use super::api_resource::RoutedResource;
 use crate::{
-    api::rest::{read_from_store, report_to_status_code},
+    api::rest::{
+        auth::Actor, check_update_compatibility, ensure_complete, ensure_not_external,
+        read_from_read_only_store, read_in_store, report_to_status_code, resolve_external_types,
+        with_query_timeout, ResolveOptions, UpdateOntologyTypeResponse,
+    },
     ontology::{
         domain_validator::{DomainValidator, ValidateOntologyType},
-        patch_id_and_parse, AccountId, PersistedOntologyIdentifier, PersistedOntologyMetadata,
-        PersistedPropertyType,
+        external_types::ExternalTypeResolver,
+        patch_id_and_parse, AccountId, BreakingChange, BreakingChangeKind, CompatibilityReport,
+        Deprecation, PersistedOntologyIdentifier, PersistedOntologyMetadata, PersistedPropertyType,
     },
     shared::identifier::GraphElementIdentifier,
     store::{
-        query::Expression, BaseUriAlreadyExists, BaseUriDoesNotExist, PropertyTypeStore, StorePool,
+        query::Expression, BaseUriAlreadyExists, BaseUriDoesNotExist, PropertyTypeStore, Store,
+        StorePool,
     },
     subgraph::{
         EdgeKind, Edges, GraphResolveDepths, OutwardEdge, StructuralQuery, Subgraph, Vertex,
@@ -44,9 +50,14 @@ use crate::{
         schemas(
             CreatePropertyTypeRequest,
             UpdatePropertyTypeRequest,
+            UpdateOntologyTypeResponse,
+            CompatibilityReport,
+            BreakingChange,
+            BreakingChangeKind,
             AccountId,
             PersistedOntologyIdentifier,
             PersistedOntologyMetadata,
+            Deprecation,
             PersistedPropertyType,
             StructuralQuery,
             GraphElementIdentifier,
@@ -85,11 +96,11 @@ impl RoutedResource for PropertyTypeResource {
 
 #[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
-struct CreatePropertyTypeRequest {
+pub struct CreatePropertyTypeRequest {
     #[schema(value_type = VAR_PROPERTY_TYPE)]
-    schema: serde_json::Value,
-    owned_by_id: AccountId,
-    actor_id: AccountId,
+    pub schema: serde_json::Value,
+    pub owned_by_id: AccountId,
+    pub actor_id: AccountId,
 }
 
 #[utoipa::path(
@@ -99,9 +110,10 @@ struct CreatePropertyTypeRequest {
     tag = "PropertyType",
     responses(
         (status = 201, content_type = "application/json", description = "The metadata of the created property type", body = PersistedOntologyMetadata),
-        (status = 422, content_type = "text/plain", description = "Provided request body is invalid"),
+        (status = 422, content_type = "text/plain", description = "Provided request body is invalid or a referenced external type could not be resolved"),
 
         (status = 409, description = "Unable to create property type in the store as the base property type ID already exists"),
+        (status = 403, description = "The actor is not allowed to act on behalf of the given accounts"),
         (status = 500, description = "Store error occurred"),
     ),
     request_body = CreatePropertyTypeRequest,
@@ -110,12 +122,41 @@ async fn create_property_type<P: StorePool + Send>(
     body: Json<CreatePropertyTypeRequest>,
     pool: Extension<Arc<P>>,
     domain_validator: Extension<DomainValidator>,
+    actor: Extension<Actor>,
+    external_type_resolver: Extension<ExternalTypeResolver>,
 ) -> Result<Json<PersistedOntologyMetadata>, StatusCode> {
-    let Json(CreatePropertyTypeRequest {
+    let mut store = pool.acquire().await.map_err(|report| {
+        tracing::error!(error=?report, "Could not acquire store");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    create_property_type_in_store(
+        &mut store,
+        body.0,
+        *actor,
+        &domain_validator,
+        &external_type_resolver,
+    )
+    .await
+    .map(Json)
+}
+
+/// Creates the property type described by `request` in the `store` on behalf of `actor`.
+pub(super) async fn create_property_type_in_store<S: Store + Send>(
+    store: &mut S,
+    request: CreatePropertyTypeRequest,
+    actor: Actor,
+    domain_validator: &DomainValidator,
+    external_type_resolver: &ExternalTypeResolver,
+) -> Result<PersistedOntologyMetadata, StatusCode> {
+    let CreatePropertyTypeRequest {
         schema,
         owned_by_id,
         actor_id,
-    }) = body;
+    } = request;
+
+    let owned_by_id = actor.act_as(owned_by_id)?;
+    let actor_id = actor.act_as(actor_id)?;
 
     let property_type: PropertyType = schema.try_into().into_report().map_err(|report| {
         tracing::error!(error=?report, "Couldn't convert schema to Property Type");
@@ -131,10 +172,7 @@ async fn create_property_type<P: StorePool + Send>(
             StatusCode::UNPROCESSABLE_ENTITY
         })?;
 
-    let mut store = pool.acquire().await.map_err(|report| {
-        tracing::error!(error=?report, "Could not acquire store");
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    resolve_external_types(external_type_resolver, store, property_type.clone()).await?;
 
     store
         .create_property_type(property_type, owned_by_id, actor_id)
@@ -149,7 +187,6 @@ async fn create_property_type<P: StorePool + Send>(
             // Insertion/update errors are considered internal server errors.
             StatusCode::INTERNAL_SERVER_ERROR
         })
-        .map(Json)
 }
 
 #[utoipa::path(
@@ -160,33 +197,41 @@ async fn create_property_type<P: StorePool + Send>(
     responses(
         (status = 200, content_type = "application/json", body = Subgraph, description = "A subgraph rooted at property types that satisfy the given query, each resolved to the requested depth."),
 
-        (status = 422, content_type = "text/plain", description = "Provided query is invalid"),
+        (status = 422, content_type = "text/plain", description = "Provided query is invalid or exceeds the limits of the Graph"),
         (status = 500, description = "Store error occurred"),
+        (status = 503, description = "The query did not complete in time"),
+    ),
+    params(
+        ("allowTruncation" = Option<bool>, Query, description = "Return a truncated subgraph instead of failing, if the query exceeds the limits of the Graph"),
     )
 )]
 async fn get_property_types_by_query<P>(
     pool: Extension<Arc<P>>,
+    Query(options): Query<ResolveOptions>,
     Json(query): Json<StructuralQuery>,
 ) -> Result<Json<Subgraph>, StatusCode>
 where
     for<'pool> P: StorePool<Store<'pool>: PropertyTypeStore> + Send,
 {
-    pool.acquire()
-        .map_err(|error| {
-            tracing::error!(?error, "Could not acquire access to the store");
-            StatusCode::INTERNAL_SERVER_ERROR
-        })
-        .and_then(|store| async move {
-            store
-                .get_property_type(&query)
-                .await
-                .map_err(|report| {
+    with_query_timeout(
+        pool.as_ref(),
+        pool.acquire_read_only()
+            .map_err(|error| {
+                tracing::error!(?error, "Could not acquire access to the store");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })
+            .and_then(|store| async move {
+                store.get_property_type(&query).await.map_err(|report| {
                     tracing::error!(error=?report, ?query, "Could not read property types from the store");
                     report_to_status_code(&report)
                 })
-        })
-        .await
-        .map(Json)
+            }),
+    )
+    .await
+    .and_then(|subgraph| {
+        ensure_complete(subgraph.truncated, &options)?;
+        Ok(Json(subgraph))
+    })
 }
 
 #[utoipa::path(
@@ -197,12 +242,13 @@ where
         (status = 200, content_type = "application/json", description = "List of all property types at their latest versions", body = [PersistedPropertyType]),
 
         (status = 500, description = "Store error occurred"),
+        (status = 503, description = "The query did not complete in time"),
     )
 )]
 async fn get_latest_property_types<P: StorePool + Send>(
     pool: Extension<Arc<P>>,
 ) -> Result<Json<Vec<PersistedPropertyType>>, StatusCode> {
-    read_from_store(pool.as_ref(), &Expression::for_latest_version())
+    read_from_read_only_store(pool.as_ref(), &Expression::for_latest_version())
         .await
         .map(Json)
 }
@@ -217,6 +263,7 @@ async fn get_latest_property_types<P: StorePool + Send>(
 
         (status = 404, description = "Property type was not found"),
         (status = 500, description = "Store error occurred"),
+        (status = 503, description = "The query did not complete in time"),
     ),
     params(
         ("uri" = String, Path, description = "The URI of the property type"),
@@ -226,7 +273,7 @@ async fn get_property_type<P: StorePool + Send>(
     uri: Path<VersionedUri>,
     pool: Extension<Arc<P>>,
 ) -> Result<Json<PersistedPropertyType>, StatusCode> {
-    read_from_store(pool.as_ref(), &Expression::for_versioned_uri(&uri.0))
+    read_from_read_only_store(pool.as_ref(), &Expression::for_versioned_uri(&uri.0))
         .await
         .and_then(|mut property_types| property_types.pop().ok_or(StatusCode::NOT_FOUND))
         .map(Json)
@@ -234,12 +281,15 @@ async fn get_property_type<P: StorePool + Send>(
 
 #[derive(ToSchema, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct UpdatePropertyTypeRequest {
+pub struct UpdatePropertyTypeRequest {
     #[schema(value_type = VAR_UPDATE_PROPERTY_TYPE)]
-    schema: serde_json::Value,
+    pub schema: serde_json::Value,
     #[schema(value_type = String)]
-    type_to_update: VersionedUri,
-    actor_id: AccountId,
+    pub type_to_update: VersionedUri,
+    pub actor_id: AccountId,
+    /// Allows updates, which may invalidate existing entities.
+    #[serde(default)]
+    pub allow_breaking_changes: bool,
 }
 
 #[utoipa::path(
@@ -247,10 +297,12 @@ struct UpdatePropertyTypeRequest {
     path = "/property-types",
     tag = "PropertyType",
     responses(
-        (status = 200, content_type = "application/json", description = "The metadata of the updated property type", body = PersistedOntologyMetadata),
+        (status = 200, content_type = "application/json", description = "The metadata of the updated property type and the compatibility of the update", body = UpdateOntologyTypeResponse),
         (status = 422, content_type = "text/plain", description = "Provided request body is invalid"),
 
         (status = 404, description = "Base property type ID was not found"),
+        (status = 403, description = "The type is an external type or the actor is not allowed to update it"),
+        (status = 409, description = "The update contains breaking changes, which were not allowed"),
         (status = 500, description = "Store error occurred"),
     ),
     request_body = UpdatePropertyTypeRequest,
@@ -258,12 +310,35 @@ struct UpdatePropertyTypeRequest {
 async fn update_property_type<P: StorePool + Send>(
     body: Json<UpdatePropertyTypeRequest>,
     pool: Extension<Arc<P>>,
-) -> Result<Json<PersistedOntologyMetadata>, StatusCode> {
-    let Json(UpdatePropertyTypeRequest {
+    external_type_resolver: Extension<ExternalTypeResolver>,
+    actor: Extension<Actor>,
+) -> Result<Json<UpdateOntologyTypeResponse>, StatusCode> {
+    let mut store = pool.acquire().await.map_err(|report| {
+        tracing::error!(error=?report, "Could not acquire store");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    update_property_type_in_store(&mut store, body.0, *actor, &external_type_resolver)
+        .await
+        .map(Json)
+}
+
+/// Updates the property type as described by `request` in the `store` on behalf of `actor`.
+pub(super) async fn update_property_type_in_store<S: Store + Send>(
+    store: &mut S,
+    request: UpdatePropertyTypeRequest,
+    actor: Actor,
+    external_type_resolver: &ExternalTypeResolver,
+) -> Result<UpdateOntologyTypeResponse, StatusCode> {
+    let UpdatePropertyTypeRequest {
         schema,
         type_to_update,
         actor_id,
-    }) = body;
+        allow_breaking_changes,
+    } = request;
+
+    ensure_not_external(external_type_resolver, &type_to_update)?;
+    let actor_id = actor.act_as(actor_id)?;
 
     let new_type_id = VersionedUri::new(
         type_to_update.base_uri().clone(),
@@ -277,10 +352,26 @@ async fn update_property_type<P: StorePool + Send>(
         //  https://app.asana.com/0/1201095311341924/1202574350052904/f
     })?;
 
-    let mut store = pool.acquire().await.map_err(|report| {
-        tracing::error!(error=?report, "Could not acquire store");
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    let previous_property_type = read_in_store::<_, PersistedPropertyType>(
+        &*store,
+        &Expression::for_versioned_uri(&type_to_update),
+    )
+    .await?
+    .pop()
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    actor.ensure_may_modify(previous_property_type.metadata().identifier().owned_by_id())?;
+
+    let compatibility = check_update_compatibility(
+        &*store,
+        type_to_update.base_uri(),
+        previous_property_type.inner(),
+        &property_type,
+        allow_breaking_changes,
+    )
+    .await?;
+
+    resolve_external_types(external_type_resolver, store, property_type.clone()).await?;
 
     store
         .update_property_type(property_type, actor_id)
@@ -295,5 +386,5 @@ async fn update_property_type<P: StorePool + Send>(
             // Insertion/update errors are considered internal server errors.
             StatusCode::INTERNAL_SERVER_ERROR
         })
-        .map(Json)
+        .map(|metadata| UpdateOntologyTypeResponse::new(metadata, compatibility))
 }
@@ -0,0 +1,150 @@
+//! Web routes for exporting and importing snapshots of the graph.
+
+use std::{io, sync::Arc};
+
+use axum::{
+    body::StreamBody,
+    extract::{BodyStream, Query},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Extension, Json, Router,
+};
+use error_stack::Report;
+use futures::{future, AsyncBufReadExt, TryStreamExt};
+use serde::Deserialize;
+use utoipa::OpenApi;
+
+use super::{api_resource::RoutedResource, auth::Actor};
+use crate::{
+    ontology::AccountId,
+    snapshot::{InvalidSnapshot, RestoreSummary, Snapshot, SnapshotError},
+    store::StorePool,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        export_snapshot,
+        import_snapshot,
+    ),
+    components(
+        schemas(RestoreSummary),
+    ),
+    tags(
+        (name = "Snapshot", description = "Snapshot import and export API")
+    )
+)]
+pub struct SnapshotResource;
+
+impl RoutedResource for SnapshotResource {
+    /// Create routes for exporting and importing snapshots.
+    fn routes<P: StorePool + Send + 'static>() -> Router {
+        // TODO: The URL format here is preliminary and will have to change.
+        Router::new().nest(
+            "/snapshot",
+            Router::new().route("/", get(export_snapshot::<P>).post(import_snapshot::<P>)),
+        )
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportFilter {
+    account_id: Option<AccountId>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/snapshot",
+    tag = "Snapshot",
+    responses(
+        (status = 200, content_type = "application/x-ndjson", description = "The snapshot as newline-delimited JSON. The first line is the header followed by one line per record. The snapshot is streamed, so if reading from the store fails, the response is aborted."),
+    ),
+    params(
+        ("accountId" = Option<AccountId>, Query, description = "Only export the records owned by this account"),
+    )
+)]
+async fn export_snapshot<P: StorePool + Send + 'static>(
+    Query(filter): Query<ExportFilter>,
+    pool: Extension<Arc<P>>,
+) -> impl IntoResponse {
+    // The status code is sent before the snapshot is read, so a failure while streaming can only
+    // be signaled by aborting the response.
+    let body = Snapshot::export_ndjson(Arc::clone(&pool), filter.account_id).map_err(|report| {
+        tracing::error!(error=?report, "Could not export snapshot");
+        io::Error::new(io::ErrorKind::Other, "could not export snapshot")
+    });
+
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        StreamBody::new(body),
+    )
+}
+
+/// Returns the status code to respond with, if reading or restoring a snapshot failed.
+fn restore_error_status(report: &Report<SnapshotError>) -> StatusCode {
+    if let Some(status_code) = report.downcast_ref::<StatusCode>() {
+        *status_code
+    } else if report.contains::<InvalidSnapshot>() {
+        StatusCode::UNPROCESSABLE_ENTITY
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/snapshot",
+    tag = "Snapshot",
+    request_body(content = String, content_type = "application/x-ndjson", description = "A snapshot as written by `GET /snapshot`"),
+    responses(
+        (status = 200, content_type = "application/json", description = "The number of restored records", body = RestoreSummary),
+        (status = 403, description = "The actor is not allowed to act on behalf of an owner of the records. Records preceding the first forbidden record may have been restored."),
+        (status = 422, description = "The snapshot is malformed or references missing records"),
+
+        (status = 500, description = "Store error occurred"),
+    )
+)]
+async fn import_snapshot<P: StorePool + Send>(
+    body: BodyStream,
+    pool: Extension<Arc<P>>,
+    actor: Extension<Actor>,
+) -> Result<Json<RestoreSummary>, StatusCode> {
+    let lines = body
+        .map_err(|error| io::Error::new(io::ErrorKind::Other, error))
+        .into_async_read()
+        .lines();
+
+    let (_, entries) = Snapshot::read_lines(lines).await.map_err(|report| {
+        tracing::error!(error=?report, "Could not read snapshot");
+        restore_error_status(&report)
+    })?;
+
+    // The snapshot is restored while it's read, so the owners are checked record by record.
+    let actor = *actor;
+    let entries = entries.and_then(move |entry| {
+        future::ready(match actor.act_as(entry.owned_by_id()) {
+            Ok(_) => Ok(entry),
+            Err(status_code) => Err(Report::new(SnapshotError)
+                .attach(status_code)
+                .attach_printable(format!(
+                    "not allowed to act on behalf of {}",
+                    entry.owned_by_id()
+                ))),
+        })
+    });
+
+    let mut store = pool.acquire().await.map_err(|report| {
+        tracing::error!(error=?report, "Could not acquire access to the store");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Snapshot::restore_entries(&mut store, entries)
+        .await
+        .map_err(|report| {
+            tracing::error!(error=?report, "Could not restore snapshot");
+            restore_error_status(&report)
+        })
+        .map(Json)
+}
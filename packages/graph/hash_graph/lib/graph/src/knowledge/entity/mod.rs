@@ -10,7 +10,19 @@ use uuid::Uuid;
 use crate::ontology::AccountId;
 
 #[derive(
-    Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema, FromSql, ToSql,
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    FromSql,
+    ToSql,
 )]
 #[repr(transparent)]
 #[postgres(transparent)]
@@ -119,6 +131,21 @@ impl PersistedEntityMetadata {
     pub const fn entity_type_id(&self) -> &VersionedUri {
         &self.entity_type_id
     }
+
+    #[must_use]
+    pub const fn created_by_id(&self) -> AccountId {
+        self.created_by_id
+    }
+
+    #[must_use]
+    pub const fn updated_by_id(&self) -> AccountId {
+        self.updated_by_id
+    }
+
+    #[must_use]
+    pub const fn removed_by_id(&self) -> Option<AccountId> {
+        self.removed_by_id
+    }
 }
 
 /// A record of an [`Entity`] that has been persisted in the datastore, with its associated
@@ -4,7 +4,7 @@
 mod entity;
 mod link;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 pub use self::{
@@ -47,7 +47,7 @@ use crate::ontology::{
 /// [`OntologyQueryDepth`]: crate::ontology::OntologyQueryDepth
 pub type KnowledgeGraphQueryDepth = u8;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LinkRootedSubgraph {
     pub link: PersistedLink,
@@ -57,4 +57,8 @@ pub struct LinkRootedSubgraph {
     pub referenced_entity_types: Vec<PersistedEntityType>,
     pub linked_entities: Vec<PersistedEntity>,
     pub links: Vec<PersistedLink>,
+    /// `true`, if the result of the query was not fully resolved because it exceeded the limits of
+    /// the store.
+    #[serde(default)]
+    pub truncated: bool,
 }
@@ -11,6 +11,8 @@
 #![feature(try_find)]
 #![feature(type_alias_impl_trait)]
 #![feature(hash_raw_entry)]
+// Not required, reason: The standard library knows best, which addresses are globally reachable
+#![feature(ip)]
 #![cfg_attr(all(doc, nightly), feature(doc_auto_cfg))]
 #![cfg_attr(not(miri), doc(test(attr(deny(warnings, clippy::all)))))]
 #![warn(
@@ -75,9 +77,11 @@ pub mod api;
 pub mod knowledge;
 pub mod ontology;
 pub mod shared;
+pub mod snapshot;
 
 pub mod store;
 
 pub mod logging;
+pub mod metrics;
 
 pub use shared::*;
@@ -0,0 +1,334 @@
+//! Metrics of the graph, exposed in the Prometheus text format.
+//!
+//! The metrics are collected in a process-wide registry returned by [`global`]. It's fed by the
+//! request tracking middleware of the REST API, by the [`PostgresStorePool`] when handing out
+//! connections, and by the [`PostgresStore`] when reading records.
+//!
+//! Queries are not compiled to SQL but evaluated on the records read from the database, so the
+//! duration of a query is split into the `execute` phase, which reads the records, and the
+//! `evaluate` phase, which filters them by the query.
+//!
+//! [`PostgresStorePool`]: crate::store::PostgresStorePool
+//! [`PostgresStore`]: crate::store::PostgresStore
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+    sync::{LazyLock, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use crate::store::PoolStatus;
+
+/// The upper bounds of the buckets of all duration histograms, in seconds.
+const DURATION_BUCKETS: [f64; 13] = [
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::default);
+
+/// Returns the process-wide metrics registry.
+#[must_use]
+pub fn global() -> &'static Metrics {
+    &METRICS
+}
+
+/// The phase of reading from the store, a duration is observed for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QueryPhase {
+    /// Reading the records from the database.
+    Execute,
+    /// Filtering the records by the query.
+    Evaluate,
+}
+
+impl QueryPhase {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Execute => "execute",
+            Self::Evaluate => "evaluate",
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct Histogram {
+    /// The number of observations less than or equal to the bound of the bucket.
+    buckets: [u64; DURATION_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, duration: Duration) {
+        let value = duration.as_secs_f64();
+        for (bound, bucket) in DURATION_BUCKETS.iter().zip(&mut self.buckets) {
+            if value <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn write(&self, output: &mut impl Write, name: &str, labels: &[(&str, &str)]) -> fmt::Result {
+        for (bound, bucket) in DURATION_BUCKETS.iter().zip(self.buckets) {
+            write!(output, "{name}_bucket")?;
+            write_labels(output, labels, Some(("le", &bound.to_string())))?;
+            writeln!(output, " {bucket}")?;
+        }
+        write!(output, "{name}_bucket")?;
+        write_labels(output, labels, Some(("le", "+Inf")))?;
+        writeln!(output, " {}", self.count)?;
+
+        write!(output, "{name}_sum")?;
+        write_labels(output, labels, None)?;
+        writeln!(output, " {}", self.sum)?;
+        write!(output, "{name}_count")?;
+        write_labels(output, labels, None)?;
+        writeln!(output, " {}", self.count)
+    }
+}
+
+fn write_labels(
+    output: &mut impl Write,
+    labels: &[(&str, &str)],
+    additional: Option<(&str, &str)>,
+) -> fmt::Result {
+    if labels.is_empty() && additional.is_none() {
+        return Ok(());
+    }
+
+    output.write_char('{')?;
+    for (index, (name, value)) in labels.iter().chain(&additional).enumerate() {
+        if index > 0 {
+            output.write_char(',')?;
+        }
+        write!(output, "{name}=\"")?;
+        for character in value.chars() {
+            match character {
+                '\\' => output.write_str("\\\\")?,
+                '"' => output.write_str("\\\"")?,
+                '\n' => output.write_str("\\n")?,
+                _ => output.write_char(character)?,
+            }
+        }
+        output.write_char('"')?;
+    }
+    output.write_char('}')
+}
+
+fn write_header(output: &mut impl Write, name: &str, kind: &str, help: &str) -> fmt::Result {
+    writeln!(output, "# HELP {name} {help}")?;
+    writeln!(output, "# TYPE {name} {kind}")
+}
+
+#[derive(Default)]
+struct MetricsState {
+    /// Keyed by method, route and status code.
+    http_requests: BTreeMap<(String, String, u16), u64>,
+    /// Keyed by method and route.
+    http_request_durations: BTreeMap<(String, String), Histogram>,
+    /// Keyed by the table read and the phase.
+    query_durations: BTreeMap<(&'static str, QueryPhase), Histogram>,
+    pool_acquisitions: Histogram,
+    pool_acquisition_errors: u64,
+}
+
+/// A registry of the metrics of the graph.
+#[derive(Default)]
+pub struct Metrics {
+    state: Mutex<MetricsState>,
+}
+
+impl Metrics {
+    fn state(&self) -> MutexGuard<'_, MetricsState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Records a handled HTTP request.
+    ///
+    /// `route` is the route matching the request rather than the requested path to keep the number
+    /// of distinct label values low.
+    pub fn observe_request(&self, method: &str, route: &str, status: u16, duration: Duration) {
+        let mut state = self.state();
+        *state
+            .http_requests
+            .entry((method.to_owned(), route.to_owned(), status))
+            .or_default() += 1;
+        state
+            .http_request_durations
+            .entry((method.to_owned(), route.to_owned()))
+            .or_default()
+            .observe(duration);
+    }
+
+    /// Records the duration of a phase of reading the records from `table`.
+    pub fn observe_query(&self, table: &'static str, phase: QueryPhase, duration: Duration) {
+        self.state()
+            .query_durations
+            .entry((table, phase))
+            .or_default()
+            .observe(duration);
+    }
+
+    /// Records an attempt to acquire a connection from the store pool.
+    pub fn observe_pool_acquisition(&self, duration: Duration, succeeded: bool) {
+        let mut state = self.state();
+        state.pool_acquisitions.observe(duration);
+        if !succeeded {
+            state.pool_acquisition_errors += 1;
+        }
+    }
+
+    /// Renders the metrics in the Prometheus text format.
+    ///
+    /// The `pool_status` is reported as gauges, if provided.
+    #[must_use]
+    pub fn render(&self, pool_status: Option<PoolStatus>) -> String {
+        let mut output = String::new();
+        self.write(&mut output, pool_status)
+            .expect("writing to a string can't fail");
+        output
+    }
+
+    fn write(&self, output: &mut impl Write, pool_status: Option<PoolStatus>) -> fmt::Result {
+        let state = self.state();
+
+        write_header(
+            output,
+            "graph_http_requests_total",
+            "counter",
+            "The number of handled HTTP requests.",
+        )?;
+        for ((method, route, status), count) in &state.http_requests {
+            output.write_str("graph_http_requests_total")?;
+            write_labels(
+                output,
+                &[
+                    ("method", method.as_str()),
+                    ("route", route.as_str()),
+                    ("status", status.to_string().as_str()),
+                ],
+                None,
+            )?;
+            writeln!(output, " {count}")?;
+        }
+
+        write_header(
+            output,
+            "graph_http_request_duration_seconds",
+            "histogram",
+            "The duration of handling HTTP requests.",
+        )?;
+        for ((method, route), histogram) in &state.http_request_durations {
+            histogram.write(output, "graph_http_request_duration_seconds", &[
+                ("method", method.as_str()),
+                ("route", route.as_str()),
+            ])?;
+        }
+
+        write_header(
+            output,
+            "graph_store_query_duration_seconds",
+            "histogram",
+            "The duration of the phases of reading from the store.",
+        )?;
+        for ((table, phase), histogram) in &state.query_durations {
+            histogram.write(output, "graph_store_query_duration_seconds", &[
+                ("table", table),
+                ("phase", phase.as_str()),
+            ])?;
+        }
+
+        write_header(
+            output,
+            "graph_store_pool_acquire_duration_seconds",
+            "histogram",
+            "The duration of acquiring a connection from the store pool.",
+        )?;
+        state
+            .pool_acquisitions
+            .write(output, "graph_store_pool_acquire_duration_seconds", &[])?;
+
+        write_header(
+            output,
+            "graph_store_pool_acquire_errors_total",
+            "counter",
+            "The number of failed attempts to acquire a connection from the store pool.",
+        )?;
+        writeln!(
+            output,
+            "graph_store_pool_acquire_errors_total {}",
+            state.pool_acquisition_errors
+        )?;
+
+        if let Some(pool_status) = pool_status {
+            write_header(
+                output,
+                "graph_store_pool_connections",
+                "gauge",
+                "The number of connections of the store pool.",
+            )?;
+            writeln!(
+                output,
+                "graph_store_pool_connections {}",
+                pool_status.connections
+            )?;
+            write_header(
+                output,
+                "graph_store_pool_idle_connections",
+                "gauge",
+                "The number of idle connections of the store pool.",
+            )?;
+            writeln!(
+                output,
+                "graph_store_pool_idle_connections {}",
+                pool_status.idle_connections
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render() {
+        let metrics = Metrics::default();
+        metrics.observe_request("GET", "/entities", 200, Duration::from_millis(20));
+        metrics.observe_request("GET", "/entities", 200, Duration::from_millis(200));
+        metrics.observe_request("GET", "/entities", 404, Duration::from_secs(20));
+        metrics.observe_query("entities", QueryPhase::Execute, Duration::from_millis(3));
+
+        let output = metrics.render(Some(PoolStatus {
+            connections: 3,
+            idle_connections: 2,
+        }));
+
+        for line in [
+            r#"graph_http_requests_total{method="GET",route="/entities",status="200"} 2"#,
+            r#"graph_http_requests_total{method="GET",route="/entities",status="404"} 1"#,
+            r#"graph_http_request_duration_seconds_bucket{method="GET",route="/entities",le="0.025"} 1"#,
+            r#"graph_http_request_duration_seconds_bucket{method="GET",route="/entities",le="10"} 2"#,
+            r#"graph_http_request_duration_seconds_bucket{method="GET",route="/entities",le="+Inf"} 3"#,
+            r#"graph_store_query_duration_seconds_count{table="entities",phase="execute"} 1"#,
+            "graph_store_pool_idle_connections 2",
+        ] {
+            assert!(
+                output.lines().any(|output_line| output_line == line),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn escape_labels() {
+        let mut output = String::new();
+        write_labels(&mut output, &[("route", "/a\"b\\c\nd")], None).expect("could not write");
+        assert_eq!(output, r#"{route="/a\"b\\c\nd"}"#);
+    }
+}
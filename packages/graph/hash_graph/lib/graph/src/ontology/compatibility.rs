@@ -0,0 +1,616 @@
+//! Classification of updates to ontology types.
+//!
+//! An update is considered _compatible_ if data which was valid against the previous version of a
+//! type stays valid against the new version, e.g. when an optional property is added to an entity
+//! type. Otherwise, e.g. if a property is removed or made required, the update is _breaking_.
+//!
+//! The checks operate on the JSON representation of the types, which is the canonical
+//! representation of the Block Protocol type system.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use type_system::{
+    uri::{BaseUri, VersionedUri},
+    DataType, EntityType, LinkType, PropertyType,
+};
+use utoipa::ToSchema;
+
+use crate::knowledge::Entity;
+
+/// The kind of a [`BreakingChange`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum BreakingChangeKind {
+    /// A property was removed from an entity type.
+    PropertyRemoved,
+    /// A property of an entity type refers to a different property type or accepts fewer values.
+    PropertyChanged,
+    /// A property of an entity type is required now.
+    PropertyMadeRequired,
+    /// A link was removed from an entity type.
+    LinkRemoved,
+    /// A link of an entity type accepts fewer destinations or changed its ordering.
+    LinkChanged,
+    /// A link of an entity type is required now.
+    LinkMadeRequired,
+    /// A possible value of a property type was removed.
+    ValueRemoved,
+    /// The primitive type of a data type changed.
+    DataTypeChanged,
+}
+
+/// A change of an ontology type which may invalidate existing data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakingChange {
+    kind: BreakingChangeKind,
+    /// The part of the type affected by the change, e.g. the base URI of a removed property.
+    subject: String,
+}
+
+impl BreakingChange {
+    #[must_use]
+    pub fn new(kind: BreakingChangeKind, subject: impl Into<String>) -> Self {
+        Self {
+            kind,
+            subject: subject.into(),
+        }
+    }
+
+    #[must_use]
+    pub const fn kind(&self) -> BreakingChangeKind {
+        self.kind
+    }
+
+    #[must_use]
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+}
+
+/// The outcome of checking the update of an ontology type against its previous version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibilityReport {
+    breaking_changes: Vec<BreakingChange>,
+    /// The number of existing entities which would fail validation against the new version.
+    invalid_entity_count: usize,
+}
+
+impl CompatibilityReport {
+    #[must_use]
+    pub const fn new(breaking_changes: Vec<BreakingChange>, invalid_entity_count: usize) -> Self {
+        Self {
+            breaking_changes,
+            invalid_entity_count,
+        }
+    }
+
+    #[must_use]
+    pub fn is_breaking(&self) -> bool {
+        !self.breaking_changes.is_empty()
+    }
+
+    #[must_use]
+    pub fn breaking_changes(&self) -> &[BreakingChange] {
+        &self.breaking_changes
+    }
+
+    #[must_use]
+    pub const fn invalid_entity_count(&self) -> usize {
+        self.invalid_entity_count
+    }
+}
+
+/// Compares a new version of an ontology type with its previous version.
+pub trait UpdateCompatibility {
+    /// Returns all changes in `self`, which may invalidate data conforming to `previous`.
+    ///
+    /// An empty list means, that the update is compatible.
+    fn breaking_changes(&self, previous: &Self) -> Vec<BreakingChange>;
+}
+
+fn to_json<T: Clone + Into<Value>>(ontology_type: &T) -> Value {
+    ontology_type.clone().into()
+}
+
+fn object<'v>(value: &'v Value, key: &str) -> Option<&'v Map<String, Value>> {
+    value.get(key).and_then(Value::as_object)
+}
+
+fn strings<'v>(value: &'v Value, key: &str) -> impl Iterator<Item = &'v str> {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+}
+
+fn versioned_uri(reference: &Value) -> Option<VersionedUri> {
+    serde_json::from_value(reference.get("$ref")?.clone()).ok()
+}
+
+/// Checks if `new` is equal to `previous` or refers to the same type in the same or a later
+/// version.
+///
+/// Updates of the referenced type are checked when that type is updated, so they are not
+/// considered here.
+fn is_same_or_later_reference(previous: &Value, new: &Value) -> bool {
+    previous == new
+        || matches!(
+            (versioned_uri(previous), versioned_uri(new)),
+            (Some(previous), Some(new))
+                if previous.base_uri() == new.base_uri() && previous.version() <= new.version()
+        )
+}
+
+/// Checks if the bounds of the array `new` accept at least the values accepted by `previous`.
+fn are_bounds_widened(previous: &Value, new: &Value) -> bool {
+    let bound = |value: &Value, key| value.get(key).and_then(Value::as_u64);
+
+    let min_items_widened =
+        bound(new, "minItems").unwrap_or(0) <= bound(previous, "minItems").unwrap_or(0);
+    let max_items_widened = match (bound(previous, "maxItems"), bound(new, "maxItems")) {
+        (_, None) => true,
+        (None, Some(_)) => false,
+        (Some(previous), Some(new)) => previous <= new,
+    };
+
+    min_items_widened && max_items_widened
+}
+
+/// Checks if every alternative in the `oneOf` of `previous` is still accepted by `new`.
+///
+/// A missing `oneOf` accepts any value.
+fn are_alternatives_widened(previous: &Value, new: &Value) -> bool {
+    match (
+        previous.get("oneOf").and_then(Value::as_array),
+        new.get("oneOf").and_then(Value::as_array),
+    ) {
+        (_, None) => true,
+        (None, Some(_)) => false,
+        (Some(previous), Some(new)) => previous.iter().all(|previous_alternative| {
+            new.iter().any(|new_alternative| {
+                is_same_or_later_reference(previous_alternative, new_alternative)
+            })
+        }),
+    }
+}
+
+fn is_compatible_property(previous: &Value, new: &Value) -> bool {
+    match (previous.get("items"), new.get("items")) {
+        (None, None) => is_same_or_later_reference(previous, new),
+        (Some(previous_items), Some(new_items)) => {
+            is_same_or_later_reference(previous_items, new_items)
+                && are_bounds_widened(previous, new)
+        }
+        _ => false,
+    }
+}
+
+fn is_compatible_link(previous: &Value, new: &Value) -> bool {
+    match (previous.get("items"), new.get("items")) {
+        (None, None) => are_alternatives_widened(previous, new),
+        (Some(previous_items), Some(new_items)) => {
+            are_alternatives_widened(previous_items, new_items)
+                && are_bounds_widened(previous, new)
+                && previous.get("ordered") == new.get("ordered")
+        }
+        _ => false,
+    }
+}
+
+impl UpdateCompatibility for EntityType {
+    fn breaking_changes(&self, previous: &Self) -> Vec<BreakingChange> {
+        let (previous, new) = (to_json(previous), to_json(self));
+        let mut breaking_changes = Vec::new();
+
+        for (key, kind_removed, kind_changed, is_compatible) in [
+            (
+                "properties",
+                BreakingChangeKind::PropertyRemoved,
+                BreakingChangeKind::PropertyChanged,
+                is_compatible_property as fn(&Value, &Value) -> bool,
+            ),
+            (
+                "links",
+                BreakingChangeKind::LinkRemoved,
+                BreakingChangeKind::LinkChanged,
+                is_compatible_link,
+            ),
+        ] {
+            let new_definitions = object(&new, key);
+            for (subject, previous_definition) in object(&previous, key).into_iter().flatten() {
+                match new_definitions.and_then(|definitions| definitions.get(subject)) {
+                    None => breaking_changes.push(BreakingChange::new(kind_removed, subject)),
+                    Some(new_definition) if !is_compatible(previous_definition, new_definition) => {
+                        breaking_changes.push(BreakingChange::new(kind_changed, subject));
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        for (key, kind) in [
+            ("required", BreakingChangeKind::PropertyMadeRequired),
+            ("requiredLinks", BreakingChangeKind::LinkMadeRequired),
+        ] {
+            let previous_required: Vec<_> = strings(&previous, key).collect();
+            breaking_changes.extend(
+                strings(&new, key)
+                    .filter(|subject| !previous_required.contains(subject))
+                    .map(|subject| BreakingChange::new(kind, subject)),
+            );
+        }
+
+        breaking_changes
+    }
+}
+
+impl UpdateCompatibility for PropertyType {
+    fn breaking_changes(&self, previous: &Self) -> Vec<BreakingChange> {
+        let (previous, new) = (to_json(previous), to_json(self));
+        let new_alternatives = new.get("oneOf").and_then(Value::as_array);
+
+        previous
+            .get("oneOf")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|previous_alternative| {
+                !new_alternatives
+                    .into_iter()
+                    .flatten()
+                    .any(|new_alternative| {
+                        is_compatible_property_value(previous_alternative, new_alternative)
+                    })
+            })
+            .map(|alternative| {
+                BreakingChange::new(BreakingChangeKind::ValueRemoved, alternative.to_string())
+            })
+            .collect()
+    }
+}
+
+fn is_compatible_property_value(previous: &Value, new: &Value) -> bool {
+    match (previous.get("type"), new.get("type")) {
+        (None, None) => is_same_or_later_reference(previous, new),
+        (Some(previous_type), Some(new_type)) if previous_type == new_type => {
+            match previous_type.as_str() {
+                Some("array") => match (previous.get("items"), new.get("items")) {
+                    (Some(previous_items), Some(new_items)) => {
+                        are_alternatives_widened(previous_items, new_items)
+                            && are_bounds_widened(previous, new)
+                    }
+                    _ => false,
+                },
+                Some("object") => is_compatible_object(previous, new),
+                _ => previous == new,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Checks if every property of the object `previous` is still accepted by `new` and no property
+/// was made required.
+fn is_compatible_object(previous: &Value, new: &Value) -> bool {
+    let new_properties = object(new, "properties");
+    let previous_required: Vec<_> = strings(previous, "required").collect();
+
+    object(previous, "properties")
+        .into_iter()
+        .flatten()
+        .all(|(property, previous_definition)| {
+            new_properties
+                .and_then(|properties| properties.get(property))
+                .map_or(false, |new_definition| {
+                    is_compatible_property(previous_definition, new_definition)
+                })
+        })
+        && strings(new, "required").all(|property| previous_required.contains(&property))
+}
+
+impl UpdateCompatibility for DataType {
+    fn breaking_changes(&self, previous: &Self) -> Vec<BreakingChange> {
+        let (previous, new) = (to_json(previous), to_json(self));
+
+        match (previous.get("type"), new.get("type")) {
+            (Some(previous_type), Some(new_type)) if previous_type != new_type => {
+                vec![BreakingChange::new(
+                    BreakingChangeKind::DataTypeChanged,
+                    format!("{previous_type} -> {new_type}"),
+                )]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl UpdateCompatibility for LinkType {
+    fn breaking_changes(&self, _previous: &Self) -> Vec<BreakingChange> {
+        // Link types don't constrain any data, so every update is compatible.
+        Vec::new()
+    }
+}
+
+/// Validates [`Entity`]s against the ontology types known to the validator.
+///
+/// The validation is shallow in the sense, that only the structure defined by the types is
+/// checked: required properties and links, unknown properties, array bounds, and the primitive
+/// type of values. Constraints, which can't be resolved because a referenced type is unknown to
+/// the validator, are considered to be satisfied.
+#[derive(Debug, Default)]
+pub struct EntityValidator {
+    types: HashMap<String, Value>,
+    updated_types: HashMap<BaseUri, Value>,
+}
+
+impl EntityValidator {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes the ontology type available for validation.
+    pub fn add_type(&mut self, ontology_type: impl Into<Value>) {
+        let ontology_type = ontology_type.into();
+        if let Some(id) = ontology_type.get("$id").and_then(Value::as_str) {
+            self.types.insert(id.to_owned(), ontology_type);
+        }
+    }
+
+    /// Validates against `ontology_type` instead of any other version of the same type.
+    ///
+    /// This is used to check, whether existing entities would stay valid, if they were upgraded to
+    /// the updated type.
+    pub fn replace_type(&mut self, ontology_type: impl Into<Value>) {
+        let ontology_type = ontology_type.into();
+        if let Some(id) = ontology_type
+            .get("$id")
+            .and_then(|id| serde_json::from_value::<VersionedUri>(id.clone()).ok())
+        {
+            self.updated_types
+                .insert(id.base_uri().clone(), ontology_type);
+        }
+    }
+
+    fn resolve_uri(&self, uri: &VersionedUri) -> Option<&Value> {
+        self.updated_types
+            .get(uri.base_uri())
+            .or_else(|| self.types.get(&uri.to_string()))
+    }
+
+    fn resolve(&self, reference: &Value) -> Option<&Value> {
+        self.resolve_uri(&versioned_uri(reference)?)
+    }
+
+    /// Returns if `entity` is valid against the entity type identified by `entity_type_id`.
+    #[must_use]
+    pub fn is_valid(&self, entity: &Entity, entity_type_id: &VersionedUri) -> bool {
+        self.resolve_uri(entity_type_id)
+            .map_or(true, |entity_type| {
+                self.are_properties_valid(
+                    entity
+                        .properties()
+                        .iter()
+                        .map(|(base_uri, value)| (base_uri.as_str(), value)),
+                    entity_type,
+                )
+            })
+    }
+
+    fn are_properties_valid<'v>(
+        &self,
+        mut properties: impl Iterator<Item = (&'v str, &'v Value)> + Clone,
+        schema: &Value,
+    ) -> bool {
+        let definitions = object(schema, "properties");
+
+        strings(schema, "required")
+            .all(|required| properties.clone().any(|(property, _)| property == required))
+            && properties.all(|(property, value)| {
+                definitions
+                    .and_then(|definitions| definitions.get(property))
+                    .map_or(false, |definition| {
+                        self.is_property_value_valid(value, definition)
+                    })
+            })
+    }
+
+    fn is_property_value_valid(&self, value: &Value, definition: &Value) -> bool {
+        match definition.get("items") {
+            Some(items) => match value.as_array() {
+                Some(values) => {
+                    are_bounds_satisfied(values, definition)
+                        && values.iter().all(|value| {
+                            self.resolve(items).map_or(true, |property_type| {
+                                self.is_valid_against_one_of(value, property_type)
+                            })
+                        })
+                }
+                None => false,
+            },
+            None => self.resolve(definition).map_or(true, |property_type| {
+                self.is_valid_against_one_of(value, property_type)
+            }),
+        }
+    }
+
+    fn is_valid_against_one_of(&self, value: &Value, schema: &Value) -> bool {
+        schema
+            .get("oneOf")
+            .and_then(Value::as_array)
+            .map_or(true, |alternatives| {
+                alternatives
+                    .iter()
+                    .any(|alternative| self.is_valid_against_alternative(value, alternative))
+            })
+    }
+
+    fn is_valid_against_alternative(&self, value: &Value, alternative: &Value) -> bool {
+        match alternative.get("type").and_then(Value::as_str) {
+            Some("object") => value.as_object().map_or(false, |properties| {
+                self.are_properties_valid(
+                    properties
+                        .iter()
+                        .map(|(property, value)| (property.as_str(), value)),
+                    alternative,
+                )
+            }),
+            Some("array") => value.as_array().map_or(false, |values| {
+                are_bounds_satisfied(values, alternative)
+                    && values.iter().all(|value| {
+                        alternative
+                            .get("items")
+                            .map_or(true, |items| self.is_valid_against_one_of(value, items))
+                    })
+            }),
+            _ => self.resolve(alternative).map_or(true, |data_type| {
+                is_valid_against_data_type(value, data_type)
+            }),
+        }
+    }
+}
+
+fn are_bounds_satisfied(values: &[Value], schema: &Value) -> bool {
+    let len = u64::try_from(values.len()).unwrap_or(u64::MAX);
+    schema
+        .get("minItems")
+        .and_then(Value::as_u64)
+        .map_or(true, |min_items| len >= min_items)
+        && schema
+            .get("maxItems")
+            .and_then(Value::as_u64)
+            .map_or(true, |max_items| len <= max_items)
+}
+
+fn is_valid_against_data_type(value: &Value, data_type: &Value) -> bool {
+    match data_type.get("type").and_then(Value::as_str) {
+        Some("string") => value.is_string(),
+        Some("number") => value.is_number(),
+        Some("boolean") => value.is_boolean(),
+        Some("null") => value.is_null(),
+        Some("object") => value.is_object(),
+        Some("array") => value.is_array(),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use graph_test_data::{data_type, entity, entity_type, property_type};
+    use serde_json::json;
+
+    use super::*;
+
+    fn parse<T: TryFrom<Value>>(json: &str) -> T {
+        let value: Value = serde_json::from_str(json).expect("invalid JSON");
+        T::try_from(value)
+            .map_err(|_| ())
+            .expect("invalid ontology type")
+    }
+
+    fn page_v2_with(patch: impl FnOnce(&mut Value)) -> EntityType {
+        let mut value: Value = serde_json::from_str(entity_type::PAGE_V2).expect("invalid JSON");
+        patch(&mut value);
+        value["$id"] = json!("https://blockprotocol.org/@alice/types/entity-type/page/v/3");
+        EntityType::try_from(value)
+            .map_err(|_| ())
+            .expect("invalid entity type")
+    }
+
+    fn kinds(breaking_changes: &[BreakingChange]) -> Vec<BreakingChangeKind> {
+        breaking_changes.iter().map(BreakingChange::kind).collect()
+    }
+
+    #[test]
+    fn adding_links_is_compatible() {
+        let page_v1: EntityType = parse(entity_type::PAGE_V1);
+        let page_v2: EntityType = parse(entity_type::PAGE_V2);
+
+        assert!(page_v2.breaking_changes(&page_v1).is_empty());
+    }
+
+    #[test]
+    fn removing_links_is_breaking() {
+        let page_v1: EntityType = parse(entity_type::PAGE_V1);
+        let page_v2: EntityType = parse(entity_type::PAGE_V2);
+
+        assert_eq!(kinds(&page_v1.breaking_changes(&page_v2)), [
+            BreakingChangeKind::LinkRemoved,
+            BreakingChangeKind::LinkRemoved
+        ]);
+    }
+
+    #[test]
+    fn making_property_required_is_breaking() {
+        let page_v2: EntityType = parse(entity_type::PAGE_V2);
+        let page_v3 = page_v2_with(|value| {
+            value["required"] =
+                json!(["https://blockprotocol.org/@alice/types/property-type/text/"]);
+        });
+
+        let breaking_changes = page_v3.breaking_changes(&page_v2);
+        assert_eq!(kinds(&breaking_changes), [
+            BreakingChangeKind::PropertyMadeRequired
+        ]);
+        assert_eq!(
+            breaking_changes[0].subject(),
+            "https://blockprotocol.org/@alice/types/property-type/text/"
+        );
+    }
+
+    #[test]
+    fn changing_link_ordering_is_breaking() {
+        let page_v2: EntityType = parse(entity_type::PAGE_V2);
+        let page_v3 = page_v2_with(|value| {
+            value["links"]["https://blockprotocol.org/@alice/types/link-type/contains/v/1"]
+                ["ordered"] = json!(false);
+        });
+
+        assert_eq!(kinds(&page_v3.breaking_changes(&page_v2)), [
+            BreakingChangeKind::LinkChanged
+        ]);
+    }
+
+    #[test]
+    fn changing_data_type_is_breaking() {
+        let text_v1: DataType = parse(data_type::TEXT_V1);
+        let mut value: Value = serde_json::from_str(data_type::TEXT_V1).expect("invalid JSON");
+        value["$id"] = json!("https://blockprotocol.org/@blockprotocol/types/data-type/text/v/2");
+        value["type"] = json!("number");
+        let text_v2 = DataType::try_from(value)
+            .map_err(|_| ())
+            .expect("invalid data type");
+
+        assert_eq!(kinds(&text_v2.breaking_changes(&text_v1)), [
+            BreakingChangeKind::DataTypeChanged
+        ]);
+        assert!(text_v1.breaking_changes(&text_v1).is_empty());
+    }
+
+    #[test]
+    fn validate_entity() {
+        let entity: Entity = serde_json::from_str(entity::PAGE_V1).expect("invalid entity");
+        let page_v2: EntityType = parse(entity_type::PAGE_V2);
+        let page_v2_id: VersionedUri = serde_json::from_value(json!(
+            "https://blockprotocol.org/@alice/types/entity-type/page/v/2"
+        ))
+        .expect("invalid URI");
+
+        let mut validator = EntityValidator::new();
+        validator.add_type(parse::<DataType>(data_type::TEXT_V1));
+        validator.add_type(parse::<PropertyType>(property_type::TEXT_V1));
+        validator.add_type(page_v2);
+        assert!(validator.is_valid(&entity, &page_v2_id));
+
+        validator.replace_type(page_v2_with(|value| {
+            value["properties"] = json!({});
+        }));
+        assert!(!validator.is_valid(&entity, &page_v2_id));
+    }
+}
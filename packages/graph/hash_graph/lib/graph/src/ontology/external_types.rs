@@ -0,0 +1,792 @@
+//! Resolution of ontology types which are hosted outside of the Graph.
+//!
+//! Types, whose URI doesn't satisfy the [`DomainValidator`], can't be created through the Graph.
+//! When a local type references such a type, the [`ExternalTypeResolver`] fetches it (and its own
+//! external references) and persists it in the store. Persisted external types are read-only and
+//! are attributed to [`EXTERNAL_TYPES_ACCOUNT_ID`] by default instead of an actual owner.
+//!
+//! As the referenced URIs are provided by the caller, external types are only fetched from an
+//! explicit list of allowed domains, [`DEFAULT_EXTERNAL_TYPE_DOMAINS`] by default.
+
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt, fs,
+    net::{IpAddr, SocketAddr},
+    path::Path,
+    sync::{Arc, PoisonError, RwLock},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use error_stack::{Context, IntoReport, Report, Result, ResultExt};
+use serde_json::Value;
+use type_system::{
+    uri::{BaseUri, VersionedUri},
+    DataType, EntityType, LinkType, PropertyType,
+};
+use uuid::Uuid;
+
+use crate::{
+    ontology::{
+        domain_validator::DomainValidator, AccountId, PersistedDataType, PersistedEntityType,
+        PersistedLinkType, PersistedPropertyType,
+    },
+    store::{crud::Read, query::Expression, QueryError, Store},
+};
+
+/// The account, which external types are attributed to.
+///
+/// External types are not owned by any account of the Graph, but the store requires an owner. The
+/// account has to exist before external types can be persisted.
+pub const EXTERNAL_TYPES_ACCOUNT_ID: AccountId =
+    AccountId::new(Uuid::from_u128(0x0000_0000_0000_4000_8000_6578_7465_726E));
+
+/// The domains external types are fetched from, unless configured otherwise.
+pub const DEFAULT_EXTERNAL_TYPE_DOMAINS: &[&str] = &["blockprotocol.org"];
+
+#[derive(Debug)]
+pub struct ExternalTypeError;
+
+impl Context for ExternalTypeError {}
+
+impl fmt::Display for ExternalTypeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("could not resolve external ontology type")
+    }
+}
+
+/// Retrieves the schema of an ontology type by its [`VersionedUri`].
+#[async_trait]
+pub trait OntologyTypeFetcher: Send + Sync {
+    /// Returns the JSON schema of the ontology type identified by `uri`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ExternalTypeError`], if the type could not be retrieved
+    async fn fetch(&self, uri: &VersionedUri) -> Result<Value, ExternalTypeError>;
+}
+
+/// The time after which fetching an ontology type over HTTP is aborted.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The maximum size of a fetched schema in bytes.
+const MAX_SCHEMA_SIZE: usize = 1024 * 1024;
+
+/// Fetches ontology types over HTTP from the location their URI points to.
+///
+/// Hosts, which resolve to a loopback, private, link-local or any other non-global address, are
+/// refused, so referenced URIs can't be used to reach services in the network of the Graph.
+/// Redirects are not followed for the same reason. Requests time out after [`FETCH_TIMEOUT`] and
+/// schemas larger than [`MAX_SCHEMA_SIZE`] are rejected.
+#[derive(Debug, Clone)]
+pub struct HttpFetcher {
+    client: reqwest::Client,
+}
+
+impl HttpFetcher {
+    /// Creates a fetcher, which reuses its HTTP client for all requests.
+    ///
+    /// # Errors
+    ///
+    /// - [`ExternalTypeError`], if the HTTP client could not be created
+    pub fn new() -> Result<Self, ExternalTypeError> {
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .dns_resolver(Arc::new(GlobalAddressResolver))
+            .timeout(FETCH_TIMEOUT)
+            .build()
+            .into_report()
+            .change_context(ExternalTypeError)?;
+
+        Ok(Self { client })
+    }
+}
+
+/// Returns if `address` is reachable from the public internet.
+fn is_global(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => address.is_global(),
+        IpAddr::V6(address) => address
+            .to_ipv4_mapped()
+            .map_or_else(|| address.is_global(), |address| address.is_global()),
+    }
+}
+
+/// Looks up `host` and ensures, that it only resolves to global addresses.
+async fn lookup_global_host(host: &str, port: u16) -> Result<Vec<SocketAddr>, ExternalTypeError> {
+    let addresses: Vec<_> = tokio::net::lookup_host((host, port))
+        .await
+        .into_report()
+        .change_context(ExternalTypeError)
+        .attach_printable_lazy(|| host.to_owned())?
+        .collect();
+    if let Some(address) = addresses.iter().find(|address| !is_global(address.ip())) {
+        return Err(Report::new(ExternalTypeError)
+            .attach_printable("host resolves to a non-global address")
+            .attach_printable(address.ip()));
+    }
+    if addresses.is_empty() {
+        return Err(Report::new(ExternalTypeError)
+            .attach_printable("host could not be resolved")
+            .attach_printable(host.to_owned()));
+    }
+
+    Ok(addresses)
+}
+
+/// Ensures, that `url` is an HTTP URL, whose host only resolves to global addresses.
+///
+/// Hosts, which are IP addresses, are not passed to the [`GlobalAddressResolver`] by the HTTP
+/// client, so they have to be checked before sending the request.
+async fn ensure_global_url(url: &reqwest::Url) -> Result<(), ExternalTypeError> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(Report::new(ExternalTypeError)
+            .attach_printable("only HTTP and HTTPS URIs can be fetched")
+            .attach_printable(url.scheme().to_owned()));
+    }
+    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        return Err(Report::new(ExternalTypeError).attach_printable("URI does not contain a host"));
+    };
+
+    lookup_global_host(host, port).await.map(drop)
+}
+
+/// Resolves hosts for the [`HttpFetcher`] and refuses hosts resolving to a non-global address.
+///
+/// Hosts are checked when the connection is established, so a host can't be resolved to a
+/// different address between the check and the request.
+struct GlobalAddressResolver;
+
+impl reqwest::dns::Resolve for GlobalAddressResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addresses: std::result::Result<reqwest::dns::Addrs, Box<dyn Error + Send + Sync>> =
+                match lookup_global_host(name.as_str(), 0).await {
+                    Ok(addresses) => Ok(Box::new(addresses.into_iter())),
+                    Err(report) => Err(format!("{report:?}").into()),
+                };
+            addresses
+        })
+    }
+}
+
+#[async_trait]
+impl OntologyTypeFetcher for HttpFetcher {
+    async fn fetch(&self, uri: &VersionedUri) -> Result<Value, ExternalTypeError> {
+        let url = reqwest::Url::parse(&uri.to_string())
+            .into_report()
+            .change_context(ExternalTypeError)
+            .attach_printable_lazy(|| uri.clone())?;
+        ensure_global_url(&url)
+            .await
+            .attach_printable_lazy(|| uri.clone())?;
+
+        let mut response = self
+            .client
+            .get(url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .into_report()
+            .change_context(ExternalTypeError)
+            .attach_printable_lazy(|| uri.clone())?;
+
+        let exceeds_size_limit = || {
+            Report::new(ExternalTypeError)
+                .attach_printable(format!("schema exceeds {MAX_SCHEMA_SIZE} bytes"))
+                .attach_printable(uri.clone())
+        };
+        if response
+            .content_length()
+            .map_or(false, |length| length > MAX_SCHEMA_SIZE as u64)
+        {
+            return Err(exceeds_size_limit());
+        }
+
+        // The content length is not required to be sent, so the limit is checked while reading
+        // the body as well.
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .into_report()
+            .change_context(ExternalTypeError)
+            .attach_printable_lazy(|| uri.clone())?
+        {
+            if body.len() + chunk.len() > MAX_SCHEMA_SIZE {
+                return Err(exceeds_size_limit());
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        serde_json::from_slice(&body)
+            .into_report()
+            .change_context(ExternalTypeError)
+            .attach_printable_lazy(|| uri.clone())
+    }
+}
+
+/// Serves ontology types from JSON files in a local directory instead of the network.
+///
+/// Every `.json` file in the directory (including subdirectories) containing an `$id` is served
+/// under that URI. This is mainly intended for tests and offline setups.
+#[derive(Debug, Clone, Default)]
+pub struct FixtureFetcher {
+    ontology_types: HashMap<String, Value>,
+}
+
+impl FixtureFetcher {
+    /// Reads all ontology types from `directory`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ExternalTypeError`], if the directory or one of the files could not be read
+    pub fn from_directory(directory: impl AsRef<Path>) -> Result<Self, ExternalTypeError> {
+        let mut fetcher = Self::default();
+        fetcher.read_directory(directory.as_ref())?;
+        Ok(fetcher)
+    }
+
+    fn read_directory(&mut self, directory: &Path) -> Result<(), ExternalTypeError> {
+        let entries = fs::read_dir(directory)
+            .into_report()
+            .change_context(ExternalTypeError)
+            .attach_printable_lazy(|| directory.display().to_string())?;
+
+        for entry in entries {
+            let path = entry
+                .into_report()
+                .change_context(ExternalTypeError)?
+                .path();
+
+            if path.is_dir() {
+                self.read_directory(&path)?;
+            } else if path
+                .extension()
+                .map_or(false, |extension| extension == "json")
+            {
+                let ontology_type: Value = serde_json::from_slice(
+                    &fs::read(&path)
+                        .into_report()
+                        .change_context(ExternalTypeError)
+                        .attach_printable_lazy(|| path.display().to_string())?,
+                )
+                .into_report()
+                .change_context(ExternalTypeError)
+                .attach_printable_lazy(|| path.display().to_string())?;
+
+                if let Some(id) = ontology_type.get("$id").and_then(Value::as_str) {
+                    self.ontology_types.insert(id.to_owned(), ontology_type);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OntologyTypeFetcher for FixtureFetcher {
+    async fn fetch(&self, uri: &VersionedUri) -> Result<Value, ExternalTypeError> {
+        self.ontology_types
+            .get(&uri.to_string())
+            .cloned()
+            .ok_or_else(|| {
+                Report::new(ExternalTypeError)
+                    .attach_printable("ontology type is not available as fixture")
+                    .attach_printable(uri.clone())
+            })
+    }
+}
+
+enum ExternalOntologyType {
+    DataType(DataType),
+    PropertyType(PropertyType),
+    LinkType(LinkType),
+    EntityType(EntityType),
+}
+
+impl ExternalOntologyType {
+    fn parse(schema: Value) -> Result<Self, ExternalTypeError> {
+        let kind = schema
+            .get("kind")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_owned();
+
+        match kind.as_str() {
+            "dataType" => DataType::try_from(schema)
+                .into_report()
+                .map(Self::DataType)
+                .change_context(ExternalTypeError),
+            "propertyType" => PropertyType::try_from(schema)
+                .into_report()
+                .map(Self::PropertyType)
+                .change_context(ExternalTypeError),
+            "linkType" => LinkType::try_from(schema)
+                .into_report()
+                .map(Self::LinkType)
+                .change_context(ExternalTypeError),
+            "entityType" => EntityType::try_from(schema)
+                .into_report()
+                .map(Self::EntityType)
+                .change_context(ExternalTypeError),
+            _ => Err(Report::new(ExternalTypeError)
+                .attach_printable("unknown kind of ontology type")
+                .attach_printable(kind)),
+        }
+    }
+}
+
+/// Collects all [`VersionedUri`]s referenced by the schema of an ontology type.
+///
+/// This includes `$ref`s and the link types used as keys in the `links` of entity types.
+pub(crate) fn collect_references(schema: &Value, references: &mut Vec<VersionedUri>) {
+    match schema {
+        Value::Object(object) => {
+            for (key, value) in object {
+                if key == "$ref" {
+                    if let Ok(uri) = serde_json::from_value(value.clone()) {
+                        references.push(uri);
+                    }
+                } else if key == "links" {
+                    if let Value::Object(links) = value {
+                        references.extend(links.keys().filter_map(|link_type| {
+                            serde_json::from_value(Value::String(link_type.clone())).ok()
+                        }));
+                    }
+                    collect_references(value, references);
+                } else {
+                    collect_references(value, references);
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_references(value, references);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Checks if an ontology type of any kind identified by `uri` is persisted in the `store`.
+pub(crate) async fn is_persisted<S: Store>(
+    store: &S,
+    uri: &VersionedUri,
+) -> Result<bool, QueryError> {
+    let query = Expression::for_versioned_uri(uri);
+
+    Ok(!Read::<PersistedDataType>::read(store, &query)
+        .await?
+        .is_empty()
+        || !Read::<PersistedPropertyType>::read(store, &query)
+            .await?
+            .is_empty()
+        || !Read::<PersistedLinkType>::read(store, &query)
+            .await?
+            .is_empty()
+        || !Read::<PersistedEntityType>::read(store, &query)
+            .await?
+            .is_empty())
+}
+
+/// Checks if any version of an ontology type identified by `base_uri` is persisted in the
+/// `store`.
+async fn is_base_uri_persisted<S: Store>(
+    store: &S,
+    base_uri: &BaseUri,
+) -> Result<bool, QueryError> {
+    let query = Expression::for_base_uri(base_uri);
+
+    Ok(!Read::<PersistedDataType>::read(store, &query)
+        .await?
+        .is_empty()
+        || !Read::<PersistedPropertyType>::read(store, &query)
+            .await?
+            .is_empty()
+        || !Read::<PersistedLinkType>::read(store, &query)
+            .await?
+            .is_empty()
+        || !Read::<PersistedEntityType>::read(store, &query)
+            .await?
+            .is_empty())
+}
+
+/// Fetches and persists ontology types, which are referenced but not hosted by the Graph.
+///
+/// Only types hosted on one of the allowed domains are fetched, see [`with_allowed_domains`].
+///
+/// Fetched schemas are cached and the cache never expires as ontology types are immutable once
+/// published. Single entries can be removed explicitly with [`invalidate`].
+///
+/// [`with_allowed_domains`]: Self::with_allowed_domains
+/// [`invalidate`]: Self::invalidate
+#[derive(Clone)]
+pub struct ExternalTypeResolver {
+    domain_validator: DomainValidator,
+    allowed_domains: Arc<[String]>,
+    fetcher: Arc<dyn OntologyTypeFetcher>,
+    cache: Arc<RwLock<HashMap<String, Value>>>,
+    account_id: AccountId,
+}
+
+impl ExternalTypeResolver {
+    /// Creates a resolver, which fetches all types not satisfying `domain_validator` with
+    /// `fetcher`.
+    #[must_use]
+    pub fn new(
+        domain_validator: DomainValidator,
+        fetcher: impl OntologyTypeFetcher + 'static,
+    ) -> Self {
+        Self {
+            domain_validator,
+            allowed_domains: DEFAULT_EXTERNAL_TYPE_DOMAINS
+                .iter()
+                .map(|&domain| domain.to_owned())
+                .collect(),
+            fetcher: Arc::new(fetcher),
+            cache: Arc::default(),
+            account_id: EXTERNAL_TYPES_ACCOUNT_ID,
+        }
+    }
+
+    /// Only fetches types hosted on one of `domains` or their subdomains instead of
+    /// [`DEFAULT_EXTERNAL_TYPE_DOMAINS`].
+    #[must_use]
+    pub fn with_allowed_domains(mut self, domains: impl IntoIterator<Item = String>) -> Self {
+        self.allowed_domains = domains
+            .into_iter()
+            .map(|domain| domain.to_lowercase())
+            .collect();
+        self
+    }
+
+    /// Attributes persisted external types to `account_id` instead of
+    /// [`EXTERNAL_TYPES_ACCOUNT_ID`].
+    #[must_use]
+    pub const fn with_account_id(mut self, account_id: AccountId) -> Self {
+        self.account_id = account_id;
+        self
+    }
+
+    /// Returns if the type identified by `uri` is hosted outside of the Graph.
+    #[must_use]
+    pub fn is_external(&self, uri: &VersionedUri) -> bool {
+        !self.domain_validator.validate_url(uri.base_uri().as_str())
+    }
+
+    /// Returns if the type identified by `uri` is hosted on one of the allowed domains.
+    #[must_use]
+    pub fn is_allowed(&self, uri: &VersionedUri) -> bool {
+        reqwest::Url::parse(uri.base_uri().as_str())
+            .ok()
+            .as_ref()
+            .and_then(reqwest::Url::host_str)
+            .map_or(false, |host| {
+                self.allowed_domains.iter().any(|domain| {
+                    host == domain.as_str()
+                        || host
+                            .strip_suffix(domain.as_str())
+                            .map_or(false, |subdomain| subdomain.ends_with('.'))
+                })
+            })
+    }
+
+    /// Removes the type identified by `uri` from the cache, so it's fetched again on next use.
+    pub fn invalidate(&self, uri: &VersionedUri) {
+        self.cache
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&uri.to_string());
+    }
+
+    async fn fetch(&self, uri: &VersionedUri) -> Result<Value, ExternalTypeError> {
+        let key = uri.to_string();
+        if let Some(schema) = self
+            .cache
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key)
+        {
+            return Ok(schema.clone());
+        }
+
+        tracing::info!(%uri, "fetching external ontology type");
+        let schema = self.fetcher.fetch(uri).await?;
+        if schema.get("$id").and_then(Value::as_str) != Some(key.as_str()) {
+            return Err(Report::new(ExternalTypeError)
+                .attach_printable("fetched schema has a different `$id`")
+                .attach_printable(uri.clone()));
+        }
+
+        self.cache
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(key, schema.clone());
+
+        Ok(schema)
+    }
+
+    /// Fetches and persists all external types referenced by `ontology_type`, which are not
+    /// persisted in the `store`, yet.
+    ///
+    /// Returns the [`VersionedUri`]s of the types which were persisted.
+    ///
+    /// # Errors
+    ///
+    /// - [`ExternalTypeError`], if a referenced type could not be fetched or persisted, e.g.
+    ///   because the account, which external types are attributed to, does not exist
+    /// - [`ExternalTypeError`], if a referenced type is not hosted on one of the allowed domains
+    pub async fn resolve<S: Store + Send>(
+        &self,
+        store: &mut S,
+        ontology_type: impl Into<Value> + Send,
+    ) -> Result<Vec<VersionedUri>, ExternalTypeError> {
+        let mut references = Vec::new();
+        collect_references(&ontology_type.into(), &mut references);
+        self.resolve_uris(store, references).await
+    }
+
+    /// Fetches and persists the external types identified by `uris` and their references, which
+    /// are not persisted in the `store`, yet.
+    ///
+    /// Returns the [`VersionedUri`]s of the types which were persisted.
+    ///
+    /// # Errors
+    ///
+    /// - [`ExternalTypeError`], if a type could not be fetched or persisted, e.g. because the
+    ///   account, which external types are attributed to, does not exist
+    /// - [`ExternalTypeError`], if a type is not hosted on one of the allowed domains
+    pub async fn resolve_uris<S: Store + Send>(
+        &self,
+        store: &mut S,
+        uris: impl IntoIterator<Item = VersionedUri> + Send,
+    ) -> Result<Vec<VersionedUri>, ExternalTypeError> {
+        let mut pending: Vec<_> = uris.into_iter().collect();
+
+        // Fetch all missing types first, so they can be inserted in dependency order afterwards.
+        let mut missing = HashMap::new();
+        let mut dependencies = HashMap::new();
+        let mut visited = HashSet::new();
+        while let Some(uri) = pending.pop() {
+            if !self.is_external(&uri)
+                || !visited.insert(uri.clone())
+                || is_persisted(store, &uri)
+                    .await
+                    .change_context(ExternalTypeError)?
+            {
+                continue;
+            }
+            if !self.is_allowed(&uri) {
+                return Err(Report::new(ExternalTypeError)
+                    .attach_printable("external types are not allowed from this domain")
+                    .attach_printable(uri));
+            }
+
+            let schema = self.fetch(&uri).await?;
+            let mut references = Vec::new();
+            collect_references(&schema, &mut references);
+            references.retain(|reference| reference != &uri);
+            pending.extend(references.iter().cloned());
+            dependencies.insert(uri.clone(), references);
+            missing.insert(uri, schema);
+        }
+
+        let mut insertion_order = Vec::with_capacity(missing.len());
+        let mut sorted = HashSet::new();
+        for uri in missing.keys() {
+            sort_dependencies(uri, &dependencies, &mut sorted, &mut insertion_order);
+        }
+
+        for uri in &insertion_order {
+            self.persist(store, uri, missing[uri].clone())
+                .await
+                .attach_printable_lazy(|| uri.clone())?;
+
+            tracing::info!(%uri, "persisted external ontology type");
+        }
+
+        Ok(insertion_order)
+    }
+
+    /// Persists the fetched `schema` of the type identified by `uri`.
+    ///
+    /// If another version of the type is already persisted, the type is added as a new version.
+    async fn persist<S: Store + Send>(
+        &self,
+        store: &mut S,
+        uri: &VersionedUri,
+        schema: Value,
+    ) -> Result<(), ExternalTypeError> {
+        let account_id = self.account_id;
+        let is_update = is_base_uri_persisted(store, uri.base_uri())
+            .await
+            .change_context(ExternalTypeError)?;
+
+        match ExternalOntologyType::parse(schema)? {
+            ExternalOntologyType::DataType(data_type) if is_update => store
+                .update_data_type(data_type, account_id)
+                .await
+                .map(drop)
+                .change_context(ExternalTypeError),
+            ExternalOntologyType::DataType(data_type) => store
+                .create_data_type(data_type, account_id, account_id)
+                .await
+                .map(drop)
+                .change_context(ExternalTypeError),
+            ExternalOntologyType::PropertyType(property_type) if is_update => store
+                .update_property_type(property_type, account_id)
+                .await
+                .map(drop)
+                .change_context(ExternalTypeError),
+            ExternalOntologyType::PropertyType(property_type) => store
+                .create_property_type(property_type, account_id, account_id)
+                .await
+                .map(drop)
+                .change_context(ExternalTypeError),
+            ExternalOntologyType::LinkType(link_type) if is_update => store
+                .update_link_type(link_type, account_id)
+                .await
+                .map(drop)
+                .change_context(ExternalTypeError),
+            ExternalOntologyType::LinkType(link_type) => store
+                .create_link_type(link_type, account_id, account_id)
+                .await
+                .map(drop)
+                .change_context(ExternalTypeError),
+            ExternalOntologyType::EntityType(entity_type) if is_update => store
+                .update_entity_type(entity_type, account_id)
+                .await
+                .map(drop)
+                .change_context(ExternalTypeError),
+            ExternalOntologyType::EntityType(entity_type) => store
+                .create_entity_type(entity_type, account_id, account_id)
+                .await
+                .map(drop)
+                .change_context(ExternalTypeError),
+        }
+    }
+}
+
+/// Appends `uri` to `insertion_order` after all of its `dependencies`.
+///
+/// Only types contained in `dependencies` are appended. Cyclic references are inserted in an
+/// arbitrary order.
+pub(crate) fn sort_dependencies(
+    uri: &VersionedUri,
+    dependencies: &HashMap<VersionedUri, Vec<VersionedUri>>,
+    sorted: &mut HashSet<VersionedUri>,
+    insertion_order: &mut Vec<VersionedUri>,
+) {
+    if !sorted.insert(uri.clone()) {
+        return;
+    }
+    if let Some(references) = dependencies.get(uri) {
+        for reference in references {
+            sort_dependencies(reference, dependencies, sorted, insertion_order);
+        }
+        insertion_order.push(uri.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use super::*;
+
+    fn uri(uri: &str) -> VersionedUri {
+        serde_json::from_value(Value::String(uri.to_owned())).expect("invalid URI")
+    }
+
+    fn resolver() -> ExternalTypeResolver {
+        ExternalTypeResolver::new(
+            DomainValidator::new(
+                Regex::new(r"http://localhost:3000/@(?P<shortname>[\w-]+)/types/(?P<kind>(?:data-type)|(?:property-type)|(?:entity-type)|(?:link-type))/[\w-]+/")
+                    .expect("invalid regex"),
+            ),
+            FixtureFetcher::default(),
+        )
+    }
+
+    #[test]
+    fn allowed_domains() {
+        let resolver = resolver();
+        assert!(resolver.is_allowed(&uri(
+            "https://blockprotocol.org/@blockprotocol/types/data-type/text/v/1"
+        )));
+        assert!(resolver.is_allowed(&uri(
+            "https://types.blockprotocol.org/@blockprotocol/types/data-type/text/v/1"
+        )));
+        assert!(!resolver.is_allowed(&uri(
+            "https://evilblockprotocol.org/@blockprotocol/types/data-type/text/v/1"
+        )));
+        assert!(!resolver.is_allowed(&uri("http://169.254.169.254/latest/meta-data/v/1")));
+
+        let resolver = resolver.with_allowed_domains(["Example.com".to_owned()]);
+        assert!(resolver.is_allowed(&uri("https://example.com/@alice/types/data-type/text/v/1")));
+        assert!(!resolver.is_allowed(&uri(
+            "https://blockprotocol.org/@blockprotocol/types/data-type/text/v/1"
+        )));
+    }
+
+    #[test]
+    fn global_addresses() {
+        for address in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fe80::1",
+            "fc00::1",
+            "::ffff:127.0.0.1",
+            "::ffff:10.0.0.1",
+        ] {
+            assert!(
+                !is_global(address.parse().expect("invalid address")),
+                "{address} is not global"
+            );
+        }
+
+        for address in ["1.1.1.1", "2606:4700:4700::1111", "::ffff:1.1.1.1"] {
+            assert!(
+                is_global(address.parse().expect("invalid address")),
+                "{address} is global"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn private_hosts_are_not_fetched() {
+        for url in [
+            "http://127.0.0.1:4000/@alice/types/data-type/text/v/1",
+            "http://localhost/@alice/types/data-type/text/v/1",
+            "http://[::1]/@alice/types/data-type/text/v/1",
+            "file:///etc/passwd",
+        ] {
+            let url = reqwest::Url::parse(url).expect("invalid URL");
+            ensure_global_url(&url)
+                .await
+                .expect_err("private host was resolved");
+        }
+    }
+
+    #[tokio::test]
+    async fn disallowed_domains_are_not_resolved() {
+        let resolver = resolver().with_allowed_domains(["example.com".to_owned()]);
+        let error = resolver
+            .resolve_uris(&mut crate::store::MemoryStore::new(), [uri(
+                "https://blockprotocol.org/@blockprotocol/types/data-type/text/v/1",
+            )])
+            .await
+            .expect_err("type from a disallowed domain was resolved");
+        assert!(format!("{error:?}").contains("not allowed"));
+    }
+}
@@ -1,15 +1,17 @@
 //! TODO: DOC
 
+mod compatibility;
 mod data_type;
 pub mod domain_validator;
 mod entity_type;
+pub mod external_types;
 mod link_type;
 mod property_type;
 
 use core::fmt;
 
 use error_stack::{Context, IntoReport, Result, ResultExt};
-use serde::{Deserialize, Serialize, Serializer};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json;
 use tokio_postgres::types::{FromSql, ToSql};
 use type_system::{uri::VersionedUri, DataType, EntityType, LinkType, PropertyType};
@@ -17,7 +19,13 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 pub use self::{
-    data_type::DataTypeQueryPath, entity_type::EntityTypeQueryPath, link_type::LinkTypeQueryPath,
+    compatibility::{
+        BreakingChange, BreakingChangeKind, CompatibilityReport, EntityValidator,
+        UpdateCompatibility,
+    },
+    data_type::DataTypeQueryPath,
+    entity_type::EntityTypeQueryPath,
+    link_type::LinkTypeQueryPath,
     property_type::PropertyTypeQueryPath,
 };
 
@@ -139,6 +147,14 @@ where
     serde_json::Value::from(ontology_type.clone()).serialize(serializer)
 }
 
+fn deserialize_ontology_type<'de, T, D>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    T: TryFrom<serde_json::Value, Error: Context>,
+    D: Deserializer<'de>,
+{
+    T::try_from(serde_json::Value::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
 /// Distance to explore when querying a rooted subgraph in the ontology.
 ///
 /// Ontology records may have references to other records, e.g. a [`PropertyType`] may reference
@@ -178,10 +194,13 @@ where
 /// _property type_ references is then resolved to a depth of `property_type_query_depth`.
 pub type OntologyQueryDepth = u8;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct PersistedDataType {
     #[schema(value_type = VAR_DATA_TYPE)]
-    #[serde(serialize_with = "serialize_ontology_type")]
+    #[serde(
+        serialize_with = "serialize_ontology_type",
+        deserialize_with = "deserialize_ontology_type"
+    )]
     inner: DataType,
     metadata: PersistedOntologyMetadata,
 }
@@ -203,10 +222,13 @@ impl PersistedDataType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct PersistedPropertyType {
     #[schema(value_type = VAR_PROPERTY_TYPE)]
-    #[serde(serialize_with = "serialize_ontology_type")]
+    #[serde(
+        serialize_with = "serialize_ontology_type",
+        deserialize_with = "deserialize_ontology_type"
+    )]
     inner: PropertyType,
     metadata: PersistedOntologyMetadata,
 }
@@ -228,10 +250,13 @@ impl PersistedPropertyType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct PersistedLinkType {
     #[schema(value_type = VAR_LINK_TYPE)]
-    #[serde(serialize_with = "serialize_ontology_type")]
+    #[serde(
+        serialize_with = "serialize_ontology_type",
+        deserialize_with = "deserialize_ontology_type"
+    )]
     inner: LinkType,
     metadata: PersistedOntologyMetadata,
 }
@@ -253,13 +278,48 @@ impl PersistedLinkType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+/// Marks an ontology type as deprecated.
+///
+/// Deprecated types can still be read and referenced, but new data should use the replacement if
+/// one is specified.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Deprecation {
+    deprecated_by_id: AccountId,
+    #[schema(value_type = Option<String>)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    replaced_by: Option<VersionedUri>,
+}
+
+impl Deprecation {
+    #[must_use]
+    pub const fn new(deprecated_by_id: AccountId, replaced_by: Option<VersionedUri>) -> Self {
+        Self {
+            deprecated_by_id,
+            replaced_by,
+        }
+    }
+
+    #[must_use]
+    pub const fn deprecated_by_id(&self) -> AccountId {
+        self.deprecated_by_id
+    }
+
+    #[must_use]
+    pub const fn replaced_by(&self) -> Option<&VersionedUri> {
+        self.replaced_by.as_ref()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PersistedOntologyMetadata {
     identifier: PersistedOntologyIdentifier,
     created_by_id: AccountId,
     updated_by_id: AccountId,
     removed_by_id: Option<AccountId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deprecation: Option<Deprecation>,
 }
 
 impl PersistedOntologyMetadata {
@@ -275,19 +335,45 @@ impl PersistedOntologyMetadata {
             created_by_id,
             updated_by_id,
             removed_by_id,
+            deprecation: None,
         }
     }
 
+    #[must_use]
+    pub fn with_deprecation(mut self, deprecation: Option<Deprecation>) -> Self {
+        self.deprecation = deprecation;
+        self
+    }
+
     #[must_use]
     pub const fn identifier(&self) -> &PersistedOntologyIdentifier {
         &self.identifier
     }
+
+    #[must_use]
+    pub const fn created_by_id(&self) -> AccountId {
+        self.created_by_id
+    }
+
+    #[must_use]
+    pub const fn updated_by_id(&self) -> AccountId {
+        self.updated_by_id
+    }
+
+    /// Returns the [`Deprecation`] of the type if it has been deprecated.
+    #[must_use]
+    pub const fn deprecation(&self) -> Option<&Deprecation> {
+        self.deprecation.as_ref()
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct PersistedEntityType {
     #[schema(value_type = VAR_ENTITY_TYPE)]
-    #[serde(serialize_with = "serialize_ontology_type")]
+    #[serde(
+        serialize_with = "serialize_ontology_type",
+        deserialize_with = "deserialize_ontology_type"
+    )]
     inner: EntityType,
     metadata: PersistedOntologyMetadata,
 }
@@ -1,7 +1,8 @@
-use serde::{Deserialize, Serialize, Serializer};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json;
 use type_system::uri::VersionedUri;
 use utoipa::{openapi, ToSchema};
+use uuid::Uuid;
 
 use crate::knowledge::EntityId;
 
@@ -43,6 +44,45 @@ impl Serialize for GraphElementIdentifier {
     }
 }
 
+impl<'de> Deserialize<'de> for GraphElementIdentifier {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let identifier = String::deserialize(deserializer)?;
+
+        if let Some(link_id) = identifier.strip_prefix("<SOURCE>") {
+            let (source_entity_id, link_id) = link_id
+                .split_once("<TARGET>")
+                .ok_or_else(|| de::Error::custom("missing `<TARGET>` in link identifier"))?;
+            let (target_entity_id, link_type_id) = link_id
+                .split_once("<TYPE>")
+                .ok_or_else(|| de::Error::custom("missing `<TYPE>` in link identifier"))?;
+
+            return Ok(Self::Temporary(LinkId {
+                source_entity_id: EntityId::new(
+                    Uuid::parse_str(source_entity_id).map_err(de::Error::custom)?,
+                ),
+                target_entity_id: EntityId::new(
+                    Uuid::parse_str(target_entity_id).map_err(de::Error::custom)?,
+                ),
+                link_type_id: serde_json::from_value(serde_json::Value::String(
+                    link_type_id.to_owned(),
+                ))
+                .map_err(de::Error::custom)?,
+            }));
+        }
+
+        if let Ok(uuid) = Uuid::parse_str(&identifier) {
+            return Ok(Self::KnowledgeGraphElementId(EntityId::new(uuid)));
+        }
+
+        serde_json::from_value(serde_json::Value::String(identifier))
+            .map(Self::OntologyElementId)
+            .map_err(de::Error::custom)
+    }
+}
+
 // TODO: We have to do this because utoipa doesn't understand serde untagged
 //  https://github.com/juhaku/utoipa/issues/320
 impl ToSchema for GraphElementIdentifier {
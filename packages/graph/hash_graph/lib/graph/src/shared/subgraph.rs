@@ -4,6 +4,7 @@ use std::collections::{
 };
 
 use serde::{Deserialize, Serialize};
+use type_system::uri::VersionedUri;
 use utoipa::{openapi, ToSchema};
 
 use crate::{
@@ -16,7 +17,7 @@ use crate::{
     store::query::Expression,
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "kind", content = "inner")]
 pub enum Vertex {
@@ -64,7 +65,7 @@ impl ToSchema for Vertex {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum EdgeKind {
     /// An entity has a link
@@ -77,10 +78,13 @@ pub enum EdgeKind {
     References,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct OutwardEdge {
     pub edge_kind: EdgeKind,
+    /// If `true`, the edge points against the direction of the [`EdgeKind`], e.g. from an entity
+    /// to a link it's the destination of.
+    pub reversed: bool,
     pub destination: GraphElementIdentifier,
 }
 
@@ -100,6 +104,18 @@ pub struct GraphResolveDepths {
     // TODO: what is this?
     #[schema(value_type = number)]
     pub link_target_entity_resolve_depth: KnowledgeGraphQueryDepth,
+    /// The depth up to which links pointing to an entity are resolved.
+    #[serde(default)]
+    #[schema(value_type = number)]
+    pub incoming_link_resolve_depth: KnowledgeGraphQueryDepth,
+    /// The depth up to which the source entities of links are resolved.
+    #[serde(default)]
+    #[schema(value_type = number)]
+    pub link_source_entity_resolve_depth: KnowledgeGraphQueryDepth,
+    /// The depth up to which the types referencing an ontology type are resolved.
+    #[serde(default)]
+    #[schema(value_type = number)]
+    pub referencing_type_resolve_depth: OntologyQueryDepth,
 }
 
 impl GraphResolveDepths {
@@ -112,17 +128,56 @@ impl GraphResolveDepths {
             link_type_resolve_depth: 0,
             link_resolve_depth: 0,
             link_target_entity_resolve_depth: 0,
+            incoming_link_resolve_depth: 0,
+            link_source_entity_resolve_depth: 0,
+            referencing_type_resolve_depth: 0,
+        }
+    }
+
+    /// Lowers every depth above the corresponding depth of `max` to that depth.
+    #[must_use]
+    pub fn limited_to(self, max: Self) -> Self {
+        Self {
+            data_type_resolve_depth: self
+                .data_type_resolve_depth
+                .min(max.data_type_resolve_depth),
+            property_type_resolve_depth: self
+                .property_type_resolve_depth
+                .min(max.property_type_resolve_depth),
+            entity_type_resolve_depth: self
+                .entity_type_resolve_depth
+                .min(max.entity_type_resolve_depth),
+            link_type_resolve_depth: self
+                .link_type_resolve_depth
+                .min(max.link_type_resolve_depth),
+            link_resolve_depth: self.link_resolve_depth.min(max.link_resolve_depth),
+            link_target_entity_resolve_depth: self
+                .link_target_entity_resolve_depth
+                .min(max.link_target_entity_resolve_depth),
+            incoming_link_resolve_depth: self
+                .incoming_link_resolve_depth
+                .min(max.incoming_link_resolve_depth),
+            link_source_entity_resolve_depth: self
+                .link_source_entity_resolve_depth
+                .min(max.link_source_entity_resolve_depth),
+            referencing_type_resolve_depth: self
+                .referencing_type_resolve_depth
+                .min(max.referencing_type_resolve_depth),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Subgraph {
     pub roots: Vec<GraphElementIdentifier>,
     pub vertices: HashMap<GraphElementIdentifier, Vertex>,
     pub edges: Edges,
     pub depths: GraphResolveDepths,
+    /// `true`, if the subgraph was not fully resolved because the query exceeded the limits of
+    /// the store.
+    #[serde(default)]
+    pub truncated: bool,
 }
 
 impl Subgraph {
@@ -133,6 +188,7 @@ impl Subgraph {
             vertices: HashMap::new(),
             edges: Edges::new(),
             depths,
+            truncated: false,
         }
     }
 }
@@ -143,21 +199,26 @@ impl Extend<Self> for Subgraph {
             self.roots.extend(subgraph.roots.into_iter());
             self.vertices.extend(subgraph.vertices.into_iter());
             self.edges.extend(subgraph.edges.into_iter());
+            self.truncated |= subgraph.truncated;
         }
     }
 }
 
 /// An [`Expression`] to query the datastore, recursively resolving according to the
 /// [`GraphResolveDepths`]
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 pub struct StructuralQuery {
     #[serde(rename = "query")]
     pub expression: Expression,
     pub graph_resolve_depths: GraphResolveDepths,
+    /// If provided, only links of these types are followed when resolving the subgraph.
+    #[serde(default)]
+    #[schema(value_type = Option<Vec<String>>)]
+    pub link_type_filter: Option<Vec<VersionedUri>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct Edges(HashMap<GraphElementIdentifier, HashSet<OutwardEdge>>);
 
 impl Edges {
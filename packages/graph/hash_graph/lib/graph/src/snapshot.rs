@@ -0,0 +1,1170 @@
+//! Export and import of the contents of a [`Store`].
+//!
+//! A [`Snapshot`] contains all ontology types, entities and links, optionally restricted to the
+//! ones owned by a single account and the ontology types they reference. It is written as
+//! newline-delimited JSON (NDJSON): The first line is a [`SnapshotHeader`] followed by one
+//! [`SnapshotEntry`] per line. Ontology types and entities are contained in all of their versions,
+//! links only if they were not removed.
+//!
+//! Restoring a snapshot preserves the identifiers, versions and metadata of the records. Records,
+//! which already exist in the store, are skipped, so restoring the same snapshot twice is a no-op.
+//!
+//! Snapshots can be processed without holding them in memory at once: [`Snapshot::export_ndjson`]
+//! streams the lines of a snapshot and [`Snapshot::read_lines`] together with
+//! [`Snapshot::restore_entries`] restores a snapshot while it's read.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    future::Future,
+    io::{self, BufRead, Write},
+    sync::Arc,
+};
+
+use error_stack::{Context, IntoReport, Report, Result, ResultExt};
+use futures::{future, pin_mut, stream, Stream, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use type_system::{uri::VersionedUri, DataType, EntityType, LinkType, PropertyType};
+use utoipa::ToSchema;
+
+use crate::{
+    knowledge::{EntityId, PersistedEntity, PersistedLink},
+    ontology::{
+        external_types::{collect_references, is_persisted, sort_dependencies},
+        AccountId, Deprecation, PersistedDataType, PersistedEntityType, PersistedLinkType,
+        PersistedOntologyMetadata, PersistedPropertyType,
+    },
+    store::{crud::Read, query::Expression, Store, StorePool, StoreTransaction},
+};
+
+/// The version of the snapshot format written by [`Snapshot::write`].
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub struct SnapshotError;
+
+impl Context for SnapshotError {}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("could not process snapshot")
+    }
+}
+
+/// The snapshot is malformed or references records, which are neither contained in the snapshot
+/// nor in the store.
+#[derive(Debug)]
+pub struct InvalidSnapshot;
+
+impl Context for InvalidSnapshot {}
+
+impl fmt::Display for InvalidSnapshot {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("the snapshot is invalid")
+    }
+}
+
+/// The first line of a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "header", rename_all = "camelCase")]
+pub struct SnapshotHeader {
+    version: u32,
+    /// The account the snapshot is restricted to.
+    account_id: Option<AccountId>,
+}
+
+impl SnapshotHeader {
+    #[must_use]
+    pub const fn version(&self) -> u32 {
+        self.version
+    }
+
+    #[must_use]
+    pub const fn account_id(&self) -> Option<AccountId> {
+        self.account_id
+    }
+}
+
+/// A single version of an ontology type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OntologyTypeSnapshot {
+    schema: Value,
+    owned_by_id: AccountId,
+    created_by_id: AccountId,
+    updated_by_id: AccountId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deprecation: Option<Deprecation>,
+}
+
+impl OntologyTypeSnapshot {
+    fn new(schema: impl Into<Value>, metadata: &PersistedOntologyMetadata) -> Self {
+        Self {
+            schema: schema.into(),
+            owned_by_id: metadata.identifier().owned_by_id(),
+            created_by_id: metadata.created_by_id(),
+            updated_by_id: metadata.updated_by_id(),
+            deprecation: metadata.deprecation().cloned(),
+        }
+    }
+
+    /// Returns all accounts referenced by the ontology type.
+    fn account_ids(&self) -> Vec<AccountId> {
+        let mut account_ids = vec![self.owned_by_id, self.created_by_id, self.updated_by_id];
+        account_ids.extend(self.deprecation.as_ref().map(Deprecation::deprecated_by_id));
+        account_ids
+    }
+
+    fn id(&self) -> Result<VersionedUri, InvalidSnapshot> {
+        self.schema
+            .get("$id")
+            .cloned()
+            .ok_or_else(|| Report::new(InvalidSnapshot).attach_printable("schema has no `$id`"))
+            .and_then(|id| {
+                serde_json::from_value(id)
+                    .into_report()
+                    .change_context(InvalidSnapshot)
+            })
+    }
+}
+
+/// A record contained in a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SnapshotEntry {
+    DataType(OntologyTypeSnapshot),
+    PropertyType(OntologyTypeSnapshot),
+    LinkType(OntologyTypeSnapshot),
+    EntityType(OntologyTypeSnapshot),
+    Entity(PersistedEntity),
+    Link(PersistedLink),
+}
+
+impl SnapshotEntry {
+    /// Returns the account owning the record.
+    #[must_use]
+    pub const fn owned_by_id(&self) -> AccountId {
+        match self {
+            Self::DataType(ontology_type)
+            | Self::PropertyType(ontology_type)
+            | Self::LinkType(ontology_type)
+            | Self::EntityType(ontology_type) => ontology_type.owned_by_id,
+            Self::Entity(entity) => entity.metadata().identifier().owned_by_id(),
+            Self::Link(link) => link.metadata().owned_by_id(),
+        }
+    }
+}
+
+/// The number of records restored from a [`Snapshot`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreSummary {
+    /// The number of accounts, which did not exist before.
+    accounts: usize,
+    /// The number of restored versions of ontology types.
+    ontology_types: usize,
+    /// The number of restored versions of entities.
+    entities: usize,
+    /// The number of restored links.
+    links: usize,
+    /// The number of records, which already existed and were skipped.
+    skipped: usize,
+}
+
+impl RestoreSummary {
+    #[must_use]
+    pub const fn accounts(&self) -> usize {
+        self.accounts
+    }
+
+    #[must_use]
+    pub const fn ontology_types(&self) -> usize {
+        self.ontology_types
+    }
+
+    #[must_use]
+    pub const fn entities(&self) -> usize {
+        self.entities
+    }
+
+    #[must_use]
+    pub const fn links(&self) -> usize {
+        self.links
+    }
+
+    #[must_use]
+    pub const fn skipped(&self) -> usize {
+        self.skipped
+    }
+}
+
+/// A parsed ontology type contained in a [`Snapshot`].
+enum OntologyType {
+    DataType(DataType),
+    PropertyType(PropertyType),
+    LinkType(LinkType),
+    EntityType(EntityType),
+}
+
+/// The kinds of records in the order they are contained in a snapshot.
+#[derive(Debug, Copy, Clone)]
+enum RecordKind {
+    DataType,
+    PropertyType,
+    LinkType,
+    EntityType,
+    Entity,
+    Link,
+}
+
+impl RecordKind {
+    const ALL: [Self; 6] = [
+        Self::DataType,
+        Self::PropertyType,
+        Self::LinkType,
+        Self::EntityType,
+        Self::Entity,
+        Self::Link,
+    ];
+}
+
+/// Reads the schemas of all ontology types matching `query` from the `store`.
+async fn read_ontology_schemas<S: Store>(
+    store: &S,
+    query: &Expression,
+) -> Result<Vec<Value>, SnapshotError> {
+    let mut schemas = Vec::new();
+    let data_types: Vec<PersistedDataType> = Read::read(store, query)
+        .await
+        .change_context(SnapshotError)?;
+    schemas.extend(
+        data_types
+            .into_iter()
+            .map(|data_type| data_type.inner().clone().into()),
+    );
+    let property_types: Vec<PersistedPropertyType> = Read::read(store, query)
+        .await
+        .change_context(SnapshotError)?;
+    schemas.extend(
+        property_types
+            .into_iter()
+            .map(|property_type| property_type.inner().clone().into()),
+    );
+    let link_types: Vec<PersistedLinkType> = Read::read(store, query)
+        .await
+        .change_context(SnapshotError)?;
+    schemas.extend(
+        link_types
+            .into_iter()
+            .map(|link_type| link_type.inner().clone().into()),
+    );
+    let entity_types: Vec<PersistedEntityType> = Read::read(store, query)
+        .await
+        .change_context(SnapshotError)?;
+    schemas.extend(
+        entity_types
+            .into_iter()
+            .map(|entity_type| entity_type.inner().clone().into()),
+    );
+    Ok(schemas)
+}
+
+/// The records contained in a snapshot.
+struct ExportScope {
+    /// The account the snapshot is restricted to.
+    account_id: Option<AccountId>,
+    /// The ontology types, which are not owned by `account_id` but referenced by the records
+    /// owned by it, either directly or transitively.
+    ///
+    /// These are included, so the snapshot can be restored into a store without them.
+    dependencies: Vec<VersionedUri>,
+}
+
+impl ExportScope {
+    async fn new<S: Store>(
+        store: &S,
+        account_id: Option<AccountId>,
+    ) -> Result<Self, SnapshotError> {
+        let Some(account_id) = account_id else {
+            return Ok(Self {
+                account_id,
+                dependencies: Vec::new(),
+            });
+        };
+
+        let owned = Expression::for_owned_by_id(account_id);
+        let mut included = HashSet::new();
+        let mut references = Vec::new();
+        for schema in read_ontology_schemas(store, &owned).await? {
+            if let Some(uri) = schema
+                .get("$id")
+                .and_then(|id| serde_json::from_value(id.clone()).ok())
+            {
+                included.insert(uri);
+            }
+            collect_references(&schema, &mut references);
+        }
+        let entities: Vec<PersistedEntity> = Read::read(store, &owned)
+            .await
+            .change_context(SnapshotError)?;
+        references.extend(
+            entities
+                .iter()
+                .map(|entity| entity.metadata().entity_type_id().clone()),
+        );
+        let links: Vec<PersistedLink> = Read::read(store, &owned)
+            .await
+            .change_context(SnapshotError)?;
+        references.extend(links.iter().map(|link| link.inner().link_type_id().clone()));
+
+        // Every pass reads the types referenced by the types of the previous pass, until no new
+        // type is referenced.
+        let mut dependencies = Vec::new();
+        loop {
+            references.retain(|uri| included.insert(uri.clone()));
+            if references.is_empty() {
+                return Ok(Self {
+                    account_id: Some(account_id),
+                    dependencies,
+                });
+            }
+            let query = Expression::Any(
+                references
+                    .iter()
+                    .map(Expression::for_versioned_uri)
+                    .collect(),
+            );
+            dependencies.append(&mut references);
+            for schema in read_ontology_schemas(store, &query).await? {
+                collect_references(&schema, &mut references);
+            }
+        }
+    }
+
+    /// The query for the ontology types contained in the snapshot.
+    fn ontology_query(&self) -> Expression {
+        self.account_id
+            .map_or_else(Expression::default, |account_id| {
+                Expression::Any(
+                    self.dependencies
+                        .iter()
+                        .map(Expression::for_versioned_uri)
+                        .chain([Expression::for_owned_by_id(account_id)])
+                        .collect(),
+                )
+            })
+    }
+
+    /// The query for the entities and links contained in the snapshot.
+    fn knowledge_query(&self) -> Expression {
+        self.account_id
+            .map_or_else(Expression::default, Expression::for_owned_by_id)
+    }
+}
+
+/// Reads all records of `kind` contained in `scope` from the `store`.
+async fn export_records<S: Store>(
+    store: &S,
+    kind: RecordKind,
+    scope: &ExportScope,
+) -> Result<Vec<SnapshotEntry>, SnapshotError> {
+    let uri_key = |uri: &VersionedUri| (uri.base_uri().as_str().to_owned(), uri.version());
+
+    // TODO: Stream the records from the store instead of reading all records of a kind at once
+    //   see https://app.asana.com/0/1202805690238892/1202923536131158/f
+    Ok(match kind {
+        RecordKind::DataType => {
+            let mut data_types: Vec<PersistedDataType> = Read::read(store, &scope.ontology_query())
+                .await
+                .change_context(SnapshotError)?;
+            data_types.sort_by_key(|data_type| uri_key(data_type.metadata().identifier().uri()));
+            data_types
+                .into_iter()
+                .map(|data_type| {
+                    SnapshotEntry::DataType(OntologyTypeSnapshot::new(
+                        data_type.inner().clone(),
+                        data_type.metadata(),
+                    ))
+                })
+                .collect()
+        }
+        RecordKind::PropertyType => {
+            let mut property_types: Vec<PersistedPropertyType> =
+                Read::read(store, &scope.ontology_query())
+                    .await
+                    .change_context(SnapshotError)?;
+            property_types
+                .sort_by_key(|property_type| uri_key(property_type.metadata().identifier().uri()));
+            property_types
+                .into_iter()
+                .map(|property_type| {
+                    SnapshotEntry::PropertyType(OntologyTypeSnapshot::new(
+                        property_type.inner().clone(),
+                        property_type.metadata(),
+                    ))
+                })
+                .collect()
+        }
+        RecordKind::LinkType => {
+            let mut link_types: Vec<PersistedLinkType> = Read::read(store, &scope.ontology_query())
+                .await
+                .change_context(SnapshotError)?;
+            link_types.sort_by_key(|link_type| uri_key(link_type.metadata().identifier().uri()));
+            link_types
+                .into_iter()
+                .map(|link_type| {
+                    SnapshotEntry::LinkType(OntologyTypeSnapshot::new(
+                        link_type.inner().clone(),
+                        link_type.metadata(),
+                    ))
+                })
+                .collect()
+        }
+        RecordKind::EntityType => {
+            let mut entity_types: Vec<PersistedEntityType> =
+                Read::read(store, &scope.ontology_query())
+                    .await
+                    .change_context(SnapshotError)?;
+            entity_types
+                .sort_by_key(|entity_type| uri_key(entity_type.metadata().identifier().uri()));
+            entity_types
+                .into_iter()
+                .map(|entity_type| {
+                    SnapshotEntry::EntityType(OntologyTypeSnapshot::new(
+                        entity_type.inner().clone(),
+                        entity_type.metadata(),
+                    ))
+                })
+                .collect()
+        }
+        RecordKind::Entity => {
+            let mut entities: Vec<PersistedEntity> = Read::read(store, &scope.knowledge_query())
+                .await
+                .change_context(SnapshotError)?;
+            entities.sort_by_key(|entity| {
+                (
+                    entity.metadata().identifier().entity_id().to_string(),
+                    entity.metadata().identifier().version(),
+                )
+            });
+            entities.into_iter().map(SnapshotEntry::Entity).collect()
+        }
+        RecordKind::Link => {
+            let mut links: Vec<PersistedLink> = Read::read(store, &scope.knowledge_query())
+                .await
+                .change_context(SnapshotError)?;
+            links.sort_by_key(|link| {
+                (
+                    link.inner().source_entity().to_string(),
+                    link.inner().link_type_id().to_string(),
+                    link.inner().index(),
+                    link.inner().target_entity().to_string(),
+                )
+            });
+            links.into_iter().map(SnapshotEntry::Link).collect()
+        }
+    })
+}
+
+/// Streams the records in the order they are contained in a snapshot.
+///
+/// The records are read one [`RecordKind`] at a time by calling `export_kind`, when the stream
+/// reaches them.
+fn export_entries<F, Fut>(
+    export_kind: F,
+) -> impl Stream<Item = Result<SnapshotEntry, SnapshotError>>
+where
+    F: FnMut(RecordKind) -> Fut,
+    Fut: Future<Output = Result<Vec<SnapshotEntry>, SnapshotError>>,
+{
+    stream::iter(RecordKind::ALL)
+        .then(export_kind)
+        .map_ok(|records| stream::iter(records.into_iter().map(Ok)))
+        .try_flatten()
+}
+
+/// Serializes `value` as a single line of a snapshot including the line break.
+fn serialize_line(value: &impl Serialize) -> Result<Vec<u8>, SnapshotError> {
+    let mut line = serde_json::to_vec(value)
+        .into_report()
+        .change_context(SnapshotError)?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+/// Parses the line with the zero-based index `line_number` of a snapshot.
+fn parse_line<T: for<'de> Deserialize<'de>>(
+    line_number: usize,
+    line: &str,
+) -> Result<T, SnapshotError> {
+    serde_json::from_str(line)
+        .into_report()
+        .change_context(InvalidSnapshot)
+        .attach_printable_lazy(|| format!("line {}", line_number + 1))
+        .change_context(SnapshotError)
+}
+
+/// Checks the `header` of a snapshot.
+fn validate_header(header: &SnapshotHeader) -> Result<(), SnapshotError> {
+    if header.version == SNAPSHOT_VERSION {
+        Ok(())
+    } else {
+        Err(Report::new(InvalidSnapshot)
+            .attach_printable(format!(
+                "unsupported snapshot version {}, expected {SNAPSHOT_VERSION}",
+                header.version
+            ))
+            .change_context(SnapshotError))
+    }
+}
+
+/// Returns an [`InvalidSnapshot`] error listing the `missing` references, if there are any.
+fn ensure_no_missing_references(
+    missing: impl IntoIterator<Item = String>,
+) -> Result<(), SnapshotError> {
+    let mut report: Option<Report<InvalidSnapshot>> = None;
+    for reference in missing {
+        let missing = Report::new(InvalidSnapshot)
+            .attach_printable(format!("missing reference: {reference}"));
+        match &mut report {
+            Some(report) => report.extend_one(missing),
+            None => report = Some(missing),
+        }
+    }
+
+    report.map_or(Ok(()), |report| Err(report.change_context(SnapshotError)))
+}
+
+/// Inserts the records of a snapshot into a [`Store`] while they are read.
+struct Restorer<'s, S> {
+    store: &'s mut S,
+    summary: RestoreSummary,
+    /// The accounts, which are known to exist in the store.
+    accounts: HashSet<AccountId>,
+    /// The entities, which are known to exist in the store.
+    entities: HashSet<EntityId>,
+    /// The ontology types read so far.
+    ///
+    /// Ontology types are collected until the first entity or link is read, so they can be
+    /// inserted after the types they reference. Afterwards, this is `None`.
+    ontology_types: Option<HashMap<VersionedUri, (OntologyType, OntologyTypeSnapshot)>>,
+}
+
+impl<'s, S: Store + Send> Restorer<'s, S> {
+    fn new(store: &'s mut S) -> Self {
+        Self {
+            store,
+            summary: RestoreSummary::default(),
+            accounts: HashSet::new(),
+            entities: HashSet::new(),
+            ontology_types: Some(HashMap::new()),
+        }
+    }
+
+    async fn restore(
+        mut self,
+        entries: impl Stream<Item = Result<SnapshotEntry, SnapshotError>> + Send,
+    ) -> Result<RestoreSummary, SnapshotError> {
+        pin_mut!(entries);
+        while let Some(entry) = entries.try_next().await? {
+            match entry {
+                SnapshotEntry::DataType(snapshot) => {
+                    self.add_ontology_type(snapshot, |schema| {
+                        DataType::try_from(schema)
+                            .into_report()
+                            .map(OntologyType::DataType)
+                    })?;
+                }
+                SnapshotEntry::PropertyType(snapshot) => {
+                    self.add_ontology_type(snapshot, |schema| {
+                        PropertyType::try_from(schema)
+                            .into_report()
+                            .map(OntologyType::PropertyType)
+                    })?;
+                }
+                SnapshotEntry::LinkType(snapshot) => {
+                    self.add_ontology_type(snapshot, |schema| {
+                        LinkType::try_from(schema)
+                            .into_report()
+                            .map(OntologyType::LinkType)
+                    })?;
+                }
+                SnapshotEntry::EntityType(snapshot) => {
+                    self.add_ontology_type(snapshot, |schema| {
+                        EntityType::try_from(schema)
+                            .into_report()
+                            .map(OntologyType::EntityType)
+                    })?;
+                }
+                SnapshotEntry::Entity(entity) => self.restore_entity(entity).await?,
+                SnapshotEntry::Link(link) => self.restore_link(link).await?,
+            }
+        }
+
+        self.restore_ontology_types().await?;
+
+        Ok(self.summary)
+    }
+
+    fn add_ontology_type<E: Context>(
+        &mut self,
+        snapshot: OntologyTypeSnapshot,
+        parse: impl FnOnce(Value) -> Result<OntologyType, E>,
+    ) -> Result<(), SnapshotError> {
+        let uri = snapshot.id().change_context(SnapshotError)?;
+        let ontology_types = self.ontology_types.as_mut().ok_or_else(|| {
+            Report::new(InvalidSnapshot)
+                .attach_printable("ontology types have to precede entities and links")
+                .attach_printable(uri.clone())
+                .change_context(SnapshotError)
+        })?;
+        let ontology_type = parse(snapshot.schema.clone())
+            .change_context(InvalidSnapshot)
+            .attach_printable_lazy(|| uri.clone())
+            .change_context(SnapshotError)?;
+        ontology_types.insert(uri, (ontology_type, snapshot));
+        Ok(())
+    }
+
+    /// Inserts the accounts, which do not exist in the store, yet.
+    async fn restore_accounts(
+        &mut self,
+        account_ids: impl IntoIterator<Item = AccountId> + Send,
+    ) -> Result<(), SnapshotError> {
+        for account_id in account_ids {
+            if self.accounts.insert(account_id)
+                && self
+                    .store
+                    .restore_account_id(account_id)
+                    .await
+                    .change_context(SnapshotError)?
+            {
+                self.summary.accounts += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks if the entity identified by `entity_id` exists in the store.
+    async fn entity_exists(&mut self, entity_id: EntityId) -> Result<bool, SnapshotError> {
+        if self.entities.contains(&entity_id) {
+            return Ok(true);
+        }
+
+        let exists = !Read::<PersistedEntity>::read(
+            &*self.store,
+            &Expression::for_latest_entity_id(entity_id),
+        )
+        .await
+        .change_context(SnapshotError)?
+        .is_empty();
+        if exists {
+            self.entities.insert(entity_id);
+        }
+        Ok(exists)
+    }
+
+    /// Inserts the collected ontology types, if they were not inserted, yet.
+    ///
+    /// All ontology types are validated before any of them is inserted.
+    async fn restore_ontology_types(&mut self) -> Result<(), SnapshotError> {
+        let Some(mut ontology_types) = self.ontology_types.take() else {
+            return Ok(());
+        };
+
+        let mut missing = Vec::new();
+        let mut referenced_types = HashSet::new();
+        for (_, snapshot) in ontology_types.values() {
+            let mut references = Vec::new();
+            collect_references(&snapshot.schema, &mut references);
+            referenced_types.extend(references);
+        }
+        for uri in referenced_types {
+            if !ontology_types.contains_key(&uri)
+                && !is_persisted(&*self.store, &uri)
+                    .await
+                    .change_context(SnapshotError)?
+            {
+                missing.push(uri.to_string());
+            }
+        }
+        ensure_no_missing_references(missing)?;
+
+        let account_ids: HashSet<_> = ontology_types
+            .values()
+            .flat_map(|(_, snapshot)| snapshot.account_ids())
+            .collect();
+        self.restore_accounts(account_ids).await?;
+
+        // Types are inserted after the types they reference and after their previous version.
+        let dependencies = ontology_types
+            .iter()
+            .map(|(uri, (_, snapshot))| {
+                let mut references = Vec::new();
+                collect_references(&snapshot.schema, &mut references);
+                references.retain(|reference| reference != uri);
+                if uri.version() > 1 {
+                    references.push(VersionedUri::new(uri.base_uri().clone(), uri.version() - 1));
+                }
+                (uri.clone(), references)
+            })
+            .collect();
+        let mut insertion_order = Vec::with_capacity(ontology_types.len());
+        let mut sorted = HashSet::new();
+        for uri in ontology_types.keys() {
+            sort_dependencies(uri, &dependencies, &mut sorted, &mut insertion_order);
+        }
+
+        let store = &mut *self.store;
+        let mut deprecations = Vec::new();
+        for uri in insertion_order {
+            if is_persisted(&*store, &uri)
+                .await
+                .change_context(SnapshotError)?
+            {
+                self.summary.skipped += 1;
+                continue;
+            }
+
+            // A new version of an existing type has to be inserted as update of the type.
+            let is_update = uri.version() > 1
+                && is_persisted(
+                    &*store,
+                    &VersionedUri::new(uri.base_uri().clone(), uri.version() - 1),
+                )
+                .await
+                .change_context(SnapshotError)?;
+
+            let Some((ontology_type, snapshot)) = ontology_types.remove(&uri) else {
+                continue;
+            };
+            let OntologyTypeSnapshot {
+                owned_by_id,
+                created_by_id,
+                updated_by_id,
+                deprecation,
+                ..
+            } = snapshot;
+            if let Some(deprecation) = deprecation {
+                deprecations.push((uri.clone(), deprecation));
+            }
+
+            let result = match (ontology_type, is_update) {
+                (OntologyType::DataType(data_type), false) => store
+                    .create_data_type(data_type, owned_by_id, created_by_id)
+                    .await
+                    .change_context(SnapshotError),
+                (OntologyType::DataType(data_type), true) => store
+                    .update_data_type(data_type, updated_by_id)
+                    .await
+                    .change_context(SnapshotError),
+                (OntologyType::PropertyType(property_type), false) => store
+                    .create_property_type(property_type, owned_by_id, created_by_id)
+                    .await
+                    .change_context(SnapshotError),
+                (OntologyType::PropertyType(property_type), true) => store
+                    .update_property_type(property_type, updated_by_id)
+                    .await
+                    .change_context(SnapshotError),
+                (OntologyType::LinkType(link_type), false) => store
+                    .create_link_type(link_type, owned_by_id, created_by_id)
+                    .await
+                    .change_context(SnapshotError),
+                (OntologyType::LinkType(link_type), true) => store
+                    .update_link_type(link_type, updated_by_id)
+                    .await
+                    .change_context(SnapshotError),
+                (OntologyType::EntityType(entity_type), false) => store
+                    .create_entity_type(entity_type, owned_by_id, created_by_id)
+                    .await
+                    .change_context(SnapshotError),
+                (OntologyType::EntityType(entity_type), true) => store
+                    .update_entity_type(entity_type, updated_by_id)
+                    .await
+                    .change_context(SnapshotError),
+            };
+            result.attach_printable_lazy(|| uri.clone())?;
+            self.summary.ontology_types += 1;
+        }
+
+        // A replacement is not referenced by the schema, so types are deprecated after all types
+        // have been inserted.
+        for (uri, deprecation) in deprecations {
+            store
+                .deprecate_ontology_type(
+                    &uri,
+                    deprecation.replaced_by().cloned(),
+                    deprecation.deprecated_by_id(),
+                )
+                .await
+                .change_context(SnapshotError)
+                .attach_printable(uri)?;
+        }
+
+        Ok(())
+    }
+
+    async fn restore_entity(&mut self, entity: PersistedEntity) -> Result<(), SnapshotError> {
+        self.restore_ontology_types().await?;
+
+        let mut missing = Vec::new();
+        if !is_persisted(&*self.store, entity.metadata().entity_type_id())
+            .await
+            .change_context(SnapshotError)?
+        {
+            missing.push(entity.metadata().entity_type_id().to_string());
+        }
+        ensure_no_missing_references(missing)?;
+
+        let metadata = entity.metadata();
+        let mut account_ids = vec![
+            metadata.identifier().owned_by_id(),
+            metadata.created_by_id(),
+            metadata.updated_by_id(),
+        ];
+        account_ids.extend(metadata.removed_by_id());
+        self.restore_accounts(account_ids).await?;
+
+        if self
+            .store
+            .restore_entity(&entity)
+            .await
+            .change_context(SnapshotError)?
+        {
+            self.summary.entities += 1;
+        } else {
+            self.summary.skipped += 1;
+        }
+        self.entities
+            .insert(entity.metadata().identifier().entity_id());
+
+        Ok(())
+    }
+
+    async fn restore_link(&mut self, link: PersistedLink) -> Result<(), SnapshotError> {
+        self.restore_ontology_types().await?;
+
+        let mut missing = Vec::new();
+        if !is_persisted(&*self.store, link.inner().link_type_id())
+            .await
+            .change_context(SnapshotError)?
+        {
+            missing.push(link.inner().link_type_id().to_string());
+        }
+        for entity_id in [link.inner().source_entity(), link.inner().target_entity()] {
+            if !self.entity_exists(entity_id).await? {
+                missing.push(entity_id.to_string());
+            }
+        }
+        ensure_no_missing_references(missing)?;
+
+        self.restore_accounts([
+            link.metadata().owned_by_id(),
+            link.metadata().created_by_id(),
+        ])
+        .await?;
+
+        let existing_links = Read::<PersistedLink>::read(
+            &*self.store,
+            &Expression::for_link_by_source_entity_id(link.inner().source_entity()),
+        )
+        .await
+        .change_context(SnapshotError)?;
+        if existing_links.iter().any(|existing_link| {
+            existing_link.inner().target_entity() == link.inner().target_entity()
+                && existing_link.inner().link_type_id() == link.inner().link_type_id()
+        }) {
+            self.summary.skipped += 1;
+            return Ok(());
+        }
+
+        self.store
+            .create_link(
+                link.inner(),
+                link.metadata().owned_by_id(),
+                link.metadata().created_by_id(),
+            )
+            .await
+            .change_context(SnapshotError)?;
+        self.summary.links += 1;
+
+        Ok(())
+    }
+}
+
+/// The contents of a [`Store`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    header: SnapshotHeader,
+    entries: Vec<SnapshotEntry>,
+}
+
+impl Snapshot {
+    #[must_use]
+    pub const fn header(&self) -> &SnapshotHeader {
+        &self.header
+    }
+
+    #[must_use]
+    pub fn entries(&self) -> &[SnapshotEntry] {
+        &self.entries
+    }
+
+    /// Reads the contents of the `store`.
+    ///
+    /// If `account_id` is provided, only records owned by this account are included together with
+    /// the ontology types they reference, so the snapshot can be restored into an empty store.
+    ///
+    /// # Errors
+    ///
+    /// - [`SnapshotError`], if reading from the `store` failed
+    pub async fn export<S: Store>(
+        store: &S,
+        account_id: Option<AccountId>,
+    ) -> Result<Self, SnapshotError> {
+        let scope = ExportScope::new(store, account_id).await?;
+        Ok(Self {
+            header: SnapshotHeader {
+                version: SNAPSHOT_VERSION,
+                account_id,
+            },
+            entries: export_entries(|kind| export_records(store, kind, &scope))
+                .try_collect()
+                .await?,
+        })
+    }
+
+    /// Streams the contents of the [`Store`]s acquired from `pool` as newline-delimited JSON.
+    ///
+    /// Every item of the returned stream is one line of the snapshot in the format written by
+    /// [`write`]. If `account_id` is provided, only records owned by this account and the ontology
+    /// types they reference are included.
+    ///
+    /// # Errors
+    ///
+    /// The stream yields [`SnapshotError`], if acquiring a [`Store`] or reading from it failed.
+    ///
+    /// [`write`]: Self::write
+    pub fn export_ndjson<P: StorePool + Send + 'static>(
+        pool: Arc<P>,
+        account_id: Option<AccountId>,
+    ) -> impl Stream<Item = Result<Vec<u8>, SnapshotError>> + Send {
+        let header = SnapshotHeader {
+            version: SNAPSHOT_VERSION,
+            account_id,
+        };
+
+        // The scope is determined once, before the first record is read.
+        let scope = async move {
+            let store = pool.acquire().await.change_context(SnapshotError)?;
+            let scope = ExportScope::new(&store, account_id).await?;
+            Ok::<_, Report<SnapshotError>>((pool, Arc::new(scope)))
+        };
+
+        stream::once(future::ready(serialize_line(&header))).chain(
+            stream::once(scope)
+                .map_ok(|(pool, scope)| {
+                    export_entries(move |kind| {
+                        let pool = Arc::clone(&pool);
+                        let scope = Arc::clone(&scope);
+                        async move {
+                            let store = pool.acquire().await.change_context(SnapshotError)?;
+                            export_records(&store, kind, &scope).await
+                        }
+                    })
+                })
+                .try_flatten()
+                .and_then(|entry| future::ready(serialize_line(&entry))),
+        )
+    }
+
+    /// Writes the snapshot as newline-delimited JSON.
+    ///
+    /// # Errors
+    ///
+    /// - [`SnapshotError`], if writing to `writer` failed
+    pub fn write(&self, mut writer: impl Write) -> Result<(), SnapshotError> {
+        serde_json::to_writer(&mut writer, &self.header)
+            .into_report()
+            .change_context(SnapshotError)?;
+        for entry in &self.entries {
+            writer
+                .write_all(b"\n")
+                .into_report()
+                .change_context(SnapshotError)?;
+            serde_json::to_writer(&mut writer, entry)
+                .into_report()
+                .change_context(SnapshotError)?;
+        }
+        writer
+            .write_all(b"\n")
+            .into_report()
+            .change_context(SnapshotError)?;
+        writer.flush().into_report().change_context(SnapshotError)
+    }
+
+    /// Reads a snapshot written by [`write`].
+    ///
+    /// Empty lines are ignored.
+    ///
+    /// # Errors
+    ///
+    /// - [`SnapshotError`], if reading from `reader` failed
+    /// - [`InvalidSnapshot`], if a line could not be parsed or the snapshot was written in an
+    ///   unsupported version
+    ///
+    /// [`write`]: Self::write
+    pub fn read(reader: impl BufRead) -> Result<Self, SnapshotError> {
+        let mut lines = reader
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()));
+
+        let (line_number, line) = lines.next().ok_or_else(|| {
+            Report::new(InvalidSnapshot)
+                .attach_printable("snapshot is empty")
+                .change_context(SnapshotError)
+        })?;
+        let header = parse_line(
+            line_number,
+            &line.into_report().change_context(SnapshotError)?,
+        )?;
+        validate_header(&header)?;
+
+        let entries = lines
+            .map(|(line_number, line)| {
+                parse_line(
+                    line_number,
+                    &line.into_report().change_context(SnapshotError)?,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { header, entries })
+    }
+
+    /// Reads a snapshot line by line from `lines`.
+    ///
+    /// The header is read immediately, the entries are parsed when the returned stream is polled.
+    /// Empty lines are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Both, this function and the returned stream, fail with
+    ///
+    /// - [`SnapshotError`], if reading from `lines` failed
+    /// - [`InvalidSnapshot`], if a line could not be parsed, is not valid UTF-8, or the snapshot
+    ///   was written in an unsupported version
+    pub async fn read_lines<L>(
+        lines: L,
+    ) -> Result<
+        (
+            SnapshotHeader,
+            impl Stream<Item = Result<SnapshotEntry, SnapshotError>>,
+        ),
+        SnapshotError,
+    >
+    where
+        L: Stream<Item = io::Result<String>> + Unpin,
+    {
+        let mut lines = lines
+            .enumerate()
+            .map(|(line_number, line)| {
+                line.map(|line| (line_number, line))
+                    .into_report()
+                    .map_err(|report| {
+                        if report.current_context().kind() == io::ErrorKind::InvalidData {
+                            report
+                                .change_context(InvalidSnapshot)
+                                .change_context(SnapshotError)
+                        } else {
+                            report.change_context(SnapshotError)
+                        }
+                    })
+            })
+            .try_filter(|(_, line)| future::ready(!line.trim().is_empty()));
+
+        let (line_number, line) = lines.try_next().await?.ok_or_else(|| {
+            Report::new(InvalidSnapshot)
+                .attach_printable("snapshot is empty")
+                .change_context(SnapshotError)
+        })?;
+        let header = parse_line(line_number, &line)?;
+        validate_header(&header)?;
+
+        let entries =
+            lines.and_then(|(line_number, line)| future::ready(parse_line(line_number, &line)));
+
+        Ok((header, entries))
+    }
+
+    /// Inserts all records of the snapshot into the `store`.
+    ///
+    /// See [`restore_entries`] for details.
+    ///
+    /// # Errors
+    ///
+    /// - [`InvalidSnapshot`], if an ontology type could not be parsed or a referenced record is
+    ///   neither contained in the snapshot nor in the `store`
+    /// - [`SnapshotError`], if inserting into the `store` failed
+    ///
+    /// [`restore_entries`]: Self::restore_entries
+    pub async fn restore<S: Store + Send>(
+        &self,
+        store: &mut S,
+    ) -> Result<RestoreSummary, SnapshotError> {
+        let ontology_types = self
+            .entries
+            .iter()
+            .filter(|entry| !matches!(entry, SnapshotEntry::Entity(_) | SnapshotEntry::Link(_)));
+        let mut entities: Vec<_> = self
+            .entries
+            .iter()
+            .filter_map(|entry| match entry {
+                SnapshotEntry::Entity(entity) => Some(entity),
+                _ => None,
+            })
+            .collect();
+        entities.sort_by_key(|entity| entity.metadata().identifier().version());
+        let links = self
+            .entries
+            .iter()
+            .filter(|entry| matches!(entry, SnapshotEntry::Link(_)));
+
+        let entries = ontology_types
+            .cloned()
+            .chain(entities.into_iter().cloned().map(SnapshotEntry::Entity))
+            .chain(links.cloned())
+            .map(Ok);
+        Self::restore_entries(store, stream::iter(entries)).await
+    }
+
+    /// Inserts the records of a snapshot into the `store` while they are read from `entries`.
+    ///
+    /// The entries are expected in the order written by [`write`]: Ontology types precede entities
+    /// and versions of an entity are sorted by their version. Ontology types are collected and
+    /// validated before any of them is inserted. Entities and links are inserted as they are read,
+    /// after the records referenced by them were checked to exist.
+    ///
+    /// Records, which already exist in the `store`, are skipped. The records are inserted in a
+    /// single transaction, so a failed restore does not insert any record.
+    ///
+    /// # Errors
+    ///
+    /// - [`InvalidSnapshot`], if an ontology type could not be parsed, is contained after an entity
+    ///   or link, or a referenced record is neither contained in the snapshot nor in the `store`
+    /// - [`SnapshotError`], if inserting into the `store` failed
+    /// - the error of `entries`, if reading an entry failed
+    ///
+    /// [`write`]: Self::write
+    pub async fn restore_entries<S: Store + Send>(
+        store: &mut S,
+        entries: impl Stream<Item = Result<SnapshotEntry, SnapshotError>> + Send,
+    ) -> Result<RestoreSummary, SnapshotError> {
+        let mut transaction = store.transaction().await.change_context(SnapshotError)?;
+        let summary = Restorer::new(transaction.store()).restore(entries).await?;
+        transaction.commit().await.change_context(SnapshotError)?;
+        Ok(summary)
+    }
+}
@@ -0,0 +1,470 @@
+//! Notifications about mutations of the graph.
+//!
+//! Every mutation of a [`Store`] results in a [`ChangeEvent`]. Stores publish the events to a
+//! [`ChangeFeed`], from where they can be consumed by subscribers, e.g. the REST API or a
+//! [`WebhookDispatcher`].
+//!
+//! The [`PostgresStore`] publishes the events through the database by sending a notification on
+//! the [`CHANGE_CHANNEL`] as part of the transaction of the mutation, so events are only emitted
+//! once the mutation is committed. [`listen_for_changes`] forwards these notifications to a
+//! [`ChangeFeed`].
+//!
+//! [`Store`]: crate::store::Store
+//! [`PostgresStore`]: crate::store::PostgresStore
+//! [`listen_for_changes`]: crate::store::listen_for_changes
+
+use std::{fmt, time::Duration};
+
+use chrono::{DateTime, Utc};
+use error_stack::{Context, IntoReport, Result, ResultExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use type_system::uri::VersionedUri;
+
+use crate::{
+    knowledge::{EntityId, Link, PersistedEntityMetadata},
+    ontology::AccountId,
+};
+
+/// The Postgres notification channel, on which the [`PostgresStore`] publishes [`ChangeEvent`]s.
+///
+/// [`PostgresStore`]: crate::store::PostgresStore
+pub const CHANGE_CHANNEL: &str = "graph_changes";
+
+/// The kind of mutation a [`ChangeEvent`] is describing.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Removed,
+}
+
+/// The resource, which was mutated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ChangedResource {
+    DataType {
+        uri: VersionedUri,
+    },
+    PropertyType {
+        uri: VersionedUri,
+    },
+    LinkType {
+        uri: VersionedUri,
+    },
+    EntityType {
+        uri: VersionedUri,
+    },
+    Entity {
+        #[serde(rename = "entityId")]
+        entity_id: EntityId,
+        version: DateTime<Utc>,
+        #[serde(rename = "entityTypeId")]
+        entity_type_id: VersionedUri,
+    },
+    Link {
+        #[serde(rename = "sourceEntityId")]
+        source_entity_id: EntityId,
+        #[serde(rename = "targetEntityId")]
+        target_entity_id: EntityId,
+        #[serde(rename = "linkTypeId")]
+        link_type_id: VersionedUri,
+    },
+}
+
+impl ChangedResource {
+    /// Returns the resource describing the version of an entity identified by `metadata`.
+    #[must_use]
+    pub fn entity(metadata: &PersistedEntityMetadata) -> Self {
+        Self::Entity {
+            entity_id: metadata.identifier().entity_id(),
+            version: metadata.identifier().version(),
+            entity_type_id: metadata.entity_type_id().clone(),
+        }
+    }
+
+    /// Returns the resource describing `link`.
+    #[must_use]
+    pub fn link(link: &Link) -> Self {
+        Self::Link {
+            source_entity_id: link.source_entity(),
+            target_entity_id: link.target_entity(),
+            link_type_id: link.link_type_id().clone(),
+        }
+    }
+
+    /// Returns the name of the resource type as used in the serialized form, e.g. `entityType`.
+    #[must_use]
+    pub const fn resource_type(&self) -> &'static str {
+        match self {
+            Self::DataType { .. } => "dataType",
+            Self::PropertyType { .. } => "propertyType",
+            Self::LinkType { .. } => "linkType",
+            Self::EntityType { .. } => "entityType",
+            Self::Entity { .. } => "entity",
+            Self::Link { .. } => "link",
+        }
+    }
+}
+
+/// A mutation of the graph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeEvent {
+    kind: ChangeKind,
+    resource: ChangedResource,
+    owned_by_id: AccountId,
+    actor_id: AccountId,
+}
+
+impl ChangeEvent {
+    #[must_use]
+    pub const fn new(
+        kind: ChangeKind,
+        resource: ChangedResource,
+        owned_by_id: AccountId,
+        actor_id: AccountId,
+    ) -> Self {
+        Self {
+            kind,
+            resource,
+            owned_by_id,
+            actor_id,
+        }
+    }
+
+    #[must_use]
+    pub const fn kind(&self) -> ChangeKind {
+        self.kind
+    }
+
+    #[must_use]
+    pub const fn resource(&self) -> &ChangedResource {
+        &self.resource
+    }
+
+    /// Returns the account owning the resource after the mutation.
+    #[must_use]
+    pub const fn owned_by_id(&self) -> AccountId {
+        self.owned_by_id
+    }
+
+    #[must_use]
+    pub const fn actor_id(&self) -> AccountId {
+        self.actor_id
+    }
+}
+
+/// Distributes [`ChangeEvent`]s to any number of subscribers.
+///
+/// Cloning the feed is cheap and the clones share the same subscribers. Events are only retained
+/// for a limited amount of time: Subscribers, which fall behind by more than the capacity of the
+/// feed, miss the oldest events.
+#[derive(Debug, Clone)]
+pub struct ChangeFeed {
+    sender: broadcast::Sender<ChangeEvent>,
+}
+
+impl ChangeFeed {
+    /// Creates a new `ChangeFeed` buffering up to `capacity` events per subscriber.
+    ///
+    /// # Panics
+    ///
+    /// - if `capacity` is zero
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            sender: broadcast::channel(capacity).0,
+        }
+    }
+
+    /// Publishes `event` to all current subscribers.
+    pub fn publish(&self, event: ChangeEvent) {
+        // Sending only fails if there are no subscribers, in which case the event is not of
+        // interest.
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribes to all events published after this call.
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<ChangeEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for ChangeFeed {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+#[derive(Debug)]
+pub struct WebhookError;
+
+impl Context for WebhookError {}
+
+impl fmt::Display for WebhookError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("could not deliver change event to webhook")
+    }
+}
+
+/// Delivers [`ChangeEvent`]s to webhooks by sending them as JSON in a `POST` request.
+///
+/// Failed deliveries are retried with an exponentially increasing delay. If an event could not be
+/// delivered after the maximum number of retries, it is dropped.
+#[derive(Debug, Clone)]
+pub struct WebhookDispatcher {
+    client: reqwest::Client,
+    urls: Vec<reqwest::Url>,
+    max_retries: u32,
+    initial_delay: Duration,
+}
+
+impl WebhookDispatcher {
+    /// Creates a dispatcher delivering events to `urls`.
+    ///
+    /// # Errors
+    ///
+    /// - [`WebhookError`], if one of the `urls` is not a valid URL
+    pub fn new(urls: impl IntoIterator<Item = impl AsRef<str>>) -> Result<Self, WebhookError> {
+        Ok(Self {
+            client: reqwest::Client::new(),
+            urls: urls
+                .into_iter()
+                .map(|url| {
+                    reqwest::Url::parse(url.as_ref())
+                        .into_report()
+                        .change_context(WebhookError)
+                        .attach_printable_lazy(|| url.as_ref().to_owned())
+                })
+                .collect::<Result<_, _>>()?,
+            max_retries: 5,
+            initial_delay: Duration::from_secs(1),
+        })
+    }
+
+    /// Sets the number of times a failed delivery is retried.
+    #[must_use]
+    pub const fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the delay before the first retry, which is doubled for every following retry.
+    #[must_use]
+    pub const fn with_initial_delay(mut self, initial_delay: Duration) -> Self {
+        self.initial_delay = initial_delay;
+        self
+    }
+
+    async fn send(&self, url: &reqwest::Url, event: &ChangeEvent) -> Result<(), WebhookError> {
+        self.client
+            .post(url.clone())
+            .json(event)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .into_report()
+            .change_context(WebhookError)
+            .attach_printable_lazy(|| url.clone())
+            .map(drop)
+    }
+
+    /// Delivers `event` to `url`, retrying failed attempts.
+    ///
+    /// # Errors
+    ///
+    /// - [`WebhookError`], if the event could not be delivered after the maximum number of retries
+    pub async fn deliver(
+        &self,
+        url: &reqwest::Url,
+        event: &ChangeEvent,
+    ) -> Result<(), WebhookError> {
+        let mut delay = self.initial_delay;
+        let mut attempt = 0;
+        loop {
+            match self.send(url, event).await {
+                Ok(()) => return Ok(()),
+                Err(report) if attempt >= self.max_retries => {
+                    return Err(report.attach_printable(format!("gave up after {attempt} retries")));
+                }
+                Err(report) => {
+                    attempt += 1;
+                    tracing::warn!(error=?report, attempt, "Could not deliver change event, retrying");
+                    tokio::time::sleep(delay).await;
+                    delay = delay.saturating_mul(2);
+                }
+            }
+        }
+    }
+
+    /// Spawns a task delivering all events published to `feed`.
+    ///
+    /// Events are delivered in the order they were published. The task runs until all clones of
+    /// the `feed` are dropped.
+    pub fn spawn(self, feed: &ChangeFeed) {
+        let mut receiver = feed.subscribe();
+        tokio::spawn(async move {
+            loop {
+                let event = match receiver.recv().await {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::error!(skipped, "Webhook dispatcher fell behind, skipped events");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+
+                for url in &self.urls {
+                    if let Err(report) = self.deliver(url, &event).await {
+                        tracing::error!(error=?report, ?event, "Dropped change event");
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::TcpListener,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use axum::{http::StatusCode, routing::post, Extension, Json, Router};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn uri(uri: &str) -> VersionedUri {
+        serde_json::from_value(json!(uri)).expect("invalid versioned URI")
+    }
+
+    #[test]
+    fn serialization() {
+        let entity_id = EntityId::new(Uuid::nil());
+        let owned_by_id = AccountId::new(Uuid::from_u128(1));
+        let actor_id = AccountId::new(Uuid::nil());
+        let link_type_id = uri("https://example.com/link-types/friend-of/v/1");
+
+        let event = ChangeEvent::new(
+            ChangeKind::Removed,
+            ChangedResource::Link {
+                source_entity_id: entity_id,
+                target_entity_id: entity_id,
+                link_type_id: link_type_id.clone(),
+            },
+            owned_by_id,
+            actor_id,
+        );
+        let value = serde_json::to_value(&event).expect("could not serialize event");
+
+        assert_eq!(
+            value,
+            json!({
+                "kind": "removed",
+                "resource": {
+                    "type": "link",
+                    "sourceEntityId": entity_id,
+                    "targetEntityId": entity_id,
+                    "linkTypeId": link_type_id,
+                },
+                "ownedById": owned_by_id,
+                "actorId": actor_id,
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<ChangeEvent>(value).expect("could not deserialize event"),
+            event
+        );
+    }
+
+    #[tokio::test]
+    async fn feed() {
+        let feed = ChangeFeed::default();
+        let event = ChangeEvent::new(
+            ChangeKind::Created,
+            ChangedResource::DataType {
+                uri: uri("https://example.com/data-types/text/v/1"),
+            },
+            AccountId::new(Uuid::nil()),
+            AccountId::new(Uuid::nil()),
+        );
+
+        // Events without subscribers are discarded.
+        feed.publish(event.clone());
+
+        let mut receiver = feed.subscribe();
+        feed.publish(event.clone());
+        assert_eq!(receiver.recv().await.expect("no event received"), event);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn webhook_retries() {
+        async fn webhook(
+            attempts: Extension<Arc<AtomicUsize>>,
+            Json(_event): Json<ChangeEvent>,
+        ) -> StatusCode {
+            // Fail the first attempt
+            if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                StatusCode::SERVICE_UNAVAILABLE
+            } else {
+                StatusCode::OK
+            }
+        }
+
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let listener = TcpListener::bind("127.0.0.1:0").expect("could not bind listener");
+        let url = format!(
+            "http://{}/",
+            listener.local_addr().expect("listener has no address")
+        );
+        let server = axum::Server::from_tcp(listener)
+            .expect("could not create server")
+            .serve(
+                Router::new()
+                    .route("/", post(webhook))
+                    .layer(Extension(Arc::clone(&attempts)))
+                    .into_make_service(),
+            );
+        tokio::spawn(server);
+
+        let dispatcher = WebhookDispatcher::new([&url])
+            .expect("invalid webhook URL")
+            .with_initial_delay(Duration::from_millis(1));
+        let url = reqwest::Url::parse(&url).expect("invalid webhook URL");
+        let event = ChangeEvent::new(
+            ChangeKind::Updated,
+            ChangedResource::EntityType {
+                uri: uri("https://example.com/entity-types/person/v/2"),
+            },
+            AccountId::new(Uuid::nil()),
+            AccountId::new(Uuid::nil()),
+        );
+
+        dispatcher
+            .deliver(&url, &event)
+            .await
+            .expect("could not deliver event");
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+        assert!(
+            dispatcher
+                .with_max_retries(0)
+                .deliver(
+                    &reqwest::Url::parse("http://127.0.0.1:1/").expect("invalid URL"),
+                    &event
+                )
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,452 @@
+use std::{
+    collections::{hash_map::RawEntryMut, HashMap},
+    future::Future,
+    hash::Hash,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use error_stack::Result;
+use type_system::uri::VersionedUri;
+
+use crate::{
+    knowledge::{EntityId, KnowledgeGraphQueryDepth, PersistedEntity, PersistedLink},
+    ontology::{
+        OntologyQueryDepth, PersistedDataType, PersistedEntityType, PersistedLinkType,
+        PersistedPropertyType,
+    },
+    shared::identifier::{GraphElementIdentifier, LinkId},
+    store::{QueryError, QueryLimits},
+    subgraph::{Edges, GraphResolveDepths, Subgraph, Vertex},
+};
+
+pub struct DependencyMap<V, T, D> {
+    resolved: HashMap<V, (T, Option<D>)>,
+}
+
+impl<V, T, D> Default for DependencyMap<V, T, D> {
+    fn default() -> Self {
+        Self {
+            resolved: HashMap::default(),
+        }
+    }
+}
+
+impl<V, T, D> DependencyMap<V, T, D> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<V, T, D> DependencyMap<V, T, D>
+where
+    V: Eq + Hash + Clone + Send + Sync,
+    T: Send,
+    D: PartialOrd + Send,
+{
+    /// Inserts a dependency into the map.
+    ///
+    /// If the dependency does not already exist in the dependency map, it will be inserted with the
+    /// provided `resolved_depth` and a reference to this dependency will be returned in order to
+    /// continue resolving it. In the case, that the dependency already exists, the
+    /// `resolved_depth` will be compared with depth used when inserting it before:
+    /// - If the previous `resolved_depth` was `None`, the dependency was not resolved yet and the
+    ///   value is returned
+    /// - If the new depth is higher, the depth will be updated and a reference to the dependency
+    ///   will be returned in order to keep resolving it
+    /// - Otherwise, `None` will be returned as no further resolution is needed
+    pub fn insert(&mut self, identifier: &V, resolved_depth: Option<D>, value: T) -> Option<&T> {
+        match self.resolved.raw_entry_mut().from_key(identifier) {
+            RawEntryMut::Vacant(entry) => {
+                let (_id, (value, _depth)) =
+                    entry.insert(identifier.clone(), (value, resolved_depth));
+                Some(value)
+            }
+            RawEntryMut::Occupied(entry) => {
+                let (value, used_depth) = entry.into_mut();
+                match (used_depth, resolved_depth) {
+                    (None, Some(_)) => Some(value),
+                    (Some(used_depth), Some(resolved_depth)) if *used_depth < resolved_depth => {
+                        *used_depth = resolved_depth;
+                        Some(value)
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Lazily inserts a dependency into the map.
+    ///
+    /// This behaves like [`insert`], but uses `resolver` to read the value to be inserted. If the
+    /// dependency does not exist yet and `limiter` does not allow another vertex, `None` is
+    /// returned without calling `resolver`.
+    ///
+    /// [`insert`]: Self::insert
+    pub async fn insert_with<F, R>(
+        &mut self,
+        identifier: &V,
+        resolved_depth: Option<D>,
+        limiter: &QueryLimiter,
+        resolver: F,
+    ) -> Result<Option<&T>, QueryError>
+    where
+        F: Fn() -> R + Send + Sync,
+        R: Future<Output = Result<T, QueryError>> + Send,
+    {
+        Ok(match self.resolved.raw_entry_mut().from_key(identifier) {
+            RawEntryMut::Vacant(entry) => {
+                if !limiter.reserve_vertex() {
+                    return Ok(None);
+                }
+                let value = resolver().await?;
+                let (_id, (value, _depth)) =
+                    entry.insert(identifier.clone(), (value, resolved_depth));
+                Some(value)
+            }
+            RawEntryMut::Occupied(entry) => {
+                let (value, used_depth) = entry.into_mut();
+                match (used_depth, resolved_depth) {
+                    (None, Some(_)) => Some(value),
+                    (Some(used_depth), Some(resolved_depth)) if *used_depth < resolved_depth => {
+                        *used_depth = resolved_depth;
+                        Some(value)
+                    }
+                    _ => None,
+                }
+            }
+        })
+    }
+
+    pub fn into_values(self) -> impl Iterator<Item = T> {
+        self.resolved.into_values().map(|value| value.0)
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_values().collect()
+    }
+}
+
+pub struct DependencySet<T, D> {
+    resolved: HashMap<T, Option<D>>,
+}
+
+impl<T, D> Default for DependencySet<T, D> {
+    fn default() -> Self {
+        Self {
+            resolved: HashMap::default(),
+        }
+    }
+}
+
+impl<T, D> DependencySet<T, D> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T, D> DependencySet<T, D>
+where
+    T: Eq + Hash + Clone,
+    D: PartialOrd + Send,
+{
+    /// Inserts a dependency into the map.
+    ///
+    /// If the dependency does not already exist in the dependency map, it will be inserted with the
+    /// provided `resolved_depth` and a reference to this dependency will be returned in order to
+    /// continue resolving it. In the case, that the dependency already exists, the
+    /// `resolved_depth` will be compared with depth used when inserting it before:
+    /// - If the previous `resolved_depth` was `None`, the dependency was not resolved yet and the
+    ///   value is returned
+    /// - If the new depth is higher, the depth will be updated and a reference to the dependency
+    ///   will be returned in order to keep resolving it
+    /// - Otherwise, `None` will be returned as no further resolution is needed
+    pub fn insert<'t, 's: 't>(
+        &'s mut self,
+        identifier: &'t T,
+        resolved_depth: Option<D>,
+    ) -> Option<&'t T> {
+        match self.resolved.raw_entry_mut().from_key(identifier) {
+            RawEntryMut::Vacant(entry) => {
+                let (value, _depth) = entry.insert(identifier.clone(), resolved_depth);
+                Some(value)
+            }
+            RawEntryMut::Occupied(entry) => {
+                let (value, used_depth) = entry.into_key_value();
+                match (used_depth, resolved_depth) {
+                    (None, Some(_)) => Some(value),
+                    (Some(used_depth), Some(resolved_depth)) if *used_depth < resolved_depth => {
+                        *used_depth = resolved_depth;
+                        Some(value)
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Inserts a dependency into the map, if `limiter` allows it.
+    ///
+    /// This behaves like [`insert`], but returns `None` if the dependency does not exist yet and
+    /// `limiter` does not allow another vertex.
+    ///
+    /// [`insert`]: Self::insert
+    pub fn insert_limited<'t, 's: 't>(
+        &'s mut self,
+        identifier: &'t T,
+        resolved_depth: Option<D>,
+        limiter: &QueryLimiter,
+    ) -> Option<&'t T> {
+        if !self.resolved.contains_key(identifier) && !limiter.reserve_vertex() {
+            return None;
+        }
+        self.insert(identifier, resolved_depth)
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_iter().collect()
+    }
+
+    pub fn remove(&mut self, value: &T) -> Option<T> {
+        self.resolved.remove_entry(value).map(|(value, _)| value)
+    }
+}
+
+impl<T, D> IntoIterator for DependencySet<T, D>
+where
+    T: Eq + Hash + Clone,
+{
+    type Item = T;
+
+    type IntoIter = impl Iterator<Item = T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.resolved.into_keys()
+    }
+}
+
+/// Enforces the [`QueryLimits`] of a store while resolving the subgraph of a [`StructuralQuery`].
+///
+/// The same limiter is used for the subgraphs of all roots of a query, so the number of vertices is
+/// limited for the whole result. Atomics are used as the resolving futures have to be `Send`.
+///
+/// [`StructuralQuery`]: crate::subgraph::StructuralQuery
+pub struct QueryLimiter {
+    graph_resolve_depths: GraphResolveDepths,
+    remaining_vertices: Option<AtomicUsize>,
+    truncated: AtomicBool,
+}
+
+impl QueryLimiter {
+    #[must_use]
+    pub fn new(limits: &QueryLimits, graph_resolve_depths: GraphResolveDepths) -> Self {
+        let limited_depths = graph_resolve_depths.limited_to(limits.max_resolve_depths());
+        Self {
+            graph_resolve_depths: limited_depths,
+            remaining_vertices: limits.max_vertices.map(AtomicUsize::new),
+            truncated: AtomicBool::new(limited_depths != graph_resolve_depths),
+        }
+    }
+
+    /// The requested depths lowered to the maximum depths of the [`QueryLimits`].
+    #[must_use]
+    pub const fn graph_resolve_depths(&self) -> GraphResolveDepths {
+        self.graph_resolve_depths
+    }
+
+    /// Reserves space for another vertex in the subgraph.
+    ///
+    /// Returns `false` and marks the subgraph as truncated, if the maximum number of vertices is
+    /// reached.
+    pub fn reserve_vertex(&self) -> bool {
+        let Some(remaining_vertices) = &self.remaining_vertices else {
+            return true;
+        };
+
+        let reserved = remaining_vertices
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |remaining| {
+                remaining.checked_sub(1)
+            })
+            .is_ok();
+        if !reserved {
+            self.truncated.store(true, Ordering::Relaxed);
+        }
+        reserved
+    }
+
+    /// Returns if the depths were lowered or a vertex was rejected.
+    #[must_use]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated.load(Ordering::Relaxed)
+    }
+}
+
+pub struct DependencyContext<'l> {
+    pub edges: Edges,
+    pub referenced_data_types: DependencyMap<VersionedUri, PersistedDataType, OntologyQueryDepth>,
+    pub referenced_property_types:
+        DependencyMap<VersionedUri, PersistedPropertyType, OntologyQueryDepth>,
+    pub referenced_link_types: DependencyMap<VersionedUri, PersistedLinkType, OntologyQueryDepth>,
+    pub referenced_entity_types:
+        DependencyMap<VersionedUri, PersistedEntityType, OntologyQueryDepth>,
+    pub linked_entities: DependencyMap<EntityId, PersistedEntity, KnowledgeGraphQueryDepth>,
+    pub links: DependencySet<PersistedLink, KnowledgeGraphQueryDepth>,
+    pub graph_resolve_depths: GraphResolveDepths,
+    /// If set, only links of these types are followed.
+    pub link_type_filter: Option<Vec<VersionedUri>>,
+    pub limiter: &'l QueryLimiter,
+}
+
+impl<'l> DependencyContext<'l> {
+    /// Creates a new context resolving up to the depths of `limiter`.
+    #[must_use]
+    pub fn new(limiter: &'l QueryLimiter, link_type_filter: Option<Vec<VersionedUri>>) -> Self {
+        Self {
+            edges: Edges::new(),
+            referenced_data_types: DependencyMap::new(),
+            referenced_property_types: DependencyMap::new(),
+            referenced_link_types: DependencyMap::new(),
+            referenced_entity_types: DependencyMap::new(),
+            linked_entities: DependencyMap::new(),
+            links: DependencySet::new(),
+            graph_resolve_depths: limiter.graph_resolve_depths(),
+            link_type_filter,
+            limiter,
+        }
+    }
+
+    #[must_use]
+    pub fn as_ref_object(&mut self) -> DependencyContextRef {
+        DependencyContextRef {
+            edges: &mut self.edges,
+            referenced_data_types: &mut self.referenced_data_types,
+            referenced_property_types: &mut self.referenced_property_types,
+            referenced_link_types: &mut self.referenced_link_types,
+            referenced_entity_types: &mut self.referenced_entity_types,
+            linked_entities: &mut self.linked_entities,
+            links: &mut self.links,
+            graph_resolve_depths: self.graph_resolve_depths,
+            link_type_filter: self.link_type_filter.as_deref(),
+            limiter: self.limiter,
+        }
+    }
+
+    #[must_use]
+    pub fn into_subgraph(self, roots: Vec<GraphElementIdentifier>) -> Subgraph {
+        let vertices = self
+            .referenced_data_types
+            .into_values()
+            .map(|data_type| {
+                (
+                    GraphElementIdentifier::OntologyElementId(
+                        data_type.metadata().identifier().uri().clone(),
+                    ),
+                    Vertex::DataType(data_type),
+                )
+            })
+            .chain(
+                self.referenced_property_types
+                    .into_values()
+                    .map(|property_type| {
+                        (
+                            GraphElementIdentifier::OntologyElementId(
+                                property_type.metadata().identifier().uri().clone(),
+                            ),
+                            Vertex::PropertyType(property_type),
+                        )
+                    }),
+            )
+            .chain(self.referenced_link_types.into_values().map(|link_type| {
+                (
+                    GraphElementIdentifier::OntologyElementId(
+                        link_type.metadata().identifier().uri().clone(),
+                    ),
+                    Vertex::LinkType(link_type),
+                )
+            }))
+            .chain(
+                self.referenced_entity_types
+                    .into_values()
+                    .map(|entity_type| {
+                        (
+                            GraphElementIdentifier::OntologyElementId(
+                                entity_type.metadata().identifier().uri().clone(),
+                            ),
+                            Vertex::EntityType(entity_type),
+                        )
+                    }),
+            )
+            .chain(self.links.into_iter().map(|link| {
+                (
+                    GraphElementIdentifier::Temporary(LinkId {
+                        source_entity_id: link.inner().source_entity(),
+                        target_entity_id: link.inner().target_entity(),
+                        link_type_id: link.inner().link_type_id().clone(),
+                    }),
+                    Vertex::Link(link),
+                )
+            }))
+            .chain(self.linked_entities.into_values().map(|entity| {
+                (
+                    GraphElementIdentifier::KnowledgeGraphElementId(
+                        entity.metadata().identifier().entity_id(),
+                    ),
+                    Vertex::Entity(entity),
+                )
+            }))
+            .collect();
+
+        Subgraph {
+            roots,
+            vertices,
+            edges: self.edges,
+            depths: self.graph_resolve_depths,
+            truncated: self.limiter.is_truncated(),
+        }
+    }
+}
+
+pub struct DependencyContextRef<'a> {
+    pub edges: &'a mut Edges,
+    pub referenced_data_types:
+        &'a mut DependencyMap<VersionedUri, PersistedDataType, OntologyQueryDepth>,
+    pub referenced_property_types:
+        &'a mut DependencyMap<VersionedUri, PersistedPropertyType, OntologyQueryDepth>,
+    pub referenced_link_types:
+        &'a mut DependencyMap<VersionedUri, PersistedLinkType, OntologyQueryDepth>,
+    pub referenced_entity_types:
+        &'a mut DependencyMap<VersionedUri, PersistedEntityType, OntologyQueryDepth>,
+    pub linked_entities: &'a mut DependencyMap<EntityId, PersistedEntity, KnowledgeGraphQueryDepth>,
+    pub links: &'a mut DependencySet<PersistedLink, KnowledgeGraphQueryDepth>,
+    pub graph_resolve_depths: GraphResolveDepths,
+    pub link_type_filter: Option<&'a [VersionedUri]>,
+    pub limiter: &'a QueryLimiter,
+}
+
+impl<'a> DependencyContextRef<'a> {
+    pub fn change_depth(
+        &mut self,
+        graph_resolve_depths: GraphResolveDepths,
+    ) -> DependencyContextRef<'_>
+where {
+        DependencyContextRef {
+            edges: self.edges,
+            referenced_data_types: self.referenced_data_types,
+            referenced_property_types: self.referenced_property_types,
+            referenced_link_types: self.referenced_link_types,
+            referenced_entity_types: self.referenced_entity_types,
+            linked_entities: self.linked_entities,
+            links: self.links,
+            graph_resolve_depths,
+            link_type_filter: self.link_type_filter,
+            limiter: self.limiter,
+        }
+    }
+
+    /// Returns if links of the type `link_type_id` are followed.
+    #[must_use]
+    pub fn follows_link_type(&self, link_type_id: &VersionedUri) -> bool {
+        self.link_type_filter.map_or(true, |link_type_filter| {
+            link_type_filter.contains(link_type_id)
+        })
+    }
+}
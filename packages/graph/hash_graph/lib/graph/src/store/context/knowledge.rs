@@ -0,0 +1,308 @@
+use std::{future::Future, pin::Pin};
+
+use error_stack::Result;
+use futures::{FutureExt, TryStreamExt};
+
+use crate::{
+    knowledge::{EntityId, PersistedEntity, PersistedLink},
+    shared::identifier::{GraphElementIdentifier, LinkId},
+    store::{
+        context::{
+            get_entity_type_as_dependency, get_link_type_as_dependency, DependencyContextRef,
+            ResolveContext,
+        },
+        QueryError,
+    },
+    subgraph::{EdgeKind, GraphResolveDepths, OutwardEdge},
+};
+
+/// Internal function to read an [`Entity`] into a [`DependencyContext`].
+///
+/// This is used to recursively resolve a type, so the result can be reused.
+///
+/// [`DependencyContext`]: super::DependencyContext
+/// [`Entity`]: crate::knowledge::Entity
+pub(in crate::store) fn get_entity_as_dependency<'a: 'b, 'b, C>(
+    store: &'a C,
+    entity_id: EntityId,
+    mut dependency_context: DependencyContextRef<'b>,
+) -> Pin<Box<dyn Future<Output = Result<(), QueryError>> + Send + 'b>>
+where
+    C: ResolveContext + Sync,
+{
+    async move {
+        let unresolved_entity = dependency_context
+            .linked_entities
+            .insert_with(
+                &entity_id,
+                Some(
+                    dependency_context
+                        .graph_resolve_depths
+                        .link_target_entity_resolve_depth,
+                ),
+                dependency_context.limiter,
+                || async {
+                    Ok(PersistedEntity::from(
+                        store.read_latest_entity_by_id(entity_id).await?,
+                    ))
+                },
+            )
+            .await?;
+
+        if let Some(entity) = unresolved_entity {
+            // Cloning the entity type ID avoids multiple borrow errors which would otherwise
+            // require us to clone the entity
+            let entity_type_id = entity.metadata().entity_type_id().clone();
+
+            dependency_context.edges.insert(
+                GraphElementIdentifier::KnowledgeGraphElementId(entity_id),
+                OutwardEdge {
+                    edge_kind: EdgeKind::HasType,
+                    reversed: false,
+                    destination: GraphElementIdentifier::OntologyElementId(entity_type_id.clone()),
+                },
+            );
+
+            if dependency_context
+                .graph_resolve_depths
+                .entity_type_resolve_depth
+                > 0
+            {
+                get_entity_type_as_dependency(
+                    store,
+                    &entity_type_id,
+                    dependency_context.change_depth(GraphResolveDepths {
+                        entity_type_resolve_depth: dependency_context
+                            .graph_resolve_depths
+                            .entity_type_resolve_depth
+                            - 1,
+                        ..dependency_context.graph_resolve_depths
+                    }),
+                )
+                .await?;
+            }
+
+            for link_record in store
+                .read_links_by_source(entity_id)
+                .await?
+                .try_collect::<Vec<_>>()
+                .await?
+            {
+                if !dependency_context.follows_link_type(&link_record.link_type_id) {
+                    continue;
+                }
+
+                dependency_context.edges.insert(
+                    GraphElementIdentifier::KnowledgeGraphElementId(entity_id),
+                    OutwardEdge {
+                        edge_kind: EdgeKind::HasLink,
+                        reversed: false,
+                        destination: GraphElementIdentifier::Temporary(LinkId {
+                            source_entity_id: link_record.source_entity_id,
+                            target_entity_id: link_record.target_entity_id,
+                            link_type_id: link_record.link_type_id.clone(),
+                        }),
+                    },
+                );
+
+                if dependency_context.graph_resolve_depths.link_resolve_depth > 0 {
+                    let link = PersistedLink::from(link_record);
+
+                    get_link_as_dependency(
+                        store,
+                        &link,
+                        dependency_context.change_depth(GraphResolveDepths {
+                            link_resolve_depth: dependency_context
+                                .graph_resolve_depths
+                                .link_resolve_depth
+                                - 1,
+                            ..dependency_context.graph_resolve_depths
+                        }),
+                    )
+                    .await?;
+                }
+            }
+
+            if dependency_context
+                .graph_resolve_depths
+                .incoming_link_resolve_depth
+                > 0
+            {
+                for link_record in store
+                    .read_links_by_target(entity_id)
+                    .await?
+                    .try_collect::<Vec<_>>()
+                    .await?
+                {
+                    if !dependency_context.follows_link_type(&link_record.link_type_id) {
+                        continue;
+                    }
+
+                    dependency_context.edges.insert(
+                        GraphElementIdentifier::KnowledgeGraphElementId(entity_id),
+                        OutwardEdge {
+                            edge_kind: EdgeKind::HasDestination,
+                            reversed: true,
+                            destination: GraphElementIdentifier::Temporary(LinkId {
+                                source_entity_id: link_record.source_entity_id,
+                                target_entity_id: link_record.target_entity_id,
+                                link_type_id: link_record.link_type_id.clone(),
+                            }),
+                        },
+                    );
+
+                    let link = PersistedLink::from(link_record);
+
+                    get_link_as_dependency(
+                        store,
+                        &link,
+                        dependency_context.change_depth(GraphResolveDepths {
+                            incoming_link_resolve_depth: dependency_context
+                                .graph_resolve_depths
+                                .incoming_link_resolve_depth
+                                - 1,
+                            ..dependency_context.graph_resolve_depths
+                        }),
+                    )
+                    .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+    .boxed()
+}
+
+/// Internal function to read a [`Link`] into a [`DependencyContext`].
+///
+/// This is used to recursively resolve a type, so the result can be reused.
+///
+/// [`DependencyContext`]: super::DependencyContext
+/// [`Link`]: crate::knowledge::Link
+pub(in crate::store) fn get_link_as_dependency<'a: 'b, 'b, C>(
+    store: &'a C,
+    link: &'a PersistedLink,
+    mut dependency_context: DependencyContextRef<'b>,
+) -> Pin<Box<dyn Future<Output = Result<(), QueryError>> + Send + 'b>>
+where
+    C: ResolveContext + Sync,
+{
+    async move {
+        if let Some(link) = dependency_context.links.insert_limited(
+            link,
+            Some(dependency_context.graph_resolve_depths.link_resolve_depth),
+            dependency_context.limiter,
+        ) {
+            // Cloning/copying here avoids multiple borrow errors which would otherwise
+            // require us to clone the Link
+            let source_entity_id = link.inner().source_entity();
+            let target_entity_id = link.inner().target_entity();
+            let link_type_id = link.inner().link_type_id().clone();
+
+            dependency_context.edges.insert(
+                GraphElementIdentifier::Temporary(LinkId {
+                    source_entity_id,
+                    target_entity_id,
+                    link_type_id: link_type_id.clone(),
+                }),
+                OutwardEdge {
+                    edge_kind: EdgeKind::HasType,
+                    reversed: false,
+                    destination: GraphElementIdentifier::OntologyElementId(link_type_id.clone()),
+                },
+            );
+
+            if dependency_context
+                .graph_resolve_depths
+                .link_type_resolve_depth
+                > 0
+            {
+                let link_type_id = link.inner().link_type_id().clone();
+                get_link_type_as_dependency(
+                    store,
+                    &link_type_id,
+                    dependency_context.change_depth(GraphResolveDepths {
+                        link_type_resolve_depth: dependency_context
+                            .graph_resolve_depths
+                            .link_type_resolve_depth
+                            - 1,
+                        ..dependency_context.graph_resolve_depths
+                    }),
+                )
+                .await?;
+            }
+
+            if dependency_context
+                .graph_resolve_depths
+                .link_source_entity_resolve_depth
+                > 0
+            {
+                // The edge back to the source entity is only added if the source is resolved, so
+                // subgraphs only following outgoing links are not changed.
+                dependency_context.edges.insert(
+                    GraphElementIdentifier::Temporary(LinkId {
+                        source_entity_id,
+                        target_entity_id,
+                        link_type_id: link_type_id.clone(),
+                    }),
+                    OutwardEdge {
+                        edge_kind: EdgeKind::HasLink,
+                        reversed: true,
+                        destination: GraphElementIdentifier::KnowledgeGraphElementId(
+                            source_entity_id,
+                        ),
+                    },
+                );
+
+                get_entity_as_dependency(
+                    store,
+                    source_entity_id,
+                    dependency_context.change_depth(GraphResolveDepths {
+                        link_source_entity_resolve_depth: dependency_context
+                            .graph_resolve_depths
+                            .link_source_entity_resolve_depth
+                            - 1,
+                        ..dependency_context.graph_resolve_depths
+                    }),
+                )
+                .await?;
+            }
+
+            dependency_context.edges.insert(
+                GraphElementIdentifier::Temporary(LinkId {
+                    source_entity_id,
+                    target_entity_id,
+                    link_type_id,
+                }),
+                OutwardEdge {
+                    edge_kind: EdgeKind::HasDestination,
+                    reversed: false,
+                    destination: GraphElementIdentifier::KnowledgeGraphElementId(target_entity_id),
+                },
+            );
+
+            if dependency_context
+                .graph_resolve_depths
+                .link_target_entity_resolve_depth
+                > 0
+            {
+                get_entity_as_dependency(
+                    store,
+                    target_entity_id,
+                    dependency_context.change_depth(GraphResolveDepths {
+                        link_target_entity_resolve_depth: dependency_context
+                            .graph_resolve_depths
+                            .link_target_entity_resolve_depth
+                            - 1,
+                        ..dependency_context.graph_resolve_depths
+                    }),
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+    .boxed()
+}
@@ -0,0 +1,91 @@
+//! Query evaluation and subgraph resolution shared by all store backends.
+//!
+//! A backend only provides read access to its records by implementing [`ResolveContext`], the
+//! evaluation of queries and the resolution of subgraphs on top of it are independent of the
+//! backend.
+
+mod dependency;
+mod knowledge;
+mod ontology;
+mod record;
+mod resolve;
+mod subgraph;
+
+use async_trait::async_trait;
+use error_stack::{Context, Result};
+use type_system::uri::{BaseUri, VersionedUri};
+
+pub use self::{
+    dependency::{DependencyContext, DependencyContextRef, QueryLimiter},
+    record::{
+        EntityRecord, EntityRecordStream, LinkRecord, LinkRecordStream, OntologyRecord,
+        OntologyRecordStream, PersistedOntologyType, ReferencingType,
+    },
+};
+pub(in crate::store) use self::{
+    knowledge::{get_entity_as_dependency, get_link_as_dependency},
+    ontology::{
+        changed_ontology_resource, get_data_type_as_dependency, get_entity_type_as_dependency,
+        get_link_type_as_dependency, get_property_type_as_dependency, OntologyDatabaseType,
+    },
+    subgraph::{resolve_link_subgraphs, resolve_subgraph},
+};
+use crate::{knowledge::EntityId, store::QueryError};
+
+/// Context used for [`Resolve`].
+///
+/// This is only used as an implementation detail inside of the [`store`] module. It's implemented
+/// by the [`PostgresStore`] and the [`MemoryStore`], so both stores share the same query
+/// evaluation.
+///
+/// [`Resolve`]: crate::store::query::Resolve
+/// [`store`]: crate::store
+/// [`PostgresStore`]: crate::store::PostgresStore
+/// [`MemoryStore`]: crate::store::MemoryStore
+// TODO: Use the context to hold query data
+//   see https://app.asana.com/0/0/1202884883200946/f
+#[async_trait]
+pub trait ResolveContext {
+    async fn read_all_ontology_types<T>(&self) -> Result<OntologyRecordStream<T>, QueryError>
+    where
+        T: OntologyDatabaseType + TryFrom<serde_json::Value, Error: Context> + Send + 'static;
+
+    async fn read_latest_ontology_type<T>(
+        &self,
+        base_uri: &BaseUri,
+    ) -> Result<OntologyRecord<T>, QueryError>
+    where
+        T: OntologyDatabaseType + TryFrom<serde_json::Value, Error: Context>;
+
+    async fn read_versioned_ontology_type<T>(
+        &self,
+        uri: &VersionedUri,
+    ) -> Result<OntologyRecord<T>, QueryError>
+    where
+        T: OntologyDatabaseType + TryFrom<serde_json::Value, Error: Context>;
+
+    /// Returns the property types and entity types referencing the ontology type `uri`.
+    async fn read_referencing_ontology_types(
+        &self,
+        uri: &VersionedUri,
+    ) -> Result<Vec<ReferencingType>, QueryError>;
+
+    async fn read_all_entities(&self) -> Result<EntityRecordStream, QueryError>;
+
+    async fn read_latest_entity_by_id(
+        &self,
+        entity_id: EntityId,
+    ) -> Result<EntityRecord, QueryError>;
+
+    async fn read_all_links(&self) -> Result<LinkRecordStream, QueryError>;
+
+    async fn read_links_by_source(
+        &self,
+        entity_id: EntityId,
+    ) -> Result<LinkRecordStream, QueryError>;
+
+    async fn read_links_by_target(
+        &self,
+        entity_id: EntityId,
+    ) -> Result<LinkRecordStream, QueryError>;
+}
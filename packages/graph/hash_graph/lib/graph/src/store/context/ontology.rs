@@ -0,0 +1,490 @@
+use std::{future::Future, pin::Pin};
+
+use error_stack::Result;
+use futures::FutureExt;
+use type_system::{uri::VersionedUri, DataType, EntityType, LinkType, PropertyType};
+
+use crate::{
+    ontology::{PersistedDataType, PersistedEntityType, PersistedLinkType, PersistedPropertyType},
+    shared::identifier::GraphElementIdentifier,
+    store::{
+        change::ChangedResource,
+        context::{DependencyContextRef, PersistedOntologyType, ReferencingType, ResolveContext},
+        QueryError,
+    },
+    subgraph::{EdgeKind, GraphResolveDepths, OutwardEdge},
+};
+
+/// Provides an abstraction over elements of the Type System stored in the Database.
+///
+/// [`PostgresDatabase`]: crate::store::PostgresDatabase
+pub trait OntologyDatabaseType {
+    /// Returns the name of the table where this type is stored.
+    fn table() -> &'static str;
+
+    fn versioned_uri(&self) -> &VersionedUri;
+
+    /// Returns the [`ChangedResource`] describing a mutation of the type identified by `uri`.
+    fn changed_resource(uri: VersionedUri) -> ChangedResource;
+}
+
+/// Returns the [`ChangedResource`] describing a mutation of the type identified by `uri`, which is
+/// stored in `table`.
+pub(in crate::store) fn changed_ontology_resource(
+    table: &str,
+    uri: VersionedUri,
+) -> ChangedResource {
+    if table == DataType::table() {
+        DataType::changed_resource(uri)
+    } else if table == PropertyType::table() {
+        PropertyType::changed_resource(uri)
+    } else if table == LinkType::table() {
+        LinkType::changed_resource(uri)
+    } else {
+        debug_assert_eq!(table, EntityType::table(), "unknown ontology table");
+        EntityType::changed_resource(uri)
+    }
+}
+
+impl OntologyDatabaseType for DataType {
+    fn table() -> &'static str {
+        "data_types"
+    }
+
+    fn versioned_uri(&self) -> &VersionedUri {
+        self.id()
+    }
+
+    fn changed_resource(uri: VersionedUri) -> ChangedResource {
+        ChangedResource::DataType { uri }
+    }
+}
+
+impl OntologyDatabaseType for PropertyType {
+    fn table() -> &'static str {
+        "property_types"
+    }
+
+    fn versioned_uri(&self) -> &VersionedUri {
+        self.id()
+    }
+
+    fn changed_resource(uri: VersionedUri) -> ChangedResource {
+        ChangedResource::PropertyType { uri }
+    }
+}
+
+impl OntologyDatabaseType for EntityType {
+    fn table() -> &'static str {
+        "entity_types"
+    }
+
+    fn versioned_uri(&self) -> &VersionedUri {
+        self.id()
+    }
+
+    fn changed_resource(uri: VersionedUri) -> ChangedResource {
+        ChangedResource::EntityType { uri }
+    }
+}
+
+impl OntologyDatabaseType for LinkType {
+    fn table() -> &'static str {
+        "link_types"
+    }
+
+    fn versioned_uri(&self) -> &VersionedUri {
+        self.id()
+    }
+
+    fn changed_resource(uri: VersionedUri) -> ChangedResource {
+        ChangedResource::LinkType { uri }
+    }
+}
+
+/// Internal function to read a [`PersistedDataType`] into a [`DependencyContext`].
+///
+/// This is used to recursively resolve a type, so the result can be reused.
+///
+/// [`DependencyContext`]: super::DependencyContext
+pub(in crate::store) async fn get_data_type_as_dependency<C>(
+    store: &C,
+    data_type_id: &VersionedUri,
+    context: DependencyContextRef<'_>,
+) -> Result<(), QueryError>
+where
+    C: ResolveContext + Sync,
+{
+    let unresolved_data_type = context
+        .referenced_data_types
+        .insert_with(
+            data_type_id,
+            Some(context.graph_resolve_depths.data_type_resolve_depth),
+            context.limiter,
+            || async {
+                Ok(PersistedDataType::from_record(
+                    store.read_versioned_ontology_type(data_type_id).await?,
+                ))
+            },
+        )
+        .await?;
+
+    if unresolved_data_type.is_some() {
+        get_referencing_types_as_dependency(store, data_type_id, context).await?;
+    }
+
+    Ok(())
+}
+
+/// Internal function to read a [`PersistedPropertyType`] into two [`DependencyContext`]s.
+///
+/// This is used to recursively resolve a type, so the result can be reused.
+///
+/// [`DependencyContext`]: super::DependencyContext
+pub(in crate::store) fn get_property_type_as_dependency<'a: 'b, 'b, C>(
+    store: &'a C,
+    property_type_id: &'b VersionedUri,
+    mut dependency_context: DependencyContextRef<'b>,
+) -> Pin<Box<dyn Future<Output = Result<(), QueryError>> + Send + 'b>>
+where
+    C: ResolveContext + Sync,
+{
+    async move {
+        let unresolved_property_type = dependency_context
+            .referenced_property_types
+            .insert_with(
+                property_type_id,
+                Some(
+                    dependency_context
+                        .graph_resolve_depths
+                        .property_type_resolve_depth,
+                ),
+                dependency_context.limiter,
+                || async {
+                    Ok(PersistedPropertyType::from_record(
+                        store.read_versioned_ontology_type(property_type_id).await?,
+                    ))
+                },
+            )
+            .await?;
+
+        if let Some(property_type) = unresolved_property_type.cloned() {
+            // TODO: Use relation tables
+            //   see https://app.asana.com/0/0/1202884883200942/f
+            for data_type_ref in property_type.inner().data_type_references() {
+                dependency_context.edges.insert(
+                    GraphElementIdentifier::OntologyElementId(property_type_id.clone()),
+                    OutwardEdge {
+                        edge_kind: EdgeKind::References,
+                        reversed: false,
+                        destination: GraphElementIdentifier::OntologyElementId(
+                            data_type_ref.uri().clone(),
+                        ),
+                    },
+                );
+                if dependency_context
+                    .graph_resolve_depths
+                    .data_type_resolve_depth
+                    > 0
+                {
+                    get_data_type_as_dependency(
+                        store,
+                        data_type_ref.uri(),
+                        dependency_context.change_depth(GraphResolveDepths {
+                            data_type_resolve_depth: dependency_context
+                                .graph_resolve_depths
+                                .data_type_resolve_depth
+                                - 1,
+                            ..dependency_context.graph_resolve_depths
+                        }),
+                    )
+                    .await?;
+                }
+            }
+
+            // TODO: Use relation tables
+            //   see https://app.asana.com/0/0/1202884883200942/f
+            for property_type_ref in property_type.inner().property_type_references() {
+                dependency_context.edges.insert(
+                    GraphElementIdentifier::OntologyElementId(property_type_id.clone()),
+                    OutwardEdge {
+                        edge_kind: EdgeKind::References,
+                        reversed: false,
+                        destination: GraphElementIdentifier::OntologyElementId(
+                            property_type_ref.uri().clone(),
+                        ),
+                    },
+                );
+
+                if dependency_context
+                    .graph_resolve_depths
+                    .property_type_resolve_depth
+                    > 0
+                {
+                    get_property_type_as_dependency(
+                        store,
+                        property_type_ref.uri(),
+                        dependency_context.change_depth(GraphResolveDepths {
+                            property_type_resolve_depth: dependency_context
+                                .graph_resolve_depths
+                                .property_type_resolve_depth
+                                - 1,
+                            ..dependency_context.graph_resolve_depths
+                        }),
+                    )
+                    .await?;
+                }
+            }
+
+            get_referencing_types_as_dependency(store, property_type_id, dependency_context)
+                .await?;
+        }
+
+        Ok(())
+    }
+    .boxed()
+}
+
+/// Internal function to read a [`PersistedLinkType`] into a [`DependencyContext`].
+///
+/// This is used to recursively resolve a type, so the result can be reused.
+///
+/// [`DependencyContext`]: super::DependencyContext
+pub(in crate::store) async fn get_link_type_as_dependency<'a, C>(
+    store: &C,
+    link_type_id: &VersionedUri,
+    context: DependencyContextRef<'a>,
+) -> Result<(), QueryError>
+where
+    C: ResolveContext + Sync,
+{
+    let unresolved_link_type = context
+        .referenced_link_types
+        .insert_with(
+            link_type_id,
+            Some(context.graph_resolve_depths.link_type_resolve_depth),
+            context.limiter,
+            || async {
+                Ok(PersistedLinkType::from_record(
+                    store.read_versioned_ontology_type(link_type_id).await?,
+                ))
+            },
+        )
+        .await?;
+
+    if unresolved_link_type.is_some() {
+        get_referencing_types_as_dependency(store, link_type_id, context).await?;
+    }
+
+    Ok(())
+}
+
+#[expect(
+    clippy::too_many_lines,
+    reason = "difficult to shrink the number of lines with destructuring and so many variables \
+              needing to be passed independently"
+)]
+/// Internal function to read a [`PersistedEntityType`] into four [`DependencyContext`]s.
+///
+/// This is used to recursively resolve a type, so the result can be reused.
+///
+/// [`DependencyContext`]: super::DependencyContext
+pub(in crate::store) fn get_entity_type_as_dependency<'a: 'b, 'b, C>(
+    store: &'a C,
+    entity_type_id: &'a VersionedUri,
+    mut dependency_context: DependencyContextRef<'b>,
+) -> Pin<Box<dyn Future<Output = Result<(), QueryError>> + Send + 'b>>
+where
+    C: ResolveContext + Sync,
+{
+    async move {
+        let unresolved_entity_type = dependency_context
+            .referenced_entity_types
+            .insert_with(
+                entity_type_id,
+                Some(
+                    dependency_context
+                        .graph_resolve_depths
+                        .entity_type_resolve_depth,
+                ),
+                dependency_context.limiter,
+                || async {
+                    Ok(PersistedEntityType::from_record(
+                        store.read_versioned_ontology_type(entity_type_id).await?,
+                    ))
+                },
+            )
+            .await?;
+
+        if let Some(entity_type) = unresolved_entity_type.cloned() {
+            for property_type_ref in entity_type.inner().property_type_references() {
+                dependency_context.edges.insert(
+                    GraphElementIdentifier::OntologyElementId(entity_type_id.clone()),
+                    OutwardEdge {
+                        edge_kind: EdgeKind::References,
+                        reversed: false,
+                        destination: GraphElementIdentifier::OntologyElementId(
+                            property_type_ref.uri().clone(),
+                        ),
+                    },
+                );
+
+                if dependency_context
+                    .graph_resolve_depths
+                    .property_type_resolve_depth
+                    > 0
+                {
+                    // TODO: Use relation tables
+                    //   see https://app.asana.com/0/0/1202884883200942/f
+                    get_property_type_as_dependency(
+                        store,
+                        property_type_ref.uri(),
+                        dependency_context.change_depth(GraphResolveDepths {
+                            property_type_resolve_depth: dependency_context
+                                .graph_resolve_depths
+                                .property_type_resolve_depth
+                                - 1,
+                            ..dependency_context.graph_resolve_depths
+                        }),
+                    )
+                    .await?;
+                }
+            }
+
+            // TODO: Use relation tables
+            //   see https://app.asana.com/0/0/1202884883200942/f
+            for (link_type_id, entity_type_ids) in entity_type.inner().link_type_references() {
+                dependency_context.edges.insert(
+                    GraphElementIdentifier::OntologyElementId(entity_type_id.clone()),
+                    OutwardEdge {
+                        edge_kind: EdgeKind::References,
+                        reversed: false,
+                        destination: GraphElementIdentifier::OntologyElementId(
+                            link_type_id.clone(),
+                        ),
+                    },
+                );
+
+                if dependency_context
+                    .graph_resolve_depths
+                    .link_type_resolve_depth
+                    > 0
+                {
+                    get_link_type_as_dependency(
+                        store,
+                        link_type_id,
+                        dependency_context.change_depth(GraphResolveDepths {
+                            link_type_resolve_depth: dependency_context
+                                .graph_resolve_depths
+                                .link_type_resolve_depth
+                                - 1,
+                            ..dependency_context.graph_resolve_depths
+                        }),
+                    )
+                    .await?;
+                }
+                for referenced_entity_type_id in entity_type_ids {
+                    dependency_context.edges.insert(
+                        GraphElementIdentifier::OntologyElementId(entity_type_id.clone()),
+                        OutwardEdge {
+                            edge_kind: EdgeKind::References,
+                            reversed: false,
+                            destination: GraphElementIdentifier::OntologyElementId(
+                                referenced_entity_type_id.uri().clone(),
+                            ),
+                        },
+                    );
+
+                    if dependency_context
+                        .graph_resolve_depths
+                        .entity_type_resolve_depth
+                        > 0
+                    {
+                        get_entity_type_as_dependency(
+                            store,
+                            referenced_entity_type_id.uri(),
+                            dependency_context.change_depth(GraphResolveDepths {
+                                entity_type_resolve_depth: dependency_context
+                                    .graph_resolve_depths
+                                    .entity_type_resolve_depth
+                                    - 1,
+                                ..dependency_context.graph_resolve_depths
+                            }),
+                        )
+                        .await?;
+                    }
+                }
+            }
+
+            get_referencing_types_as_dependency(store, entity_type_id, dependency_context).await?;
+        }
+
+        Ok(())
+    }
+    .boxed()
+}
+
+/// Internal function to read the types referencing an ontology type into a [`DependencyContext`].
+///
+/// This resolves the reversed [`EdgeKind::References`] edges up to the
+/// `referencing_type_resolve_depth`.
+///
+/// [`DependencyContext`]: super::DependencyContext
+pub(in crate::store) fn get_referencing_types_as_dependency<'a: 'b, 'b, C>(
+    store: &'a C,
+    ontology_type_id: &'b VersionedUri,
+    mut dependency_context: DependencyContextRef<'b>,
+) -> Pin<Box<dyn Future<Output = Result<(), QueryError>> + Send + 'b>>
+where
+    C: ResolveContext + Sync,
+{
+    async move {
+        if dependency_context
+            .graph_resolve_depths
+            .referencing_type_resolve_depth
+            == 0
+        {
+            return Ok(());
+        }
+
+        for referencing_type in store
+            .read_referencing_ontology_types(ontology_type_id)
+            .await?
+        {
+            let (ReferencingType::PropertyType(referencing_type_id)
+            | ReferencingType::EntityType(referencing_type_id)) = &referencing_type;
+
+            dependency_context.edges.insert(
+                GraphElementIdentifier::OntologyElementId(ontology_type_id.clone()),
+                OutwardEdge {
+                    edge_kind: EdgeKind::References,
+                    reversed: true,
+                    destination: GraphElementIdentifier::OntologyElementId(
+                        referencing_type_id.clone(),
+                    ),
+                },
+            );
+
+            let referencing_context = dependency_context.change_depth(GraphResolveDepths {
+                referencing_type_resolve_depth: dependency_context
+                    .graph_resolve_depths
+                    .referencing_type_resolve_depth
+                    - 1,
+                ..dependency_context.graph_resolve_depths
+            });
+            match referencing_type {
+                ReferencingType::PropertyType(ref property_type_id) => {
+                    get_property_type_as_dependency(store, property_type_id, referencing_context)
+                        .await?;
+                }
+                ReferencingType::EntityType(ref entity_type_id) => {
+                    get_entity_type_as_dependency(store, entity_type_id, referencing_context)
+                        .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+    .boxed()
+}
@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use error_stack::Result;
+use futures::stream::BoxStream;
+use type_system::{uri::VersionedUri, DataType, EntityType, LinkType, PropertyType};
+
+use crate::{
+    knowledge::{
+        Entity, EntityId, Link, PersistedEntity, PersistedEntityIdentifier, PersistedLink,
+    },
+    ontology::{
+        AccountId, Deprecation, PersistedDataType, PersistedEntityType, PersistedLinkType,
+        PersistedOntologyIdentifier, PersistedOntologyMetadata, PersistedPropertyType,
+    },
+    store::QueryError,
+};
+
+pub type OntologyRecordStream<T> = BoxStream<'static, Result<OntologyRecord<T>, QueryError>>;
+
+/// Associates a database entry with the information about the latest version of the corresponding
+/// entry.
+///
+/// This is used for filtering by the latest version.
+#[derive(Debug)]
+pub struct OntologyRecord<T> {
+    pub record: T,
+    pub owned_by_id: AccountId,
+    pub created_by_id: AccountId,
+    pub updated_by_id: AccountId,
+    pub removed_by_id: Option<AccountId>,
+    pub deprecation: Option<Deprecation>,
+    pub is_latest: bool,
+}
+
+/// An ontology type referencing another ontology type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ReferencingType {
+    PropertyType(VersionedUri),
+    EntityType(VersionedUri),
+}
+
+pub type EntityRecordStream = BoxStream<'static, Result<EntityRecord, QueryError>>;
+
+pub struct EntityRecord {
+    pub entity: Entity,
+    pub id: EntityId,
+    pub version: DateTime<Utc>,
+    pub entity_type_id: VersionedUri,
+    pub owned_by_id: AccountId,
+    pub created_by_id: AccountId,
+    pub updated_by_id: AccountId,
+    pub removed_by_id: Option<AccountId>,
+    pub is_latest: bool,
+}
+
+impl From<EntityRecord> for PersistedEntity {
+    fn from(record: EntityRecord) -> Self {
+        Self::new(
+            record.entity,
+            PersistedEntityIdentifier::new(record.id, record.version, record.owned_by_id),
+            record.entity_type_id,
+            record.created_by_id,
+            record.updated_by_id,
+            record.removed_by_id,
+        )
+    }
+}
+
+pub type LinkRecordStream = BoxStream<'static, Result<LinkRecord, QueryError>>;
+
+pub struct LinkRecord {
+    pub link_type_id: VersionedUri,
+    pub source_entity_id: EntityId,
+    pub target_entity_id: EntityId,
+    pub owned_by_id: AccountId,
+    pub index: Option<i32>,
+    pub created_by_id: AccountId,
+}
+
+impl From<LinkRecord> for PersistedLink {
+    fn from(record: LinkRecord) -> Self {
+        Self::new(
+            Link::new(
+                record.source_entity_id,
+                record.target_entity_id,
+                record.link_type_id,
+                record.index,
+            ),
+            record.owned_by_id,
+            record.created_by_id,
+        )
+    }
+}
+
+pub trait PersistedOntologyType {
+    type Inner;
+
+    fn from_record(record: OntologyRecord<Self::Inner>) -> Self;
+}
+
+impl PersistedOntologyType for PersistedDataType {
+    type Inner = DataType;
+
+    fn from_record(data_type: OntologyRecord<Self::Inner>) -> Self {
+        let identifier =
+            PersistedOntologyIdentifier::new(data_type.record.id().clone(), data_type.owned_by_id);
+
+        Self::new(
+            data_type.record,
+            PersistedOntologyMetadata::new(
+                identifier,
+                data_type.created_by_id,
+                data_type.updated_by_id,
+                data_type.removed_by_id,
+            )
+            .with_deprecation(data_type.deprecation),
+        )
+    }
+}
+
+impl PersistedOntologyType for PersistedPropertyType {
+    type Inner = PropertyType;
+
+    fn from_record(property_type: OntologyRecord<Self::Inner>) -> Self {
+        let identifier = PersistedOntologyIdentifier::new(
+            property_type.record.id().clone(),
+            property_type.owned_by_id,
+        );
+
+        Self::new(
+            property_type.record,
+            PersistedOntologyMetadata::new(
+                identifier,
+                property_type.created_by_id,
+                property_type.updated_by_id,
+                property_type.removed_by_id,
+            )
+            .with_deprecation(property_type.deprecation),
+        )
+    }
+}
+
+impl PersistedOntologyType for PersistedLinkType {
+    type Inner = LinkType;
+
+    fn from_record(link_type: OntologyRecord<Self::Inner>) -> Self {
+        let identifier =
+            PersistedOntologyIdentifier::new(link_type.record.id().clone(), link_type.owned_by_id);
+
+        Self::new(
+            link_type.record,
+            PersistedOntologyMetadata::new(
+                identifier,
+                link_type.created_by_id,
+                link_type.updated_by_id,
+                link_type.removed_by_id,
+            )
+            .with_deprecation(link_type.deprecation),
+        )
+    }
+}
+
+impl PersistedOntologyType for PersistedEntityType {
+    type Inner = EntityType;
+
+    fn from_record(entity_type: OntologyRecord<Self::Inner>) -> Self {
+        let identifier = PersistedOntologyIdentifier::new(
+            entity_type.record.id().clone(),
+            entity_type.owned_by_id,
+        );
+        Self::new(
+            entity_type.record,
+            PersistedOntologyMetadata::new(
+                identifier,
+                entity_type.created_by_id,
+                entity_type.updated_by_id,
+                entity_type.removed_by_id,
+            )
+            .with_deprecation(entity_type.deprecation),
+        )
+    }
+}
@@ -6,7 +6,7 @@ use futures::{stream, StreamExt, TryStreamExt};
 use type_system::{DataType, DataTypeReference};
 
 use crate::store::{
-    postgres::context::{OntologyRecord, PostgresContext},
+    context::{OntologyRecord, ResolveContext},
     query::{
         Literal, PathSegment, Resolve, ResolveError, Version, UNIMPLEMENTED_LITERAL_OBJECT,
         UNIMPLEMENTED_WILDCARDS,
@@ -16,7 +16,7 @@ use crate::store::{
 #[async_trait]
 impl<C, S> Resolve<C> for HashSet<&DataTypeReference, S>
 where
-    C: PostgresContext + Sync + ?Sized,
+    C: ResolveContext + Sync + ?Sized,
     S: BuildHasher + Sync,
 {
     async fn resolve(&self, path: &[PathSegment], context: &C) -> Result<Literal, ResolveError> {
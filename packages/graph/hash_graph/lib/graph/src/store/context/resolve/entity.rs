@@ -6,7 +6,7 @@ use type_system::{uri::BaseUri, EntityType};
 use crate::{
     knowledge::Entity,
     store::{
-        postgres::context::{EntityRecord, PostgresContext},
+        context::{EntityRecord, ResolveContext},
         query::{
             Literal, PathSegment, Resolve, ResolveError, Version, UNIMPLEMENTED_LITERAL_OBJECT,
         },
@@ -16,7 +16,7 @@ use crate::{
 #[async_trait]
 impl<C> Resolve<C> for EntityRecord
 where
-    C: PostgresContext + Sync + ?Sized,
+    C: ResolveContext + Sync + ?Sized,
 {
     async fn resolve(&self, path: &[PathSegment], context: &C) -> Result<Literal, ResolveError> {
         match path {
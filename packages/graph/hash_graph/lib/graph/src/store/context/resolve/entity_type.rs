@@ -3,14 +3,14 @@ use error_stack::Result;
 use type_system::EntityType;
 
 use crate::store::{
-    postgres::context::{OntologyRecord, PostgresContext},
+    context::{OntologyRecord, ResolveContext},
     query::{Literal, PathSegment, Resolve, ResolveError, Version, UNIMPLEMENTED_LITERAL_OBJECT},
 };
 
 #[async_trait]
 impl<C> Resolve<C> for OntologyRecord<EntityType>
 where
-    C: PostgresContext + Sync + ?Sized,
+    C: ResolveContext + Sync + ?Sized,
 {
     async fn resolve(&self, path: &[PathSegment], context: &C) -> Result<Literal, ResolveError> {
         match path {
@@ -3,14 +3,14 @@ use error_stack::{Result, ResultExt};
 use type_system::LinkType;
 
 use crate::store::{
-    postgres::context::{LinkRecord, PostgresContext},
+    context::{LinkRecord, ResolveContext},
     query::{Literal, PathSegment, Resolve, ResolveError, UNIMPLEMENTED_LITERAL_OBJECT},
 };
 
 #[async_trait]
 impl<C> Resolve<C> for LinkRecord
 where
-    C: PostgresContext + Sync + ?Sized,
+    C: ResolveContext + Sync + ?Sized,
 {
     async fn resolve(&self, path: &[PathSegment], context: &C) -> Result<Literal, ResolveError> {
         match path {
@@ -6,14 +6,14 @@ use futures::{stream, StreamExt, TryStreamExt};
 use type_system::{uri::VersionedUri, EntityType, EntityTypeReference, LinkType};
 
 use crate::store::{
-    postgres::context::{OntologyRecord, PostgresContext},
+    context::{OntologyRecord, ResolveContext},
     query::{Literal, PathSegment, Resolve, ResolveError, Version, UNIMPLEMENTED_LITERAL_OBJECT},
 };
 
 #[async_trait]
 impl<C, S> Resolve<C> for HashMap<&VersionedUri, &[EntityTypeReference], S>
 where
-    C: PostgresContext + Sync + ?Sized,
+    C: ResolveContext + Sync + ?Sized,
     S: BuildHasher + Sync,
 {
     async fn resolve(&self, path: &[PathSegment], context: &C) -> Result<Literal, ResolveError> {
@@ -75,7 +75,7 @@ where
 #[async_trait]
 impl<C> Resolve<C> for OntologyRecord<LinkType>
 where
-    C: PostgresContext + Sync + ?Sized,
+    C: ResolveContext + Sync + ?Sized,
 {
     async fn resolve(&self, path: &[PathSegment], context: &C) -> Result<Literal, ResolveError> {
         match path {
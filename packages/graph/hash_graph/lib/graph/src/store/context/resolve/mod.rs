@@ -0,0 +1,11 @@
+//! [`Resolve`] implementations for the records of the [`ResolveContext`].
+//!
+//! [`Resolve`]: crate::store::query::Resolve
+//! [`ResolveContext`]: crate::store::context::ResolveContext
+
+mod data_type;
+mod entity;
+mod entity_type;
+mod link;
+mod link_type;
+mod property_type;
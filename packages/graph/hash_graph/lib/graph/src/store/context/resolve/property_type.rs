@@ -6,7 +6,7 @@ use futures::{stream, StreamExt, TryStreamExt};
 use type_system::{PropertyType, PropertyTypeReference};
 
 use crate::store::{
-    postgres::context::{OntologyRecord, PostgresContext},
+    context::{OntologyRecord, ResolveContext},
     query::{
         Literal, PathSegment, Resolve, ResolveError, Version, UNIMPLEMENTED_LITERAL_OBJECT,
         UNIMPLEMENTED_WILDCARDS,
@@ -16,7 +16,7 @@ use crate::store::{
 #[async_trait]
 impl<C, S> Resolve<C> for HashSet<&PropertyTypeReference, S>
 where
-    C: PostgresContext + Sync + ?Sized,
+    C: ResolveContext + Sync + ?Sized,
     S: BuildHasher + Sync,
 {
     async fn resolve(&self, path: &[PathSegment], context: &C) -> Result<Literal, ResolveError> {
@@ -54,7 +54,7 @@ where
 #[async_trait]
 impl<C> Resolve<C> for OntologyRecord<PropertyType>
 where
-    C: PostgresContext + Sync + ?Sized,
+    C: ResolveContext + Sync + ?Sized,
 {
     async fn resolve(&self, path: &[PathSegment], context: &C) -> Result<Literal, ResolveError> {
         match path {
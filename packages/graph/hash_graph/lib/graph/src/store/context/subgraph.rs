@@ -0,0 +1,237 @@
+use async_trait::async_trait;
+use error_stack::{Report, Result};
+use futures::{future, stream, StreamExt, TryStreamExt};
+
+use crate::{
+    knowledge::{LinkRootedSubgraph, PersistedEntity, PersistedLink},
+    ontology::{PersistedDataType, PersistedEntityType, PersistedLinkType, PersistedPropertyType},
+    shared::identifier::GraphElementIdentifier,
+    store::{
+        context::{
+            get_data_type_as_dependency, get_entity_as_dependency, get_entity_type_as_dependency,
+            get_link_as_dependency, get_link_type_as_dependency, get_property_type_as_dependency,
+            DependencyContext, QueryLimiter, ResolveContext,
+        },
+        QueryError, QueryLimits,
+    },
+    subgraph::{StructuralQuery, Subgraph},
+};
+
+/// A vertex, which can be the root of the [`Subgraph`] of a [`StructuralQuery`].
+#[async_trait]
+pub(in crate::store) trait SubgraphRoot: Sized + Send {
+    /// Adds the root to `dependency_context` and resolves its dependencies.
+    ///
+    /// Returns the identifier of the root inside of the [`Subgraph`].
+    async fn resolve_as_root<C>(
+        self,
+        store: &C,
+        dependency_context: &mut DependencyContext<'_>,
+    ) -> Result<GraphElementIdentifier, QueryError>
+    where
+        C: ResolveContext + Sync;
+}
+
+#[async_trait]
+impl SubgraphRoot for PersistedDataType {
+    async fn resolve_as_root<C>(
+        self,
+        store: &C,
+        dependency_context: &mut DependencyContext<'_>,
+    ) -> Result<GraphElementIdentifier, QueryError>
+    where
+        C: ResolveContext + Sync,
+    {
+        let data_type_id = self.metadata().identifier().uri().clone();
+        dependency_context
+            .referenced_data_types
+            .insert(&data_type_id, None, self);
+
+        get_data_type_as_dependency(store, &data_type_id, dependency_context.as_ref_object())
+            .await?;
+
+        Ok(GraphElementIdentifier::OntologyElementId(data_type_id))
+    }
+}
+
+#[async_trait]
+impl SubgraphRoot for PersistedPropertyType {
+    async fn resolve_as_root<C>(
+        self,
+        store: &C,
+        dependency_context: &mut DependencyContext<'_>,
+    ) -> Result<GraphElementIdentifier, QueryError>
+    where
+        C: ResolveContext + Sync,
+    {
+        let property_type_id = self.metadata().identifier().uri().clone();
+        dependency_context
+            .referenced_property_types
+            .insert(&property_type_id, None, self);
+
+        get_property_type_as_dependency(
+            store,
+            &property_type_id,
+            dependency_context.as_ref_object(),
+        )
+        .await?;
+
+        Ok(GraphElementIdentifier::OntologyElementId(property_type_id))
+    }
+}
+
+#[async_trait]
+impl SubgraphRoot for PersistedLinkType {
+    async fn resolve_as_root<C>(
+        self,
+        store: &C,
+        dependency_context: &mut DependencyContext<'_>,
+    ) -> Result<GraphElementIdentifier, QueryError>
+    where
+        C: ResolveContext + Sync,
+    {
+        let link_type_id = self.metadata().identifier().uri().clone();
+        dependency_context
+            .referenced_link_types
+            .insert(&link_type_id, None, self);
+
+        get_link_type_as_dependency(store, &link_type_id, dependency_context.as_ref_object())
+            .await?;
+
+        Ok(GraphElementIdentifier::OntologyElementId(link_type_id))
+    }
+}
+
+#[async_trait]
+impl SubgraphRoot for PersistedEntityType {
+    async fn resolve_as_root<C>(
+        self,
+        store: &C,
+        dependency_context: &mut DependencyContext<'_>,
+    ) -> Result<GraphElementIdentifier, QueryError>
+    where
+        C: ResolveContext + Sync,
+    {
+        let entity_type_id = self.metadata().identifier().uri().clone();
+        dependency_context
+            .referenced_entity_types
+            .insert(&entity_type_id, None, self);
+
+        get_entity_type_as_dependency(store, &entity_type_id, dependency_context.as_ref_object())
+            .await?;
+
+        Ok(GraphElementIdentifier::OntologyElementId(entity_type_id))
+    }
+}
+
+#[async_trait]
+impl SubgraphRoot for PersistedEntity {
+    async fn resolve_as_root<C>(
+        self,
+        store: &C,
+        dependency_context: &mut DependencyContext<'_>,
+    ) -> Result<GraphElementIdentifier, QueryError>
+    where
+        C: ResolveContext + Sync,
+    {
+        let entity_id = self.metadata().identifier().entity_id();
+        dependency_context
+            .linked_entities
+            .insert(&entity_id, None, self);
+
+        get_entity_as_dependency(store, entity_id, dependency_context.as_ref_object()).await?;
+
+        Ok(GraphElementIdentifier::KnowledgeGraphElementId(entity_id))
+    }
+}
+
+/// Resolves the [`Subgraph`] of `query` for the `roots` matching its expression.
+///
+/// The subgraphs of the roots are merged. Roots are skipped, once the maximum number of vertices
+/// of `query_limits` is reached, in which case the subgraph is marked as truncated.
+pub(in crate::store) async fn resolve_subgraph<C, R>(
+    store: &C,
+    roots: Vec<R>,
+    query: &StructuralQuery,
+    query_limits: &QueryLimits,
+) -> Result<Subgraph, QueryError>
+where
+    C: ResolveContext + Sync,
+    R: SubgraphRoot,
+{
+    let limiter = QueryLimiter::new(query_limits, query.graph_resolve_depths);
+    let limiter = &limiter;
+    let subgraphs = stream::iter(roots)
+        .take_while(|_| future::ready(limiter.reserve_vertex()))
+        .then(|root| async move {
+            let mut dependency_context =
+                DependencyContext::new(limiter, query.link_type_filter.clone());
+
+            let root = root.resolve_as_root(store, &mut dependency_context).await?;
+
+            Ok::<_, Report<QueryError>>(dependency_context.into_subgraph(vec![root]))
+        })
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    let mut subgraph = Subgraph::new(limiter.graph_resolve_depths());
+    subgraph.extend(subgraphs);
+    subgraph.truncated = limiter.is_truncated();
+
+    Ok(subgraph)
+}
+
+/// Resolves a [`LinkRootedSubgraph`] of `query` for every link of `roots`.
+///
+/// Roots are skipped, once the maximum number of vertices of `query_limits` is reached, in which
+/// case every subgraph is marked as truncated.
+pub(in crate::store) async fn resolve_link_subgraphs<C>(
+    store: &C,
+    roots: Vec<PersistedLink>,
+    query: &StructuralQuery,
+    query_limits: &QueryLimits,
+) -> Result<Vec<LinkRootedSubgraph>, QueryError>
+where
+    C: ResolveContext + Sync,
+{
+    let limiter = QueryLimiter::new(query_limits, query.graph_resolve_depths);
+    let limiter = &limiter;
+    let mut subgraphs = stream::iter(roots)
+        .take_while(|_| future::ready(limiter.reserve_vertex()))
+        .then(|link| async move {
+            let mut dependency_context =
+                DependencyContext::new(limiter, query.link_type_filter.clone());
+
+            dependency_context.links.insert(&link, None);
+
+            get_link_as_dependency(store, &link, dependency_context.as_ref_object()).await?;
+
+            let root = dependency_context
+                .links
+                .remove(&link)
+                .expect("root was not added to the subgraph");
+
+            Ok::<_, Report<QueryError>>(LinkRootedSubgraph {
+                link: root,
+                referenced_data_types: dependency_context.referenced_data_types.into_vec(),
+                referenced_property_types: dependency_context.referenced_property_types.into_vec(),
+                referenced_link_types: dependency_context.referenced_link_types.into_vec(),
+                referenced_entity_types: dependency_context.referenced_entity_types.into_vec(),
+                linked_entities: dependency_context.linked_entities.into_vec(),
+                links: dependency_context.links.into_vec(),
+                truncated: false,
+            })
+        })
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    // Roots might have been skipped after the last subgraph was resolved, so every subgraph is
+    // marked if the result is truncated.
+    if limiter.is_truncated() {
+        for subgraph in &mut subgraphs {
+            subgraph.truncated = true;
+        }
+    }
+
+    Ok(subgraphs)
+}
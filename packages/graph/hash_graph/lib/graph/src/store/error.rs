@@ -97,3 +97,51 @@ impl fmt::Display for LinkRemovalError {
 }
 
 impl Context for LinkRemovalError {}
+
+#[derive(Debug)]
+#[must_use]
+pub struct AccountDoesNotExist;
+
+impl fmt::Display for AccountDoesNotExist {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("account does not exist")
+    }
+}
+
+impl Context for AccountDoesNotExist {}
+
+#[derive(Debug)]
+#[must_use]
+pub struct InvalidReplacement;
+
+impl fmt::Display for InvalidReplacement {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("a deprecated type can only be replaced by another type of the same kind")
+    }
+}
+
+impl Context for InvalidReplacement {}
+
+#[derive(Debug)]
+#[must_use]
+pub struct OntologyTypeDoesNotExist;
+
+impl fmt::Display for OntologyTypeDoesNotExist {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("ontology type does not exist")
+    }
+}
+
+impl Context for OntologyTypeDoesNotExist {}
+
+#[derive(Debug)]
+#[must_use]
+pub struct UnexpectedOwner;
+
+impl fmt::Display for UnexpectedOwner {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("the resource is owned by a different account than expected")
+    }
+}
+
+impl Context for UnexpectedOwner {}
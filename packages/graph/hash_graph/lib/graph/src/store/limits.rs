@@ -0,0 +1,111 @@
+use crate::{
+    knowledge::KnowledgeGraphQueryDepth, ontology::OntologyQueryDepth, subgraph::GraphResolveDepths,
+};
+
+/// The default maximum depth, to which ontology types are resolved on each axis.
+const DEFAULT_MAX_ONTOLOGY_RESOLVE_DEPTH: OntologyQueryDepth = 16;
+
+/// The default maximum depth, to which entities and links are resolved on each axis.
+const DEFAULT_MAX_KNOWLEDGE_RESOLVE_DEPTH: KnowledgeGraphQueryDepth = 8;
+
+/// The default maximum number of vertices in the subgraph of a single query.
+const DEFAULT_MAX_VERTICES: usize = 10_000;
+
+/// The default number of seconds after which reading the subgraph of a single query is aborted.
+const DEFAULT_QUERY_TIMEOUT: u64 = 30;
+
+/// Limits the cost of resolving the subgraph of a [`StructuralQuery`].
+///
+/// Requested depths above the maximum are lowered to the maximum and the resolution stops as soon
+/// as the subgraph contains `max_vertices` vertices. In both cases the returned subgraph is marked
+/// as truncated.
+///
+/// [`StructuralQuery`]: crate::subgraph::StructuralQuery
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::Args))]
+pub struct QueryLimits {
+    /// The maximum depth, to which ontology types are resolved on each axis
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long = "query-max-ontology-resolve-depth",
+            default_value_t = DEFAULT_MAX_ONTOLOGY_RESOLVE_DEPTH,
+            env = "HASH_GRAPH_QUERY_MAX_ONTOLOGY_RESOLVE_DEPTH"
+        )
+    )]
+    pub max_ontology_resolve_depth: OntologyQueryDepth,
+
+    /// The maximum depth, to which entities and links are resolved on each axis
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long = "query-max-knowledge-resolve-depth",
+            default_value_t = DEFAULT_MAX_KNOWLEDGE_RESOLVE_DEPTH,
+            env = "HASH_GRAPH_QUERY_MAX_KNOWLEDGE_RESOLVE_DEPTH"
+        )
+    )]
+    pub max_knowledge_resolve_depth: KnowledgeGraphQueryDepth,
+
+    /// The maximum number of vertices in the subgraph of a single query
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long = "query-max-vertices",
+            default_value = "10000",
+            env = "HASH_GRAPH_QUERY_MAX_VERTICES"
+        )
+    )]
+    pub max_vertices: Option<usize>,
+
+    /// The number of seconds after which reading the subgraph of a single query is aborted
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long = "query-timeout",
+            default_value = "30",
+            env = "HASH_GRAPH_QUERY_TIMEOUT"
+        )
+    )]
+    pub timeout: Option<u64>,
+}
+
+impl QueryLimits {
+    /// Creates `QueryLimits`, which don't limit the resolution at all.
+    #[must_use]
+    pub const fn unlimited() -> Self {
+        Self {
+            max_ontology_resolve_depth: OntologyQueryDepth::MAX,
+            max_knowledge_resolve_depth: KnowledgeGraphQueryDepth::MAX,
+            max_vertices: None,
+            timeout: None,
+        }
+    }
+
+    /// Returns the maximum depth for every axis of the [`GraphResolveDepths`].
+    #[must_use]
+    pub const fn max_resolve_depths(&self) -> GraphResolveDepths {
+        GraphResolveDepths {
+            data_type_resolve_depth: self.max_ontology_resolve_depth,
+            property_type_resolve_depth: self.max_ontology_resolve_depth,
+            entity_type_resolve_depth: self.max_ontology_resolve_depth,
+            link_type_resolve_depth: self.max_ontology_resolve_depth,
+            link_resolve_depth: self.max_knowledge_resolve_depth,
+            link_target_entity_resolve_depth: self.max_knowledge_resolve_depth,
+            incoming_link_resolve_depth: self.max_knowledge_resolve_depth,
+            link_source_entity_resolve_depth: self.max_knowledge_resolve_depth,
+            referencing_type_resolve_depth: self.max_ontology_resolve_depth,
+        }
+    }
+}
+
+impl Default for QueryLimits {
+    /// Bounds every limit, so a single query can't exhaust the store.
+    fn default() -> Self {
+        Self {
+            max_ontology_resolve_depth: DEFAULT_MAX_ONTOLOGY_RESOLVE_DEPTH,
+            max_knowledge_resolve_depth: DEFAULT_MAX_KNOWLEDGE_RESOLVE_DEPTH,
+            max_vertices: Some(DEFAULT_MAX_VERTICES),
+            timeout: Some(DEFAULT_QUERY_TIMEOUT),
+        }
+    }
+}
@@ -0,0 +1,557 @@
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use error_stack::{bail, Report, Result, ResultExt};
+use futures::{stream, TryStreamExt};
+use type_system::{
+    uri::{BaseUri, VersionedUri},
+    EntityType, LinkType,
+};
+use uuid::Uuid;
+
+use crate::{
+    knowledge::{
+        Entity, EntityId, Link, LinkRootedSubgraph, PersistedEntity, PersistedEntityIdentifier,
+        PersistedEntityMetadata, PersistedLink,
+    },
+    ontology::{external_types::collect_references, AccountId},
+    store::{
+        change::{ChangeKind, ChangedResource},
+        context::{resolve_link_subgraphs, resolve_subgraph},
+        crud::Read,
+        error::{EntityDoesNotExist, LinkRemovalError},
+        memory::{EntityEntry, LinkEntry, MemoryState, MemoryStore},
+        query::{Expression, ExpressionError, Literal},
+        search::{collect_texts, split_terms, EntitySearch, EntitySearchHit},
+        EntityStore, InsertionError, LinkStore, QueryError, UpdateError,
+    },
+    subgraph::{StructuralQuery, Subgraph},
+};
+
+impl MemoryState {
+    /// Searches the latest versions of all entities by scanning their text.
+    ///
+    /// # Errors
+    ///
+    /// - if one of the [`EntityType`]s to filter by doesn't exist
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "the rank is only used to order the hits"
+    )]
+    fn search_entities(&self, search: &EntitySearch) -> Result<Vec<EntitySearchHit>, QueryError> {
+        let terms = search.terms();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        for entity_type_id in search.entity_type_ids.iter().flatten() {
+            if !self.contains_ontology_type::<EntityType>(entity_type_id) {
+                return Err(Report::new(QueryError)
+                    .attach_printable("entity type does not exist")
+                    .attach_printable(entity_type_id.clone()));
+            }
+        }
+
+        let mut hits = Vec::new();
+        for (entity_id, versions) in &self.entities {
+            let Some(entry) = versions.last() else {
+                continue;
+            };
+            if search
+                .entity_type_ids
+                .as_ref()
+                .map_or(false, |ids| !ids.contains(&entry.entity_type_id))
+                || search
+                    .owned_by_ids
+                    .as_ref()
+                    .map_or(false, |ids| !ids.contains(&entry.owned_by_id))
+            {
+                continue;
+            }
+
+            let mut texts = Vec::new();
+            for value in entry.entity.properties().values() {
+                collect_texts(value, &mut texts);
+            }
+            let words: Vec<_> = texts
+                .into_iter()
+                .flat_map(split_terms)
+                .map(str::to_lowercase)
+                .collect();
+            let all_terms_match = (0..terms.len()).all(|index| {
+                words
+                    .iter()
+                    .any(|word| search.term_matches(&terms, index, word))
+            });
+            if !all_terms_match {
+                continue;
+            }
+
+            let matches = words
+                .iter()
+                .filter(|word| search.matches(&terms, word))
+                .count();
+            let highlights = if search.highlight {
+                entry
+                    .entity
+                    .properties()
+                    .iter()
+                    .filter_map(|(base_uri, value)| {
+                        let highlighted = search.highlight(&terms, value.as_str()?)?;
+                        Some((base_uri.to_string(), highlighted))
+                    })
+                    .collect()
+            } else {
+                HashMap::new()
+            };
+
+            hits.push(EntitySearchHit::new(
+                PersistedEntity::from(entry.to_record(*entity_id, true)),
+                matches as f32 / words.len() as f32,
+                highlights,
+            ));
+        }
+
+        hits.sort_by(|lhs, rhs| {
+            rhs.rank().total_cmp(&lhs.rank()).then_with(|| {
+                lhs.entity()
+                    .metadata()
+                    .identifier()
+                    .entity_id()
+                    .cmp(&rhs.entity().metadata().identifier().entity_id())
+            })
+        });
+        hits.truncate(usize::try_from(search.limit).unwrap_or(usize::MAX));
+
+        Ok(hits)
+    }
+
+    /// Returns the latest versions of all entities, whose entity type references any version of
+    /// `base_uri`, either directly or transitively.
+    fn latest_entities_referencing_type(&self, base_uri: &BaseUri) -> Vec<PersistedEntity> {
+        let mut referencing_types: HashSet<VersionedUri> = self
+            .ontology_types
+            .get(base_uri)
+            .into_iter()
+            .flat_map(|types| types.versions.keys())
+            .map(|version| VersionedUri::new(base_uri.clone(), *version))
+            .collect();
+
+        // Every pass adds the types referencing one of the types found so far, until no new type
+        // is found.
+        loop {
+            let newly_referencing: Vec<_> = self
+                .ontology_types
+                .iter()
+                .flat_map(|(base_uri, types)| {
+                    types.versions.iter().map(move |(version, entry)| {
+                        (VersionedUri::new(base_uri.clone(), *version), entry)
+                    })
+                })
+                .filter(|(uri, entry)| {
+                    let mut references = Vec::new();
+                    collect_references(&entry.schema, &mut references);
+                    !referencing_types.contains(uri)
+                        && references
+                            .iter()
+                            .any(|reference| referencing_types.contains(reference))
+                })
+                .map(|(uri, _)| uri)
+                .collect();
+            if newly_referencing.is_empty() {
+                break;
+            }
+            referencing_types.extend(newly_referencing);
+        }
+
+        let mut entities: Vec<_> = self
+            .entities
+            .iter()
+            .filter_map(|(entity_id, versions)| {
+                let entry = versions.last()?;
+                referencing_types
+                    .contains(&entry.entity_type_id)
+                    .then(|| PersistedEntity::from(entry.to_record(*entity_id, true)))
+            })
+            .collect();
+        entities.sort_by_key(|entity| entity.metadata().identifier().entity_id());
+        entities
+    }
+
+    /// Inserts a new version of the [`Entity`] identified by `entity_id`.
+    ///
+    /// The version is guaranteed to be later than the version of the previous entry.
+    ///
+    /// # Errors
+    ///
+    /// - if the [`EntityType`] doesn't exist
+    /// - if one of the referenced accounts does not exist
+    fn insert_entity(
+        &mut self,
+        entity_id: EntityId,
+        entity: Entity,
+        entity_type_id: VersionedUri,
+        owned_by_id: AccountId,
+        created_by_id: AccountId,
+        updated_by_id: AccountId,
+    ) -> Result<PersistedEntityMetadata, InsertionError> {
+        if !self.contains_ontology_type::<EntityType>(&entity_type_id) {
+            return Err(Report::new(InsertionError)
+                .attach_printable("entity type does not exist")
+                .attach_printable(entity_type_id));
+        }
+        self.ensure_account_exists(owned_by_id)?;
+        self.ensure_account_exists(created_by_id)?;
+        self.ensure_account_exists(updated_by_id)?;
+
+        // TODO: Validate entity against entity type
+        //  https://app.asana.com/0/0/1202629282579257/f
+
+        let versions = self.entities.entry(entity_id).or_default();
+        let now = Utc::now();
+        let version = match versions.last() {
+            Some(previous) if previous.version >= now => {
+                previous.version + Duration::microseconds(1)
+            }
+            _ => now,
+        };
+
+        versions.push(EntityEntry {
+            entity,
+            version,
+            entity_type_id: entity_type_id.clone(),
+            owned_by_id,
+            created_by_id,
+            updated_by_id,
+            removed_by_id: None,
+        });
+
+        Ok(PersistedEntityMetadata::new(
+            PersistedEntityIdentifier::new(entity_id, version, owned_by_id),
+            entity_type_id,
+            created_by_id,
+            updated_by_id,
+            None,
+        ))
+    }
+
+    /// Checks, that none of the `entity_ids` is already used and that they are unique.
+    fn ensure_entity_ids_are_unused(
+        &self,
+        entity_ids: impl IntoIterator<Item = EntityId>,
+    ) -> Result<(), InsertionError> {
+        let mut seen = HashSet::new();
+        for entity_id in entity_ids {
+            if self.entities.contains_key(&entity_id) || !seen.insert(entity_id) {
+                return Err(Report::new(InsertionError)
+                    .attach_printable("entity id already exists")
+                    .attach_printable(entity_id));
+            }
+        }
+        Ok(())
+    }
+
+    fn position_of_link(&self, link: &Link) -> Option<usize> {
+        self.links.iter().position(|entry| {
+            entry.link.source_entity() == link.source_entity()
+                && entry.link.target_entity() == link.target_entity()
+                && entry.link.link_type_id() == link.link_type_id()
+        })
+    }
+}
+
+#[async_trait]
+impl Read<PersistedEntity> for MemoryStore {
+    type Query<'q> = Expression;
+
+    async fn read<'query>(
+        &self,
+        query: &Self::Query<'query>,
+    ) -> Result<Vec<PersistedEntity>, QueryError> {
+        let records = self.state().entity_records();
+
+        stream::iter(records.into_iter().map(Ok))
+            .try_filter_map(|record| async move {
+                if let Literal::Bool(result) = query
+                    .evaluate(&record, self)
+                    .await
+                    .change_context(QueryError)?
+                {
+                    Ok(result.then(|| PersistedEntity::from(record)))
+                } else {
+                    bail!(
+                        Report::new(ExpressionError)
+                            .attach_printable("does not result in a boolean value")
+                            .change_context(QueryError)
+                    );
+                }
+            })
+            .try_collect()
+            .await
+    }
+}
+
+#[async_trait]
+impl Read<PersistedLink> for MemoryStore {
+    type Query<'q> = Expression;
+
+    async fn read<'query>(
+        &self,
+        query: &Self::Query<'query>,
+    ) -> Result<Vec<PersistedLink>, QueryError> {
+        let records = self.state().link_records(|_| true);
+
+        stream::iter(records.into_iter().map(Ok))
+            .try_filter_map(|record| async move {
+                if let Literal::Bool(result) = query
+                    .evaluate(&record, self)
+                    .await
+                    .change_context(QueryError)?
+                {
+                    Ok(result.then(|| PersistedLink::from(record)))
+                } else {
+                    bail!(
+                        Report::new(ExpressionError)
+                            .attach_printable("does not result in a boolean value")
+                            .change_context(QueryError)
+                    );
+                }
+            })
+            .try_collect()
+            .await
+    }
+}
+
+#[async_trait]
+impl EntityStore for MemoryStore {
+    async fn create_entity(
+        &mut self,
+        entity: Entity,
+        entity_type_id: VersionedUri,
+        owned_by_id: AccountId,
+        entity_id: Option<EntityId>,
+        created_by_id: AccountId,
+    ) -> Result<PersistedEntityMetadata, InsertionError> {
+        let mut state = self.state_mut();
+
+        let entity_id = entity_id.unwrap_or_else(|| EntityId::new(Uuid::new_v4()));
+        state.ensure_entity_ids_are_unused([entity_id])?;
+
+        let metadata = state.insert_entity(
+            entity_id,
+            entity,
+            entity_type_id,
+            owned_by_id,
+            created_by_id,
+            created_by_id,
+        )?;
+
+        state.publish_change(
+            ChangeKind::Created,
+            ChangedResource::entity(&metadata),
+            owned_by_id,
+            created_by_id,
+        );
+
+        Ok(metadata)
+    }
+
+    #[doc(hidden)]
+    #[cfg(feature = "__internal_bench")]
+    async fn insert_entities_batched_by_type(
+        &mut self,
+        entities: impl IntoIterator<Item = (Option<EntityId>, Entity), IntoIter: Send> + Send,
+        entity_type_id: VersionedUri,
+        owned_by_id: AccountId,
+    ) -> Result<Vec<EntityId>, InsertionError> {
+        let mut state = self.state_mut();
+
+        let (entity_ids, entities): (Vec<_>, Vec<_>) = entities
+            .into_iter()
+            .map(|(id, entity)| (id.unwrap_or_else(|| EntityId::new(Uuid::new_v4())), entity))
+            .unzip();
+
+        // Validate everything up front, so no entity is inserted if one of them fails.
+        state.ensure_entity_ids_are_unused(entity_ids.iter().copied())?;
+        if !state.contains_ontology_type::<EntityType>(&entity_type_id) {
+            return Err(Report::new(InsertionError)
+                .attach_printable("entity type does not exist")
+                .attach_printable(entity_type_id));
+        }
+        state.ensure_account_exists(owned_by_id)?;
+
+        for (entity_id, entity) in entity_ids.iter().copied().zip(entities) {
+            state.insert_entity(
+                entity_id,
+                entity,
+                entity_type_id.clone(),
+                owned_by_id,
+                owned_by_id,
+                owned_by_id,
+            )?;
+        }
+
+        Ok(entity_ids)
+    }
+
+    async fn get_entity(&self, query: &StructuralQuery) -> Result<Subgraph, QueryError> {
+        resolve_subgraph(
+            self,
+            Read::<PersistedEntity>::read(self, &query.expression).await?,
+            query,
+            &self.query_limits,
+        )
+        .await
+    }
+
+    async fn search_entities(
+        &self,
+        search: &EntitySearch,
+    ) -> Result<Vec<EntitySearchHit>, QueryError> {
+        self.state().search_entities(search)
+    }
+
+    async fn get_latest_entities_referencing_type(
+        &self,
+        base_uri: &BaseUri,
+    ) -> Result<Vec<PersistedEntity>, QueryError> {
+        Ok(self.state().latest_entities_referencing_type(base_uri))
+    }
+
+    async fn update_entity(
+        &mut self,
+        entity_id: EntityId,
+        entity: Entity,
+        entity_type_id: VersionedUri,
+        updated_by_id: AccountId,
+    ) -> Result<PersistedEntityMetadata, UpdateError> {
+        let mut state = self.state_mut();
+
+        let (owned_by_id, created_by_id) = match state
+            .entities
+            .get(&entity_id)
+            .and_then(|versions| versions.last())
+        {
+            Some(previous_entity) => (previous_entity.owned_by_id, previous_entity.created_by_id),
+            None => {
+                return Err(Report::new(EntityDoesNotExist)
+                    .attach_printable(entity_id)
+                    .change_context(UpdateError));
+            }
+        };
+
+        let metadata = state
+            .insert_entity(
+                entity_id,
+                entity,
+                entity_type_id,
+                owned_by_id,
+                created_by_id,
+                updated_by_id,
+            )
+            .change_context(UpdateError)?;
+
+        state.publish_change(
+            ChangeKind::Updated,
+            ChangedResource::entity(&metadata),
+            owned_by_id,
+            updated_by_id,
+        );
+
+        Ok(metadata)
+    }
+}
+
+#[async_trait]
+impl LinkStore for MemoryStore {
+    async fn create_link(
+        &mut self,
+        link: &Link,
+        owned_by_id: AccountId,
+        created_by_id: AccountId,
+    ) -> Result<(), InsertionError> {
+        let mut state = self.state_mut();
+
+        if !state.contains_ontology_type::<LinkType>(link.link_type_id()) {
+            return Err(Report::new(InsertionError)
+                .attach_printable("link type does not exist")
+                .attach_printable(link.source_entity())
+                .attach_lazy(|| link.clone()));
+        }
+        for entity_id in [link.source_entity(), link.target_entity()] {
+            if !state.entities.contains_key(&entity_id) {
+                return Err(Report::new(InsertionError)
+                    .attach_printable("entity does not exist")
+                    .attach_printable(entity_id)
+                    .attach_lazy(|| link.clone()));
+            }
+        }
+        if state.position_of_link(link).is_some() {
+            return Err(Report::new(InsertionError)
+                .attach_printable("link already exists")
+                .attach_lazy(|| link.clone()));
+        }
+        state
+            .ensure_account_exists(owned_by_id)
+            .attach_lazy(|| link.clone())?;
+        state
+            .ensure_account_exists(created_by_id)
+            .attach_lazy(|| link.clone())?;
+
+        state.links.push(LinkEntry {
+            link: link.clone(),
+            owned_by_id,
+            created_by_id,
+        });
+
+        state.publish_change(
+            ChangeKind::Created,
+            ChangedResource::link(link),
+            owned_by_id,
+            created_by_id,
+        );
+
+        Ok(())
+    }
+
+    async fn get_links(
+        &self,
+        query: &StructuralQuery,
+    ) -> Result<Vec<LinkRootedSubgraph>, QueryError> {
+        resolve_link_subgraphs(
+            self,
+            Read::<PersistedLink>::read(self, &query.expression).await?,
+            query,
+            &self.query_limits,
+        )
+        .await
+    }
+
+    async fn remove_link(
+        &mut self,
+        link: &Link,
+        removed_by_id: AccountId,
+    ) -> Result<(), LinkRemovalError> {
+        let mut state = self.state_mut();
+
+        state
+            .ensure_account_exists(removed_by_id)
+            .change_context(LinkRemovalError)?;
+
+        match state.position_of_link(link) {
+            Some(position) => {
+                let removed = state.links.remove(position);
+                state.publish_change(
+                    ChangeKind::Removed,
+                    ChangedResource::link(link),
+                    removed.owned_by_id,
+                    removed_by_id,
+                );
+                Ok(())
+            }
+            None => Err(Report::new(LinkRemovalError)
+                .attach_printable("link does not exist")
+                .attach_lazy(|| link.clone())),
+        }
+    }
+}
@@ -0,0 +1,641 @@
+//! In-memory implementation of the [`Store`] traits.
+//!
+//! The [`MemoryStore`] shares the query evaluation and subgraph resolution with the
+//! [`PostgresStore`], so it provides the same semantics without requiring a database. This makes it
+//! suitable for tests and for embedding the graph into other applications.
+//!
+//! [`Store`]: crate::store::Store
+//! [`PostgresStore`]: crate::store::PostgresStore
+
+mod knowledge;
+mod ontology;
+mod pool;
+mod transaction;
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use error_stack::{Context, IntoReport, Report, Result, ResultExt};
+use futures::{stream, StreamExt};
+use type_system::{
+    uri::{BaseUri, VersionedUri},
+    EntityType, PropertyType,
+};
+
+pub use self::{pool::MemoryStorePool, transaction::MemoryTransaction};
+use crate::{
+    knowledge::{Entity, EntityId, Link, PersistedEntity},
+    ontology::{
+        external_types::collect_references, AccountId, Deprecation, PersistedOntologyIdentifier,
+        PersistedOntologyMetadata,
+    },
+    store::{
+        change::{ChangeEvent, ChangeFeed, ChangeKind, ChangedResource},
+        context::{
+            EntityRecord, EntityRecordStream, LinkRecord, LinkRecordStream, OntologyDatabaseType,
+            OntologyRecord, OntologyRecordStream, ReferencingType, ResolveContext,
+        },
+        error::{AccountDoesNotExist, OntologyTypeDoesNotExist, VersionedUriAlreadyExists},
+        AccountStore, BaseUriAlreadyExists, BaseUriDoesNotExist, InsertionError, QueryError,
+        QueryLimits, SnapshotStore, UpdateError,
+    },
+};
+
+/// A [`Store`] keeping all data in memory.
+///
+/// Cloning the store is cheap and the clones share the same data.
+///
+/// [`Store`]: crate::store::Store
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    state: Arc<RwLock<MemoryState>>,
+    query_limits: QueryLimits,
+}
+
+impl MemoryStore {
+    /// Creates a new, empty `MemoryStore`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publishes all mutations of the store to `change_feed`.
+    #[must_use]
+    pub fn with_change_feed(self, change_feed: ChangeFeed) -> Self {
+        self.state_mut().change_feed = Some(change_feed);
+        self
+    }
+
+    /// Limits the cost of structural queries by `query_limits`.
+    #[must_use]
+    pub const fn with_query_limits(mut self, query_limits: QueryLimits) -> Self {
+        self.query_limits = query_limits;
+        self
+    }
+
+    // The guards must not be held across an `.await` point: Resolving a query reads from the
+    // store again.
+    fn state(&self) -> RwLockReadGuard<'_, MemoryState> {
+        self.state.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn state_mut(&self) -> RwLockWriteGuard<'_, MemoryState> {
+        let mut state = self.state.write().unwrap_or_else(PoisonError::into_inner);
+        state.revision += 1;
+        state
+    }
+}
+
+/// An entry of a single version of an [`OntologyDatabaseType`].
+#[derive(Clone)]
+struct OntologyTypeEntry {
+    schema: serde_json::Value,
+    owned_by_id: AccountId,
+    created_by_id: AccountId,
+    updated_by_id: AccountId,
+    removed_by_id: Option<AccountId>,
+    deprecation: Option<Deprecation>,
+}
+
+impl OntologyTypeEntry {
+    fn to_record<T>(&self, is_latest: bool) -> Result<OntologyRecord<T>, QueryError>
+    where
+        T: TryFrom<serde_json::Value, Error: Context>,
+    {
+        Ok(OntologyRecord {
+            record: T::try_from(self.schema.clone())
+                .into_report()
+                .change_context(QueryError)?,
+            owned_by_id: self.owned_by_id,
+            created_by_id: self.created_by_id,
+            updated_by_id: self.updated_by_id,
+            removed_by_id: self.removed_by_id,
+            deprecation: self.deprecation.clone(),
+            is_latest,
+        })
+    }
+
+    fn to_metadata(&self, uri: VersionedUri) -> PersistedOntologyMetadata {
+        PersistedOntologyMetadata::new(
+            PersistedOntologyIdentifier::new(uri, self.owned_by_id),
+            self.created_by_id,
+            self.updated_by_id,
+            self.removed_by_id,
+        )
+        .with_deprecation(self.deprecation.clone())
+    }
+}
+
+/// All versions of an [`OntologyDatabaseType`] sharing the same [`BaseUri`].
+#[derive(Clone)]
+struct OntologyTypeVersions {
+    /// The [`OntologyDatabaseType::table()`] of the type, used to distinguish the kind of the
+    /// type.
+    table: &'static str,
+    versions: BTreeMap<u32, OntologyTypeEntry>,
+}
+
+impl OntologyTypeVersions {
+    fn latest_version(&self) -> u32 {
+        *self
+            .versions
+            .keys()
+            .next_back()
+            .expect("ontology type without any version")
+    }
+}
+
+/// An entry of a single version of an [`Entity`].
+#[derive(Clone)]
+struct EntityEntry {
+    entity: Entity,
+    version: DateTime<Utc>,
+    entity_type_id: VersionedUri,
+    owned_by_id: AccountId,
+    created_by_id: AccountId,
+    updated_by_id: AccountId,
+    removed_by_id: Option<AccountId>,
+}
+
+impl EntityEntry {
+    fn to_record(&self, entity_id: EntityId, is_latest: bool) -> EntityRecord {
+        EntityRecord {
+            entity: self.entity.clone(),
+            id: entity_id,
+            version: self.version,
+            entity_type_id: self.entity_type_id.clone(),
+            owned_by_id: self.owned_by_id,
+            created_by_id: self.created_by_id,
+            updated_by_id: self.updated_by_id,
+            removed_by_id: self.removed_by_id,
+            is_latest,
+        }
+    }
+}
+
+/// An active [`Link`].
+#[derive(Clone)]
+struct LinkEntry {
+    link: Link,
+    owned_by_id: AccountId,
+    created_by_id: AccountId,
+}
+
+impl LinkEntry {
+    fn to_record(&self) -> LinkRecord {
+        LinkRecord {
+            link_type_id: self.link.link_type_id().clone(),
+            source_entity_id: self.link.source_entity(),
+            target_entity_id: self.link.target_entity(),
+            owned_by_id: self.owned_by_id,
+            index: self.link.index(),
+            created_by_id: self.created_by_id,
+        }
+    }
+}
+
+#[derive(Default)]
+struct MemoryState {
+    accounts: HashSet<AccountId>,
+    ontology_types: HashMap<BaseUri, OntologyTypeVersions>,
+    /// The versions of each entity, ordered from oldest to latest.
+    entities: HashMap<EntityId, Vec<EntityEntry>>,
+    links: Vec<LinkEntry>,
+    change_feed: Option<ChangeFeed>,
+    /// The changes of a transaction, which are published once the transaction is committed.
+    pending_changes: Option<Vec<ChangeEvent>>,
+    /// Incremented on every write access to detect concurrent modifications.
+    revision: u64,
+}
+
+impl MemoryState {
+    fn publish_change(
+        &mut self,
+        kind: ChangeKind,
+        resource: ChangedResource,
+        owned_by_id: AccountId,
+        actor_id: AccountId,
+    ) {
+        self.publish(ChangeEvent::new(kind, resource, owned_by_id, actor_id));
+    }
+
+    fn publish(&mut self, event: ChangeEvent) {
+        if let Some(pending_changes) = &mut self.pending_changes {
+            pending_changes.push(event);
+        } else if let Some(change_feed) = &self.change_feed {
+            change_feed.publish(event);
+        }
+    }
+
+    fn ensure_account_exists(&self, account_id: AccountId) -> Result<(), InsertionError> {
+        if self.accounts.contains(&account_id) {
+            Ok(())
+        } else {
+            Err(Report::new(AccountDoesNotExist)
+                .attach_printable(account_id)
+                .change_context(InsertionError))
+        }
+    }
+
+    /// Checks if the [`VersionedUri`] exists and refers to an ontology type of kind `T`.
+    fn contains_ontology_type<T: OntologyDatabaseType>(&self, uri: &VersionedUri) -> bool {
+        self.ontology_types
+            .get(uri.base_uri())
+            .map_or(false, |types| {
+                types.table == T::table() && types.versions.contains_key(&uri.version())
+            })
+    }
+
+    /// Returns the [`OntologyTypeVersions`] of `base_uri` and the requested version of it.
+    ///
+    /// If `version` is `None`, the latest version is returned.
+    fn ontology_type_entry(
+        &self,
+        base_uri: &BaseUri,
+        version: Option<u32>,
+    ) -> Result<(&OntologyTypeVersions, VersionedUri, &OntologyTypeEntry), QueryError> {
+        let types = self.ontology_types.get(base_uri).ok_or_else(|| {
+            Report::new(OntologyTypeDoesNotExist)
+                .attach_printable(base_uri.clone())
+                .change_context(QueryError)
+        })?;
+        let version = version.unwrap_or_else(|| types.latest_version());
+        let uri = VersionedUri::new(base_uri.clone(), version);
+        let entry = types.versions.get(&version).ok_or_else(|| {
+            Report::new(OntologyTypeDoesNotExist)
+                .attach_printable(uri.clone())
+                .change_context(QueryError)
+        })?;
+        Ok((types, uri, entry))
+    }
+
+    fn ontology_types_of_kind<T: OntologyDatabaseType>(
+        &self,
+        base_uri: &BaseUri,
+    ) -> Result<&OntologyTypeVersions, QueryError> {
+        self.ontology_types
+            .get(base_uri)
+            .filter(|types| types.table == T::table())
+            .ok_or_else(|| Report::new(QueryError).attach_printable(base_uri.clone()))
+    }
+
+    fn ontology_records<T>(&self) -> Result<Vec<OntologyRecord<T>>, QueryError>
+    where
+        T: OntologyDatabaseType + TryFrom<serde_json::Value, Error: Context>,
+    {
+        self.ontology_types
+            .values()
+            .filter(|types| types.table == T::table())
+            .flat_map(|types| {
+                let latest_version = types.latest_version();
+                types
+                    .versions
+                    .iter()
+                    .rev()
+                    .map(move |(version, entry)| entry.to_record(*version == latest_version))
+            })
+            .collect()
+    }
+
+    /// Returns the property types and entity types, which reference `uri` in their schema.
+    fn referencing_types(&self, uri: &VersionedUri) -> Vec<ReferencingType> {
+        let mut referencing_types = Vec::new();
+        for (base_uri, types) in &self.ontology_types {
+            let referencing_type: fn(VersionedUri) -> ReferencingType =
+                if types.table == PropertyType::table() {
+                    ReferencingType::PropertyType
+                } else if types.table == EntityType::table() {
+                    ReferencingType::EntityType
+                } else {
+                    continue;
+                };
+
+            for (version, entry) in &types.versions {
+                let mut references = Vec::new();
+                collect_references(&entry.schema, &mut references);
+                if references.contains(uri) {
+                    referencing_types.push(referencing_type(VersionedUri::new(
+                        base_uri.clone(),
+                        *version,
+                    )));
+                }
+            }
+        }
+        referencing_types
+    }
+
+    /// Inserts the specified [`OntologyDatabaseType`].
+    ///
+    /// # Errors
+    ///
+    /// - if the [`BaseUri`] already exists
+    /// - if one of the referenced accounts does not exist
+    fn create_ontology_type<T>(
+        &mut self,
+        database_type: T,
+        owned_by_id: AccountId,
+        created_by_id: AccountId,
+    ) -> Result<PersistedOntologyMetadata, InsertionError>
+    where
+        T: OntologyDatabaseType + Into<serde_json::Value>,
+    {
+        let uri = database_type.versioned_uri().clone();
+
+        if self.ontology_types.contains_key(uri.base_uri()) {
+            return Err(Report::new(BaseUriAlreadyExists)
+                .attach_printable(uri.base_uri().clone())
+                .change_context(InsertionError));
+        }
+
+        self.ensure_account_exists(owned_by_id)?;
+        self.ensure_account_exists(created_by_id)?;
+
+        self.ontology_types
+            .insert(uri.base_uri().clone(), OntologyTypeVersions {
+                table: T::table(),
+                versions: BTreeMap::from([(uri.version(), OntologyTypeEntry {
+                    schema: database_type.into(),
+                    owned_by_id,
+                    created_by_id,
+                    updated_by_id: created_by_id,
+                    removed_by_id: None,
+                    deprecation: None,
+                })]),
+            });
+        self.publish_change(
+            ChangeKind::Created,
+            T::changed_resource(uri.clone()),
+            owned_by_id,
+            created_by_id,
+        );
+
+        Ok(PersistedOntologyMetadata::new(
+            PersistedOntologyIdentifier::new(uri, owned_by_id),
+            created_by_id,
+            created_by_id,
+            None,
+        ))
+    }
+
+    /// Inserts a new version of the specified [`OntologyDatabaseType`].
+    ///
+    /// The owner and the creator are taken from the latest version of the type.
+    ///
+    /// # Errors
+    ///
+    /// - if the [`BaseUri`] does not already exist
+    /// - if the [`BaseUri`] refers to an ontology type of a different kind
+    /// - if the [`VersionedUri`] already exists
+    /// - if the account referred to by `updated_by_id` does not exist
+    fn update_ontology_type<T>(
+        &mut self,
+        database_type: T,
+        updated_by_id: AccountId,
+    ) -> Result<PersistedOntologyMetadata, UpdateError>
+    where
+        T: OntologyDatabaseType + Into<serde_json::Value>,
+    {
+        let uri = database_type.versioned_uri().clone();
+
+        if !self.ontology_types.contains_key(uri.base_uri()) {
+            return Err(Report::new(BaseUriDoesNotExist)
+                .attach_printable(uri.base_uri().clone())
+                .change_context(UpdateError));
+        }
+
+        self.ensure_account_exists(updated_by_id)
+            .change_context(UpdateError)?;
+
+        let types = self
+            .ontology_types
+            .get_mut(uri.base_uri())
+            .filter(|types| types.table == T::table())
+            .ok_or_else(|| Report::new(QueryError).attach_printable(uri.base_uri().clone()))
+            .change_context(UpdateError)?;
+
+        if types.versions.contains_key(&uri.version()) {
+            return Err(Report::new(InsertionError)
+                .attach_printable(VersionedUriAlreadyExists)
+                .attach(uri.clone())
+                .change_context(UpdateError));
+        }
+
+        let latest = &types.versions[&types.latest_version()];
+        let owned_by_id = latest.owned_by_id;
+        let created_by_id = latest.created_by_id;
+
+        types.versions.insert(uri.version(), OntologyTypeEntry {
+            schema: database_type.into(),
+            owned_by_id,
+            created_by_id,
+            updated_by_id,
+            removed_by_id: None,
+            deprecation: None,
+        });
+        self.publish_change(
+            ChangeKind::Updated,
+            T::changed_resource(uri.clone()),
+            owned_by_id,
+            updated_by_id,
+        );
+
+        Ok(PersistedOntologyMetadata::new(
+            PersistedOntologyIdentifier::new(uri, owned_by_id),
+            created_by_id,
+            updated_by_id,
+            None,
+        ))
+    }
+
+    fn entity_records(&self) -> Vec<EntityRecord> {
+        self.entities
+            .iter()
+            .flat_map(|(entity_id, versions)| {
+                let latest_index = versions.len() - 1;
+                versions
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .map(move |(index, entry)| entry.to_record(*entity_id, index == latest_index))
+            })
+            .collect()
+    }
+
+    fn latest_entity_record(&self, entity_id: EntityId) -> Result<EntityRecord, QueryError> {
+        self.entities
+            .get(&entity_id)
+            .and_then(|versions| versions.last())
+            .map(|entry| entry.to_record(entity_id, true))
+            .ok_or_else(|| Report::new(QueryError))
+    }
+
+    /// Returns the records of all links matching `filter`.
+    ///
+    /// The records are ordered by their index, links without an index come last.
+    fn link_records(&self, filter: impl Fn(&Link) -> bool) -> Vec<LinkRecord> {
+        let mut records: Vec<_> = self
+            .links
+            .iter()
+            .filter(|entry| filter(&entry.link))
+            .map(LinkEntry::to_record)
+            .collect();
+        records.sort_by_key(|record| (record.index.is_none(), record.index));
+        records
+    }
+}
+
+#[async_trait]
+impl ResolveContext for MemoryStore {
+    async fn read_all_ontology_types<T>(&self) -> Result<OntologyRecordStream<T>, QueryError>
+    where
+        T: OntologyDatabaseType + TryFrom<serde_json::Value, Error: Context> + Send + 'static,
+    {
+        let records = self
+            .state()
+            .ontology_records()
+            .attach_printable("could not read ontology types")?;
+        Ok(stream::iter(records.into_iter().map(Ok)).boxed())
+    }
+
+    async fn read_latest_ontology_type<T>(
+        &self,
+        base_uri: &BaseUri,
+    ) -> Result<OntologyRecord<T>, QueryError>
+    where
+        T: OntologyDatabaseType + TryFrom<serde_json::Value, Error: Context>,
+    {
+        let state = self.state();
+        let types = state.ontology_types_of_kind::<T>(base_uri)?;
+        types.versions[&types.latest_version()]
+            .to_record(true)
+            .attach_printable("could not read ontology type")
+            .attach_printable_lazy(|| base_uri.clone())
+    }
+
+    async fn read_versioned_ontology_type<T>(
+        &self,
+        uri: &VersionedUri,
+    ) -> Result<OntologyRecord<T>, QueryError>
+    where
+        T: OntologyDatabaseType + TryFrom<serde_json::Value, Error: Context>,
+    {
+        let state = self.state();
+        let types = state.ontology_types_of_kind::<T>(uri.base_uri())?;
+        types
+            .versions
+            .get(&uri.version())
+            .ok_or_else(|| Report::new(QueryError))
+            .and_then(|entry| entry.to_record(uri.version() == types.latest_version()))
+            .attach_printable("could not read ontology type")
+            .attach_printable_lazy(|| uri.clone())
+    }
+
+    async fn read_referencing_ontology_types(
+        &self,
+        uri: &VersionedUri,
+    ) -> Result<Vec<ReferencingType>, QueryError> {
+        Ok(self.state().referencing_types(uri))
+    }
+
+    async fn read_all_entities(&self) -> Result<EntityRecordStream, QueryError> {
+        let records = self.state().entity_records();
+        Ok(stream::iter(records.into_iter().map(Ok)).boxed())
+    }
+
+    async fn read_latest_entity_by_id(
+        &self,
+        entity_id: EntityId,
+    ) -> Result<EntityRecord, QueryError> {
+        self.state()
+            .latest_entity_record(entity_id)
+            .attach_printable("could not read entity")
+            .attach_printable(entity_id)
+    }
+
+    async fn read_all_links(&self) -> Result<LinkRecordStream, QueryError> {
+        let records = self.state().link_records(|_| true);
+        Ok(stream::iter(records.into_iter().map(Ok)).boxed())
+    }
+
+    async fn read_links_by_source(
+        &self,
+        entity_id: EntityId,
+    ) -> Result<LinkRecordStream, QueryError> {
+        let records = self
+            .state()
+            .link_records(|link| link.source_entity() == entity_id);
+        Ok(stream::iter(records.into_iter().map(Ok)).boxed())
+    }
+
+    async fn read_links_by_target(
+        &self,
+        entity_id: EntityId,
+    ) -> Result<LinkRecordStream, QueryError> {
+        let records = self
+            .state()
+            .link_records(|link| link.target_entity() == entity_id);
+        Ok(stream::iter(records.into_iter().map(Ok)).boxed())
+    }
+}
+
+#[async_trait]
+impl AccountStore for MemoryStore {
+    async fn insert_account_id(&mut self, account_id: AccountId) -> Result<(), InsertionError> {
+        if self.state_mut().accounts.insert(account_id) {
+            Ok(())
+        } else {
+            Err(Report::new(InsertionError)
+                .attach_printable("account already exists")
+                .attach_printable(account_id))
+        }
+    }
+}
+
+#[async_trait]
+impl SnapshotStore for MemoryStore {
+    async fn restore_account_id(&mut self, account_id: AccountId) -> Result<bool, InsertionError> {
+        Ok(self.state_mut().accounts.insert(account_id))
+    }
+
+    async fn restore_entity(&mut self, entity: &PersistedEntity) -> Result<bool, InsertionError> {
+        let mut state = self.state_mut();
+
+        let metadata = entity.metadata();
+        let entity_id = metadata.identifier().entity_id();
+        let version = metadata.identifier().version();
+
+        if !state.contains_ontology_type::<EntityType>(metadata.entity_type_id()) {
+            return Err(Report::new(InsertionError)
+                .attach_printable("entity type does not exist")
+                .attach_printable(metadata.entity_type_id().clone()));
+        }
+        state.ensure_account_exists(metadata.identifier().owned_by_id())?;
+        state.ensure_account_exists(metadata.created_by_id())?;
+        state.ensure_account_exists(metadata.updated_by_id())?;
+        if let Some(removed_by_id) = metadata.removed_by_id() {
+            state.ensure_account_exists(removed_by_id)?;
+        }
+
+        // The versions are kept ordered, so the position of the restored version is looked up.
+        let versions = state.entities.entry(entity_id).or_default();
+        match versions.binary_search_by_key(&version, |entry| entry.version) {
+            Ok(_) => Ok(false),
+            Err(position) => {
+                versions.insert(position, EntityEntry {
+                    entity: entity.inner().clone(),
+                    version,
+                    entity_type_id: metadata.entity_type_id().clone(),
+                    owned_by_id: metadata.identifier().owned_by_id(),
+                    created_by_id: metadata.created_by_id(),
+                    updated_by_id: metadata.updated_by_id(),
+                    removed_by_id: metadata.removed_by_id(),
+                });
+                Ok(true)
+            }
+        }
+    }
+}
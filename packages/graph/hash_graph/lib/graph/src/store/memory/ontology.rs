@@ -0,0 +1,446 @@
+use async_trait::async_trait;
+use error_stack::{bail, Context, Report, Result, ResultExt};
+use futures::{stream, TryStreamExt};
+use type_system::{
+    uri::{BaseUri, VersionedUri},
+    DataType, EntityType, LinkType, PropertyType,
+};
+
+use crate::{
+    ontology::{
+        AccountId, Deprecation, PersistedDataType, PersistedEntityType, PersistedLinkType,
+        PersistedOntologyMetadata, PersistedPropertyType,
+    },
+    store::{
+        change::ChangeKind,
+        context::{
+            changed_ontology_resource, resolve_subgraph, OntologyDatabaseType, OntologyRecord,
+            PersistedOntologyType,
+        },
+        crud::Read,
+        error::{InvalidReplacement, UnexpectedOwner},
+        memory::{MemoryState, MemoryStore},
+        query::{Expression, ExpressionError, Literal, Resolve},
+        BaseUriDoesNotExist, DataTypeStore, EntityTypeStore, InsertionError, LinkTypeStore,
+        OntologyTypeStore, PropertyTypeStore, QueryError, UpdateError,
+    },
+    subgraph::{StructuralQuery, Subgraph},
+};
+
+impl MemoryState {
+    /// Ensures, that all referenced ontology types exist and are of kind `T`.
+    ///
+    /// A type may reference itself, so `referencing_type_id` is considered to exist.
+    fn ensure_references_exist<'r, T: OntologyDatabaseType>(
+        &self,
+        references: impl IntoIterator<Item = &'r VersionedUri>,
+        referencing_type_id: &VersionedUri,
+    ) -> Result<(), QueryError> {
+        for uri in references {
+            if uri != referencing_type_id && !self.contains_ontology_type::<T>(uri) {
+                bail!(Report::new(QueryError).attach_printable(uri.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    fn ensure_property_type_references_exist(
+        &self,
+        property_type: &PropertyType,
+    ) -> Result<(), InsertionError> {
+        self.ensure_references_exist::<PropertyType>(
+            property_type
+                .property_type_references()
+                .into_iter()
+                .map(|reference| reference.uri()),
+            property_type.id(),
+        )
+        .change_context(InsertionError)
+        .attach_printable("Could not find referenced property types")?;
+
+        self.ensure_references_exist::<DataType>(
+            property_type
+                .data_type_references()
+                .into_iter()
+                .map(|reference| reference.uri()),
+            property_type.id(),
+        )
+        .change_context(InsertionError)
+        .attach_printable("Could not find referenced data types")
+    }
+
+    fn ensure_entity_type_references_exist(
+        &self,
+        entity_type: &EntityType,
+    ) -> Result<(), InsertionError> {
+        self.ensure_references_exist::<PropertyType>(
+            entity_type
+                .property_type_references()
+                .into_iter()
+                .map(|reference| reference.uri()),
+            entity_type.id(),
+        )
+        .change_context(InsertionError)
+        .attach_printable("Could not find referenced property types")?;
+
+        self.ensure_references_exist::<LinkType>(
+            entity_type
+                .link_type_references()
+                .into_iter()
+                .map(|(link_type_id, _)| link_type_id),
+            entity_type.id(),
+        )
+        .change_context(InsertionError)
+        .attach_printable("Could not find referenced link types")?;
+
+        self.ensure_references_exist::<EntityType>(
+            entity_type
+                .link_type_references()
+                .into_iter()
+                .flat_map(|(_, entity_type_references)| entity_type_references)
+                .map(|reference| reference.uri()),
+            entity_type.id(),
+        )
+        .change_context(InsertionError)
+        .attach_printable("Could not find referenced entity types")
+    }
+}
+
+#[async_trait]
+impl<T> Read<T> for MemoryStore
+where
+    T: PersistedOntologyType + Send,
+    T::Inner: OntologyDatabaseType + TryFrom<serde_json::Value, Error: Context> + Send + 'static,
+    OntologyRecord<T::Inner>: Resolve<Self> + Sync,
+{
+    type Query<'q> = Expression;
+
+    async fn read<'query>(&self, query: &Self::Query<'query>) -> Result<Vec<T>, QueryError> {
+        let records = self.state().ontology_records::<T::Inner>()?;
+
+        stream::iter(records.into_iter().map(Ok))
+            .try_filter_map(|ontology_type| async move {
+                if let Literal::Bool(result) = query
+                    .evaluate(&ontology_type, self)
+                    .await
+                    .change_context(QueryError)?
+                {
+                    Ok(result.then(|| T::from_record(ontology_type)))
+                } else {
+                    bail!(
+                        Report::new(ExpressionError)
+                            .attach_printable("does not result in a boolean value")
+                            .change_context(QueryError)
+                    );
+                }
+            })
+            .try_collect()
+            .await
+    }
+}
+
+#[async_trait]
+impl DataTypeStore for MemoryStore {
+    async fn create_data_type(
+        &mut self,
+        data_type: DataType,
+        owned_by_id: AccountId,
+        created_by_id: AccountId,
+    ) -> Result<PersistedOntologyMetadata, InsertionError> {
+        self.state_mut()
+            .create_ontology_type(data_type, owned_by_id, created_by_id)
+    }
+
+    async fn get_data_type(&self, query: &StructuralQuery) -> Result<Subgraph, QueryError> {
+        resolve_subgraph(
+            self,
+            Read::<PersistedDataType>::read(self, &query.expression).await?,
+            query,
+            &self.query_limits,
+        )
+        .await
+    }
+
+    async fn update_data_type(
+        &mut self,
+        data_type: DataType,
+        updated_by_id: AccountId,
+    ) -> Result<PersistedOntologyMetadata, UpdateError> {
+        self.state_mut()
+            .update_ontology_type(data_type, updated_by_id)
+    }
+}
+
+#[async_trait]
+impl PropertyTypeStore for MemoryStore {
+    async fn create_property_type(
+        &mut self,
+        property_type: PropertyType,
+        owned_by_id: AccountId,
+        created_by_id: AccountId,
+    ) -> Result<PersistedOntologyMetadata, InsertionError> {
+        let mut state = self.state_mut();
+
+        state
+            .ensure_property_type_references_exist(&property_type)
+            .attach_printable_lazy(|| {
+                format!(
+                    "could not insert references for property type: {}",
+                    property_type.id()
+                )
+            })
+            .attach_lazy(|| property_type.clone())?;
+
+        state.create_ontology_type(property_type, owned_by_id, created_by_id)
+    }
+
+    async fn get_property_type(&self, query: &StructuralQuery) -> Result<Subgraph, QueryError> {
+        resolve_subgraph(
+            self,
+            Read::<PersistedPropertyType>::read(self, &query.expression).await?,
+            query,
+            &self.query_limits,
+        )
+        .await
+    }
+
+    async fn update_property_type(
+        &mut self,
+        property_type: PropertyType,
+        updated_by: AccountId,
+    ) -> Result<PersistedOntologyMetadata, UpdateError> {
+        let mut state = self.state_mut();
+
+        state
+            .ensure_property_type_references_exist(&property_type)
+            .change_context(UpdateError)
+            .attach_printable_lazy(|| {
+                format!(
+                    "could not insert references for property type: {}",
+                    property_type.id()
+                )
+            })
+            .attach_lazy(|| property_type.clone())?;
+
+        state.update_ontology_type(property_type, updated_by)
+    }
+}
+
+#[async_trait]
+impl LinkTypeStore for MemoryStore {
+    async fn create_link_type(
+        &mut self,
+        link_type: LinkType,
+        owned_by_id: AccountId,
+        created_by_id: AccountId,
+    ) -> Result<PersistedOntologyMetadata, InsertionError> {
+        self.state_mut()
+            .create_ontology_type(link_type, owned_by_id, created_by_id)
+    }
+
+    async fn get_link_type(&self, query: &StructuralQuery) -> Result<Subgraph, QueryError> {
+        resolve_subgraph(
+            self,
+            Read::<PersistedLinkType>::read(self, &query.expression).await?,
+            query,
+            &self.query_limits,
+        )
+        .await
+    }
+
+    async fn update_link_type(
+        &mut self,
+        link_type: LinkType,
+        updated_by: AccountId,
+    ) -> Result<PersistedOntologyMetadata, UpdateError> {
+        self.state_mut().update_ontology_type(link_type, updated_by)
+    }
+}
+
+#[async_trait]
+impl EntityTypeStore for MemoryStore {
+    async fn create_entity_type(
+        &mut self,
+        entity_type: EntityType,
+        owned_by_id: AccountId,
+        created_by_id: AccountId,
+    ) -> Result<PersistedOntologyMetadata, InsertionError> {
+        let mut state = self.state_mut();
+
+        state
+            .ensure_entity_type_references_exist(&entity_type)
+            .attach_printable_lazy(|| {
+                format!(
+                    "could not insert references for entity type: {}",
+                    entity_type.id()
+                )
+            })
+            .attach_lazy(|| entity_type.clone())?;
+
+        state.create_ontology_type(entity_type, owned_by_id, created_by_id)
+    }
+
+    async fn get_entity_type(&self, query: &StructuralQuery) -> Result<Subgraph, QueryError> {
+        resolve_subgraph(
+            self,
+            Read::<PersistedEntityType>::read(self, &query.expression).await?,
+            query,
+            &self.query_limits,
+        )
+        .await
+    }
+
+    async fn update_entity_type(
+        &mut self,
+        entity_type: EntityType,
+        updated_by: AccountId,
+    ) -> Result<PersistedOntologyMetadata, UpdateError> {
+        let mut state = self.state_mut();
+
+        state
+            .ensure_entity_type_references_exist(&entity_type)
+            .change_context(UpdateError)
+            .attach_printable_lazy(|| {
+                format!(
+                    "could not insert references for entity type: {}",
+                    entity_type.id()
+                )
+            })
+            .attach_lazy(|| entity_type.clone())?;
+
+        state.update_ontology_type(entity_type, updated_by)
+    }
+}
+
+#[async_trait]
+impl OntologyTypeStore for MemoryStore {
+    async fn get_ontology_type_metadata(
+        &self,
+        uri: &VersionedUri,
+    ) -> Result<PersistedOntologyMetadata, QueryError> {
+        let state = self.state();
+        let (_, uri, entry) = state
+            .ontology_type_entry(uri.base_uri(), Some(uri.version()))
+            .attach_printable("could not read ontology type")?;
+        Ok(entry.to_metadata(uri))
+    }
+
+    async fn get_latest_ontology_type_metadata(
+        &self,
+        base_uri: &BaseUri,
+    ) -> Result<PersistedOntologyMetadata, QueryError> {
+        let state = self.state();
+        let (_, uri, entry) = state
+            .ontology_type_entry(base_uri, None)
+            .attach_printable("could not read ontology type")?;
+        Ok(entry.to_metadata(uri))
+    }
+
+    async fn deprecate_ontology_type(
+        &mut self,
+        uri: &VersionedUri,
+        replaced_by: Option<VersionedUri>,
+        actor_id: AccountId,
+    ) -> Result<PersistedOntologyMetadata, UpdateError> {
+        let mut state = self.state_mut();
+
+        let (types, ..) = state
+            .ontology_type_entry(uri.base_uri(), Some(uri.version()))
+            .change_context(UpdateError)?;
+        let table = types.table;
+
+        if let Some(replaced_by) = &replaced_by {
+            if replaced_by == uri {
+                return Err(Report::new(InvalidReplacement)
+                    .attach_printable("an ontology type cannot be replaced by itself")
+                    .attach_printable(uri.clone())
+                    .change_context(UpdateError));
+            }
+
+            let (replacement_types, ..) = state
+                .ontology_type_entry(replaced_by.base_uri(), Some(replaced_by.version()))
+                .change_context(InvalidReplacement)
+                .attach_printable("replacement does not exist")
+                .change_context(UpdateError)?;
+            if replacement_types.table != table {
+                return Err(Report::new(InvalidReplacement)
+                    .attach_printable("replacement is of a different kind")
+                    .attach_printable(replaced_by.clone())
+                    .change_context(UpdateError));
+            }
+        }
+
+        state
+            .ensure_account_exists(actor_id)
+            .change_context(UpdateError)?;
+
+        let entry = state
+            .ontology_types
+            .get_mut(uri.base_uri())
+            .and_then(|types| types.versions.get_mut(&uri.version()))
+            .expect("ontology type was checked to exist");
+        entry.deprecation = Some(Deprecation::new(actor_id, replaced_by));
+        let metadata = entry.to_metadata(uri.clone());
+
+        state.publish_change(
+            ChangeKind::Updated,
+            changed_ontology_resource(table, uri.clone()),
+            metadata.identifier().owned_by_id(),
+            actor_id,
+        );
+
+        Ok(metadata)
+    }
+
+    async fn transfer_ontology_type_ownership(
+        &mut self,
+        base_uri: &BaseUri,
+        current_owned_by_id: AccountId,
+        owned_by_id: AccountId,
+        actor_id: AccountId,
+    ) -> Result<PersistedOntologyMetadata, UpdateError> {
+        let mut state = self.state_mut();
+
+        if !state.ontology_types.contains_key(base_uri) {
+            return Err(Report::new(BaseUriDoesNotExist)
+                .attach_printable(base_uri.clone())
+                .change_context(UpdateError));
+        }
+
+        let (_, _, latest) = state
+            .ontology_type_entry(base_uri, None)
+            .change_context(UpdateError)?;
+        if latest.owned_by_id != current_owned_by_id {
+            return Err(Report::new(UnexpectedOwner)
+                .attach_printable(latest.owned_by_id)
+                .change_context(UpdateError));
+        }
+
+        state
+            .ensure_account_exists(owned_by_id)
+            .change_context(UpdateError)?;
+        state
+            .ensure_account_exists(actor_id)
+            .change_context(UpdateError)?;
+
+        let types = state
+            .ontology_types
+            .get_mut(base_uri)
+            .expect("base URI was checked to exist");
+        for entry in types.versions.values_mut() {
+            entry.owned_by_id = owned_by_id;
+        }
+        let table = types.table;
+        let uri = VersionedUri::new(base_uri.clone(), types.latest_version());
+        let metadata = types.versions[&uri.version()].to_metadata(uri.clone());
+
+        state.publish_change(
+            ChangeKind::Updated,
+            changed_ontology_resource(table, uri),
+            owned_by_id,
+            actor_id,
+        );
+
+        Ok(metadata)
+    }
+}
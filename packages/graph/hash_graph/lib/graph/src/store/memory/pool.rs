@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use error_stack::Result;
+
+use crate::store::{MemoryStore, StoreError, StorePool};
+
+/// A [`StorePool`] handing out [`MemoryStore`]s.
+///
+/// All stores acquired from the same pool share the same data.
+#[derive(Clone, Default)]
+pub struct MemoryStorePool {
+    store: MemoryStore,
+}
+
+impl MemoryStorePool {
+    /// Creates a new `MemoryStorePool` with an empty [`MemoryStore`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new `MemoryStorePool` handing out the provided [`MemoryStore`].
+    #[must_use]
+    pub const fn from_store(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl StorePool for MemoryStorePool {
+    type Error = StoreError;
+    type Store<'pool> = MemoryStore;
+
+    async fn acquire(&self) -> Result<Self::Store<'_>, Self::Error> {
+        Ok(self.store.clone())
+    }
+
+    async fn acquire_owned(&self) -> Result<Self::Store<'static>, Self::Error> {
+        Ok(self.store.clone())
+    }
+
+    fn query_timeout(&self) -> Option<Duration> {
+        self.store.query_limits.timeout.map(Duration::from_secs)
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    mem,
+    sync::{Arc, RwLock},
+};
+
+use async_trait::async_trait;
+use error_stack::{Report, Result};
+
+use super::MemoryState;
+use crate::store::{MemoryStore, StoreError, StoreTransaction, TransactionalStore};
+
+/// A transaction of a [`MemoryStore`].
+///
+/// The transaction operates on a copy of the data, which replaces the data of the store it was
+/// started from when committing. Committing fails, if that store was modified in the meantime.
+///
+/// Conflicts are not detected per record: Any write to the store, which happened after the
+/// transaction was started, fails the commit, even if it does not overlap with the writes of the
+/// transaction. Callers, which expect concurrent writes, have to retry the whole transaction.
+pub struct MemoryTransaction {
+    store: MemoryStore,
+    parent: MemoryStore,
+    revision: u64,
+}
+
+#[async_trait]
+impl TransactionalStore for MemoryStore {
+    type Transaction<'t> = MemoryTransaction;
+
+    async fn transaction(&mut self) -> Result<Self::Transaction<'_>, StoreError> {
+        let state = self.state();
+        let copy = MemoryState {
+            accounts: state.accounts.clone(),
+            ontology_types: state.ontology_types.clone(),
+            entities: state.entities.clone(),
+            links: state.links.clone(),
+            change_feed: None,
+            pending_changes: Some(Vec::new()),
+            revision: 0,
+        };
+
+        Ok(MemoryTransaction {
+            store: MemoryStore {
+                state: Arc::new(RwLock::new(copy)),
+                query_limits: self.query_limits,
+            },
+            parent: self.clone(),
+            revision: state.revision,
+        })
+    }
+}
+
+#[async_trait]
+impl StoreTransaction for MemoryTransaction {
+    type Store = MemoryStore;
+
+    fn store(&mut self) -> &mut Self::Store {
+        &mut self.store
+    }
+
+    async fn commit(self) -> Result<(), StoreError> {
+        let mut state = self.store.state_mut();
+        let mut parent = self.parent.state_mut();
+
+        // Acquiring the lock already incremented the revision
+        if parent.revision != self.revision + 1 {
+            return Err(Report::new(StoreError)
+                .attach_printable("the store was modified concurrently to the transaction"));
+        }
+
+        parent.accounts = mem::take(&mut state.accounts);
+        parent.ontology_types = mem::take(&mut state.ontology_types);
+        parent.entities = mem::take(&mut state.entities);
+        parent.links = mem::take(&mut state.links);
+
+        for event in state.pending_changes.take().unwrap_or_default() {
+            parent.publish(event);
+        }
+
+        Ok(())
+    }
+}
@@ -1,20 +1,34 @@
+pub mod change;
 pub mod crud;
 pub mod error;
 pub mod query;
+pub mod search;
 
+mod context;
+mod limits;
+mod memory;
 mod pool;
 mod postgres;
 
-use std::fmt;
+use std::{borrow::Cow, fmt};
 
 use async_trait::async_trait;
-use error_stack::{Context, Result};
-use type_system::{uri::VersionedUri, DataType, EntityType, LinkType, PropertyType};
+use error_stack::{Context, IntoReport, Report, Result, ResultExt};
+use tokio_postgres_rustls::MakeRustlsConnect;
+use type_system::{
+    uri::{BaseUri, VersionedUri},
+    DataType, EntityType, LinkType, PropertyType,
+};
 
 pub use self::{
     error::{BaseUriAlreadyExists, BaseUriDoesNotExist, InsertionError, QueryError, UpdateError},
-    pool::StorePool,
-    postgres::{AsClient, PostgresStore, PostgresStorePool},
+    limits::QueryLimits,
+    memory::{MemoryStore, MemoryStorePool},
+    pool::{PoolStatus, StorePool},
+    postgres::{
+        listen_for_changes, migration, AsClient, DatabasePoolConfig, DatabaseTlsConfig,
+        PostgresStore, PostgresStorePool, SslMode,
+    },
 };
 use crate::{
     knowledge::{
@@ -25,7 +39,11 @@ use crate::{
         AccountId, PersistedDataType, PersistedEntityType, PersistedLinkType,
         PersistedOntologyMetadata, PersistedPropertyType,
     },
-    store::{error::LinkRemovalError, query::Expression},
+    store::{
+        error::LinkRemovalError,
+        query::Expression,
+        search::{EntitySearch, EntitySearchHit},
+    },
     subgraph::{StructuralQuery, Subgraph},
 };
 
@@ -88,6 +106,28 @@ pub struct DatabaseConnectionInfo {
         clap(long, default_value = "graph", env = "HASH_GRAPH_PG_DATABASE")
     )]
     database: String,
+
+    /// A `postgres://` URL to connect to, which takes precedence over the user, password, host,
+    /// port and database name
+    #[cfg_attr(
+        feature = "clap",
+        clap(long = "database-url", env = "HASH_GRAPH_PG_URL")
+    )]
+    url: Option<String>,
+
+    /// A `postgres://` URL of a read replica, which is used for requests only reading from the
+    /// database
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long = "database-read-replica-url",
+            env = "HASH_GRAPH_PG_READ_REPLICA_URL"
+        )
+    )]
+    read_replica_url: Option<String>,
+
+    #[cfg_attr(feature = "clap", clap(flatten))]
+    tls: DatabaseTlsConfig,
 }
 
 impl DatabaseConnectionInfo {
@@ -107,9 +147,40 @@ impl DatabaseConnectionInfo {
             host,
             port,
             database,
+            url: None,
+            read_replica_url: None,
+            tls: DatabaseTlsConfig {
+                ssl_mode: None,
+                root_cert: None,
+                cert: None,
+                key: None,
+            },
         }
     }
 
+    /// Connects to the database at the `postgres://` URL instead of using the individual
+    /// connection parameters.
+    #[must_use]
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Uses the database at the `postgres://` URL for operations, which only read from the
+    /// database.
+    #[must_use]
+    pub fn with_read_replica_url(mut self, url: impl Into<String>) -> Self {
+        self.read_replica_url = Some(url.into());
+        self
+    }
+
+    /// Sets the TLS configuration used to connect to the database.
+    #[must_use]
+    pub fn with_tls(mut self, tls: DatabaseTlsConfig) -> Self {
+        self.tls = tls;
+        self
+    }
+
     /// Creates a database connection url.
     ///
     /// Note, that this will reveal the password, so the returned output should not be printed. The
@@ -118,6 +189,10 @@ impl DatabaseConnectionInfo {
     /// [`Display`]: core::fmt::Display.
     #[must_use]
     pub fn url(&self) -> String {
+        if let Some(url) = &self.url {
+            return url.clone();
+        }
+
         let db_type = match self.database_type {
             DatabaseType::Postgres => "postgres",
         };
@@ -159,10 +234,88 @@ impl DatabaseConnectionInfo {
     pub fn database(&self) -> &str {
         &self.database
     }
+
+    /// Returns the connection information of the read replica, if one is configured.
+    ///
+    /// The read replica is connected to with the same TLS configuration.
+    #[must_use]
+    pub fn read_replica(&self) -> Option<Self> {
+        self.read_replica_url.as_ref().map(|url| Self {
+            url: Some(url.clone()),
+            read_replica_url: None,
+            ..self.clone()
+        })
+    }
+
+    #[must_use]
+    pub const fn tls(&self) -> &DatabaseTlsConfig {
+        &self.tls
+    }
+
+    /// Parses the URL, if one is configured.
+    ///
+    /// The `sslmode` parameter is removed from the URL and returned separately, as
+    /// `tokio-postgres` rejects the modes verifying the server.
+    ///
+    /// # Errors
+    ///
+    /// - if the URL is invalid
+    /// - if the `sslmode` of the URL is not supported
+    pub(crate) fn parse_url(
+        &self,
+    ) -> Result<Option<(tokio_postgres::Config, Option<SslMode>)>, StoreError> {
+        let Some(url) = &self.url else {
+            return Ok(None);
+        };
+
+        let (url, ssl_mode) = split_ssl_mode(url).attach_printable_lazy(|| self.clone())?;
+        let config = url
+            .parse()
+            .into_report()
+            .change_context(StoreError)
+            .attach_printable_lazy(|| self.clone())?;
+        Ok(Some((config, ssl_mode)))
+    }
+
+    /// Returns how TLS is used to connect to the database.
+    ///
+    /// If the mode is not configured explicitly, the `sslmode` parameter of the URL is used
+    /// (`prefer` by default). If no URL is provided either, TLS is disabled.
+    ///
+    /// # Errors
+    ///
+    /// - if the URL is invalid
+    /// - if the `sslmode` of the URL is not supported
+    pub fn ssl_mode(&self) -> Result<SslMode, StoreError> {
+        if let Some(ssl_mode) = self.tls.ssl_mode {
+            return Ok(ssl_mode);
+        }
+
+        Ok(match self.parse_url()? {
+            Some((config, ssl_mode)) => {
+                ssl_mode.unwrap_or_else(|| SslMode::from_connection_mode(config.get_ssl_mode()))
+            }
+            None => SslMode::Disable,
+        })
+    }
+
+    /// Creates the connector used to establish TLS connections to the database.
+    ///
+    /// # Errors
+    ///
+    /// - if the URL is invalid
+    /// - if the certificates or the private key could not be read
+    pub fn make_tls_connect(&self) -> Result<MakeRustlsConnect, StoreError> {
+        self.tls.make_tls_connect(self.ssl_mode()?)
+    }
 }
 
 impl fmt::Display for DatabaseConnectionInfo {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(url) = &self.url {
+            return write_masked_url(fmt, url);
+        }
+
         let db_type = match self.database_type {
             DatabaseType::Postgres => "postgres",
         };
@@ -174,6 +327,137 @@ impl fmt::Display for DatabaseConnectionInfo {
     }
 }
 
+/// Writes `url` with the password replaced by `***`.
+fn write_masked_url(fmt: &mut fmt::Formatter<'_>, url: &str) -> fmt::Result {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        // Not a URL, so the password can't be located
+        return fmt.write_str("***");
+    };
+    // The user info can't contain any of these characters unescaped
+    let (authority, path) = rest.split_at(rest.find(['/', '?', '#']).unwrap_or(rest.len()));
+    match authority.rsplit_once('@') {
+        Some((user_info, host)) => match user_info.split_once(':') {
+            Some((user, _password)) => write!(fmt, "{scheme}://{user}:***@{host}{path}"),
+            None => fmt.write_str(url),
+        },
+        None => fmt.write_str(url),
+    }
+}
+
+/// Parses the value of an `sslmode` parameter.
+fn parse_ssl_mode(value: &str) -> Result<SslMode, StoreError> {
+    SslMode::from_parameter(value).ok_or_else(|| {
+        Report::new(StoreError).attach_printable(format!("unsupported sslmode `{value}`"))
+    })
+}
+
+/// Removes the `sslmode` parameter from the connection string `url` and returns its value
+/// separately.
+///
+/// Both `postgres://` URLs and connection strings in the key-value format are supported.
+///
+/// # Errors
+///
+/// - if the `sslmode` is not supported
+/// - if the key-value connection string is malformed
+fn split_ssl_mode(url: &str) -> Result<(Cow<'_, str>, Option<SslMode>), StoreError> {
+    if !url.starts_with("postgres://") && !url.starts_with("postgresql://") {
+        return split_key_value_ssl_mode(url);
+    }
+    let Some((base, query)) = url.split_once('?') else {
+        return Ok((Cow::Borrowed(url), None));
+    };
+    let (query, fragment) = query.split_at(query.find('#').unwrap_or(query.len()));
+
+    let mut ssl_mode = None;
+    let mut parameters = Vec::new();
+    for parameter in query.split('&') {
+        match parameter.split_once('=') {
+            Some(("sslmode", value)) => ssl_mode = Some(parse_ssl_mode(value)?),
+            _ => parameters.push(parameter),
+        }
+    }
+
+    if ssl_mode.is_none() {
+        return Ok((Cow::Borrowed(url), None));
+    }
+
+    let mut url = base.to_owned();
+    if !parameters.is_empty() {
+        url.push('?');
+        url.push_str(&parameters.join("&"));
+    }
+    url.push_str(fragment);
+    Ok((Cow::Owned(url), ssl_mode))
+}
+
+/// Removes the `sslmode` pair from the key-value connection string `config` and returns its value
+/// separately.
+///
+/// Pairs are separated by whitespace and values may be single-quoted, as described in the
+/// [`tokio_postgres::Config`] documentation.
+///
+/// # Errors
+///
+/// - if the `sslmode` is not supported
+/// - if a pair is malformed
+fn split_key_value_ssl_mode(config: &str) -> Result<(Cow<'_, str>, Option<SslMode>), StoreError> {
+    let malformed =
+        || Report::new(StoreError).attach_printable("malformed key-value connection string");
+
+    let mut ssl_mode = None;
+    let mut pairs = Vec::new();
+    let mut rest = config.trim_start();
+    while !rest.is_empty() {
+        let pair = rest;
+
+        let key_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .ok_or_else(malformed)?;
+        let (key, after_key) = rest.split_at(key_end);
+        if key.is_empty() {
+            return Err(malformed());
+        }
+        rest = after_key.trim_start();
+        rest = rest.strip_prefix('=').ok_or_else(malformed)?.trim_start();
+
+        let mut value = String::new();
+        let mut chars = rest.char_indices();
+        let value_end = if rest.starts_with('\'') {
+            chars.next();
+            loop {
+                match chars.next().ok_or_else(malformed)? {
+                    (index, '\'') => break index + 1,
+                    (_, '\\') => value.push(chars.next().ok_or_else(malformed)?.1),
+                    (_, c) => value.push(c),
+                }
+            }
+        } else {
+            loop {
+                match chars.next() {
+                    None => break rest.len(),
+                    Some((index, c)) if c.is_whitespace() => break index,
+                    Some((_, '\\')) => value.push(chars.next().ok_or_else(malformed)?.1),
+                    Some((_, c)) => value.push(c),
+                }
+            }
+        };
+        rest = rest.split_at(value_end).1;
+
+        if key == "sslmode" {
+            ssl_mode = Some(parse_ssl_mode(&value)?);
+        } else {
+            pairs.push(pair.split_at(pair.len() - rest.len()).0);
+        }
+        rest = rest.trim_start();
+    }
+
+    if ssl_mode.is_none() {
+        return Ok((Cow::Borrowed(config), None));
+    }
+    Ok((Cow::Owned(pairs.join(" ")), ssl_mode))
+}
+
 /// Describes the API of a store implementation.
 ///
 /// # Errors
@@ -185,8 +469,49 @@ pub trait Store = AccountStore
     + PropertyTypeStore
     + LinkTypeStore
     + EntityTypeStore
+    + OntologyTypeStore
     + EntityStore
-    + LinkStore;
+    + LinkStore
+    + SnapshotStore
+    + TransactionalStore;
+
+/// Describes the API of a store implementation, which is able to apply multiple mutations
+/// atomically.
+#[async_trait]
+pub trait TransactionalStore {
+    /// The transaction returned when starting a transaction.
+    type Transaction<'t>: StoreTransaction
+    where
+        Self: 't;
+
+    /// Starts a new transaction.
+    ///
+    /// Mutations done through [`StoreTransaction::store`] are only applied to this store once the
+    /// transaction is committed. Dropping the transaction without committing it rolls back all of
+    /// its mutations.
+    ///
+    /// # Errors
+    ///
+    /// - if the transaction could not be started
+    async fn transaction(&mut self) -> Result<Self::Transaction<'_>, StoreError>;
+}
+
+/// A transaction started by [`TransactionalStore::transaction`].
+#[async_trait]
+pub trait StoreTransaction: Send {
+    /// The store used to read and mutate the data within the transaction.
+    type Store: Store + Send;
+
+    /// Returns the store operating within the transaction.
+    fn store(&mut self) -> &mut Self::Store;
+
+    /// Applies all mutations done within the transaction.
+    ///
+    /// # Errors
+    ///
+    /// - if the transaction could not be committed, in which case none of the mutations are applied
+    async fn commit(self) -> Result<(), StoreError>;
+}
 
 /// Describes the API of a store implementation for accounts.
 #[async_trait]
@@ -199,6 +524,34 @@ pub trait AccountStore {
     async fn insert_account_id(&mut self, account_id: AccountId) -> Result<(), InsertionError>;
 }
 
+/// Describes the API of a store implementation for restoring [`Snapshot`]s.
+///
+/// In contrast to the other store traits, records are inserted with the metadata provided instead
+/// of generating it. Records, which already exist, are skipped.
+///
+/// [`Snapshot`]: crate::snapshot::Snapshot
+#[async_trait]
+pub trait SnapshotStore {
+    /// Inserts the specified [`AccountId`] if it does not exist, yet.
+    ///
+    /// Returns `true` if the account was inserted.
+    ///
+    /// # Errors
+    ///
+    /// - if insertion failed.
+    async fn restore_account_id(&mut self, account_id: AccountId) -> Result<bool, InsertionError>;
+
+    /// Inserts the version of the [`Entity`] described by `entity` if it does not exist, yet.
+    ///
+    /// Returns `true` if the version was inserted.
+    ///
+    /// # Errors
+    ///
+    /// - if the [`EntityType`] doesn't exist
+    /// - if one of the referenced accounts does not exist
+    async fn restore_entity(&mut self, entity: &PersistedEntity) -> Result<bool, InsertionError>;
+}
+
 /// Describes the API of a store implementation for [`DataType`]s.
 #[async_trait]
 pub trait DataTypeStore: for<'q> crud::Read<PersistedDataType, Query<'q> = Expression> {
@@ -349,6 +702,78 @@ pub trait LinkTypeStore: for<'q> crud::Read<PersistedLinkType, Query<'q> = Expre
     ) -> Result<PersistedOntologyMetadata, UpdateError>;
 }
 
+/// Describes the API of a store implementation for managing ontology types independent of their
+/// kind.
+#[async_trait]
+pub trait OntologyTypeStore {
+    /// Returns the metadata of the ontology type identified by `uri`.
+    ///
+    /// # Errors
+    ///
+    /// - [`OntologyTypeDoesNotExist`], if the ontology type doesn't exist.
+    ///
+    /// [`OntologyTypeDoesNotExist`]: error::OntologyTypeDoesNotExist
+    async fn get_ontology_type_metadata(
+        &self,
+        uri: &VersionedUri,
+    ) -> Result<PersistedOntologyMetadata, QueryError>;
+
+    /// Returns the metadata of the latest version of the ontology type identified by `base_uri`.
+    ///
+    /// # Errors
+    ///
+    /// - [`OntologyTypeDoesNotExist`], if the [`BaseUri`] doesn't exist.
+    ///
+    /// [`OntologyTypeDoesNotExist`]: error::OntologyTypeDoesNotExist
+    async fn get_latest_ontology_type_metadata(
+        &self,
+        base_uri: &BaseUri,
+    ) -> Result<PersistedOntologyMetadata, QueryError>;
+
+    /// Marks the ontology type identified by `uri` as deprecated, optionally in favor of
+    /// `replaced_by`.
+    ///
+    /// Deprecated types can still be read, and deprecating a type again replaces its previous
+    /// [`Deprecation`].
+    ///
+    /// # Errors
+    ///
+    /// - if the ontology type doesn't exist
+    /// - if `replaced_by` doesn't exist, is of a different kind, or is `uri` itself
+    /// - if the account referred to by `actor_id` does not exist
+    ///
+    /// [`Deprecation`]: crate::ontology::Deprecation
+    async fn deprecate_ontology_type(
+        &mut self,
+        uri: &VersionedUri,
+        replaced_by: Option<VersionedUri>,
+        actor_id: AccountId,
+    ) -> Result<PersistedOntologyMetadata, UpdateError>;
+
+    /// Transfers the ownership of all versions of the ontology type identified by `base_uri` from
+    /// `current_owned_by_id` to `owned_by_id`.
+    ///
+    /// Checking the current owner and transferring the ownership is done atomically, so of multiple
+    /// concurrent transfers from the same owner only one succeeds.
+    ///
+    /// Returns the metadata of the latest version.
+    ///
+    /// # Errors
+    ///
+    /// - if the [`BaseUri`] doesn't exist
+    /// - [`UnexpectedOwner`], if the type is not owned by `current_owned_by_id`
+    /// - if one of the accounts referred to by `owned_by_id` or `actor_id` does not exist
+    ///
+    /// [`UnexpectedOwner`]: error::UnexpectedOwner
+    async fn transfer_ontology_type_ownership(
+        &mut self,
+        base_uri: &BaseUri,
+        current_owned_by_id: AccountId,
+        owned_by_id: AccountId,
+        actor_id: AccountId,
+    ) -> Result<PersistedOntologyMetadata, UpdateError>;
+}
+
 /// Describes the API of a store implementation for [Entities].
 ///
 /// [Entities]: crate::knowledge::Entity
@@ -404,6 +829,32 @@ pub trait EntityStore: for<'q> crud::Read<PersistedEntity, Query<'q> = Expressio
     /// - if the requested [`Entity`] doesn't exist
     async fn get_entity(&self, query: &StructuralQuery) -> Result<Subgraph, QueryError>;
 
+    /// Searches the latest versions of all [`Entity`]s by the text of their properties.
+    ///
+    /// The hits are ordered by their relevance, the most relevant hit comes first.
+    ///
+    /// # Errors
+    ///
+    /// - if one of the [`EntityType`]s to filter by doesn't exist
+    async fn search_entities(
+        &self,
+        search: &EntitySearch,
+    ) -> Result<Vec<EntitySearchHit>, QueryError>;
+
+    /// Returns the latest versions of all [`Entity`]s, whose [`EntityType`] references any
+    /// version of the ontology type identified by `base_uri`, either directly or transitively.
+    ///
+    /// If `base_uri` identifies an [`EntityType`], the [`Entity`]s of that type are returned as
+    /// well.
+    ///
+    /// # Errors
+    ///
+    /// - if reading the [`Entity`]s from the store fails
+    async fn get_latest_entities_referencing_type(
+        &self,
+        base_uri: &BaseUri,
+    ) -> Result<Vec<PersistedEntity>, QueryError>;
+
     /// Update an existing [`Entity`].
     ///
     /// # Errors
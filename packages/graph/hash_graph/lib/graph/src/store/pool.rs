@@ -1,8 +1,19 @@
+use std::time::Duration;
+
 use async_trait::async_trait;
 use error_stack::Result;
 
 use crate::store::Store;
 
+/// The connections of a [`StorePool`] at a point in time.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PoolStatus {
+    /// The number of connections currently managed by the pool.
+    pub connections: u32,
+    /// The number of connections currently idle.
+    pub idle_connections: u32,
+}
+
 /// Managed pool to keep track about [`Store`]s.
 #[async_trait]
 pub trait StorePool: Sync {
@@ -21,4 +32,27 @@ pub trait StorePool: Sync {
     /// [`StorePool::acquire`] (which stores a lifetime-bound reference to the `StorePool`) should
     /// be preferred whenever possible.
     async fn acquire_owned(&self) -> Result<Self::Store<'static>, Self::Error>;
+
+    /// Retrieves a [`Store`] from the pool, which is only used for reading.
+    ///
+    /// The returned [`Store`] may be backed by a read replica, which might lag behind, so reads,
+    /// which are the base of a modification, should use [`StorePool::acquire`] instead. By default,
+    /// this is the same as [`StorePool::acquire`].
+    async fn acquire_read_only(&self) -> Result<Self::Store<'_>, Self::Error> {
+        self.acquire().await
+    }
+
+    /// Returns the time after which reading the subgraph of a single query is aborted.
+    ///
+    /// Returns `None`, if queries are not aborted.
+    fn query_timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Returns the current status of the connections of the pool.
+    ///
+    /// Returns `None`, if the pool does not manage connections.
+    fn status(&self) -> Option<PoolStatus> {
+        None
+    }
 }
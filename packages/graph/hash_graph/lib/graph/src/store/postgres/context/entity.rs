@@ -1,42 +1,17 @@
-use chrono::{DateTime, Utc};
 use error_stack::{IntoReport, Result, ResultExt};
 use futures::{Stream, StreamExt};
 use tokio_postgres::{GenericClient, RowStream};
 use type_system::uri::{BaseUri, VersionedUri};
 
 use crate::{
-    knowledge::{Entity, EntityId, PersistedEntity, PersistedEntityIdentifier},
-    ontology::AccountId,
-    store::{postgres::parameter_list, AsClient, QueryError},
+    knowledge::EntityId,
+    store::{
+        context::{EntityRecord, EntityRecordStream},
+        postgres::parameter_list,
+        AsClient, QueryError,
+    },
 };
 
-pub struct EntityRecord {
-    pub entity: Entity,
-    pub id: EntityId,
-    pub version: DateTime<Utc>,
-    pub entity_type_id: VersionedUri,
-    pub owned_by_id: AccountId,
-    pub created_by_id: AccountId,
-    pub updated_by_id: AccountId,
-    pub removed_by_id: Option<AccountId>,
-    pub is_latest: bool,
-}
-
-impl From<EntityRecord> for PersistedEntity {
-    fn from(record: EntityRecord) -> Self {
-        Self::new(
-            record.entity,
-            PersistedEntityIdentifier::new(record.id, record.version, record.owned_by_id),
-            record.entity_type_id,
-            record.created_by_id,
-            record.updated_by_id,
-            record.removed_by_id,
-        )
-    }
-}
-
-pub type RecordStream = impl Stream<Item = Result<EntityRecord, QueryError>>;
-
 fn row_stream_to_record_stream(
     row_stream: RowStream,
 ) -> impl Stream<Item = Result<EntityRecord, QueryError>> {
@@ -60,7 +35,7 @@ fn row_stream_to_record_stream(
     })
 }
 
-pub async fn read_all_entities(client: &impl AsClient) -> Result<RecordStream, QueryError> {
+pub async fn read_all_entities(client: &impl AsClient) -> Result<EntityRecordStream, QueryError> {
     let row_stream = client
         .as_client()
         .query_raw(
@@ -76,7 +51,7 @@ pub async fn read_all_entities(client: &impl AsClient) -> Result<RecordStream, Q
         .await
         .into_report()
         .change_context(QueryError)?;
-    Ok(row_stream_to_record_stream(row_stream))
+    Ok(row_stream_to_record_stream(row_stream).boxed())
 }
 
 pub async fn read_latest_entity_by_id(
@@ -117,3 +92,61 @@ pub async fn read_latest_entity_by_id(
         is_latest: true,
     })
 }
+
+/// Reads the latest version of every entity, whose entity type references any version of the
+/// ontology type identified by `base_uri`, either directly or through other ontology types.
+///
+/// The versions of the type itself are considered referencing as well, so for an entity type
+/// this includes the entities of that type.
+pub async fn read_latest_entities_referencing_type(
+    client: &impl AsClient,
+    base_uri: &BaseUri,
+) -> Result<EntityRecordStream, QueryError> {
+    let row_stream = client
+        .as_client()
+        .query_raw(
+            r#"
+            WITH RECURSIVE type_references AS (
+                SELECT source_property_type_version_id AS source_version_id, target_property_type_version_id AS target_version_id
+                FROM property_type_property_type_references
+                UNION ALL
+                SELECT source_property_type_version_id, target_data_type_version_id
+                FROM property_type_data_type_references
+                UNION ALL
+                SELECT source_entity_type_version_id, target_property_type_version_id
+                FROM entity_type_property_type_references
+                UNION ALL
+                SELECT source_entity_type_version_id, target_link_type_version_id
+                FROM entity_type_link_type_references
+                UNION ALL
+                SELECT source_entity_type_version_id, target_entity_type_version_id
+                FROM entity_type_entity_type_links
+            ), referencing_types AS (
+                SELECT version_id
+                FROM type_ids
+                WHERE base_uri = $1
+                UNION
+                SELECT type_references.source_version_id
+                FROM type_references
+                INNER JOIN referencing_types
+                ON type_references.target_version_id = referencing_types.version_id
+            ), latest_entities AS (
+                SELECT DISTINCT ON (entity_id) *
+                FROM entities
+                ORDER BY entity_id, version DESC
+            )
+            SELECT properties, entity_id, latest_entities.version, type_ids.base_uri, type_ids.version, owned_by_id, created_by_id, updated_by_id, removed_by_id, TRUE
+            FROM latest_entities
+            INNER JOIN referencing_types
+            ON referencing_types.version_id = latest_entities.entity_type_version_id
+            INNER JOIN type_ids
+            ON type_ids.version_id = latest_entities.entity_type_version_id
+            ORDER BY entity_id;
+            "#,
+            parameter_list([&base_uri.as_str()]),
+        )
+        .await
+        .into_report()
+        .change_context(QueryError)?;
+    Ok(row_stream_to_record_stream(row_stream).boxed())
+}
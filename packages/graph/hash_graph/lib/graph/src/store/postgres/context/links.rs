@@ -4,37 +4,14 @@ use tokio_postgres::{GenericClient, RowStream};
 use type_system::uri::{BaseUri, VersionedUri};
 
 use crate::{
-    knowledge::{EntityId, Link, PersistedLink},
-    ontology::AccountId,
-    store::{postgres::parameter_list, AsClient, QueryError},
+    knowledge::EntityId,
+    store::{
+        context::{LinkRecord, LinkRecordStream},
+        postgres::parameter_list,
+        AsClient, QueryError,
+    },
 };
 
-pub struct LinkRecord {
-    pub link_type_id: VersionedUri,
-    pub source_entity_id: EntityId,
-    pub target_entity_id: EntityId,
-    pub owned_by_id: AccountId,
-    pub index: Option<i32>,
-    pub created_by_id: AccountId,
-}
-
-impl From<LinkRecord> for PersistedLink {
-    fn from(record: LinkRecord) -> Self {
-        Self::new(
-            Link::new(
-                record.source_entity_id,
-                record.target_entity_id,
-                record.link_type_id,
-                record.index,
-            ),
-            record.owned_by_id,
-            record.created_by_id,
-        )
-    }
-}
-
-pub type RecordStream = impl Stream<Item = Result<LinkRecord, QueryError>>;
-
 fn row_stream_to_record_stream(
     row_stream: RowStream,
 ) -> impl Stream<Item = Result<LinkRecord, QueryError>> {
@@ -55,7 +32,7 @@ fn row_stream_to_record_stream(
     })
 }
 
-pub async fn read_all_links(client: &impl AsClient) -> Result<RecordStream, QueryError> {
+pub async fn read_all_links(client: &impl AsClient) -> Result<LinkRecordStream, QueryError> {
     let row_stream = client
         .as_client()
         .query_raw(
@@ -71,13 +48,13 @@ pub async fn read_all_links(client: &impl AsClient) -> Result<RecordStream, Quer
         .await
         .into_report()
         .change_context(QueryError)?;
-    Ok(row_stream_to_record_stream(row_stream))
+    Ok(row_stream_to_record_stream(row_stream).boxed())
 }
 
 pub async fn read_links_by_source(
     client: &impl AsClient,
     entity_id: EntityId,
-) -> Result<RecordStream, QueryError> {
+) -> Result<LinkRecordStream, QueryError> {
     let row_stream = client
         .as_client()
         .query_raw(
@@ -94,18 +71,18 @@ pub async fn read_links_by_source(
         .await
         .into_report()
         .change_context(QueryError)?;
-    Ok(row_stream_to_record_stream(row_stream))
+    Ok(row_stream_to_record_stream(row_stream).boxed())
 }
 
 pub async fn read_links_by_target(
     client: &impl AsClient,
     entity_id: EntityId,
-) -> Result<RecordStream, QueryError> {
+) -> Result<LinkRecordStream, QueryError> {
     let row_stream = client
         .as_client()
         .query_raw(
             r#"
-            SELECT base_uri, version, source_entity_id, target_entity_id, owned_by_id, created_by_id
+            SELECT base_uri, version, source_entity_id, target_entity_id, owned_by_id, created_by_id, link_index
             FROM links
             JOIN type_ids ON version_id = link_type_version_id
             WHERE target_entity_id = $1
@@ -117,5 +94,5 @@ pub async fn read_links_by_target(
         .await
         .into_report()
         .change_context(QueryError)?;
-    Ok(row_stream_to_record_stream(row_stream))
+    Ok(row_stream_to_record_stream(row_stream).boxed())
 }
@@ -6,65 +6,26 @@ use async_trait::async_trait;
 use error_stack::{Context, Result, ResultExt};
 use type_system::uri::{BaseUri, VersionedUri};
 
-pub use self::{entity::EntityRecord, links::LinkRecord, ontology::OntologyRecord};
+pub use self::{
+    entity::read_latest_entities_referencing_type,
+    ontology::{read_type_metadata, OntologyMetadataRecord},
+};
 use crate::{
     knowledge::EntityId,
-    store::{postgres::ontology::OntologyDatabaseType, AsClient, PostgresStore, QueryError},
+    store::{
+        context::{
+            EntityRecord, EntityRecordStream, LinkRecordStream, OntologyDatabaseType,
+            OntologyRecord, OntologyRecordStream, ReferencingType, ResolveContext,
+        },
+        AsClient, PostgresStore, QueryError,
+    },
 };
 
-/// Context used for [`Resolve`].
-///
-/// This is only used as an implementation detail inside of the [`postgres`] module.
-///
-/// [`Resolve`]: crate::store::query::Resolve
-/// [`postgres`]: super::super
-// TODO: Use the context to hold query data
-//   see https://app.asana.com/0/0/1202884883200946/f
-#[async_trait]
-pub trait PostgresContext {
-    async fn read_all_ontology_types<T>(&self) -> Result<ontology::RecordStream<T>, QueryError>
-    where
-        T: OntologyDatabaseType + TryFrom<serde_json::Value, Error: Context>;
-
-    async fn read_latest_ontology_type<T>(
-        &self,
-        base_uri: &BaseUri,
-    ) -> Result<OntologyRecord<T>, QueryError>
-    where
-        T: OntologyDatabaseType + TryFrom<serde_json::Value, Error: Context>;
-
-    async fn read_versioned_ontology_type<T>(
-        &self,
-        uri: &VersionedUri,
-    ) -> Result<OntologyRecord<T>, QueryError>
-    where
-        T: OntologyDatabaseType + TryFrom<serde_json::Value, Error: Context>;
-
-    async fn read_all_entities(&self) -> Result<entity::RecordStream, QueryError>;
-
-    async fn read_latest_entity_by_id(
-        &self,
-        entity_id: EntityId,
-    ) -> Result<EntityRecord, QueryError>;
-
-    async fn read_all_links(&self) -> Result<links::RecordStream, QueryError>;
-
-    async fn read_links_by_source(
-        &self,
-        entity_id: EntityId,
-    ) -> Result<links::RecordStream, QueryError>;
-
-    async fn read_links_by_target(
-        &self,
-        entity_id: EntityId,
-    ) -> Result<links::RecordStream, QueryError>;
-}
-
 #[async_trait]
-impl<C: AsClient> PostgresContext for PostgresStore<C> {
-    async fn read_all_ontology_types<T>(&self) -> Result<ontology::RecordStream<T>, QueryError>
+impl<C: AsClient> ResolveContext for PostgresStore<C> {
+    async fn read_all_ontology_types<T>(&self) -> Result<OntologyRecordStream<T>, QueryError>
     where
-        T: OntologyDatabaseType + TryFrom<serde_json::Value, Error: Context>,
+        T: OntologyDatabaseType + TryFrom<serde_json::Value, Error: Context> + Send + 'static,
     {
         ontology::read_all_types(&self.client, T::table())
             .await
@@ -97,7 +58,17 @@ impl<C: AsClient> PostgresContext for PostgresStore<C> {
             .attach_printable_lazy(|| base_uri.clone())
     }
 
-    async fn read_all_entities(&self) -> Result<entity::RecordStream, QueryError> {
+    async fn read_referencing_ontology_types(
+        &self,
+        uri: &VersionedUri,
+    ) -> Result<Vec<ReferencingType>, QueryError> {
+        ontology::read_referencing_types(&self.client, uri)
+            .await
+            .attach_printable("could not read referencing ontology types")
+            .attach_printable_lazy(|| uri.clone())
+    }
+
+    async fn read_all_entities(&self) -> Result<EntityRecordStream, QueryError> {
         entity::read_all_entities(&self.client)
             .await
             .attach_printable("could not read entities")
@@ -113,7 +84,7 @@ impl<C: AsClient> PostgresContext for PostgresStore<C> {
             .attach_printable(entity_id)
     }
 
-    async fn read_all_links(&self) -> Result<links::RecordStream, QueryError> {
+    async fn read_all_links(&self) -> Result<LinkRecordStream, QueryError> {
         links::read_all_links(&self.client)
             .await
             .attach_printable("could not read links")
@@ -122,7 +93,7 @@ impl<C: AsClient> PostgresContext for PostgresStore<C> {
     async fn read_links_by_source(
         &self,
         entity_id: EntityId,
-    ) -> Result<links::RecordStream, QueryError> {
+    ) -> Result<LinkRecordStream, QueryError> {
         links::read_links_by_source(&self.client, entity_id)
             .await
             .attach_printable("could not read outgoing links")
@@ -132,7 +103,7 @@ impl<C: AsClient> PostgresContext for PostgresStore<C> {
     async fn read_links_by_target(
         &self,
         entity_id: EntityId,
-    ) -> Result<links::RecordStream, QueryError> {
+    ) -> Result<LinkRecordStream, QueryError> {
         links::read_links_by_target(&self.client, entity_id)
             .await
             .attach_printable("could not read incoming links")
@@ -1,33 +1,33 @@
-use error_stack::{Context, IntoReport, Result, ResultExt};
+use std::str::FromStr;
+
+use error_stack::{Context, IntoReport, Report, Result, ResultExt};
 use futures::{Stream, StreamExt};
-use tokio_postgres::{GenericClient, RowStream};
+use tokio_postgres::{GenericClient, Row, RowStream};
 use type_system::uri::{BaseUri, VersionedUri};
 
 use crate::{
-    ontology::AccountId,
+    ontology::{AccountId, Deprecation, PersistedOntologyIdentifier, PersistedOntologyMetadata},
     store::{
-        postgres::{ontology::OntologyDatabaseType, parameter_list},
+        context::{OntologyDatabaseType, OntologyRecord, OntologyRecordStream, ReferencingType},
+        error::OntologyTypeDoesNotExist,
+        postgres::{parameter_list, version_id::VersionId},
         AsClient, QueryError,
     },
 };
 
-pub type RecordStream<T>
-where
-    T: TryFrom<serde_json::Value, Error: Context>,
-= impl Stream<Item = Result<OntologyRecord<T>, QueryError>>;
+/// Reads the `deprecated_by_id` column at `index` and the `replaced_by` column following it.
+fn deprecation_from_row(row: &Row, index: usize) -> Result<Option<Deprecation>, QueryError> {
+    let Some(deprecated_by_id) = row.get::<_, Option<AccountId>>(index) else {
+        return Ok(None);
+    };
+    let replaced_by = row
+        .get::<_, Option<String>>(index + 1)
+        .map(|uri| VersionedUri::from_str(&uri))
+        .transpose()
+        .into_report()
+        .change_context(QueryError)?;
 
-/// Associates a database entry with the information about the latest version of the corresponding
-/// entry.
-///
-/// This is used for filtering by the latest version.
-#[derive(Debug)]
-pub struct OntologyRecord<T> {
-    pub record: T,
-    pub owned_by_id: AccountId,
-    pub created_by_id: AccountId,
-    pub updated_by_id: AccountId,
-    pub removed_by_id: Option<AccountId>,
-    pub is_latest: bool,
+    Ok(Some(Deprecation::new(deprecated_by_id, replaced_by)))
 }
 
 fn row_stream_to_record_stream<T>(
@@ -49,6 +49,7 @@ where
             created_by_id: row.get(3),
             updated_by_id: row.get(4),
             removed_by_id: row.get(5),
+            deprecation: deprecation_from_row(&row, 6)?,
         })
     })
 }
@@ -56,16 +57,16 @@ where
 pub async fn read_all_types<T>(
     client: &impl AsClient,
     table: &str,
-) -> Result<RecordStream<T>, QueryError>
+) -> Result<OntologyRecordStream<T>, QueryError>
 where
-    T: TryFrom<serde_json::Value, Error: Context>,
+    T: TryFrom<serde_json::Value, Error: Context> + Send + 'static,
 {
     let row_stream = client
         .as_client()
         .query_raw(
             &format!(
                 r#"
-                SELECT schema, owned_by_id, MAX(version) OVER (PARTITION by base_uri) = version as latest, created_by_id, updated_by_id, removed_by_id
+                SELECT schema, owned_by_id, MAX(version) OVER (PARTITION by base_uri) = version as latest, created_by_id, updated_by_id, removed_by_id, deprecated_by_id, replaced_by
                 FROM {table} type_table
                 INNER JOIN type_ids
                 ON type_table.version_id = type_ids.version_id
@@ -76,7 +77,7 @@ where
         )
         .await
         .into_report().change_context(QueryError)?;
-    Ok(row_stream_to_record_stream(row_stream))
+    Ok(row_stream_to_record_stream(row_stream).boxed())
 }
 
 pub async fn read_latest_type<T>(
@@ -91,7 +92,7 @@ where
         .query_one(
             &format!(
                 r#"
-                SELECT schema, owned_by_id, created_by_id, updated_by_id, removed_by_id
+                SELECT schema, owned_by_id, created_by_id, updated_by_id, removed_by_id, deprecated_by_id, replaced_by
                 FROM {} type_table
                 INNER JOIN type_ids
                 ON type_table.version_id = type_ids.version_id
@@ -116,6 +117,7 @@ where
     let created_by_id = row.get(2);
     let updated_by_id = row.get(3);
     let removed_by_id = row.get(4);
+    let deprecation = deprecation_from_row(&row, 5)?;
 
     Ok(OntologyRecord {
         record,
@@ -124,6 +126,7 @@ where
         created_by_id,
         updated_by_id,
         removed_by_id,
+        deprecation,
     })
 }
 
@@ -143,7 +146,7 @@ where
                     SELECT MAX(version) as latest
                     FROM type_ids
                     WHERE base_uri = $1
-                ), created_by_id, updated_by_id, removed_by_id
+                ), created_by_id, updated_by_id, removed_by_id, deprecated_by_id, replaced_by
                 FROM {} type_table
                 INNER JOIN type_ids
                 ON type_table.version_id = type_ids.version_id
@@ -165,6 +168,7 @@ where
     let created_by_id = row.get(3);
     let updated_by_id = row.get(4);
     let removed_by_id = row.get(5);
+    let deprecation = deprecation_from_row(&row, 6)?;
 
     Ok(OntologyRecord {
         record,
@@ -173,5 +177,132 @@ where
         created_by_id,
         updated_by_id,
         removed_by_id,
+        deprecation,
+    })
+}
+
+/// The metadata of an ontology type, which is read independently of the kind of the type.
+#[derive(Debug)]
+pub struct OntologyMetadataRecord {
+    /// The [`OntologyDatabaseType::table()`] the type is stored in.
+    pub table: String,
+    pub version_id: VersionId,
+    pub metadata: PersistedOntologyMetadata,
+}
+
+/// Reads the metadata of the ontology type identified by `base_uri` and `version`.
+///
+/// If `version` is `None`, the latest version is read.
+pub async fn read_type_metadata(
+    client: &impl AsClient,
+    base_uri: &BaseUri,
+    version: Option<u32>,
+) -> Result<OntologyMetadataRecord, QueryError> {
+    let row = client
+        .as_client()
+        .query_opt(
+            r#"
+            WITH type_tables AS (
+                SELECT 'data_types' AS table_name, version_id, owned_by_id, created_by_id, updated_by_id, removed_by_id, deprecated_by_id, replaced_by
+                FROM data_types
+                UNION ALL
+                SELECT 'property_types', version_id, owned_by_id, created_by_id, updated_by_id, removed_by_id, deprecated_by_id, replaced_by
+                FROM property_types
+                UNION ALL
+                SELECT 'link_types', version_id, owned_by_id, created_by_id, updated_by_id, removed_by_id, deprecated_by_id, replaced_by
+                FROM link_types
+                UNION ALL
+                SELECT 'entity_types', version_id, owned_by_id, created_by_id, updated_by_id, removed_by_id, deprecated_by_id, replaced_by
+                FROM entity_types
+            )
+            SELECT table_name, type_tables.version_id, version, owned_by_id, created_by_id, updated_by_id, removed_by_id, deprecated_by_id, replaced_by
+            FROM type_tables
+            INNER JOIN type_ids
+            ON type_tables.version_id = type_ids.version_id
+            WHERE base_uri = $1 AND version = COALESCE($2, (
+                SELECT MAX(version)
+                FROM type_ids
+                WHERE base_uri = $1
+            ));
+            "#,
+            &[&base_uri.as_str(), &version.map(i64::from)],
+        )
+        .await
+        .into_report()
+        .change_context(QueryError)?
+        .ok_or_else(|| Report::new(OntologyTypeDoesNotExist).change_context(QueryError))?;
+
+    let uri = VersionedUri::new(base_uri.clone(), row.get::<_, i64>(2) as u32);
+    let metadata = PersistedOntologyMetadata::new(
+        PersistedOntologyIdentifier::new(uri, row.get(3)),
+        row.get(4),
+        row.get(5),
+        row.get(6),
+    )
+    .with_deprecation(deprecation_from_row(&row, 7)?);
+
+    Ok(OntologyMetadataRecord {
+        table: row.get(0),
+        version_id: row.get(1),
+        metadata,
     })
 }
+
+pub async fn read_referencing_types(
+    client: &impl AsClient,
+    uri: &VersionedUri,
+) -> Result<Vec<ReferencingType>, QueryError> {
+    Ok(client
+        .as_client()
+        .query(
+            r#"
+            WITH target AS (
+                SELECT version_id
+                FROM type_ids
+                WHERE base_uri = $1 AND version = $2
+            ), referencing AS (
+                SELECT source_property_type_version_id AS version_id, FALSE AS is_entity_type
+                FROM property_type_property_type_references
+                WHERE target_property_type_version_id = (SELECT version_id FROM target)
+                UNION
+                SELECT source_property_type_version_id, FALSE
+                FROM property_type_data_type_references
+                WHERE target_data_type_version_id = (SELECT version_id FROM target)
+                UNION
+                SELECT source_entity_type_version_id, TRUE
+                FROM entity_type_property_type_references
+                WHERE target_property_type_version_id = (SELECT version_id FROM target)
+                UNION
+                SELECT source_entity_type_version_id, TRUE
+                FROM entity_type_link_type_references
+                WHERE target_link_type_version_id = (SELECT version_id FROM target)
+                UNION
+                SELECT source_entity_type_version_id, TRUE
+                FROM entity_type_entity_type_links
+                WHERE target_entity_type_version_id = (SELECT version_id FROM target)
+            )
+            SELECT base_uri, version, is_entity_type
+            FROM referencing
+            INNER JOIN type_ids
+            ON referencing.version_id = type_ids.version_id
+            ORDER BY base_uri, version;
+            "#,
+            &[&uri.base_uri().as_str(), &i64::from(uri.version())],
+        )
+        .await
+        .into_report()
+        .change_context(QueryError)?
+        .into_iter()
+        .map(|row| {
+            let uri = VersionedUri::new(
+                BaseUri::new(row.get(0)).expect("invalid BaseUri"),
+                row.get::<_, i64>(1) as u32,
+            );
+            if row.get(2) {
+                ReferencingType::EntityType(uri)
+            } else {
+                ReferencingType::PropertyType(uri)
+            }
+        })
+        .collect())
+}
@@ -1,130 +1,28 @@
 mod read;
-mod resolve;
-
-use std::{future::Future, pin::Pin};
+mod search;
 
 use async_trait::async_trait;
 use error_stack::{IntoReport, Report, Result, ResultExt};
-use futures::{stream, FutureExt, StreamExt, TryStreamExt};
+use futures::TryStreamExt;
 use tokio_postgres::GenericClient;
-use type_system::uri::VersionedUri;
+use type_system::uri::{BaseUri, VersionedUri};
 use uuid::Uuid;
 
 use crate::{
-    identifier::{GraphElementIdentifier, LinkId},
-    knowledge::{Entity, EntityId, PersistedEntity, PersistedEntityMetadata, PersistedLink},
+    knowledge::{Entity, EntityId, PersistedEntity, PersistedEntityMetadata},
     ontology::AccountId,
     store::{
+        change::{ChangeEvent, ChangeKind, ChangedResource},
+        context::{resolve_subgraph, ResolveContext},
         crud::Read,
         error::EntityDoesNotExist,
-        postgres::{context::PostgresContext, DependencyContext, DependencyContextRef},
+        postgres::context::read_latest_entities_referencing_type,
+        search::{EntitySearch, EntitySearchHit},
         AsClient, EntityStore, InsertionError, PostgresStore, QueryError, UpdateError,
     },
-    subgraph::{EdgeKind, GraphResolveDepths, OutwardEdge, StructuralQuery, Subgraph},
+    subgraph::{StructuralQuery, Subgraph},
 };
 
-impl<C: AsClient> PostgresStore<C> {
-    /// Internal method to read an [`Entity`] into a [`DependencyContext`].
-    ///
-    /// This is used to recursively resolve a type, so the result can be reused.
-    pub(crate) fn get_entity_as_dependency<'a: 'b, 'b>(
-        &'a self,
-        entity_id: EntityId,
-        mut dependency_context: DependencyContextRef<'b>,
-    ) -> Pin<Box<dyn Future<Output = Result<(), QueryError>> + Send + 'b>> {
-        async move {
-            let unresolved_entity = dependency_context
-                .linked_entities
-                .insert_with(
-                    &entity_id,
-                    Some(
-                        dependency_context
-                            .graph_resolve_depths
-                            .link_target_entity_resolve_depth,
-                    ),
-                    || async {
-                        Ok(PersistedEntity::from(
-                            self.read_latest_entity_by_id(entity_id).await?,
-                        ))
-                    },
-                )
-                .await?;
-
-            if let Some(entity) = unresolved_entity {
-                // Cloning the entity type ID avoids multiple borrow errors which would otherwise
-                // require us to clone the entity
-                let entity_type_id = entity.metadata().entity_type_id().clone();
-
-                dependency_context.edges.insert(
-                    GraphElementIdentifier::KnowledgeGraphElementId(entity_id),
-                    OutwardEdge {
-                        edge_kind: EdgeKind::HasType,
-                        destination: GraphElementIdentifier::OntologyElementId(
-                            entity_type_id.clone(),
-                        ),
-                    },
-                );
-
-                if dependency_context
-                    .graph_resolve_depths
-                    .entity_type_resolve_depth
-                    > 0
-                {
-                    self.get_entity_type_as_dependency(
-                        &entity_type_id,
-                        dependency_context.change_depth(GraphResolveDepths {
-                            entity_type_resolve_depth: dependency_context
-                                .graph_resolve_depths
-                                .entity_type_resolve_depth
-                                - 1,
-                            ..dependency_context.graph_resolve_depths
-                        }),
-                    )
-                    .await?;
-                }
-
-                for link_record in self
-                    .read_links_by_source(entity_id)
-                    .await?
-                    .try_collect::<Vec<_>>()
-                    .await?
-                {
-                    dependency_context.edges.insert(
-                        GraphElementIdentifier::KnowledgeGraphElementId(entity_id),
-                        OutwardEdge {
-                            edge_kind: EdgeKind::HasLink,
-                            destination: GraphElementIdentifier::Temporary(LinkId {
-                                source_entity_id: link_record.source_entity_id,
-                                target_entity_id: link_record.target_entity_id,
-                                link_type_id: link_record.link_type_id.clone(),
-                            }),
-                        },
-                    );
-
-                    if dependency_context.graph_resolve_depths.link_resolve_depth > 0 {
-                        let link = PersistedLink::from(link_record);
-
-                        self.get_link_as_dependency(
-                            &link,
-                            dependency_context.change_depth(GraphResolveDepths {
-                                link_resolve_depth: dependency_context
-                                    .graph_resolve_depths
-                                    .link_resolve_depth
-                                    - 1,
-                                ..dependency_context.graph_resolve_depths
-                            }),
-                        )
-                        .await?;
-                    }
-                }
-            }
-
-            Ok(())
-        }
-        .boxed()
-    }
-}
-
 #[async_trait]
 impl<C: AsClient> EntityStore for PostgresStore<C> {
     async fn create_entity(
@@ -159,6 +57,15 @@ impl<C: AsClient> EntityStore for PostgresStore<C> {
             )
             .await?;
 
+        transaction
+            .notify_change(&ChangeEvent::new(
+                ChangeKind::Created,
+                ChangedResource::entity(&metadata),
+                owned_by_id,
+                created_by_id,
+            ))
+            .await?;
+
         transaction
             .client
             .commit()
@@ -223,34 +130,35 @@ impl<C: AsClient> EntityStore for PostgresStore<C> {
     }
 
     async fn get_entity(&self, query: &StructuralQuery) -> Result<Subgraph, QueryError> {
-        let StructuralQuery {
-            ref expression,
-            graph_resolve_depths,
-        } = *query;
-
-        let subgraphs = stream::iter(Read::<PersistedEntity>::read(self, expression).await?)
-            .then(|entity| async move {
-                let mut dependency_context = DependencyContext::new(graph_resolve_depths);
-
-                let entity_id = entity.metadata().identifier().entity_id();
-                dependency_context
-                    .linked_entities
-                    .insert(&entity_id, None, entity);
-
-                self.get_entity_as_dependency(entity_id, dependency_context.as_ref_object())
-                    .await?;
-
-                let root = GraphElementIdentifier::KnowledgeGraphElementId(entity_id);
-
-                Ok::<_, Report<QueryError>>(dependency_context.into_subgraph(vec![root]))
-            })
-            .try_collect::<Vec<_>>()
-            .await?;
+        resolve_subgraph(
+            self,
+            Read::<PersistedEntity>::read(self, &query.expression).await?,
+            query,
+            &self.query_limits,
+        )
+        .await
+    }
 
-        let mut subgraph = Subgraph::new(graph_resolve_depths);
-        subgraph.extend(subgraphs);
+    async fn search_entities(
+        &self,
+        search: &EntitySearch,
+    ) -> Result<Vec<EntitySearchHit>, QueryError> {
+        self.search_latest_entities(search)
+            .await
+            .attach_printable_lazy(|| search.query.clone())
+    }
 
-        Ok(subgraph)
+    async fn get_latest_entities_referencing_type(
+        &self,
+        base_uri: &BaseUri,
+    ) -> Result<Vec<PersistedEntity>, QueryError> {
+        read_latest_entities_referencing_type(&self.client, base_uri)
+            .await
+            .attach_printable("could not read entities referencing ontology type")
+            .attach_printable_lazy(|| base_uri.clone())?
+            .map_ok(PersistedEntity::from)
+            .try_collect()
+            .await
     }
 
     async fn update_entity(
@@ -298,6 +206,16 @@ impl<C: AsClient> EntityStore for PostgresStore<C> {
             .await
             .change_context(UpdateError)?;
 
+        transaction
+            .notify_change(&ChangeEvent::new(
+                ChangeKind::Updated,
+                ChangedResource::entity(&metadata),
+                previous_entity.owned_by_id,
+                updated_by_id,
+            ))
+            .await
+            .change_context(UpdateError)?;
+
         transaction
             .client
             .commit()
@@ -1,12 +1,15 @@
+use std::time::Instant;
+
 use async_trait::async_trait;
 use error_stack::{bail, Report, Result, ResultExt};
 use futures::{stream, StreamExt, TryStreamExt};
 
 use crate::{
     knowledge::{PersistedEntity, PersistedEntityIdentifier},
+    metrics::{self, QueryPhase},
     store::{
+        context::ResolveContext,
         crud,
-        postgres::context::PostgresContext,
         query::{Expression, ExpressionError, Literal},
         AsClient, PostgresStore, QueryError,
     },
@@ -22,7 +25,12 @@ impl<C: AsClient> crud::Read<PersistedEntity> for PostgresStore<C> {
     ) -> Result<Vec<PersistedEntity>, QueryError> {
         // TODO: We need to work around collecting all records before filtering
         //   related: https://app.asana.com/0/1202805690238892/1202923536131158/f
-        stream::iter(self.read_all_entities().await?.collect::<Vec<_>>().await)
+        let start = Instant::now();
+        let records = self.read_all_entities().await?.collect::<Vec<_>>().await;
+        metrics::global().observe_query("entities", QueryPhase::Execute, start.elapsed());
+
+        let start = Instant::now();
+        let entities: Result<Vec<_>, QueryError> = stream::iter(records)
             .try_filter_map(|record| async move {
                 if let Literal::Bool(result) = query
                     .evaluate(&record, self)
@@ -52,6 +60,8 @@ impl<C: AsClient> crud::Read<PersistedEntity> for PostgresStore<C> {
                 }
             })
             .try_collect()
-            .await
+            .await;
+        metrics::global().observe_query("entities", QueryPhase::Evaluate, start.elapsed());
+        entities
     }
 }
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use error_stack::{IntoReport, Result, ResultExt};
+use tokio_postgres::GenericClient;
+use type_system::uri::{BaseUri, VersionedUri};
+
+use crate::{
+    knowledge::{PersistedEntity, PersistedEntityIdentifier},
+    store::{
+        search::{EntitySearch, EntitySearchHit, HIGHLIGHT_END, HIGHLIGHT_START},
+        AsClient, PostgresStore, QueryError,
+    },
+};
+
+impl<C: AsClient> PostgresStore<C> {
+    /// Searches the latest versions of all entities using the `search_vector` index.
+    pub(super) async fn search_latest_entities(
+        &self,
+        search: &EntitySearch,
+    ) -> Result<Vec<EntitySearchHit>, QueryError> {
+        let Some(tsquery) = search.to_tsquery() else {
+            return Ok(Vec::new());
+        };
+
+        let entity_type_version_ids = match &search.entity_type_ids {
+            Some(entity_type_ids) => {
+                let mut version_ids = Vec::with_capacity(entity_type_ids.len());
+                for entity_type_id in entity_type_ids {
+                    version_ids.push(self.version_id_by_uri(entity_type_id).await?);
+                }
+                Some(version_ids)
+            }
+            None => None,
+        };
+        let headline_options =
+            format!("StartSel={HIGHLIGHT_START}, StopSel={HIGHLIGHT_END}, HighlightAll=TRUE");
+
+        self.as_client()
+            .query(
+                r#"
+                SELECT properties, entities.entity_id, entities.version, type_ids.base_uri, type_ids.version, owned_by_id, created_by_id, updated_by_id, removed_by_id,
+                    ts_rank(search_vector, query) AS rank,
+                    CASE WHEN $4::BOOLEAN THEN (
+                        SELECT jsonb_object_agg(property.key, ts_headline('simple', property.value #>> '{}', query, $6))
+                        FROM jsonb_each(properties) AS property
+                        WHERE jsonb_typeof(property.value) = 'string'
+                          AND to_tsvector('simple', property.value #>> '{}') @@ query
+                    ) END AS highlights
+                FROM entities
+                INNER JOIN type_ids ON type_ids.version_id = entities.entity_type_version_id
+                CROSS JOIN to_tsquery('simple', $1) AS query
+                WHERE search_vector @@ query
+                  AND entities.version = (
+                      SELECT MAX(latest.version)
+                      FROM entities AS latest
+                      WHERE latest.entity_id = entities.entity_id
+                  )
+                  AND ($2::UUID[] IS NULL OR entity_type_version_id = ANY($2))
+                  AND ($3::UUID[] IS NULL OR owned_by_id = ANY($3))
+                ORDER BY rank DESC, entities.entity_id
+                LIMIT $5::BIGINT;
+                "#,
+                &[
+                    &tsquery,
+                    &entity_type_version_ids,
+                    &search.owned_by_ids,
+                    &search.highlight,
+                    &i64::from(search.limit),
+                    &headline_options,
+                ],
+            )
+            .await
+            .into_report()
+            .change_context(QueryError)?
+            .into_iter()
+            .map(|row| {
+                let entity = PersistedEntity::new(
+                    serde_json::from_value(row.get(0)).expect("invalid entity"),
+                    PersistedEntityIdentifier::new(row.get(1), row.get(2), row.get(5)),
+                    VersionedUri::new(
+                        BaseUri::new(row.get(3)).expect("invalid BaseUri"),
+                        row.get::<_, i64>(4) as u32,
+                    ),
+                    row.get(6),
+                    row.get(7),
+                    row.get(8),
+                );
+                let highlights: HashMap<String, String> = row
+                    .get::<_, Option<serde_json::Value>>(10)
+                    .map(serde_json::from_value)
+                    .transpose()
+                    .into_report()
+                    .change_context(QueryError)?
+                    .unwrap_or_default();
+
+                Ok(EntitySearchHit::new(entity, row.get(9), highlights))
+            })
+            .collect()
+    }
+}
@@ -1,12 +1,15 @@
+use std::time::Instant;
+
 use async_trait::async_trait;
 use error_stack::{bail, Report, Result, ResultExt};
 use futures::{stream, StreamExt, TryStreamExt};
 
 use crate::{
     knowledge::PersistedLink,
+    metrics::{self, QueryPhase},
     store::{
+        context::ResolveContext,
         crud,
-        postgres::context::PostgresContext,
         query::{Expression, ExpressionError, Literal},
         AsClient, PostgresStore, QueryError,
     },
@@ -22,7 +25,12 @@ impl<C: AsClient> crud::Read<PersistedLink> for PostgresStore<C> {
     ) -> Result<Vec<PersistedLink>, QueryError> {
         // TODO: We need to work around collecting all records before filtering
         //   related: https://app.asana.com/0/1202805690238892/1202923536131158/f
-        stream::iter(self.read_all_links().await?.collect::<Vec<_>>().await)
+        let start = Instant::now();
+        let records = self.read_all_links().await?.collect::<Vec<_>>().await;
+        metrics::global().observe_query("links", QueryPhase::Execute, start.elapsed());
+
+        let start = Instant::now();
+        let links: Result<Vec<_>, QueryError> = stream::iter(records)
             .try_filter_map(|record| async move {
                 if let Literal::Bool(result) = query
                     .evaluate(&record, self)
@@ -39,6 +47,8 @@ impl<C: AsClient> crud::Read<PersistedLink> for PostgresStore<C> {
                 }
             })
             .try_collect()
-            .await
+            .await;
+        metrics::global().observe_query("links", QueryPhase::Evaluate, start.elapsed());
+        links
     }
 }
@@ -1,2 +1,4 @@
 mod entity;
 mod link;
+
+pub(in crate::store) use self::{entity::get_entity_as_dependency, link::get_link_as_dependency};
@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use error_stack::{IntoReport, Result, ResultExt};
+use futures::{stream, StreamExt};
+use tokio::{sync::mpsc, task::JoinHandle};
+use tokio_postgres::{
+    tls::{MakeTlsConnect, TlsConnect},
+    AsyncMessage, Client, Config, Notification, Socket,
+};
+
+use crate::store::{
+    change::{ChangeEvent, ChangeFeed, CHANGE_CHANNEL},
+    postgres::pool::connection_config,
+    DatabaseConnectionInfo, StoreError,
+};
+
+/// The delay before the first attempt to reconnect, which is doubled for every failed attempt.
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// The maximum delay between two attempts to reconnect.
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
+/// A connection listening on the [`CHANGE_CHANNEL`].
+struct Listener {
+    /// The client has to be kept alive, otherwise the connection is closed.
+    _client: Client,
+    /// Receives the notifications until the connection is closed.
+    notifications: mpsc::UnboundedReceiver<Notification>,
+}
+
+impl Listener {
+    async fn connect<Tls>(config: &Config, tls: Tls) -> Result<Self, StoreError>
+    where
+        Tls: MakeTlsConnect<Socket, Stream: Send + 'static>,
+    {
+        let (client, mut connection) = config
+            .connect(tls)
+            .await
+            .into_report()
+            .change_context(StoreError)?;
+
+        // The connection has to be polled for the client to make progress, so notifications are
+        // received in a separate task.
+        let (sender, notifications) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut messages = stream::poll_fn(|cx| connection.poll_message(cx));
+            while let Some(message) = messages.next().await {
+                match message {
+                    Ok(AsyncMessage::Notification(notification)) => {
+                        if sender.send(notification).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(error) => {
+                        tracing::error!(%error, "Connection listening for changes was closed");
+                        break;
+                    }
+                }
+            }
+        });
+
+        client
+            .batch_execute(&format!("LISTEN {CHANGE_CHANNEL};"))
+            .await
+            .into_report()
+            .change_context(StoreError)?;
+
+        Ok(Self {
+            _client: client,
+            notifications,
+        })
+    }
+
+    /// Connects again until it succeeds, waiting an exponentially increasing delay between the
+    /// attempts.
+    async fn reconnect<Tls>(config: &Config, tls: &Tls) -> Self
+    where
+        Tls: MakeTlsConnect<Socket, Stream: Send + 'static> + Clone,
+    {
+        let mut delay = INITIAL_RECONNECT_DELAY;
+        loop {
+            tokio::time::sleep(delay).await;
+            match Self::connect(config, tls.clone()).await {
+                Ok(listener) => {
+                    tracing::info!("Reconnected to listen for changes");
+                    return listener;
+                }
+                Err(report) => {
+                    tracing::error!(error=?report, ?delay, "Could not reconnect to listen for changes");
+                    delay = delay.saturating_mul(2).min(MAX_RECONNECT_DELAY);
+                }
+            }
+        }
+    }
+
+    /// Publishes the received notifications to `feed` until the connection is closed.
+    async fn forward(&mut self, feed: &ChangeFeed) {
+        while let Some(notification) = self.notifications.recv().await {
+            match serde_json::from_str::<ChangeEvent>(notification.payload()) {
+                Ok(event) => feed.publish(event),
+                Err(error) => {
+                    tracing::error!(
+                        %error,
+                        payload = notification.payload(),
+                        "Received invalid change event"
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Forwards the [`ChangeEvent`]s published by [`PostgresStore`]s to `feed`.
+///
+/// A dedicated connection to the database is opened, which listens on the [`CHANGE_CHANNEL`]. If
+/// the connection is closed, e.g. because the database restarted, the returned task reconnects
+/// with an exponentially increasing delay. Changes published while the connection is closed are
+/// not received. The task runs until it's aborted.
+///
+/// # Errors
+///
+/// - if connecting to the database failed
+/// - if listening on the [`CHANGE_CHANNEL`] failed
+///
+/// [`PostgresStore`]: crate::store::PostgresStore
+pub async fn listen_for_changes<Tls>(
+    db_info: &DatabaseConnectionInfo,
+    tls: Tls,
+    feed: ChangeFeed,
+) -> Result<JoinHandle<()>, StoreError>
+where
+    Tls: MakeTlsConnect<
+            Socket,
+            Stream: Send + 'static,
+            TlsConnect: Send + TlsConnect<Socket, Future: Send>,
+        > + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    let config = connection_config(db_info)?;
+    let mut listener = Listener::connect(&config, tls.clone())
+        .await
+        .attach_printable_lazy(|| db_info.clone())?;
+
+    Ok(tokio::spawn(async move {
+        loop {
+            listener.forward(&feed).await;
+            tracing::warn!("Stopped listening for changes, reconnecting");
+            listener = Listener::reconnect(&config, &tls).await;
+        }
+    }))
+}
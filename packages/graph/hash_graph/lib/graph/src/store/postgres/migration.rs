@@ -0,0 +1,373 @@
+//! Schema migrations of the Postgres database.
+//!
+//! The migrations are SQL files embedded into the Graph. Their file names have the format
+//! `<version>_<name>.sql` and they are applied in the order of their version. Applied migrations
+//! are recorded in the `graph_migrations` table together with a checksum of their SQL, so changes
+//! to already applied migrations are detected.
+
+use std::{collections::HashMap, fmt};
+
+use chrono::{DateTime, Utc};
+use error_stack::{Context, IntoReport, Report, Result, ResultExt};
+use include_dir::{include_dir, Dir};
+use sha2::{Digest, Sha256};
+use tokio_postgres::GenericClient;
+
+use crate::store::{AsClient, PostgresStore};
+
+static MIGRATIONS: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/src/store/postgres/migrations");
+
+/// The key of the advisory lock preventing concurrent migrations of the same database.
+const MIGRATION_LOCK_KEY: i64 = 0x4841_5348_4752_4150;
+
+#[derive(Debug)]
+pub struct MigrationError;
+
+impl Context for MigrationError {}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("could not migrate the database schema")
+    }
+}
+
+/// A migration embedded into the Graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    version: i64,
+    name: &'static str,
+    sql: &'static str,
+    checksum: String,
+}
+
+impl Migration {
+    fn parse(file_name: &'static str, sql: &'static str) -> Option<Self> {
+        let (version, name) = file_name.strip_suffix(".sql")?.split_once('_')?;
+        Some(Self {
+            version: version.parse().ok()?,
+            name,
+            sql,
+            checksum: format!("{:x}", Sha256::digest(sql.as_bytes())),
+        })
+    }
+
+    #[must_use]
+    pub const fn version(&self) -> i64 {
+        self.version
+    }
+
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The SHA-256 checksum of the SQL of the migration, encoded as hex string.
+    #[must_use]
+    pub fn checksum(&self) -> &str {
+        &self.checksum
+    }
+}
+
+impl fmt::Display for Migration {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}_{}", self.version, self.name)
+    }
+}
+
+/// Returns all migrations embedded into the Graph ordered by their version.
+///
+/// # Errors
+///
+/// - [`MigrationError`], if an embedded file is not a valid migration
+pub fn migrations() -> Result<Vec<Migration>, MigrationError> {
+    let mut migrations = MIGRATIONS
+        .files()
+        .map(|file| {
+            let file_name = file
+                .path()
+                .file_name()
+                .and_then(|file_name| file_name.to_str())
+                .unwrap_or_default();
+            file.contents_utf8()
+                .and_then(|sql| Migration::parse(file_name, sql))
+                .ok_or_else(|| {
+                    Report::new(MigrationError)
+                        .attach_printable(format!("invalid migration file: {file_name}"))
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    migrations.sort_by_key(Migration::version);
+
+    Ok(migrations)
+}
+
+/// The state of a migration in the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationState {
+    /// The migration was applied.
+    Applied { applied_at: DateTime<Utc> },
+    /// The migration was applied, but the embedded migration was changed afterwards.
+    Modified { applied_at: DateTime<Utc> },
+    /// The migration was not applied yet.
+    Pending,
+    /// The migration was applied, but it's not embedded into this version of the Graph.
+    Unknown { applied_at: DateTime<Utc> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    version: i64,
+    name: String,
+    state: MigrationState,
+}
+
+impl MigrationStatus {
+    #[must_use]
+    pub const fn version(&self) -> i64 {
+        self.version
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub const fn state(&self) -> &MigrationState {
+        &self.state
+    }
+}
+
+impl fmt::Display for MigrationStatus {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}_{}: ", self.version, self.name)?;
+        match self.state {
+            MigrationState::Applied { applied_at } => write!(fmt, "applied at {applied_at}"),
+            MigrationState::Modified { applied_at } => {
+                write!(fmt, "applied at {applied_at}, but modified afterwards")
+            }
+            MigrationState::Pending => fmt.write_str("pending"),
+            MigrationState::Unknown { applied_at } => {
+                write!(fmt, "applied at {applied_at}, but unknown to this version")
+            }
+        }
+    }
+}
+
+/// A migration recorded in the `graph_migrations` table.
+struct AppliedMigration {
+    name: String,
+    checksum: String,
+    applied_at: DateTime<Utc>,
+}
+
+async fn applied_migrations(
+    client: &impl GenericClient,
+) -> Result<HashMap<i64, AppliedMigration>, MigrationError> {
+    let table_exists: bool = client
+        .query_one("SELECT to_regclass('graph_migrations') IS NOT NULL;", &[])
+        .await
+        .into_report()
+        .change_context(MigrationError)?
+        .get(0);
+    if !table_exists {
+        return Ok(HashMap::new());
+    }
+
+    Ok(client
+        .query(
+            "SELECT version, name, checksum, applied_at FROM graph_migrations;",
+            &[],
+        )
+        .await
+        .into_report()
+        .change_context(MigrationError)?
+        .into_iter()
+        .map(|row| {
+            (row.get(0), AppliedMigration {
+                name: row.get(1),
+                checksum: row.get(2),
+                applied_at: row.get(3),
+            })
+        })
+        .collect())
+}
+
+impl<C: AsClient> PostgresStore<C> {
+    /// Returns the state of all embedded and all applied migrations ordered by their version.
+    ///
+    /// # Errors
+    ///
+    /// - [`MigrationError`], if reading the applied migrations failed
+    pub async fn migration_status(&self) -> Result<Vec<MigrationStatus>, MigrationError> {
+        let mut applied = applied_migrations(self.as_client()).await?;
+
+        let mut status = migrations()?
+            .into_iter()
+            .map(|migration| {
+                let state = match applied.remove(&migration.version) {
+                    Some(applied) if applied.checksum == migration.checksum => {
+                        MigrationState::Applied {
+                            applied_at: applied.applied_at,
+                        }
+                    }
+                    Some(applied) => MigrationState::Modified {
+                        applied_at: applied.applied_at,
+                    },
+                    None => MigrationState::Pending,
+                };
+                MigrationStatus {
+                    version: migration.version,
+                    name: migration.name.to_owned(),
+                    state,
+                }
+            })
+            .collect::<Vec<_>>();
+        status.extend(
+            applied
+                .into_iter()
+                .map(|(version, applied)| MigrationStatus {
+                    version,
+                    name: applied.name,
+                    state: MigrationState::Unknown {
+                        applied_at: applied.applied_at,
+                    },
+                }),
+        );
+        status.sort_by_key(MigrationStatus::version);
+
+        Ok(status)
+    }
+
+    /// Checks that all embedded migrations are applied and none of them were modified.
+    ///
+    /// # Errors
+    ///
+    /// - [`MigrationError`], if a migration is pending, modified or unknown
+    pub async fn verify_migrations(&self) -> Result<(), MigrationError> {
+        let mut report: Option<Report<MigrationError>> = None;
+        for status in self.migration_status().await? {
+            if !matches!(status.state, MigrationState::Applied { .. }) {
+                let error = Report::new(MigrationError).attach_printable(status.to_string());
+                match &mut report {
+                    Some(report) => report.extend_one(error),
+                    None => report = Some(error),
+                }
+            }
+        }
+
+        report.map_or(Ok(()), Err)
+    }
+
+    /// Applies all pending migrations and returns them.
+    ///
+    /// The migrations are applied in a single transaction, so either all or none of them are
+    /// applied. Concurrent calls, e.g. from multiple instances of the Graph, wait for each other.
+    ///
+    /// # Errors
+    ///
+    /// - [`MigrationError`], if an applied migration was modified or is unknown to this version of
+    ///   the Graph
+    /// - [`MigrationError`], if applying a migration failed
+    pub async fn migrate(&mut self) -> Result<Vec<Migration>, MigrationError> {
+        let transaction = self
+            .as_mut_client()
+            .transaction()
+            .await
+            .into_report()
+            .change_context(MigrationError)?;
+
+        transaction
+            .execute("SELECT pg_advisory_xact_lock($1);", &[&MIGRATION_LOCK_KEY])
+            .await
+            .into_report()
+            .change_context(MigrationError)?;
+        transaction
+            .batch_execute(
+                r#"
+                CREATE TABLE IF NOT EXISTS graph_migrations (
+                    version BIGINT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    checksum TEXT NOT NULL,
+                    applied_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT clock_timestamp()
+                );
+                "#,
+            )
+            .await
+            .into_report()
+            .change_context(MigrationError)?;
+
+        let mut applied = applied_migrations(&transaction).await?;
+        let mut pending = Vec::new();
+        for migration in migrations()? {
+            match applied.remove(&migration.version) {
+                Some(applied) if applied.checksum != migration.checksum => {
+                    return Err(Report::new(MigrationError)
+                        .attach_printable(format!("migration {migration} was modified")));
+                }
+                Some(_) => {}
+                None => pending.push(migration),
+            }
+        }
+        if let Some(version) = applied.keys().max() {
+            return Err(Report::new(MigrationError).attach_printable(format!(
+                "migration {version} is unknown, the database was migrated by a newer version"
+            )));
+        }
+
+        for migration in &pending {
+            tracing::info!(%migration, "applying migration");
+            transaction
+                .batch_execute(migration.sql)
+                .await
+                .into_report()
+                .change_context(MigrationError)
+                .attach_printable_lazy(|| migration.to_string())?;
+            transaction
+                .execute(
+                    "INSERT INTO graph_migrations (version, name, checksum) VALUES ($1, $2, $3);",
+                    &[&migration.version, &migration.name, &migration.checksum],
+                )
+                .await
+                .into_report()
+                .change_context(MigrationError)?;
+        }
+
+        transaction
+            .commit()
+            .await
+            .into_report()
+            .change_context(MigrationError)?;
+
+        Ok(pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_migrations_are_valid() {
+        let migrations = migrations().expect("invalid migration");
+        assert!(!migrations.is_empty());
+        assert!(
+            migrations
+                .windows(2)
+                .all(|pair| pair[0].version() < pair[1].version()),
+            "migration versions are not unique"
+        );
+    }
+
+    #[test]
+    fn parse_file_name() {
+        let migration =
+            Migration::parse("1656417312397_initial.sql", "SELECT 1;").expect("invalid migration");
+        assert_eq!(migration.version(), 1_656_417_312_397);
+        assert_eq!(migration.name(), "initial");
+        assert_eq!(migration.to_string(), "1656417312397_initial");
+
+        assert!(Migration::parse("initial.sql", "").is_none());
+        assert!(Migration::parse("1_initial.ts", "").is_none());
+    }
+}
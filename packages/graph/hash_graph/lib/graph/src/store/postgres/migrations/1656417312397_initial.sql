@@ -0,0 +1,137 @@
+-- All statements are idempotent, so databases set up by the previous TypeScript migrations are
+-- adopted without changes.
+
+CREATE TABLE IF NOT EXISTS accounts (
+    account_id UUID PRIMARY KEY
+);
+
+CREATE TABLE IF NOT EXISTS base_uris (
+    base_uri TEXT PRIMARY KEY
+);
+
+-- TODO: rename this to type_internal_version_ids or something to distinguish it from versioned
+--   URIs and from entity ids - https://app.asana.com/0/1202805690238892/1203214689883089/f
+CREATE TABLE IF NOT EXISTS version_ids (
+    version_id UUID PRIMARY KEY
+);
+
+CREATE TABLE IF NOT EXISTS type_ids (
+    base_uri TEXT NOT NULL REFERENCES base_uris,
+    version BIGINT NOT NULL,
+    version_id UUID REFERENCES version_ids,
+    CONSTRAINT type_ids_primary_key PRIMARY KEY (base_uri, version)
+);
+COMMENT ON TABLE type_ids IS 'This table is a boundary to define the actual identification scheme for our kinds of types. Assume that we use the UUIDs on the types to look up more specific ID details.';
+
+-- TODO: remove the `removed_by_id` columns of the ontology tables if we introduce a delete table
+--   similar to links - https://app.asana.com/0/1201095311341924/1202697596928142/f
+CREATE TABLE IF NOT EXISTS data_types (
+    version_id UUID PRIMARY KEY REFERENCES version_ids,
+    schema JSONB NOT NULL,
+    owned_by_id UUID NOT NULL REFERENCES accounts,
+    created_by_id UUID NOT NULL REFERENCES accounts,
+    updated_by_id UUID NOT NULL REFERENCES accounts,
+    removed_by_id UUID REFERENCES accounts
+);
+
+CREATE TABLE IF NOT EXISTS property_types (
+    version_id UUID PRIMARY KEY REFERENCES version_ids,
+    schema JSONB NOT NULL,
+    owned_by_id UUID NOT NULL REFERENCES accounts,
+    created_by_id UUID NOT NULL REFERENCES accounts,
+    updated_by_id UUID NOT NULL REFERENCES accounts,
+    removed_by_id UUID REFERENCES accounts
+);
+
+CREATE TABLE IF NOT EXISTS entity_types (
+    version_id UUID PRIMARY KEY REFERENCES version_ids,
+    schema JSONB NOT NULL,
+    owned_by_id UUID NOT NULL REFERENCES accounts,
+    created_by_id UUID NOT NULL REFERENCES accounts,
+    updated_by_id UUID NOT NULL REFERENCES accounts,
+    removed_by_id UUID REFERENCES accounts
+);
+
+CREATE TABLE IF NOT EXISTS link_types (
+    version_id UUID PRIMARY KEY REFERENCES version_ids,
+    schema JSONB NOT NULL,
+    owned_by_id UUID NOT NULL REFERENCES accounts,
+    created_by_id UUID NOT NULL REFERENCES accounts,
+    updated_by_id UUID NOT NULL REFERENCES accounts,
+    removed_by_id UUID REFERENCES accounts
+);
+
+CREATE TABLE IF NOT EXISTS property_type_property_type_references (
+    source_property_type_version_id UUID NOT NULL REFERENCES property_types,
+    target_property_type_version_id UUID NOT NULL REFERENCES property_types
+);
+
+CREATE TABLE IF NOT EXISTS property_type_data_type_references (
+    source_property_type_version_id UUID NOT NULL REFERENCES property_types,
+    target_data_type_version_id UUID NOT NULL REFERENCES data_types
+);
+
+CREATE TABLE IF NOT EXISTS entity_type_property_type_references (
+    source_entity_type_version_id UUID NOT NULL REFERENCES entity_types,
+    target_property_type_version_id UUID NOT NULL REFERENCES property_types
+);
+
+CREATE TABLE IF NOT EXISTS entity_type_link_type_references (
+    source_entity_type_version_id UUID NOT NULL REFERENCES entity_types,
+    target_link_type_version_id UUID NOT NULL REFERENCES link_types
+);
+
+CREATE TABLE IF NOT EXISTS entity_type_entity_type_links (
+    source_entity_type_version_id UUID NOT NULL REFERENCES entity_types,
+    target_entity_type_version_id UUID NOT NULL REFERENCES entity_types
+);
+
+CREATE TABLE IF NOT EXISTS entity_ids (
+    entity_id UUID PRIMARY KEY
+);
+
+CREATE TABLE IF NOT EXISTS entities (
+    entity_id UUID NOT NULL REFERENCES entity_ids,
+    version TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT clock_timestamp(),
+    entity_type_version_id UUID NOT NULL REFERENCES entity_types,
+    properties JSONB NOT NULL,
+    owned_by_id UUID NOT NULL REFERENCES accounts,
+    created_by_id UUID NOT NULL REFERENCES accounts,
+    updated_by_id UUID NOT NULL REFERENCES accounts,
+    -- TODO: remove this column if we introduce a delete table similar to links
+    --   https://app.asana.com/0/1201095311341924/1202697596928142/f
+    removed_by_id UUID REFERENCES accounts,
+    CONSTRAINT entities_primary_key PRIMARY KEY (entity_id, version)
+);
+
+-- Currently links are between unversioned entities. Ideally we'd have links between versioned
+-- entities -> unversioned entities.
+CREATE TABLE IF NOT EXISTS links (
+    source_entity_id UUID NOT NULL REFERENCES entity_ids,
+    target_entity_id UUID NOT NULL REFERENCES entity_ids,
+    link_type_version_id UUID NOT NULL REFERENCES link_types,
+    -- TODO: this is where we could do fractional indexing
+    --   https://app.asana.com/0/1200211978612931/1202085856561975/f
+    link_index INTEGER,
+    owned_by_id UUID NOT NULL REFERENCES accounts,
+    created_by_id UUID NOT NULL REFERENCES accounts,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    CONSTRAINT links_pkey PRIMARY KEY (source_entity_id, target_entity_id, link_type_version_id)
+);
+
+-- link_histories has no unique index!
+CREATE TABLE IF NOT EXISTS link_histories (
+    -- We should consider whether these should reference entity_ids or not. If we allow GDPR
+    -- removal of entities, this constraint has to fail/cascade depending on desired output.
+    source_entity_id UUID NOT NULL REFERENCES entity_ids,
+    target_entity_id UUID NOT NULL REFERENCES entity_ids,
+    link_type_version_id UUID NOT NULL REFERENCES link_types,
+    -- TODO: this is where we could do fractional indexing
+    --   https://app.asana.com/0/1200211978612931/1202085856561975/f
+    link_index INTEGER,
+    owned_by_id UUID NOT NULL REFERENCES accounts,
+    created_by_id UUID NOT NULL REFERENCES accounts,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    removed_by_id UUID NOT NULL REFERENCES accounts,
+    removed_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
@@ -0,0 +1,9 @@
+-- The text of an entity consists of all string values of its properties. The `PostgresStore` keeps
+-- the vector up to date when inserting entities.
+ALTER TABLE entities ADD COLUMN IF NOT EXISTS search_vector TSVECTOR;
+
+UPDATE entities
+SET search_vector = jsonb_to_tsvector('simple', properties, '["string"]')
+WHERE search_vector IS NULL;
+
+CREATE INDEX IF NOT EXISTS entities_search_vector_index ON entities USING GIN (search_vector);
@@ -0,0 +1,13 @@
+-- A deprecated type can still be read, `replaced_by` optionally holds the versioned URI of the type,
+-- which should be used instead.
+ALTER TABLE data_types ADD COLUMN IF NOT EXISTS deprecated_by_id UUID REFERENCES accounts;
+ALTER TABLE data_types ADD COLUMN IF NOT EXISTS replaced_by TEXT;
+
+ALTER TABLE property_types ADD COLUMN IF NOT EXISTS deprecated_by_id UUID REFERENCES accounts;
+ALTER TABLE property_types ADD COLUMN IF NOT EXISTS replaced_by TEXT;
+
+ALTER TABLE entity_types ADD COLUMN IF NOT EXISTS deprecated_by_id UUID REFERENCES accounts;
+ALTER TABLE entity_types ADD COLUMN IF NOT EXISTS replaced_by TEXT;
+
+ALTER TABLE link_types ADD COLUMN IF NOT EXISTS deprecated_by_id UUID REFERENCES accounts;
+ALTER TABLE link_types ADD COLUMN IF NOT EXISTS replaced_by TEXT;
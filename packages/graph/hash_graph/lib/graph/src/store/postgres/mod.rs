@@ -1,17 +1,14 @@
+mod context;
 mod knowledge;
 mod ontology;
 
-mod context;
+mod listener;
+pub mod migration;
 mod pool;
 mod query;
+mod tls;
 mod version_id;
 
-use std::{
-    collections::{hash_map::RawEntryMut, HashMap},
-    future::Future,
-    hash::Hash,
-};
-
 use async_trait::async_trait;
 use error_stack::{Context, IntoReport, Report, Result, ResultExt};
 use postgres_types::ToSql;
@@ -24,362 +21,27 @@ use type_system::{
 };
 use uuid::Uuid;
 
-use self::context::{OntologyRecord, PostgresContext};
 pub use self::{
-    ontology::PersistedOntologyType,
-    pool::{AsClient, PostgresStorePool},
+    listener::listen_for_changes,
+    pool::{AsClient, DatabasePoolConfig, PostgresStorePool},
+    tls::{DatabaseTlsConfig, SslMode},
 };
 use super::error::LinkRemovalError;
 use crate::{
     knowledge::{
-        Entity, EntityId, KnowledgeGraphQueryDepth, Link, PersistedEntity,
-        PersistedEntityIdentifier, PersistedEntityMetadata, PersistedLink,
+        Entity, EntityId, Link, PersistedEntity, PersistedEntityIdentifier, PersistedEntityMetadata,
     },
-    ontology::{
-        AccountId, OntologyQueryDepth, PersistedDataType, PersistedEntityType, PersistedLinkType,
-        PersistedOntologyIdentifier, PersistedOntologyMetadata, PersistedPropertyType,
-    },
-    shared::identifier::{GraphElementIdentifier, LinkId},
+    ontology::{AccountId, PersistedOntologyIdentifier, PersistedOntologyMetadata},
     store::{
+        change::{ChangeEvent, ChangeKind, CHANGE_CHANNEL},
+        context::{OntologyDatabaseType, OntologyRecord, ResolveContext},
         error::VersionedUriAlreadyExists,
-        postgres::{ontology::OntologyDatabaseType, version_id::VersionId},
+        postgres::version_id::VersionId,
         AccountStore, BaseUriAlreadyExists, BaseUriDoesNotExist, InsertionError, QueryError,
-        UpdateError,
+        QueryLimits, SnapshotStore, StoreError, StoreTransaction, TransactionalStore, UpdateError,
     },
-    subgraph::{Edges, GraphResolveDepths, Subgraph, Vertex},
 };
 
-pub struct DependencyMap<V, T, D> {
-    resolved: HashMap<V, (T, Option<D>)>,
-}
-
-impl<V, T, D> Default for DependencyMap<V, T, D> {
-    fn default() -> Self {
-        Self {
-            resolved: HashMap::default(),
-        }
-    }
-}
-
-impl<V, T, D> DependencyMap<V, T, D> {
-    pub fn new() -> Self {
-        Self::default()
-    }
-}
-
-impl<V, T, D> DependencyMap<V, T, D>
-where
-    V: Eq + Hash + Clone + Send + Sync,
-    T: Send,
-    D: PartialOrd + Send,
-{
-    /// Inserts a dependency into the map.
-    ///
-    /// If the dependency does not already exist in the dependency map, it will be inserted with the
-    /// provided `resolved_depth` and a reference to this dependency will be returned in order to
-    /// continue resolving it. In the case, that the dependency already exists, the
-    /// `resolved_depth` will be compared with depth used when inserting it before:
-    /// - If the previous `resolved_depth` was `None`, the dependency was not resolved yet and the
-    ///   value is returned
-    /// - If the new depth is higher, the depth will be updated and a reference to the dependency
-    ///   will be returned in order to keep resolving it
-    /// - Otherwise, `None` will be returned as no further resolution is needed
-    pub fn insert(&mut self, identifier: &V, resolved_depth: Option<D>, value: T) -> Option<&T> {
-        match self.resolved.raw_entry_mut().from_key(identifier) {
-            RawEntryMut::Vacant(entry) => {
-                let (_id, (value, _depth)) =
-                    entry.insert(identifier.clone(), (value, resolved_depth));
-                Some(value)
-            }
-            RawEntryMut::Occupied(entry) => {
-                let (value, used_depth) = entry.into_mut();
-                match (used_depth, resolved_depth) {
-                    (None, Some(_)) => Some(value),
-                    (Some(used_depth), Some(resolved_depth)) if *used_depth < resolved_depth => {
-                        *used_depth = resolved_depth;
-                        Some(value)
-                    }
-                    _ => None,
-                }
-            }
-        }
-    }
-
-    /// Lazily inserts a dependency into the map.
-    ///
-    /// This behaves like [`insert`], but uses `resolver` to read the value to be inserted.
-    ///
-    /// [`insert`]: Self::insert
-    pub async fn insert_with<F, R>(
-        &mut self,
-        identifier: &V,
-        resolved_depth: Option<D>,
-        resolver: F,
-    ) -> Result<Option<&T>, QueryError>
-    where
-        F: Fn() -> R + Send + Sync,
-        R: Future<Output = Result<T, QueryError>> + Send,
-    {
-        Ok(match self.resolved.raw_entry_mut().from_key(identifier) {
-            RawEntryMut::Vacant(entry) => {
-                let value = resolver().await?;
-                let (_id, (value, _depth)) =
-                    entry.insert(identifier.clone(), (value, resolved_depth));
-                Some(value)
-            }
-            RawEntryMut::Occupied(entry) => {
-                let (value, used_depth) = entry.into_mut();
-                match (used_depth, resolved_depth) {
-                    (None, Some(_)) => Some(value),
-                    (Some(used_depth), Some(resolved_depth)) if *used_depth < resolved_depth => {
-                        *used_depth = resolved_depth;
-                        Some(value)
-                    }
-                    _ => None,
-                }
-            }
-        })
-    }
-
-    pub fn into_values(self) -> impl Iterator<Item = T> {
-        self.resolved.into_values().map(|value| value.0)
-    }
-
-    pub fn into_vec(self) -> Vec<T> {
-        self.into_values().collect()
-    }
-}
-
-pub struct DependencySet<T, D> {
-    resolved: HashMap<T, Option<D>>,
-}
-
-impl<T, D> Default for DependencySet<T, D> {
-    fn default() -> Self {
-        Self {
-            resolved: HashMap::default(),
-        }
-    }
-}
-
-impl<T, D> DependencySet<T, D> {
-    pub fn new() -> Self {
-        Self::default()
-    }
-}
-
-impl<T, D> DependencySet<T, D>
-where
-    T: Eq + Hash + Clone,
-    D: PartialOrd + Send,
-{
-    /// Inserts a dependency into the map.
-    ///
-    /// If the dependency does not already exist in the dependency map, it will be inserted with the
-    /// provided `resolved_depth` and a reference to this dependency will be returned in order to
-    /// continue resolving it. In the case, that the dependency already exists, the
-    /// `resolved_depth` will be compared with depth used when inserting it before:
-    /// - If the previous `resolved_depth` was `None`, the dependency was not resolved yet and the
-    ///   value is returned
-    /// - If the new depth is higher, the depth will be updated and a reference to the dependency
-    ///   will be returned in order to keep resolving it
-    /// - Otherwise, `None` will be returned as no further resolution is needed
-    pub fn insert<'t, 's: 't>(
-        &'s mut self,
-        identifier: &'t T,
-        resolved_depth: Option<D>,
-    ) -> Option<&'t T> {
-        match self.resolved.raw_entry_mut().from_key(identifier) {
-            RawEntryMut::Vacant(entry) => {
-                let (value, _depth) = entry.insert(identifier.clone(), resolved_depth);
-                Some(value)
-            }
-            RawEntryMut::Occupied(entry) => {
-                let (value, used_depth) = entry.into_key_value();
-                match (used_depth, resolved_depth) {
-                    (None, Some(_)) => Some(value),
-                    (Some(used_depth), Some(resolved_depth)) if *used_depth < resolved_depth => {
-                        *used_depth = resolved_depth;
-                        Some(value)
-                    }
-                    _ => None,
-                }
-            }
-        }
-    }
-
-    pub fn into_vec(self) -> Vec<T> {
-        self.into_iter().collect()
-    }
-
-    pub fn remove(&mut self, value: &T) -> Option<T> {
-        self.resolved.remove_entry(value).map(|(value, _)| value)
-    }
-}
-
-impl<T, D> IntoIterator for DependencySet<T, D>
-where
-    T: Eq + Hash + Clone,
-{
-    type Item = T;
-
-    type IntoIter = impl Iterator<Item = T>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        self.resolved.into_keys()
-    }
-}
-
-pub struct DependencyContext {
-    pub edges: Edges,
-    pub referenced_data_types: DependencyMap<VersionedUri, PersistedDataType, OntologyQueryDepth>,
-    pub referenced_property_types:
-        DependencyMap<VersionedUri, PersistedPropertyType, OntologyQueryDepth>,
-    pub referenced_link_types: DependencyMap<VersionedUri, PersistedLinkType, OntologyQueryDepth>,
-    pub referenced_entity_types:
-        DependencyMap<VersionedUri, PersistedEntityType, OntologyQueryDepth>,
-    pub linked_entities: DependencyMap<EntityId, PersistedEntity, KnowledgeGraphQueryDepth>,
-    pub links: DependencySet<PersistedLink, KnowledgeGraphQueryDepth>,
-    pub graph_resolve_depths: GraphResolveDepths,
-}
-
-impl DependencyContext {
-    #[must_use]
-    pub fn new(graph_resolve_depths: GraphResolveDepths) -> Self {
-        Self {
-            edges: Edges::new(),
-            referenced_data_types: DependencyMap::new(),
-            referenced_property_types: DependencyMap::new(),
-            referenced_link_types: DependencyMap::new(),
-            referenced_entity_types: DependencyMap::new(),
-            linked_entities: DependencyMap::new(),
-            links: DependencySet::new(),
-            graph_resolve_depths,
-        }
-    }
-
-    #[must_use]
-    pub fn as_ref_object(&mut self) -> DependencyContextRef {
-        DependencyContextRef {
-            edges: &mut self.edges,
-            referenced_data_types: &mut self.referenced_data_types,
-            referenced_property_types: &mut self.referenced_property_types,
-            referenced_link_types: &mut self.referenced_link_types,
-            referenced_entity_types: &mut self.referenced_entity_types,
-            linked_entities: &mut self.linked_entities,
-            links: &mut self.links,
-            graph_resolve_depths: self.graph_resolve_depths,
-        }
-    }
-
-    #[must_use]
-    pub fn into_subgraph(self, roots: Vec<GraphElementIdentifier>) -> Subgraph {
-        let vertices = self
-            .referenced_data_types
-            .into_values()
-            .map(|data_type| {
-                (
-                    GraphElementIdentifier::OntologyElementId(
-                        data_type.metadata().identifier().uri().clone(),
-                    ),
-                    Vertex::DataType(data_type),
-                )
-            })
-            .chain(
-                self.referenced_property_types
-                    .into_values()
-                    .map(|property_type| {
-                        (
-                            GraphElementIdentifier::OntologyElementId(
-                                property_type.metadata().identifier().uri().clone(),
-                            ),
-                            Vertex::PropertyType(property_type),
-                        )
-                    }),
-            )
-            .chain(self.referenced_link_types.into_values().map(|link_type| {
-                (
-                    GraphElementIdentifier::OntologyElementId(
-                        link_type.metadata().identifier().uri().clone(),
-                    ),
-                    Vertex::LinkType(link_type),
-                )
-            }))
-            .chain(
-                self.referenced_entity_types
-                    .into_values()
-                    .map(|entity_type| {
-                        (
-                            GraphElementIdentifier::OntologyElementId(
-                                entity_type.metadata().identifier().uri().clone(),
-                            ),
-                            Vertex::EntityType(entity_type),
-                        )
-                    }),
-            )
-            .chain(self.links.into_iter().map(|link| {
-                (
-                    GraphElementIdentifier::Temporary(LinkId {
-                        source_entity_id: link.inner().source_entity(),
-                        target_entity_id: link.inner().target_entity(),
-                        link_type_id: link.inner().link_type_id().clone(),
-                    }),
-                    Vertex::Link(link),
-                )
-            }))
-            .chain(self.linked_entities.into_values().map(|entity| {
-                (
-                    GraphElementIdentifier::KnowledgeGraphElementId(
-                        entity.metadata().identifier().entity_id(),
-                    ),
-                    Vertex::Entity(entity),
-                )
-            }))
-            .collect();
-
-        Subgraph {
-            roots,
-            vertices,
-            edges: self.edges,
-            depths: self.graph_resolve_depths,
-        }
-    }
-}
-
-pub struct DependencyContextRef<'a> {
-    pub edges: &'a mut Edges,
-    pub referenced_data_types:
-        &'a mut DependencyMap<VersionedUri, PersistedDataType, OntologyQueryDepth>,
-    pub referenced_property_types:
-        &'a mut DependencyMap<VersionedUri, PersistedPropertyType, OntologyQueryDepth>,
-    pub referenced_link_types:
-        &'a mut DependencyMap<VersionedUri, PersistedLinkType, OntologyQueryDepth>,
-    pub referenced_entity_types:
-        &'a mut DependencyMap<VersionedUri, PersistedEntityType, OntologyQueryDepth>,
-    pub linked_entities: &'a mut DependencyMap<EntityId, PersistedEntity, KnowledgeGraphQueryDepth>,
-    pub links: &'a mut DependencySet<PersistedLink, KnowledgeGraphQueryDepth>,
-    pub graph_resolve_depths: GraphResolveDepths,
-}
-
-impl<'a> DependencyContextRef<'a> {
-    pub fn change_depth(
-        &mut self,
-        graph_resolve_depths: GraphResolveDepths,
-    ) -> DependencyContextRef<'_>
-where {
-        DependencyContextRef {
-            edges: self.edges,
-            referenced_data_types: self.referenced_data_types,
-            referenced_property_types: self.referenced_property_types,
-            referenced_link_types: self.referenced_link_types,
-            referenced_entity_types: self.referenced_entity_types,
-            linked_entities: self.linked_entities,
-            links: self.links,
-            graph_resolve_depths,
-        }
-    }
-}
-
 /// Utility function used for [`GenericClient::query_raw`] to infer the parameter as
 /// [`dyn ToSql`][ToSql].
 ///
@@ -391,6 +53,7 @@ fn parameter_list<const N: usize>(list: [&(dyn ToSql + Sync); N]) -> [&(dyn ToSq
 /// A Postgres-backed store
 pub struct PostgresStore<C> {
     client: C,
+    query_limits: QueryLimits,
 }
 
 impl<C> PostgresStore<C>
@@ -400,7 +63,17 @@ where
     /// Creates a new `PostgresDatabase` object.
     #[must_use]
     pub const fn new(client: C) -> Self {
-        Self { client }
+        Self {
+            client,
+            query_limits: QueryLimits::unlimited(),
+        }
+    }
+
+    /// Limits the cost of structural queries by `query_limits`.
+    #[must_use]
+    pub const fn with_query_limits(mut self, query_limits: QueryLimits) -> Self {
+        self.query_limits = query_limits;
+        self
     }
 
     /// Checks if the specified [`BaseUri`] exists in the database.
@@ -640,6 +313,14 @@ where
         )
         .await?;
 
+        self.notify_change(&ChangeEvent::new(
+            ChangeKind::Created,
+            T::changed_resource(uri.clone()),
+            owned_by_id,
+            created_by_id,
+        ))
+        .await?;
+
         Ok((
             version_id,
             PersistedOntologyMetadata::new(
@@ -715,6 +396,15 @@ where
         .await
         .change_context(UpdateError)?;
 
+        self.notify_change(&ChangeEvent::new(
+            ChangeKind::Updated,
+            T::changed_resource(uri.clone()),
+            owned_by_id,
+            updated_by_id,
+        ))
+        .await
+        .change_context(UpdateError)?;
+
         Ok((
             version_id,
             PersistedOntologyMetadata::new(
@@ -974,8 +664,8 @@ where
             .change_context(InsertionError)?;
         let version = self.as_client().query_one(
                 r#"
-                    INSERT INTO entities (entity_id, version, entity_type_version_id, properties, owned_by_id, created_by_id, updated_by_id)
-                    VALUES ($1, clock_timestamp(), $2, $3, $4, $5, $6)
+                    INSERT INTO entities (entity_id, version, entity_type_version_id, properties, owned_by_id, created_by_id, updated_by_id, search_vector)
+                    VALUES ($1, clock_timestamp(), $2, $3, $4, $5, $6, jsonb_to_tsvector('simple', $3, '["string"]'))
                     RETURNING version;
                 "#,
                 &[&entity_id, &entity_type_version_id, &value, &owned_by_id, &created_by_id, &updated_by_id]
@@ -1061,6 +751,8 @@ where
     /// Moves a [`Link`] associated with an [`AccountId`] from the `links` table into the
     /// `link_histories` table.
     ///
+    /// Returns the account owning the removed [`Link`].
+    ///
     /// # Errors
     ///
     /// - if the [`Link`] doesn't exist
@@ -1070,7 +762,7 @@ where
         &self,
         link: &Link,
         removed_by_id: AccountId,
-    ) -> Result<(), LinkRemovalError> {
+    ) -> Result<AccountId, LinkRemovalError> {
         let link_type_version_id = self
             .version_id_by_uri(link.link_type_id())
             .await
@@ -1095,7 +787,7 @@ where
                     link_index, owned_by_id, created_by_id, created_at, removed_by_id, removed_at)
                 -- When inserting into `link_histories`, `removed_by_id` and `removed_at` are provided
                 SELECT *, $4, clock_timestamp() FROM removed
-                RETURNING source_entity_id, target_entity_id, link_type_version_id;
+                RETURNING owned_by_id;
                 "#,
                 &[
                     &link.source_entity(),
@@ -1106,7 +798,29 @@ where
             )
             .await
             .into_report()
-            .change_context(LinkRemovalError)?;
+            .change_context(LinkRemovalError)
+            .map(|row| row.get(0))
+    }
+
+    /// Publishes `event` on the [`CHANGE_CHANNEL`].
+    ///
+    /// When called inside of a transaction, the event is only published once the transaction is
+    /// committed.
+    ///
+    /// # Errors
+    ///
+    /// - if sending the notification failed.
+    async fn notify_change(&self, event: &ChangeEvent) -> Result<(), InsertionError> {
+        let payload = serde_json::to_string(event)
+            .into_report()
+            .change_context(InsertionError)?;
+
+        self.as_client()
+            .execute("SELECT pg_notify($1, $2);", &[&CHANGE_CHANNEL, &payload])
+            .await
+            .into_report()
+            .change_context(InsertionError)
+            .attach_printable(payload)?;
 
         Ok(())
     }
@@ -1178,6 +892,7 @@ impl PostgresStore<Transaction<'_>> {
             Type::UUID,
         ]);
         futures::pin_mut!(writer);
+        let mut inserted_ids = Vec::new();
         for (entity_id, entity) in entity_ids.into_iter().zip(entities) {
             let value = serde_json::to_value(entity)
                 .into_report()
@@ -1196,13 +911,31 @@ impl PostgresStore<Transaction<'_>> {
                 .into_report()
                 .change_context(InsertionError)
                 .attach_printable(entity_id)?;
+            inserted_ids.push(entity_id);
         }
 
-        writer
+        let inserted = writer
             .finish()
             .await
             .into_report()
-            .change_context(InsertionError)
+            .change_context(InsertionError)?;
+
+        // `COPY` cannot compute the search vector, so it's populated afterwards for the inserted
+        // entities only
+        self.as_client()
+            .execute(
+                r#"
+                UPDATE entities
+                SET search_vector = jsonb_to_tsvector('simple', properties, '["string"]')
+                WHERE entity_id = ANY($1) AND search_vector IS NULL;
+                "#,
+                &[&inserted_ids],
+            )
+            .await
+            .into_report()
+            .change_context(InsertionError)?;
+
+        Ok(inserted)
     }
 }
 
@@ -1226,3 +959,130 @@ impl<C: AsClient> AccountStore for PostgresStore<C> {
         Ok(())
     }
 }
+
+#[async_trait]
+impl<C: AsClient> SnapshotStore for PostgresStore<C> {
+    async fn restore_account_id(&mut self, account_id: AccountId) -> Result<bool, InsertionError> {
+        let inserted = self
+            .as_client()
+            .execute(
+                r#"
+                INSERT INTO accounts (account_id)
+                VALUES ($1)
+                ON CONFLICT DO NOTHING;
+                "#,
+                &[&account_id],
+            )
+            .await
+            .into_report()
+            .change_context(InsertionError)
+            .attach_printable(account_id)?;
+
+        Ok(inserted == 1)
+    }
+
+    async fn restore_entity(&mut self, entity: &PersistedEntity) -> Result<bool, InsertionError> {
+        let transaction = PostgresStore::new(
+            self.as_mut_client()
+                .transaction()
+                .await
+                .into_report()
+                .change_context(InsertionError)?,
+        );
+
+        let metadata = entity.metadata();
+        let entity_id = metadata.identifier().entity_id();
+
+        let entity_type_version_id = transaction
+            .version_id_by_uri(metadata.entity_type_id())
+            .await
+            .change_context(InsertionError)?;
+        let properties = serde_json::to_value(entity.inner())
+            .into_report()
+            .change_context(InsertionError)?;
+
+        transaction
+            .as_client()
+            .execute(
+                r#"
+                INSERT INTO entity_ids (entity_id)
+                VALUES ($1)
+                ON CONFLICT DO NOTHING;
+                "#,
+                &[&entity_id],
+            )
+            .await
+            .into_report()
+            .change_context(InsertionError)
+            .attach_printable(entity_id)?;
+
+        let inserted = transaction
+            .as_client()
+            .execute(
+                r#"
+                INSERT INTO entities (entity_id, version, entity_type_version_id, properties, owned_by_id, created_by_id, updated_by_id, removed_by_id, search_vector)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, jsonb_to_tsvector('simple', $4, '["string"]'))
+                ON CONFLICT DO NOTHING;
+                "#,
+                &[
+                    &entity_id,
+                    &metadata.identifier().version(),
+                    &entity_type_version_id,
+                    &properties,
+                    &metadata.identifier().owned_by_id(),
+                    &metadata.created_by_id(),
+                    &metadata.updated_by_id(),
+                    &metadata.removed_by_id(),
+                ],
+            )
+            .await
+            .into_report()
+            .change_context(InsertionError)
+            .attach_printable(entity_id)?;
+
+        transaction
+            .client
+            .commit()
+            .await
+            .into_report()
+            .change_context(InsertionError)?;
+
+        Ok(inserted == 1)
+    }
+}
+
+#[async_trait]
+impl<C: AsClient> TransactionalStore for PostgresStore<C> {
+    type Transaction<'t> = PostgresStore<Transaction<'t>> where Self: 't;
+
+    async fn transaction(&mut self) -> Result<Self::Transaction<'_>, StoreError> {
+        // Starting a transaction within a transaction creates a savepoint, so mutations, which use
+        // a transaction on their own, are still atomic.
+        let query_limits = self.query_limits;
+        Ok(PostgresStore::new(
+            self.as_mut_client()
+                .transaction()
+                .await
+                .into_report()
+                .change_context(StoreError)?,
+        )
+        .with_query_limits(query_limits))
+    }
+}
+
+#[async_trait]
+impl StoreTransaction for PostgresStore<Transaction<'_>> {
+    type Store = Self;
+
+    fn store(&mut self) -> &mut Self::Store {
+        self
+    }
+
+    async fn commit(self) -> Result<(), StoreError> {
+        self.client
+            .commit()
+            .await
+            .into_report()
+            .change_context(StoreError)
+    }
+}
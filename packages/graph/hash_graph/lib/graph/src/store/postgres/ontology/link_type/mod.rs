@@ -1,51 +1,17 @@
-mod resolve;
-
 use async_trait::async_trait;
-use error_stack::{IntoReport, Report, Result, ResultExt};
-use futures::{stream, StreamExt, TryStreamExt};
+use error_stack::{IntoReport, Result, ResultExt};
 use tokio_postgres::GenericClient;
-use type_system::{uri::VersionedUri, LinkType};
+use type_system::LinkType;
 
 use crate::{
     ontology::{AccountId, PersistedLinkType, PersistedOntologyMetadata},
-    shared::identifier::GraphElementIdentifier,
     store::{
-        crud::Read,
-        postgres::{
-            context::PostgresContext, DependencyContext, DependencyContextRef,
-            PersistedOntologyType,
-        },
-        AsClient, InsertionError, LinkTypeStore, PostgresStore, QueryError, UpdateError,
+        context::resolve_subgraph, crud::Read, AsClient, InsertionError, LinkTypeStore,
+        PostgresStore, QueryError, UpdateError,
     },
     subgraph::{StructuralQuery, Subgraph},
 };
 
-impl<C: AsClient> PostgresStore<C> {
-    /// Internal method to read a [`PersistedLinkType`] into a [`DependencyContext`].
-    ///
-    /// This is used to recursively resolve a type, so the result can be reused.
-    pub(crate) async fn get_link_type_as_dependency<'a>(
-        &self,
-        link_type_id: &VersionedUri,
-        context: DependencyContextRef<'a>,
-    ) -> Result<(), QueryError> {
-        let _unresolved_link_type = context
-            .referenced_link_types
-            .insert_with(
-                link_type_id,
-                Some(context.graph_resolve_depths.link_type_resolve_depth),
-                || async {
-                    Ok(PersistedLinkType::from_record(
-                        self.read_versioned_ontology_type(link_type_id).await?,
-                    ))
-                },
-            )
-            .await?;
-
-        Ok(())
-    }
-}
-
 #[async_trait]
 impl<C: AsClient> LinkTypeStore for PostgresStore<C> {
     async fn create_link_type(
@@ -77,34 +43,13 @@ impl<C: AsClient> LinkTypeStore for PostgresStore<C> {
     }
 
     async fn get_link_type(&self, query: &StructuralQuery) -> Result<Subgraph, QueryError> {
-        let StructuralQuery {
-            ref expression,
-            graph_resolve_depths,
-        } = *query;
-
-        let subgraphs = stream::iter(Read::<PersistedLinkType>::read(self, expression).await?)
-            .then(|link_type| async move {
-                let mut dependency_context = DependencyContext::new(graph_resolve_depths);
-
-                let link_type_id = link_type.metadata().identifier().uri().clone();
-                dependency_context
-                    .referenced_link_types
-                    .insert(&link_type_id, None, link_type);
-
-                self.get_link_type_as_dependency(&link_type_id, dependency_context.as_ref_object())
-                    .await?;
-
-                let root = GraphElementIdentifier::OntologyElementId(link_type_id);
-
-                Ok::<_, Report<QueryError>>(dependency_context.into_subgraph(vec![root]))
-            })
-            .try_collect::<Vec<_>>()
-            .await?;
-
-        let mut subgraph = Subgraph::new(graph_resolve_depths);
-        subgraph.extend(subgraphs);
-
-        Ok(subgraph)
+        resolve_subgraph(
+            self,
+            Read::<PersistedLinkType>::read(self, &query.expression).await?,
+            query,
+            &self.query_limits,
+        )
+        .await
     }
 
     async fn update_link_type(
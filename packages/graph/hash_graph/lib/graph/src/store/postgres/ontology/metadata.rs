@@ -0,0 +1,287 @@
+use async_trait::async_trait;
+use error_stack::{IntoReport, Report, Result, ResultExt};
+use tokio_postgres::GenericClient;
+use type_system::uri::{BaseUri, VersionedUri};
+
+use crate::{
+    ontology::{AccountId, PersistedOntologyMetadata},
+    store::{
+        change::{ChangeEvent, ChangeKind},
+        context::changed_ontology_resource,
+        error::{AccountDoesNotExist, InvalidReplacement, UnexpectedOwner},
+        postgres::context::{read_type_metadata, OntologyMetadataRecord},
+        AsClient, BaseUriDoesNotExist, OntologyTypeStore, PostgresStore, QueryError, UpdateError,
+    },
+};
+
+impl<C: AsClient> PostgresStore<C> {
+    /// Checks that the account referred to by `account_id` exists.
+    ///
+    /// # Errors
+    ///
+    /// - if the account does not exist.
+    async fn ensure_account_exists(&self, account_id: AccountId) -> Result<(), UpdateError> {
+        let exists: bool = self
+            .as_client()
+            .query_one(
+                r#"
+                SELECT EXISTS(
+                    SELECT 1
+                    FROM accounts
+                    WHERE account_id = $1
+                );
+                "#,
+                &[&account_id],
+            )
+            .await
+            .into_report()
+            .change_context(UpdateError)?
+            .get(0);
+
+        if exists {
+            Ok(())
+        } else {
+            Err(Report::new(AccountDoesNotExist)
+                .attach_printable(account_id)
+                .change_context(UpdateError))
+        }
+    }
+}
+
+#[async_trait]
+impl<C: AsClient> OntologyTypeStore for PostgresStore<C> {
+    async fn get_ontology_type_metadata(
+        &self,
+        uri: &VersionedUri,
+    ) -> Result<PersistedOntologyMetadata, QueryError> {
+        Ok(
+            read_type_metadata(&self.client, uri.base_uri(), Some(uri.version()))
+                .await
+                .attach_printable("could not read ontology type")
+                .attach_printable_lazy(|| uri.clone())?
+                .metadata,
+        )
+    }
+
+    async fn get_latest_ontology_type_metadata(
+        &self,
+        base_uri: &BaseUri,
+    ) -> Result<PersistedOntologyMetadata, QueryError> {
+        Ok(read_type_metadata(&self.client, base_uri, None)
+            .await
+            .attach_printable("could not read ontology type")
+            .attach_printable_lazy(|| base_uri.clone())?
+            .metadata)
+    }
+
+    async fn deprecate_ontology_type(
+        &mut self,
+        uri: &VersionedUri,
+        replaced_by: Option<VersionedUri>,
+        actor_id: AccountId,
+    ) -> Result<PersistedOntologyMetadata, UpdateError> {
+        let transaction = PostgresStore::new(
+            self.as_mut_client()
+                .transaction()
+                .await
+                .into_report()
+                .change_context(UpdateError)?,
+        );
+
+        let OntologyMetadataRecord {
+            table, version_id, ..
+        } = read_type_metadata(&transaction.client, uri.base_uri(), Some(uri.version()))
+            .await
+            .change_context(UpdateError)
+            .attach_printable_lazy(|| uri.clone())?;
+
+        if let Some(replaced_by) = &replaced_by {
+            if replaced_by == uri {
+                return Err(Report::new(InvalidReplacement)
+                    .attach_printable("an ontology type cannot be replaced by itself")
+                    .attach_printable(uri.clone())
+                    .change_context(UpdateError));
+            }
+
+            let replacement = read_type_metadata(
+                &transaction.client,
+                replaced_by.base_uri(),
+                Some(replaced_by.version()),
+            )
+            .await
+            .change_context(InvalidReplacement)
+            .attach_printable("replacement does not exist")
+            .attach_printable_lazy(|| replaced_by.clone())
+            .change_context(UpdateError)?;
+            if replacement.table != table {
+                return Err(Report::new(InvalidReplacement)
+                    .attach_printable("replacement is of a different kind")
+                    .attach_printable(replaced_by.clone())
+                    .change_context(UpdateError));
+            }
+        }
+
+        transaction.ensure_account_exists(actor_id).await?;
+
+        transaction
+            .as_client()
+            .execute(
+                &format!(
+                    r#"
+                    UPDATE {table}
+                    SET deprecated_by_id = $2, replaced_by = $3
+                    WHERE version_id = $1;
+                    "#
+                ),
+                &[
+                    &version_id,
+                    &actor_id,
+                    &replaced_by.as_ref().map(ToString::to_string),
+                ],
+            )
+            .await
+            .into_report()
+            .change_context(UpdateError)
+            .attach_printable_lazy(|| uri.clone())?;
+
+        let metadata = read_type_metadata(&transaction.client, uri.base_uri(), Some(uri.version()))
+            .await
+            .change_context(UpdateError)?
+            .metadata;
+
+        transaction
+            .notify_change(&ChangeEvent::new(
+                ChangeKind::Updated,
+                changed_ontology_resource(&table, uri.clone()),
+                metadata.identifier().owned_by_id(),
+                actor_id,
+            ))
+            .await
+            .change_context(UpdateError)?;
+
+        transaction
+            .client
+            .commit()
+            .await
+            .into_report()
+            .change_context(UpdateError)?;
+
+        Ok(metadata)
+    }
+
+    async fn transfer_ontology_type_ownership(
+        &mut self,
+        base_uri: &BaseUri,
+        current_owned_by_id: AccountId,
+        owned_by_id: AccountId,
+        actor_id: AccountId,
+    ) -> Result<PersistedOntologyMetadata, UpdateError> {
+        let transaction = PostgresStore::new(
+            self.as_mut_client()
+                .transaction()
+                .await
+                .into_report()
+                .change_context(UpdateError)?,
+        );
+
+        if !transaction
+            .contains_base_uri(base_uri)
+            .await
+            .change_context(UpdateError)?
+        {
+            return Err(Report::new(BaseUriDoesNotExist)
+                .attach_printable(base_uri.clone())
+                .change_context(UpdateError));
+        }
+
+        transaction.ensure_account_exists(owned_by_id).await?;
+        transaction.ensure_account_exists(actor_id).await?;
+
+        let OntologyMetadataRecord { table, .. } =
+            read_type_metadata(&transaction.client, base_uri, None)
+                .await
+                .change_context(UpdateError)
+                .attach_printable_lazy(|| base_uri.clone())?;
+
+        // Concurrent transfers wait for the lock until this transaction finished, so they read the
+        // owner set by this transfer afterwards.
+        transaction
+            .as_client()
+            .execute(
+                &format!(
+                    r#"
+                    SELECT 1
+                    FROM {table}
+                    WHERE version_id IN (
+                        SELECT version_id
+                        FROM type_ids
+                        WHERE base_uri = $1
+                    )
+                    FOR UPDATE;
+                    "#
+                ),
+                &[&base_uri.as_str()],
+            )
+            .await
+            .into_report()
+            .change_context(UpdateError)
+            .attach_printable_lazy(|| base_uri.clone())?;
+
+        let current_owner = read_type_metadata(&transaction.client, base_uri, None)
+            .await
+            .change_context(UpdateError)?
+            .metadata
+            .identifier()
+            .owned_by_id();
+        if current_owner != current_owned_by_id {
+            return Err(Report::new(UnexpectedOwner)
+                .attach_printable(current_owner)
+                .change_context(UpdateError));
+        }
+
+        transaction
+            .as_client()
+            .execute(
+                &format!(
+                    r#"
+                    UPDATE {table}
+                    SET owned_by_id = $2
+                    WHERE version_id IN (
+                        SELECT version_id
+                        FROM type_ids
+                        WHERE base_uri = $1
+                    );
+                    "#
+                ),
+                &[&base_uri.as_str(), &owned_by_id],
+            )
+            .await
+            .into_report()
+            .change_context(UpdateError)
+            .attach_printable_lazy(|| base_uri.clone())?;
+
+        let metadata = read_type_metadata(&transaction.client, base_uri, None)
+            .await
+            .change_context(UpdateError)?
+            .metadata;
+
+        transaction
+            .notify_change(&ChangeEvent::new(
+                ChangeKind::Updated,
+                changed_ontology_resource(&table, metadata.identifier().uri().clone()),
+                owned_by_id,
+                actor_id,
+            ))
+            .await
+            .change_context(UpdateError)?;
+
+        transaction
+            .client
+            .commit()
+            .await
+            .into_report()
+            .change_context(UpdateError)?;
+
+        Ok(metadata)
+    }
+}
@@ -1,125 +1,17 @@
-mod resolve;
-
-use std::{future::Future, pin::Pin};
-
 use async_trait::async_trait;
-use error_stack::{IntoReport, Report, Result, ResultExt};
-use futures::{stream, FutureExt, StreamExt, TryStreamExt};
+use error_stack::{IntoReport, Result, ResultExt};
 use tokio_postgres::GenericClient;
-use type_system::{uri::VersionedUri, PropertyType};
+use type_system::PropertyType;
 
 use crate::{
     ontology::{AccountId, PersistedOntologyMetadata, PersistedPropertyType},
-    shared::identifier::GraphElementIdentifier,
     store::{
-        crud::Read,
-        postgres::{
-            context::PostgresContext, DependencyContext, DependencyContextRef,
-            PersistedOntologyType,
-        },
-        AsClient, InsertionError, PostgresStore, PropertyTypeStore, QueryError, UpdateError,
+        context::resolve_subgraph, crud::Read, AsClient, InsertionError, PostgresStore,
+        PropertyTypeStore, QueryError, UpdateError,
     },
-    subgraph::{EdgeKind, GraphResolveDepths, OutwardEdge, StructuralQuery, Subgraph},
+    subgraph::{StructuralQuery, Subgraph},
 };
 
-impl<C: AsClient> PostgresStore<C> {
-    /// Internal method to read a [`PersistedPropertyType`] into two [`DependencyContext`]s.
-    ///
-    /// This is used to recursively resolve a type, so the result can be reused.
-    pub(crate) fn get_property_type_as_dependency<'a: 'b, 'b>(
-        &'a self,
-        property_type_id: &'b VersionedUri,
-        mut dependency_context: DependencyContextRef<'b>,
-    ) -> Pin<Box<dyn Future<Output = Result<(), QueryError>> + Send + 'b>> {
-        async move {
-            let unresolved_property_type = dependency_context
-                .referenced_property_types
-                .insert_with(
-                    property_type_id,
-                    Some(
-                        dependency_context
-                            .graph_resolve_depths
-                            .property_type_resolve_depth,
-                    ),
-                    || async {
-                        Ok(PersistedPropertyType::from_record(
-                            self.read_versioned_ontology_type(property_type_id).await?,
-                        ))
-                    },
-                )
-                .await?;
-
-            if let Some(property_type) = unresolved_property_type.cloned() {
-                // TODO: Use relation tables
-                //   see https://app.asana.com/0/0/1202884883200942/f
-                for data_type_ref in property_type.inner().data_type_references() {
-                    dependency_context.edges.insert(
-                        GraphElementIdentifier::OntologyElementId(property_type_id.clone()),
-                        OutwardEdge {
-                            edge_kind: EdgeKind::References,
-                            destination: GraphElementIdentifier::OntologyElementId(
-                                data_type_ref.uri().clone(),
-                            ),
-                        },
-                    );
-                    if dependency_context
-                        .graph_resolve_depths
-                        .data_type_resolve_depth
-                        > 0
-                    {
-                        self.get_data_type_as_dependency(
-                            data_type_ref.uri(),
-                            dependency_context.change_depth(GraphResolveDepths {
-                                data_type_resolve_depth: dependency_context
-                                    .graph_resolve_depths
-                                    .data_type_resolve_depth
-                                    - 1,
-                                ..dependency_context.graph_resolve_depths
-                            }),
-                        )
-                        .await?;
-                    }
-                }
-
-                // TODO: Use relation tables
-                //   see https://app.asana.com/0/0/1202884883200942/f
-                for property_type_ref in property_type.inner().property_type_references() {
-                    dependency_context.edges.insert(
-                        GraphElementIdentifier::OntologyElementId(property_type_id.clone()),
-                        OutwardEdge {
-                            edge_kind: EdgeKind::References,
-                            destination: GraphElementIdentifier::OntologyElementId(
-                                property_type_ref.uri().clone(),
-                            ),
-                        },
-                    );
-
-                    if dependency_context
-                        .graph_resolve_depths
-                        .property_type_resolve_depth
-                        > 0
-                    {
-                        self.get_property_type_as_dependency(
-                            property_type_ref.uri(),
-                            dependency_context.change_depth(GraphResolveDepths {
-                                property_type_resolve_depth: dependency_context
-                                    .graph_resolve_depths
-                                    .property_type_resolve_depth
-                                    - 1,
-                                ..dependency_context.graph_resolve_depths
-                            }),
-                        )
-                        .await?;
-                    }
-                }
-            }
-
-            Ok(())
-        }
-        .boxed()
-    }
-}
-
 #[async_trait]
 impl<C: AsClient> PropertyTypeStore for PostgresStore<C> {
     async fn create_property_type(
@@ -166,39 +58,13 @@ impl<C: AsClient> PropertyTypeStore for PostgresStore<C> {
     }
 
     async fn get_property_type(&self, query: &StructuralQuery) -> Result<Subgraph, QueryError> {
-        let StructuralQuery {
-            ref expression,
-            graph_resolve_depths,
-        } = *query;
-
-        let subgraphs = stream::iter(Read::<PersistedPropertyType>::read(self, expression).await?)
-            .then(|property_type| async move {
-                let mut dependency_context = DependencyContext::new(graph_resolve_depths);
-
-                let property_type_id = property_type.metadata().identifier().uri().clone();
-                dependency_context.referenced_property_types.insert(
-                    &property_type_id,
-                    None,
-                    property_type,
-                );
-
-                self.get_property_type_as_dependency(
-                    &property_type_id,
-                    dependency_context.as_ref_object(),
-                )
-                .await?;
-
-                let root = GraphElementIdentifier::OntologyElementId(property_type_id);
-
-                Ok::<_, Report<QueryError>>(dependency_context.into_subgraph(vec![root]))
-            })
-            .try_collect::<Vec<_>>()
-            .await?;
-
-        let mut subgraph = Subgraph::new(graph_resolve_depths);
-        subgraph.extend(subgraphs);
-
-        Ok(subgraph)
+        resolve_subgraph(
+            self,
+            Read::<PersistedPropertyType>::read(self, &query.expression).await?,
+            query,
+            &self.query_limits,
+        )
+        .await
     }
 
     async fn update_property_type(
@@ -5,9 +5,9 @@ use chrono::{DateTime, Utc};
 use error_stack::{bail, IntoReport, Report, Result, ResultExt};
 use futures::{future::BoxFuture, FutureExt};
 use serde::{Deserialize, Serialize};
-use type_system::uri::VersionedUri;
+use type_system::uri::{BaseUri, VersionedUri};
 
-use crate::knowledge::EntityId;
+use crate::{knowledge::EntityId, ontology::AccountId};
 
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -201,6 +201,30 @@ impl Expression {
         ])
     }
 
+    #[must_use]
+    pub fn for_base_uri(base_uri: &BaseUri) -> Self {
+        Self::Eq(vec![
+            Self::Path(Path {
+                segments: vec![PathSegment {
+                    identifier: "baseUri".to_owned(),
+                }],
+            }),
+            Self::Literal(Literal::String(base_uri.to_string())),
+        ])
+    }
+
+    #[must_use]
+    pub fn for_owned_by_id(account_id: AccountId) -> Self {
+        Self::Eq(vec![
+            Self::Path(Path {
+                segments: vec![PathSegment {
+                    identifier: "ownedById".to_owned(),
+                }],
+            }),
+            Self::Literal(Literal::String(account_id.to_string())),
+        ])
+    }
+
     #[must_use]
     pub fn for_latest_version() -> Self {
         Self::Eq(vec![
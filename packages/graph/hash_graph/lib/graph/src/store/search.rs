@@ -0,0 +1,261 @@
+//! Full-text search over the properties of entities.
+//!
+//! The text of an [`Entity`] consists of all string values of its properties, including nested
+//! ones. It's split into terms at every character, which is not alphanumeric, and the terms are
+//! compared case-insensitively. The [`PostgresStore`] keeps a `tsvector` of the text of every
+//! entity version up to date, so searching is backed by a full-text index.
+//!
+//! [`Entity`]: crate::knowledge::Entity
+//! [`PostgresStore`]: crate::store::PostgresStore
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use type_system::uri::VersionedUri;
+use utoipa::ToSchema;
+
+use crate::{knowledge::PersistedEntity, ontology::AccountId};
+
+/// The marker inserted before a matched term when highlighting.
+pub const HIGHLIGHT_START: &str = "<mark>";
+/// The marker inserted after a matched term when highlighting.
+pub const HIGHLIGHT_END: &str = "</mark>";
+
+const fn default_limit() -> u32 {
+    20
+}
+
+/// A full-text search over the latest versions of all entities.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct EntitySearch {
+    /// The text to search for. An entity matches, if its text contains every term of the query.
+    pub query: String,
+    /// If `true`, the last term of the query also matches terms starting with it, which is
+    /// suitable for typeahead.
+    #[serde(default)]
+    pub prefix: bool,
+    /// Only entities of one of these entity types are returned.
+    #[serde(default)]
+    #[schema(value_type = Option<Vec<String>>)]
+    pub entity_type_ids: Option<Vec<VersionedUri>>,
+    /// Only entities owned by one of these accounts are returned.
+    #[serde(default)]
+    pub owned_by_ids: Option<Vec<AccountId>>,
+    /// If `true`, the matched terms in the string properties of the hits are highlighted.
+    #[serde(default)]
+    pub highlight: bool,
+    /// The maximum number of hits returned.
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+}
+
+impl EntitySearch {
+    /// Creates a search for `query` with the default options.
+    #[must_use]
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            prefix: false,
+            entity_type_ids: None,
+            owned_by_ids: None,
+            highlight: false,
+            limit: default_limit(),
+        }
+    }
+
+    /// Returns the lowercased terms of the query.
+    #[must_use]
+    pub fn terms(&self) -> Vec<String> {
+        split_terms(&self.query).map(str::to_lowercase).collect()
+    }
+
+    /// Returns if `term` matches the term of the query at `index`.
+    ///
+    /// `term` has to be lowercased.
+    pub(crate) fn term_matches(&self, terms: &[String], index: usize, term: &str) -> bool {
+        if self.prefix && index + 1 == terms.len() {
+            term.starts_with(&terms[index])
+        } else {
+            term == terms[index]
+        }
+    }
+
+    /// Returns if `term` matches any term of the query.
+    ///
+    /// `term` has to be lowercased.
+    #[must_use]
+    pub fn matches(&self, terms: &[String], term: &str) -> bool {
+        (0..terms.len()).any(|index| self.term_matches(terms, index, term))
+    }
+
+    /// Returns the query as input for Postgres' `to_tsquery`.
+    ///
+    /// Returns `None`, if the query does not contain any term.
+    pub(crate) fn to_tsquery(&self) -> Option<String> {
+        let terms = self.terms();
+        let last = terms.len().checked_sub(1)?;
+        Some(
+            terms
+                .iter()
+                .enumerate()
+                .map(|(index, term)| {
+                    if self.prefix && index == last {
+                        format!("{term}:*")
+                    } else {
+                        term.clone()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" & "),
+        )
+    }
+
+    /// Surrounds every term in `text` matching the query with [`HIGHLIGHT_START`] and
+    /// [`HIGHLIGHT_END`].
+    ///
+    /// Returns `None`, if no term matches.
+    #[must_use]
+    pub fn highlight(&self, terms: &[String], text: &str) -> Option<String> {
+        let mut highlighted = String::with_capacity(text.len());
+        let mut term = String::new();
+        let mut matched = false;
+        for character in text.chars() {
+            if character.is_alphanumeric() {
+                term.push(character);
+            } else {
+                matched |= self.push_term(terms, &mut term, &mut highlighted);
+                highlighted.push(character);
+            }
+        }
+        matched |= self.push_term(terms, &mut term, &mut highlighted);
+
+        matched.then_some(highlighted)
+    }
+
+    /// Moves `term` to `highlighted` and returns if it matches the query.
+    fn push_term(&self, terms: &[String], term: &mut String, highlighted: &mut String) -> bool {
+        let matched = !term.is_empty() && self.matches(terms, &term.to_lowercase());
+        if matched {
+            highlighted.push_str(HIGHLIGHT_START);
+            highlighted.push_str(term);
+            highlighted.push_str(HIGHLIGHT_END);
+        } else {
+            highlighted.push_str(term);
+        }
+        term.clear();
+        matched
+    }
+}
+
+/// An entity matching an [`EntitySearch`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EntitySearchHit {
+    entity: PersistedEntity,
+    rank: f32,
+    highlights: HashMap<String, String>,
+}
+
+impl EntitySearchHit {
+    #[must_use]
+    pub const fn new(
+        entity: PersistedEntity,
+        rank: f32,
+        highlights: HashMap<String, String>,
+    ) -> Self {
+        Self {
+            entity,
+            rank,
+            highlights,
+        }
+    }
+
+    #[must_use]
+    pub const fn entity(&self) -> &PersistedEntity {
+        &self.entity
+    }
+
+    /// The relevance of the hit, higher values are more relevant.
+    ///
+    /// Ranks are only comparable between hits of the same search.
+    #[must_use]
+    pub const fn rank(&self) -> f32 {
+        self.rank
+    }
+
+    /// The highlighted values of the top-level string properties containing a match, keyed by the
+    /// base URI of the property.
+    ///
+    /// This is empty, if highlighting was not requested.
+    #[must_use]
+    pub const fn highlights(&self) -> &HashMap<String, String> {
+        &self.highlights
+    }
+}
+
+/// Splits `text` into its terms.
+pub(crate) fn split_terms(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|character: char| !character.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+}
+
+/// Collects all string values in `value` into `texts`.
+pub(crate) fn collect_texts<'v>(value: &'v serde_json::Value, texts: &mut Vec<&'v str>) {
+    match value {
+        serde_json::Value::String(text) => texts.push(text),
+        serde_json::Value::Array(values) => {
+            for value in values {
+                collect_texts(value, texts);
+            }
+        }
+        serde_json::Value::Object(object) => {
+            for value in object.values() {
+                collect_texts(value, texts);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terms() {
+        let search = EntitySearch::new("  Alice's  friend-of (Bob) ");
+        assert_eq!(search.terms(), ["alice", "s", "friend", "of", "bob"]);
+        assert_eq!(
+            search.to_tsquery().as_deref(),
+            Some("alice & s & friend & of & bob")
+        );
+
+        assert_eq!(EntitySearch::new(" !? ").to_tsquery(), None);
+    }
+
+    #[test]
+    fn prefix() {
+        let search = EntitySearch {
+            prefix: true,
+            ..EntitySearch::new("Alice Sm")
+        };
+        let terms = search.terms();
+        assert_eq!(search.to_tsquery().as_deref(), Some("alice & sm:*"));
+        assert!(search.matches(&terms, "smith"));
+        assert!(!search.matches(&terms, "alicent"));
+    }
+
+    #[test]
+    fn highlight() {
+        let search = EntitySearch::new("alice");
+        let terms = search.terms();
+        assert_eq!(
+            search
+                .highlight(&terms, "Alice and alice's friend")
+                .as_deref(),
+            Some("<mark>Alice</mark> and <mark>alice</mark>'s friend")
+        );
+        assert_eq!(search.highlight(&terms, "Bob"), None);
+    }
+}
@@ -0,0 +1,192 @@
+use std::{net::TcpListener, str::FromStr, sync::Arc};
+
+use graph::{
+    api::rest::{
+        auth::Authentication, rest_api_router, BatchEntityId, BatchOperation, BatchOperationResult,
+        BatchRequest, CreateEntityOperation, CreateLinkOperation, OperationReference,
+    },
+    knowledge::{PersistedEntity, PersistedLink},
+    ontology::{
+        domain_validator::DomainValidator,
+        external_types::{ExternalTypeResolver, FixtureFetcher},
+        AccountId,
+    },
+    store::{
+        change::ChangeFeed, crud::Read, query::Expression, AccountStore, DataTypeStore,
+        EntityTypeStore, LinkTypeStore, MemoryStore, MemoryStorePool, PropertyTypeStore,
+    },
+};
+use graph_test_data::{data_type, entity, entity_type, link_type, property_type};
+use regex::Regex;
+use type_system::{uri::VersionedUri, DataType, EntityType, LinkType, PropertyType};
+use uuid::Uuid;
+
+const FRIEND_OF_V1_ID: &str = "https://blockprotocol.org/@alice/types/link-type/friend-of/v/1";
+const PERSON_V1_ID: &str = "https://blockprotocol.org/@alice/types/entity-type/person/v/1";
+
+fn uri(uri: &str) -> VersionedUri {
+    serde_json::from_value(serde_json::Value::String(uri.to_owned())).expect("invalid URI")
+}
+
+/// Seeds `store` with an account and the types required to create people.
+async fn seed(store: &mut MemoryStore) -> AccountId {
+    let account_id = AccountId::new(Uuid::new_v4());
+    store
+        .insert_account_id(account_id)
+        .await
+        .expect("could not insert account id");
+    store
+        .create_data_type(
+            DataType::from_str(data_type::TEXT_V1).expect("could not parse data type"),
+            account_id,
+            account_id,
+        )
+        .await
+        .expect("could not create data type");
+    store
+        .create_property_type(
+            PropertyType::from_str(property_type::NAME_V1).expect("could not parse property type"),
+            account_id,
+            account_id,
+        )
+        .await
+        .expect("could not create property type");
+    store
+        .create_link_type(
+            LinkType::from_str(link_type::FRIEND_OF_V1).expect("could not parse link type"),
+            account_id,
+            account_id,
+        )
+        .await
+        .expect("could not create link type");
+    store
+        .create_entity_type(
+            EntityType::from_str(entity_type::PERSON_V1).expect("could not parse entity type"),
+            account_id,
+            account_id,
+        )
+        .await
+        .expect("could not create entity type");
+    account_id
+}
+
+/// Serves the REST API backed by `store` on a random port and returns the URL of `/batch`.
+fn serve(store: MemoryStore) -> String {
+    let domain_validator = DomainValidator::new(
+        Regex::new(
+            r"https://blockprotocol.org/@(?P<shortname>[\w-]+)/types/(?P<kind>(?:data-type)|(?:property-type)|(?:entity-type)|(?:link-type))/[\w-]+/",
+        )
+        .expect("invalid domain regex"),
+    );
+    let router = rest_api_router(
+        Arc::new(MemoryStorePool::from_store(store)),
+        domain_validator.clone(),
+        ExternalTypeResolver::new(domain_validator, FixtureFetcher::default()),
+        Authentication::default(),
+        ChangeFeed::default(),
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").expect("could not bind to a port");
+    let address = listener
+        .local_addr()
+        .expect("could not read the address of the listener");
+    tokio::spawn(
+        axum::Server::from_tcp(listener)
+            .expect("could not start the server")
+            .serve(router.into_make_service()),
+    );
+
+    format!("http://{address}/batch")
+}
+
+fn create_person(account_id: AccountId, person: &str) -> BatchOperation {
+    BatchOperation::CreateEntity(CreateEntityOperation {
+        entity: serde_json::from_str(person).expect("could not parse entity"),
+        entity_type_id: uri(PERSON_V1_ID),
+        owned_by_id: account_id,
+        entity_id: None,
+        actor_id: account_id,
+    })
+}
+
+async fn apply_batch(url: &str, operations: Vec<BatchOperation>) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(url)
+        .json(&BatchRequest { operations })
+        .send()
+        .await
+        .expect("could not send batch")
+}
+
+#[tokio::test]
+async fn references_resolve_to_earlier_operations() {
+    let mut store = MemoryStore::new();
+    let account_id = seed(&mut store).await;
+    let url = serve(store.clone());
+
+    let response = apply_batch(&url, vec![
+        create_person(account_id, entity::PERSON_A_V1),
+        create_person(account_id, entity::PERSON_B_V1),
+        BatchOperation::CreateLink(CreateLinkOperation {
+            source_entity_id: BatchEntityId::Reference(OperationReference { result_of: 0 }),
+            target_entity_id: BatchEntityId::Reference(OperationReference { result_of: 1 }),
+            link_type_id: uri(FRIEND_OF_V1_ID),
+            owned_by_id: account_id,
+            actor_id: account_id,
+            index: None,
+        }),
+    ])
+    .await;
+    assert!(response.status().is_success(), "{}", response.status());
+
+    let results: Vec<BatchOperationResult> =
+        response.json().await.expect("could not parse results");
+    let [
+        BatchOperationResult::CreateEntity(alice),
+        BatchOperationResult::CreateEntity(bob),
+        BatchOperationResult::CreateLink(_),
+    ] = results.as_slice() else {
+        panic!("unexpected batch results");
+    };
+
+    let links: Vec<PersistedLink> = Read::read(&store, &Expression::default())
+        .await
+        .expect("could not read links");
+    assert_eq!(links.len(), 1);
+    assert_eq!(
+        links[0].inner().source_entity(),
+        alice.identifier().entity_id()
+    );
+    assert_eq!(
+        links[0].inner().target_entity(),
+        bob.identifier().entity_id()
+    );
+}
+
+#[tokio::test]
+async fn failed_operation_rolls_back_the_batch() {
+    let mut store = MemoryStore::new();
+    let account_id = seed(&mut store).await;
+    let url = serve(store.clone());
+
+    // The link refers to an operation, which is not applied before it, so the batch fails after
+    // the entity was created
+    let response = apply_batch(&url, vec![
+        create_person(account_id, entity::PERSON_A_V1),
+        BatchOperation::CreateLink(CreateLinkOperation {
+            source_entity_id: BatchEntityId::Reference(OperationReference { result_of: 0 }),
+            target_entity_id: BatchEntityId::Reference(OperationReference { result_of: 1 }),
+            link_type_id: uri(FRIEND_OF_V1_ID),
+            owned_by_id: account_id,
+            actor_id: account_id,
+            index: None,
+        }),
+    ])
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::UNPROCESSABLE_ENTITY);
+
+    let entities: Vec<PersistedEntity> = Read::read(&store, &Expression::default())
+        .await
+        .expect("could not read entities");
+    assert!(entities.is_empty(), "entity of a failed batch was inserted");
+}
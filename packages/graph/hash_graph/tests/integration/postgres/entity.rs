@@ -1,4 +1,4 @@
-use graph::knowledge::Entity;
+use graph::{knowledge::Entity, store::search::EntitySearch};
 use graph_test_data::{data_type, entity, entity_type, link_type, property_type};
 use type_system::uri::{BaseUri, VersionedUri};
 
@@ -123,3 +123,214 @@ async fn update() {
 
     assert_eq!(persisted_entity.inner(), &page_v2);
 }
+
+#[tokio::test]
+async fn search() {
+    let alice: Entity = serde_json::from_str(entity::PERSON_A_V1).expect("could not parse entity");
+    let bob: Entity = serde_json::from_str(entity::PERSON_B_V1).expect("could not parse entity");
+    let charles: Entity =
+        serde_json::from_str(entity::PERSON_C_V1).expect("could not parse entity");
+
+    let mut database = DatabaseTestWrapper::new().await;
+    let mut api = database
+        .seed(
+            [data_type::TEXT_V1],
+            [property_type::NAME_V1],
+            [link_type::FRIEND_OF_V1],
+            [entity_type::PERSON_V1],
+        )
+        .await
+        .expect("could not seed database");
+
+    let person_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/entity-type/person/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+
+    let alice_id = api
+        .create_entity(alice, person_type_id.clone(), None)
+        .await
+        .expect("could not create entity")
+        .identifier()
+        .entity_id();
+    api.create_entity(bob, person_type_id.clone(), None)
+        .await
+        .expect("could not create entity");
+    let charles_id = api
+        .create_entity(charles, person_type_id.clone(), None)
+        .await
+        .expect("could not create entity")
+        .identifier()
+        .entity_id();
+
+    let hits = api
+        .search_entities(&EntitySearch {
+            highlight: true,
+            entity_type_ids: Some(vec![person_type_id.clone()]),
+            ..EntitySearch::new("ALICE")
+        })
+        .await
+        .expect("could not search entities");
+    assert_eq!(hits.len(), 1);
+    assert_eq!(
+        hits[0].entity().metadata().identifier().entity_id(),
+        alice_id
+    );
+    assert_eq!(
+        hits[0]
+            .highlights()
+            .get("https://blockprotocol.org/@alice/types/property-type/name/")
+            .map(String::as_str),
+        Some("<mark>Alice</mark>")
+    );
+
+    // Typeahead
+    let hits = api
+        .search_entities(&EntitySearch::new("char"))
+        .await
+        .expect("could not search entities");
+    assert!(hits.is_empty());
+    let hits = api
+        .search_entities(&EntitySearch {
+            prefix: true,
+            ..EntitySearch::new("char")
+        })
+        .await
+        .expect("could not search entities");
+    assert_eq!(hits.len(), 1);
+    assert_eq!(
+        hits[0].entity().metadata().identifier().entity_id(),
+        charles_id
+    );
+
+    // Only the latest version is searched
+    let alicia: Entity = serde_json::from_value(serde_json::json!({
+        "https://blockprotocol.org/@alice/types/property-type/name/": "Alicia"
+    }))
+    .expect("could not parse entity");
+    api.update_entity(alice_id, alicia, person_type_id)
+        .await
+        .expect("could not update entity");
+    let hits = api
+        .search_entities(&EntitySearch::new("alice"))
+        .await
+        .expect("could not search entities");
+    assert!(hits.is_empty());
+}
+
+#[tokio::test]
+async fn create_atomically() {
+    let alice: Entity = serde_json::from_str(entity::PERSON_A_V1).expect("could not parse entity");
+    let bob: Entity = serde_json::from_str(entity::PERSON_B_V1).expect("could not parse entity");
+    let charles: Entity =
+        serde_json::from_str(entity::PERSON_C_V1).expect("could not parse entity");
+
+    let mut database = DatabaseTestWrapper::new().await;
+    let mut api = database
+        .seed(
+            [data_type::TEXT_V1],
+            [property_type::NAME_V1],
+            [link_type::FRIEND_OF_V1],
+            [entity_type::PERSON_V1],
+        )
+        .await
+        .expect("could not seed database");
+
+    let person_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/entity-type/person/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+
+    let metadata = api
+        .create_entities_atomically([
+            (alice.clone(), person_type_id.clone(), None),
+            (bob, person_type_id.clone(), None),
+        ])
+        .await
+        .expect("could not create entities");
+    assert_eq!(metadata.len(), 2);
+    let alice_id = metadata[0].identifier().entity_id();
+
+    // Creating Alice a second time fails, so Charles is not created either
+    api.create_entities_atomically([
+        (charles, person_type_id.clone(), None),
+        (alice.clone(), person_type_id, Some(alice_id)),
+    ])
+    .await
+    .expect_err("could create an entity with an existing ID");
+
+    let hits = api
+        .search_entities(&EntitySearch::new("charles"))
+        .await
+        .expect("could not search entities");
+    assert!(hits.is_empty());
+
+    let persisted_alice = api
+        .get_entity(alice_id)
+        .await
+        .expect("could not get entity");
+    assert_eq!(persisted_alice.inner(), &alice);
+}
+
+#[tokio::test]
+async fn referencing_type() {
+    let person: Entity = serde_json::from_str(entity::PERSON_A_V1).expect("could not parse entity");
+    let organization: Entity =
+        serde_json::from_str(entity::ORGANIZATION_V1).expect("could not parse entity");
+
+    let mut database = DatabaseTestWrapper::new().await;
+    let mut api = database
+        .seed(
+            [data_type::TEXT_V1, data_type::NUMBER_V1],
+            [property_type::NAME_V1],
+            [link_type::FRIEND_OF_V1],
+            [entity_type::PERSON_V1, entity_type::ORGANIZATION_V1],
+        )
+        .await
+        .expect("could not seed database");
+
+    let person_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/entity-type/person/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+    let organization_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/entity-type/organization/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+    api.create_entity(person, person_type_id.clone(), None)
+        .await
+        .expect("could not create entity");
+    api.create_entity(organization, organization_type_id, None)
+        .await
+        .expect("could not create entity");
+
+    // Both entity types reference the text data type through the name property type
+    let text_base_uri =
+        BaseUri::new("https://blockprotocol.org/@blockprotocol/types/data-type/text/".to_owned())
+            .expect("couldn't construct Base URI");
+    let entities = api
+        .get_latest_entities_referencing_type(&text_base_uri)
+        .await
+        .expect("could not read entities");
+    assert_eq!(entities.len(), 2);
+
+    let entities = api
+        .get_latest_entities_referencing_type(person_type_id.base_uri())
+        .await
+        .expect("could not read entities");
+    assert_eq!(entities.len(), 1);
+    assert_eq!(entities[0].metadata().entity_type_id(), &person_type_id);
+
+    let number_base_uri =
+        BaseUri::new("https://blockprotocol.org/@blockprotocol/types/data-type/number/".to_owned())
+            .expect("couldn't construct Base URI");
+    let entities = api
+        .get_latest_entities_referencing_type(&number_base_uri)
+        .await
+        .expect("could not read entities");
+    assert!(entities.is_empty());
+}
@@ -1,7 +1,12 @@
 use std::str::FromStr;
 
+use graph::{
+    ontology::AccountId,
+    store::error::{AccountDoesNotExist, UnexpectedOwner},
+};
 use graph_test_data::{data_type, entity_type, link_type, property_type};
-use type_system::EntityType;
+use type_system::{EntityType, PropertyType};
+use uuid::Uuid;
 
 use crate::postgres::DatabaseTestWrapper;
 
@@ -95,3 +100,185 @@ async fn update() {
     assert_eq!(&page_et_v1, returned_page_et_v1.inner());
     assert_eq!(&page_et_v2, returned_page_et_v2.inner());
 }
+
+#[tokio::test]
+async fn deprecate() {
+    let page_et_v1 =
+        EntityType::from_str(entity_type::PAGE_V1).expect("could not parse entity type");
+    let page_et_v2 =
+        EntityType::from_str(entity_type::PAGE_V2).expect("could not parse entity type");
+
+    let mut database = DatabaseTestWrapper::new().await;
+    let mut api = database
+        .seed(
+            [data_type::TEXT_V1],
+            [property_type::TEXT_V1, property_type::NAME_V1],
+            [
+                link_type::WRITTEN_BY_V1,
+                link_type::CONTAINS_V1,
+                link_type::FRIEND_OF_V1,
+            ],
+            [entity_type::PERSON_V1, entity_type::BLOCK_V1],
+        )
+        .await
+        .expect("could not seed database");
+
+    api.create_entity_type(page_et_v1.clone())
+        .await
+        .expect("could not create entity type");
+    api.update_entity_type(page_et_v2.clone())
+        .await
+        .expect("could not update entity type");
+
+    let name_pt =
+        PropertyType::from_str(property_type::NAME_V1).expect("could not parse property type");
+    _ = api
+        .deprecate_ontology_type(page_et_v1.id(), Some(page_et_v1.id().clone()))
+        .await
+        .expect_err("a type cannot replace itself");
+    _ = api
+        .deprecate_ontology_type(page_et_v1.id(), Some(name_pt.id().clone()))
+        .await
+        .expect_err("a property type cannot replace an entity type");
+
+    let metadata = api
+        .deprecate_ontology_type(page_et_v1.id(), Some(page_et_v2.id().clone()))
+        .await
+        .expect("could not deprecate entity type");
+    assert_eq!(
+        metadata
+            .deprecation()
+            .expect("entity type is not deprecated")
+            .replaced_by(),
+        Some(page_et_v2.id())
+    );
+
+    let returned_page_et_v1 = api
+        .get_entity_type(page_et_v1.id())
+        .await
+        .expect("could not get entity type");
+    assert_eq!(returned_page_et_v1.metadata(), &metadata);
+
+    let returned_page_et_v2 = api
+        .get_entity_type(page_et_v2.id())
+        .await
+        .expect("could not get entity type");
+    assert!(returned_page_et_v2.metadata().deprecation().is_none());
+}
+
+#[tokio::test]
+async fn transfer_ownership() {
+    let page_et_v1 =
+        EntityType::from_str(entity_type::PAGE_V1).expect("could not parse entity type");
+    let page_et_v2 =
+        EntityType::from_str(entity_type::PAGE_V2).expect("could not parse entity type");
+
+    let mut database = DatabaseTestWrapper::new().await;
+    let mut api = database
+        .seed(
+            [data_type::TEXT_V1],
+            [property_type::TEXT_V1, property_type::NAME_V1],
+            [
+                link_type::WRITTEN_BY_V1,
+                link_type::CONTAINS_V1,
+                link_type::FRIEND_OF_V1,
+            ],
+            [entity_type::PERSON_V1, entity_type::BLOCK_V1],
+        )
+        .await
+        .expect("could not seed database");
+
+    api.create_entity_type(page_et_v1.clone())
+        .await
+        .expect("could not create entity type");
+    api.update_entity_type(page_et_v2.clone())
+        .await
+        .expect("could not update entity type");
+
+    let metadata = api
+        .transfer_ontology_type_ownership_to_new_account(page_et_v1.id().base_uri())
+        .await
+        .expect("could not transfer ownership");
+    assert_eq!(metadata.identifier().uri(), page_et_v2.id());
+
+    for uri in [page_et_v1.id(), page_et_v2.id()] {
+        let entity_type = api
+            .get_entity_type(uri)
+            .await
+            .expect("could not get entity type");
+        assert_eq!(
+            entity_type.metadata().identifier().owned_by_id(),
+            metadata.identifier().owned_by_id()
+        );
+    }
+}
+
+#[tokio::test]
+async fn transfer_ownership_to_unknown_account() {
+    let page_et_v1 =
+        EntityType::from_str(entity_type::PAGE_V1).expect("could not parse entity type");
+
+    let mut database = DatabaseTestWrapper::new().await;
+    let mut api = database
+        .seed(
+            [data_type::TEXT_V1],
+            [property_type::TEXT_V1, property_type::NAME_V1],
+            [
+                link_type::WRITTEN_BY_V1,
+                link_type::CONTAINS_V1,
+                link_type::FRIEND_OF_V1,
+            ],
+            [entity_type::PERSON_V1, entity_type::BLOCK_V1],
+        )
+        .await
+        .expect("could not seed database");
+
+    api.create_entity_type(page_et_v1.clone())
+        .await
+        .expect("could not create entity type");
+
+    let report = api
+        .transfer_ontology_type_ownership(
+            page_et_v1.id().base_uri(),
+            AccountId::new(Uuid::new_v4()),
+        )
+        .await
+        .expect_err("transferred ownership to an unknown account");
+    assert!(report.contains::<AccountDoesNotExist>());
+}
+
+#[tokio::test]
+async fn transfer_ownership_of_type_owned_by_another_account() {
+    let page_et_v1 =
+        EntityType::from_str(entity_type::PAGE_V1).expect("could not parse entity type");
+
+    let mut database = DatabaseTestWrapper::new().await;
+    let mut api = database
+        .seed(
+            [data_type::TEXT_V1],
+            [property_type::TEXT_V1, property_type::NAME_V1],
+            [
+                link_type::WRITTEN_BY_V1,
+                link_type::CONTAINS_V1,
+                link_type::FRIEND_OF_V1,
+            ],
+            [entity_type::PERSON_V1, entity_type::BLOCK_V1],
+        )
+        .await
+        .expect("could not seed database");
+
+    api.create_entity_type(page_et_v1.clone())
+        .await
+        .expect("could not create entity type");
+    api.transfer_ontology_type_ownership_to_new_account(page_et_v1.id().base_uri())
+        .await
+        .expect("could not transfer ownership");
+
+    // The type is not owned by the account of `api` anymore, so a transfer relying on the previous
+    // owner, e.g. a concurrent one, fails
+    let report = api
+        .transfer_ontology_type_ownership_to_new_account(page_et_v1.id().base_uri())
+        .await
+        .expect_err("transferred ownership from a previous owner");
+    assert!(report.contains::<UnexpectedOwner>());
+}
@@ -0,0 +1,157 @@
+use std::str::FromStr;
+
+use graph::ontology::{
+    domain_validator::DomainValidator,
+    external_types::{ExternalTypeResolver, FixtureFetcher},
+};
+use regex::Regex;
+use serde_json::json;
+use type_system::{DataType, EntityType, PropertyType};
+
+use crate::postgres::DatabaseTestWrapper;
+
+/// Creates a resolver, which treats all types of `@alice` as local types and serves all other types
+/// from the test data.
+fn resolver() -> ExternalTypeResolver {
+    let domain_validator = DomainValidator::new(
+        Regex::new(
+            r"https://blockprotocol.org/@(?P<shortname>alice)/types/(?P<kind>(?:data-type)|(?:property-type)|(?:entity-type)|(?:link-type))/[\w-]+/",
+        )
+        .expect("invalid regex"),
+    );
+    let fetcher = FixtureFetcher::from_directory(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../tests/test_data/src"
+    ))
+    .expect("could not read test data");
+
+    ExternalTypeResolver::new(domain_validator, fetcher)
+}
+
+#[tokio::test]
+async fn resolve_referenced_data_type() {
+    let text_dt =
+        DataType::from_str(graph_test_data::data_type::TEXT_V1).expect("could not parse data type");
+    let text_pt = PropertyType::from_str(graph_test_data::property_type::TEXT_V1)
+        .expect("could not parse property type");
+
+    let mut database = DatabaseTestWrapper::new().await;
+    let mut api = database
+        .seed([], [], [], [])
+        .await
+        .expect("could not seed database");
+
+    let resolver = resolver();
+    let resolved = api
+        .resolve_external_types(&resolver, text_pt.clone())
+        .await
+        .expect("could not resolve external types");
+    assert_eq!(resolved, [text_dt.id().clone()]);
+
+    api.create_property_type(text_pt.clone())
+        .await
+        .expect("could not create property type");
+
+    let data_type = api
+        .get_data_type(text_dt.id())
+        .await
+        .expect("could not get data type");
+    assert_eq!(data_type.inner(), &text_dt);
+
+    // Types are only persisted once
+    let resolved = api
+        .resolve_external_types(&resolver, text_pt)
+        .await
+        .expect("could not resolve external types");
+    assert!(resolved.is_empty());
+}
+
+#[tokio::test]
+async fn local_types_are_not_fetched() {
+    let page_et = EntityType::from_str(graph_test_data::entity_type::PAGE_V1)
+        .expect("could not parse entity type");
+
+    let mut database = DatabaseTestWrapper::new().await;
+    let mut api = database
+        .seed([], [], [], [])
+        .await
+        .expect("could not seed database");
+
+    // The referenced text property type of `@alice` is available as fixture, but it's a local type
+    let resolved = api
+        .resolve_external_types(&resolver(), page_et)
+        .await
+        .expect("could not resolve external types");
+    assert!(resolved.is_empty());
+}
+
+/// Creates a property type of `@alice` with the values of the data type `data_type_id`.
+fn property_type(name: &str, data_type_id: &str) -> PropertyType {
+    PropertyType::try_from(json!({
+        "kind": "propertyType",
+        "$id": format!("https://blockprotocol.org/@alice/types/property-type/{name}/v/1"),
+        "title": name,
+        "pluralTitle": name,
+        "oneOf": [{ "$ref": data_type_id }]
+    }))
+    .expect("could not parse property type")
+}
+
+#[tokio::test]
+async fn resolve_new_version_of_persisted_type() {
+    let object_v1 = DataType::from_str(graph_test_data::data_type::OBJECT_V1)
+        .expect("could not parse data type");
+    let object_v2 = DataType::from_str(graph_test_data::data_type::OBJECT_V2)
+        .expect("could not parse data type");
+
+    let mut database = DatabaseTestWrapper::new().await;
+    let mut api = database
+        .seed([], [], [], [])
+        .await
+        .expect("could not seed database");
+
+    let resolver = resolver();
+    let resolved = api
+        .resolve_external_types(
+            &resolver,
+            property_type("settings", &object_v1.id().to_string()),
+        )
+        .await
+        .expect("could not resolve external types");
+    assert_eq!(resolved, [object_v1.id().clone()]);
+
+    // The base URI of the object data type already exists, so the new version is added to it
+    let resolved = api
+        .resolve_external_types(
+            &resolver,
+            property_type("preferences", &object_v2.id().to_string()),
+        )
+        .await
+        .expect("could not resolve new version of external type");
+    assert_eq!(resolved, [object_v2.id().clone()]);
+
+    for data_type in [object_v1, object_v2] {
+        let persisted = api
+            .get_data_type(data_type.id())
+            .await
+            .expect("could not get data type");
+        assert_eq!(persisted.inner(), &data_type);
+    }
+}
+
+#[tokio::test]
+async fn types_of_disallowed_domains_are_not_resolved() {
+    let text_pt = PropertyType::from_str(graph_test_data::property_type::TEXT_V1)
+        .expect("could not parse property type");
+
+    let mut database = DatabaseTestWrapper::new().await;
+    let mut api = database
+        .seed([], [], [], [])
+        .await
+        .expect("could not seed database");
+
+    let resolver = resolver().with_allowed_domains(["example.com".to_owned()]);
+    api.resolve_external_types(&resolver, text_pt)
+        .await
+        .expect_err("could resolve type from disallowed domain");
+}
@@ -1,3 +1,7 @@
+use graph::{
+    shared::identifier::GraphElementIdentifier,
+    subgraph::{EdgeKind, GraphResolveDepths},
+};
 use graph_test_data::{data_type, entity, entity_type, link_type, property_type};
 use type_system::uri::{BaseUri, VersionedUri};
 
@@ -293,3 +297,210 @@ async fn ordered_links() {
             .collect::<Vec<_>>()[..]
     )
 }
+
+#[tokio::test]
+async fn outgoing_links_are_not_reversed() {
+    let person_a = serde_json::from_str(entity::PERSON_A_V1).expect("could not parse entity");
+    let person_b = serde_json::from_str(entity::PERSON_B_V1).expect("could not parse entity");
+
+    let mut database = DatabaseTestWrapper::new().await;
+    let mut api = database
+        .seed(
+            [data_type::TEXT_V1],
+            [property_type::NAME_V1],
+            [link_type::FRIEND_OF_V1],
+            [entity_type::PERSON_V1],
+        )
+        .await
+        .expect("could not seed database");
+
+    let person_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/entity-type/person/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+
+    let friend_link_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/link-type/friend-of/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+
+    let person_a_id = api
+        .create_entity(person_a, person_type_id.clone(), None)
+        .await
+        .expect("could not create entity")
+        .identifier()
+        .entity_id();
+
+    let person_b_id = api
+        .create_entity(person_b, person_type_id, None)
+        .await
+        .expect("could not create entity")
+        .identifier()
+        .entity_id();
+
+    api.create_link(person_a_id, person_b_id, friend_link_type_id)
+        .await
+        .expect("could not create link");
+
+    let subgraph = api
+        .get_entity_subgraph(
+            person_a_id,
+            GraphResolveDepths {
+                link_resolve_depth: 1,
+                link_target_entity_resolve_depth: 1,
+                ..GraphResolveDepths::zeroed()
+            },
+            None,
+        )
+        .await
+        .expect("could not get entity");
+
+    assert!(
+        subgraph
+            .vertices
+            .contains_key(&GraphElementIdentifier::KnowledgeGraphElementId(
+                person_b_id
+            ))
+    );
+    assert!(
+        subgraph
+            .edges
+            .clone()
+            .into_iter()
+            .flat_map(|(_, edges)| edges)
+            .all(|edge| !edge.reversed),
+        "only outgoing edges are expected"
+    );
+}
+
+#[tokio::test]
+async fn get_incoming_links() {
+    let person_a = serde_json::from_str(entity::PERSON_A_V1).expect("could not parse entity");
+    let person_b = serde_json::from_str(entity::PERSON_B_V1).expect("could not parse entity");
+    let person_c = serde_json::from_str(entity::PERSON_C_V1).expect("could not parse entity");
+
+    let mut database = DatabaseTestWrapper::new().await;
+    let mut api = database
+        .seed(
+            [data_type::TEXT_V1],
+            [property_type::NAME_V1],
+            [link_type::FRIEND_OF_V1, link_type::ACQUAINTANCE_OF_V1],
+            [entity_type::PERSON_V1],
+        )
+        .await
+        .expect("could not seed database");
+
+    let person_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/entity-type/person/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+
+    let friend_link_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/link-type/friend-of/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+
+    let acquaintance_link_type_id = VersionedUri::new(
+        BaseUri::new(
+            "https://blockprotocol.org/@alice/types/link-type/acquaintance-of/".to_owned(),
+        )
+        .expect("couldn't construct Base URI"),
+        1,
+    );
+
+    let person_a_id = api
+        .create_entity(person_a, person_type_id.clone(), None)
+        .await
+        .expect("could not create entity")
+        .identifier()
+        .entity_id();
+
+    let person_b_id = api
+        .create_entity(person_b, person_type_id.clone(), None)
+        .await
+        .expect("could not create entity")
+        .identifier()
+        .entity_id();
+
+    let person_c_id = api
+        .create_entity(person_c, person_type_id, None)
+        .await
+        .expect("could not create entity")
+        .identifier()
+        .entity_id();
+
+    api.create_link(person_a_id, person_b_id, friend_link_type_id.clone())
+        .await
+        .expect("could not create link");
+
+    api.create_link(person_c_id, person_b_id, acquaintance_link_type_id.clone())
+        .await
+        .expect("could not create link");
+
+    let graph_resolve_depths = GraphResolveDepths {
+        incoming_link_resolve_depth: 1,
+        link_source_entity_resolve_depth: 1,
+        ..GraphResolveDepths::zeroed()
+    };
+
+    let subgraph = api
+        .get_entity_subgraph(person_b_id, graph_resolve_depths, None)
+        .await
+        .expect("could not get entity");
+
+    for source_entity_id in [person_a_id, person_c_id] {
+        assert!(
+            subgraph
+                .vertices
+                .contains_key(&GraphElementIdentifier::KnowledgeGraphElementId(
+                    source_entity_id
+                ))
+        );
+    }
+
+    let incoming_link_ids: Vec<_> = subgraph
+        .edges
+        .clone()
+        .into_iter()
+        .filter(|(identifier, _)| {
+            identifier == &GraphElementIdentifier::KnowledgeGraphElementId(person_b_id)
+        })
+        .flat_map(|(_, edges)| edges)
+        .filter(|edge| edge.reversed && edge.edge_kind == EdgeKind::HasDestination)
+        .map(|edge| edge.destination)
+        .collect();
+    assert_eq!(incoming_link_ids.len(), 2);
+    assert!(
+        incoming_link_ids
+            .iter()
+            .all(|link_id| subgraph.vertices.contains_key(link_id))
+    );
+
+    let subgraph = api
+        .get_entity_subgraph(
+            person_b_id,
+            graph_resolve_depths,
+            Some(vec![friend_link_type_id]),
+        )
+        .await
+        .expect("could not get entity");
+
+    assert!(
+        subgraph
+            .vertices
+            .contains_key(&GraphElementIdentifier::KnowledgeGraphElementId(
+                person_a_id
+            ))
+    );
+    assert!(
+        !subgraph
+            .vertices
+            .contains_key(&GraphElementIdentifier::KnowledgeGraphElementId(
+                person_c_id
+            ))
+    );
+}
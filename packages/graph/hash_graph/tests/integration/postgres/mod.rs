@@ -1,59 +1,112 @@
 mod data_type;
 mod entity;
 mod entity_type;
+mod external_types;
 mod link_type;
 mod links;
+mod pool;
 mod property_type;
+mod snapshot;
 
 use std::str::FromStr;
 
-use error_stack::{Report, Result};
+use error_stack::{Report, Result, ResultExt};
+use futures::stream;
 use graph::{
     knowledge::{Entity, EntityId, Link, PersistedEntity, PersistedEntityMetadata, PersistedLink},
     ontology::{
+        external_types::{ExternalTypeError, ExternalTypeResolver},
         AccountId, PersistedDataType, PersistedEntityType, PersistedLinkType,
         PersistedOntologyMetadata, PersistedPropertyType,
     },
     shared::identifier::GraphElementIdentifier,
+    snapshot::{RestoreSummary, Snapshot, SnapshotError},
     store::{
         error::LinkRemovalError,
         query::{Expression, Literal, Path, PathSegment},
+        search::{EntitySearch, EntitySearchHit},
         AccountStore, AsClient, DataTypeStore, DatabaseConnectionInfo, DatabaseType, EntityStore,
-        EntityTypeStore, InsertionError, LinkStore, LinkTypeStore, PostgresStore,
-        PostgresStorePool, PropertyTypeStore, QueryError, StorePool, UpdateError,
+        EntityTypeStore, InsertionError, LinkStore, LinkTypeStore, MemoryStore, MemoryStorePool,
+        OntologyTypeStore, PostgresStore, PostgresStorePool, PropertyTypeStore, QueryError,
+        QueryLimits, StorePool, StoreTransaction, TransactionalStore, UpdateError,
     },
-    subgraph::{GraphResolveDepths, StructuralQuery, Vertex},
+    subgraph::{GraphResolveDepths, StructuralQuery, Subgraph, Vertex},
 };
 use tokio_postgres::{NoTls, Transaction};
-use type_system::{uri::VersionedUri, DataType, EntityType, LinkType, PropertyType};
+use type_system::{
+    uri::{BaseUri, VersionedUri},
+    DataType, EntityType, LinkType, PropertyType,
+};
 use uuid::Uuid;
 
+/// Environment variable to select the store the integration tests are run against.
+///
+/// Setting it to `memory` runs the tests against the [`MemoryStore`], otherwise a Postgres database
+/// is required.
+const TEST_STORE_ENV: &str = "HASH_GRAPH_TEST_STORE";
+
+enum TestBackend {
+    Postgres {
+        _pool: PostgresStorePool<NoTls>,
+        connection: <PostgresStorePool<NoTls> as StorePool>::Store<'static>,
+    },
+    Memory(MemoryStorePool),
+}
+
 pub struct DatabaseTestWrapper {
-    _pool: PostgresStorePool<NoTls>,
-    connection: <PostgresStorePool<NoTls> as StorePool>::Store<'static>,
+    backend: TestBackend,
+}
+
+enum TestStore<'pool> {
+    Postgres(PostgresStore<Transaction<'pool>>),
+    Memory(MemoryStore),
+}
+
+/// Calls `$body` with `$store` bound to the store backing the [`TestStore`].
+macro_rules! with_store {
+    ($test_store:expr, | $store:ident | $body:expr) => {
+        match $test_store {
+            TestStore::Postgres($store) => $body,
+            TestStore::Memory($store) => $body,
+        }
+    };
 }
 
 pub struct DatabaseApi<'pool> {
-    store: PostgresStore<Transaction<'pool>>,
+    store: TestStore<'pool>,
     account_id: AccountId,
 }
 
+/// Returns the connection info of the test database, or `None` if the tests are run against the
+/// [`MemoryStore`].
+fn postgres_connection_info() -> Option<DatabaseConnectionInfo> {
+    const USER: &str = "graph";
+    const PASSWORD: &str = "graph";
+    const HOST: &str = "localhost";
+    const PORT: u16 = 5432;
+    const DATABASE: &str = "graph";
+
+    if std::env::var(TEST_STORE_ENV).map_or(false, |store| store == "memory") {
+        return None;
+    }
+
+    Some(DatabaseConnectionInfo::new(
+        DatabaseType::Postgres,
+        USER.to_owned(),
+        PASSWORD.to_owned(),
+        HOST.to_owned(),
+        PORT,
+        DATABASE.to_owned(),
+    ))
+}
+
 impl DatabaseTestWrapper {
     pub async fn new() -> Self {
-        const USER: &str = "graph";
-        const PASSWORD: &str = "graph";
-        const HOST: &str = "localhost";
-        const PORT: u16 = 5432;
-        const DATABASE: &str = "graph";
-
-        let connection_info = DatabaseConnectionInfo::new(
-            DatabaseType::Postgres,
-            USER.to_owned(),
-            PASSWORD.to_owned(),
-            HOST.to_owned(),
-            PORT,
-            DATABASE.to_owned(),
-        );
+        let Some(connection_info) = postgres_connection_info() else {
+            return Self {
+                backend: TestBackend::Memory(MemoryStorePool::new()),
+            };
+        };
 
         let pool = PostgresStorePool::new(&connection_info, NoTls)
             .await
@@ -65,8 +118,10 @@ impl DatabaseTestWrapper {
             .expect("could not acquire a database connection");
 
         Self {
-            _pool: pool,
-            connection,
+            backend: TestBackend::Postgres {
+                _pool: pool,
+                connection,
+            },
         }
     }
 
@@ -83,60 +138,70 @@ impl DatabaseTestWrapper {
         L: IntoIterator<Item = &'static str>,
         E: IntoIterator<Item = &'static str>,
     {
-        let mut store = PostgresStore::new(
-            self.connection
-                .as_mut_client()
-                .transaction()
-                .await
-                .expect("could not start test transaction"),
-        );
+        let mut store = match &mut self.backend {
+            TestBackend::Postgres { connection, .. } => TestStore::Postgres(PostgresStore::new(
+                connection
+                    .as_mut_client()
+                    .transaction()
+                    .await
+                    .expect("could not start test transaction"),
+            )),
+            TestBackend::Memory(pool) => TestStore::Memory(
+                pool.acquire()
+                    .await
+                    .expect("could not acquire a memory store"),
+            ),
+        };
 
         let account_id = AccountId::new(Uuid::new_v4());
-        store
-            .insert_account_id(account_id)
-            .await
-            .expect("could not insert account id");
-
-        for data_type in data_types {
-            store
-                .create_data_type(
-                    DataType::from_str(data_type).expect("could not parse data type"),
-                    account_id,
-                    account_id,
-                )
-                .await?;
-        }
-
-        for property_type in property_types {
-            store
-                .create_property_type(
-                    PropertyType::from_str(property_type).expect("could not parse property type"),
-                    account_id,
-                    account_id,
-                )
-                .await?;
-        }
-
-        // Insert link types before entity types so entity types can refer to them
-        for link_type in link_types {
+        with_store!(&mut store, |store| {
             store
-                .create_link_type(
-                    LinkType::from_str(link_type).expect("could not parse link type"),
-                    account_id,
-                    account_id,
-                )
-                .await?;
-        }
-
-        for entity_type in entity_types {
-            store
-                .create_entity_type(
-                    EntityType::from_str(entity_type).expect("could not parse entity type"),
-                    account_id,
-                    account_id,
-                )
-                .await?;
-        }
+                .insert_account_id(account_id)
+                .await
+                .expect("could not insert account id");
+
+            for data_type in data_types {
+                store
+                    .create_data_type(
+                        DataType::from_str(data_type).expect("could not parse data type"),
+                        account_id,
+                        account_id,
+                    )
+                    .await?;
+            }
+
+            for property_type in property_types {
+                store
+                    .create_property_type(
+                        PropertyType::from_str(property_type)
+                            .expect("could not parse property type"),
+                        account_id,
+                        account_id,
+                    )
+                    .await?;
+            }
+
+            // Insert link types before entity types so entity types can refer to them
+            for link_type in link_types {
+                store
+                    .create_link_type(
+                        LinkType::from_str(link_type).expect("could not parse link type"),
+                        account_id,
+                        account_id,
+                    )
+                    .await?;
+            }
+
+            for entity_type in entity_types {
+                store
+                    .create_entity_type(
+                        EntityType::from_str(entity_type).expect("could not parse entity type"),
+                        account_id,
+                        account_id,
+                    )
+                    .await?;
+            }
+        });
 
         Ok(DatabaseApi { store, account_id })
     }
@@ -144,29 +209,65 @@ impl DatabaseTestWrapper {
 
 // TODO: Add get_all_* methods
 impl DatabaseApi<'_> {
+    #[must_use]
+    pub fn with_query_limits(self, query_limits: QueryLimits) -> Self {
+        let store = match self.store {
+            TestStore::Postgres(store) => {
+                TestStore::Postgres(store.with_query_limits(query_limits))
+            }
+            TestStore::Memory(store) => TestStore::Memory(store.with_query_limits(query_limits)),
+        };
+        Self {
+            store,
+            account_id: self.account_id,
+        }
+    }
+
+    /// Inserts a new account, which creates all further records.
+    pub async fn switch_to_new_account(&mut self) -> AccountId {
+        let account_id = AccountId::new(Uuid::new_v4());
+        with_store!(&mut self.store, |store| store
+            .insert_account_id(account_id)
+            .await)
+        .expect("could not insert account id");
+        self.account_id = account_id;
+        account_id
+    }
+
+    pub async fn resolve_external_types(
+        &mut self,
+        resolver: &ExternalTypeResolver,
+        ontology_type: impl Into<serde_json::Value> + Send,
+    ) -> Result<Vec<VersionedUri>, ExternalTypeError> {
+        let resolver = resolver.clone().with_account_id(self.account_id);
+        with_store!(&mut self.store, |store| resolver
+            .resolve(store, ontology_type)
+            .await)
+    }
+
     pub async fn create_data_type(
         &mut self,
         data_type: DataType,
     ) -> Result<PersistedOntologyMetadata, InsertionError> {
-        self.store
+        with_store!(&mut self.store, |store| store
             .create_data_type(data_type, self.account_id, self.account_id)
-            .await
+            .await)
     }
 
     pub async fn get_data_type(
         &mut self,
         uri: &VersionedUri,
     ) -> Result<PersistedDataType, QueryError> {
-        let vertex = self
-            .store
+        let vertex = with_store!(&self.store, |store| store
             .get_data_type(&StructuralQuery {
                 expression: Expression::for_versioned_uri(uri),
                 graph_resolve_depths: GraphResolveDepths::zeroed(),
+                link_type_filter: None,
             })
-            .await?
-            .vertices
-            .remove(&GraphElementIdentifier::OntologyElementId(uri.clone()))
-            .expect("no data type found");
+            .await)?
+        .vertices
+        .remove(&GraphElementIdentifier::OntologyElementId(uri.clone()))
+        .expect("no data type found");
 
         match vertex {
             Vertex::DataType(persisted_data_type) => Ok(persisted_data_type),
@@ -178,34 +279,34 @@ impl DatabaseApi<'_> {
         &mut self,
         data_type: DataType,
     ) -> Result<PersistedOntologyMetadata, UpdateError> {
-        self.store
+        with_store!(&mut self.store, |store| store
             .update_data_type(data_type, self.account_id)
-            .await
+            .await)
     }
 
     pub async fn create_property_type(
         &mut self,
         property_type: PropertyType,
     ) -> Result<PersistedOntologyMetadata, InsertionError> {
-        self.store
+        with_store!(&mut self.store, |store| store
             .create_property_type(property_type, self.account_id, self.account_id)
-            .await
+            .await)
     }
 
     pub async fn get_property_type(
         &mut self,
         uri: &VersionedUri,
     ) -> Result<PersistedPropertyType, QueryError> {
-        let vertex = self
-            .store
+        let vertex = with_store!(&self.store, |store| store
             .get_property_type(&StructuralQuery {
                 expression: Expression::for_versioned_uri(uri),
                 graph_resolve_depths: GraphResolveDepths::zeroed(),
+                link_type_filter: None,
             })
-            .await?
-            .vertices
-            .remove(&GraphElementIdentifier::OntologyElementId(uri.clone()))
-            .expect("no property type found");
+            .await)?
+        .vertices
+        .remove(&GraphElementIdentifier::OntologyElementId(uri.clone()))
+        .expect("no property type found");
 
         match vertex {
             Vertex::PropertyType(persisted_property_type) => Ok(persisted_property_type),
@@ -213,38 +314,52 @@ impl DatabaseApi<'_> {
         }
     }
 
+    pub async fn get_property_type_subgraph(
+        &self,
+        uri: &VersionedUri,
+        graph_resolve_depths: GraphResolveDepths,
+    ) -> Result<Subgraph, QueryError> {
+        with_store!(&self.store, |store| store
+            .get_property_type(&StructuralQuery {
+                expression: Expression::for_versioned_uri(uri),
+                graph_resolve_depths,
+                link_type_filter: None,
+            })
+            .await)
+    }
+
     pub async fn update_property_type(
         &mut self,
         property_type: PropertyType,
     ) -> Result<PersistedOntologyMetadata, UpdateError> {
-        self.store
+        with_store!(&mut self.store, |store| store
             .update_property_type(property_type, self.account_id)
-            .await
+            .await)
     }
 
     pub async fn create_entity_type(
         &mut self,
         entity_type: EntityType,
     ) -> Result<PersistedOntologyMetadata, InsertionError> {
-        self.store
+        with_store!(&mut self.store, |store| store
             .create_entity_type(entity_type, self.account_id, self.account_id)
-            .await
+            .await)
     }
 
     pub async fn get_entity_type(
         &mut self,
         uri: &VersionedUri,
     ) -> Result<PersistedEntityType, QueryError> {
-        let vertex = self
-            .store
+        let vertex = with_store!(&self.store, |store| store
             .get_entity_type(&StructuralQuery {
                 expression: Expression::for_versioned_uri(uri),
                 graph_resolve_depths: GraphResolveDepths::zeroed(),
+                link_type_filter: None,
             })
-            .await?
-            .vertices
-            .remove(&GraphElementIdentifier::OntologyElementId(uri.clone()))
-            .expect("no entity type found");
+            .await)?
+        .vertices
+        .remove(&GraphElementIdentifier::OntologyElementId(uri.clone()))
+        .expect("no entity type found");
 
         match vertex {
             Vertex::EntityType(persisted_entity_type) => Ok(persisted_entity_type),
@@ -256,34 +371,82 @@ impl DatabaseApi<'_> {
         &mut self,
         entity_type: EntityType,
     ) -> Result<PersistedOntologyMetadata, UpdateError> {
-        self.store
+        with_store!(&mut self.store, |store| store
             .update_entity_type(entity_type, self.account_id)
-            .await
+            .await)
+    }
+
+    pub async fn deprecate_ontology_type(
+        &mut self,
+        uri: &VersionedUri,
+        replaced_by: Option<VersionedUri>,
+    ) -> Result<PersistedOntologyMetadata, UpdateError> {
+        with_store!(&mut self.store, |store| store
+            .deprecate_ontology_type(uri, replaced_by, self.account_id)
+            .await)
+    }
+
+    pub async fn transfer_ontology_type_ownership(
+        &mut self,
+        base_uri: &BaseUri,
+        owned_by_id: AccountId,
+    ) -> Result<PersistedOntologyMetadata, UpdateError> {
+        with_store!(&mut self.store, |store| store
+            .transfer_ontology_type_ownership(
+                base_uri,
+                self.account_id,
+                owned_by_id,
+                self.account_id
+            )
+            .await)
+    }
+
+    /// Creates a new account and transfers the ownership of the type identified by `base_uri` to
+    /// it.
+    pub async fn transfer_ontology_type_ownership_to_new_account(
+        &mut self,
+        base_uri: &BaseUri,
+    ) -> Result<PersistedOntologyMetadata, UpdateError> {
+        let owned_by_id = AccountId::new(Uuid::new_v4());
+        with_store!(&mut self.store, |store| {
+            store
+                .insert_account_id(owned_by_id)
+                .await
+                .change_context(UpdateError)?;
+            store
+                .transfer_ontology_type_ownership(
+                    base_uri,
+                    self.account_id,
+                    owned_by_id,
+                    self.account_id,
+                )
+                .await
+        })
     }
 
     pub async fn create_link_type(
         &mut self,
         link_type: LinkType,
     ) -> Result<PersistedOntologyMetadata, InsertionError> {
-        self.store
+        with_store!(&mut self.store, |store| store
             .create_link_type(link_type, self.account_id, self.account_id)
-            .await
+            .await)
     }
 
     pub async fn get_link_type(
         &mut self,
         uri: &VersionedUri,
     ) -> Result<PersistedLinkType, QueryError> {
-        let vertex = self
-            .store
+        let vertex = with_store!(&self.store, |store| store
             .get_link_type(&StructuralQuery {
                 expression: Expression::for_versioned_uri(uri),
                 graph_resolve_depths: GraphResolveDepths::zeroed(),
+                link_type_filter: None,
             })
-            .await?
-            .vertices
-            .remove(&GraphElementIdentifier::OntologyElementId(uri.clone()))
-            .expect("no link type found");
+            .await)?
+        .vertices
+        .remove(&GraphElementIdentifier::OntologyElementId(uri.clone()))
+        .expect("no link type found");
 
         match vertex {
             Vertex::LinkType(persisted_link_type) => Ok(persisted_link_type),
@@ -295,9 +458,9 @@ impl DatabaseApi<'_> {
         &mut self,
         link_type: LinkType,
     ) -> Result<PersistedOntologyMetadata, UpdateError> {
-        self.store
+        with_store!(&mut self.store, |store| store
             .update_link_type(link_type, self.account_id)
-            .await
+            .await)
     }
 
     pub async fn create_entity(
@@ -306,7 +469,7 @@ impl DatabaseApi<'_> {
         entity_type_id: VersionedUri,
         entity_id: Option<EntityId>,
     ) -> Result<PersistedEntityMetadata, InsertionError> {
-        self.store
+        with_store!(&mut self.store, |store| store
             .create_entity(
                 entity,
                 entity_type_id,
@@ -314,20 +477,20 @@ impl DatabaseApi<'_> {
                 entity_id,
                 self.account_id,
             )
-            .await
+            .await)
     }
 
     pub async fn get_entity(&mut self, entity_id: EntityId) -> Result<PersistedEntity, QueryError> {
-        let vertex = self
-            .store
+        let vertex = with_store!(&self.store, |store| store
             .get_entity(&StructuralQuery {
                 expression: Expression::for_latest_entity_id(entity_id),
                 graph_resolve_depths: GraphResolveDepths::zeroed(),
+                link_type_filter: None,
             })
-            .await?
-            .vertices
-            .remove(&GraphElementIdentifier::KnowledgeGraphElementId(entity_id))
-            .expect("no entity found");
+            .await)?
+        .vertices
+        .remove(&GraphElementIdentifier::KnowledgeGraphElementId(entity_id))
+        .expect("no entity found");
 
         match vertex {
             Vertex::Entity(persisted_entity) => Ok(persisted_entity),
@@ -335,15 +498,75 @@ impl DatabaseApi<'_> {
         }
     }
 
+    pub async fn get_entity_subgraph(
+        &self,
+        entity_id: EntityId,
+        graph_resolve_depths: GraphResolveDepths,
+        link_type_filter: Option<Vec<VersionedUri>>,
+    ) -> Result<Subgraph, QueryError> {
+        with_store!(&self.store, |store| store
+            .get_entity(&StructuralQuery {
+                expression: Expression::for_latest_entity_id(entity_id),
+                graph_resolve_depths,
+                link_type_filter,
+            })
+            .await)
+    }
+
+    pub async fn search_entities(
+        &self,
+        search: &EntitySearch,
+    ) -> Result<Vec<EntitySearchHit>, QueryError> {
+        with_store!(&self.store, |store| store.search_entities(search).await)
+    }
+
+    pub async fn get_latest_entities_referencing_type(
+        &self,
+        base_uri: &BaseUri,
+    ) -> Result<Vec<PersistedEntity>, QueryError> {
+        with_store!(&self.store, |store| store
+            .get_latest_entities_referencing_type(base_uri)
+            .await)
+    }
+
     pub async fn update_entity(
         &mut self,
         entity_id: EntityId,
         entity: Entity,
         entity_type_id: VersionedUri,
     ) -> Result<PersistedEntityMetadata, UpdateError> {
-        self.store
+        with_store!(&mut self.store, |store| store
             .update_entity(entity_id, entity, entity_type_id, self.account_id)
-            .await
+            .await)
+    }
+
+    /// Creates the entities in a single transaction, so either all or none of them are created.
+    pub async fn create_entities_atomically(
+        &mut self,
+        entities: impl IntoIterator<Item = (Entity, VersionedUri, Option<EntityId>)>,
+    ) -> Result<Vec<PersistedEntityMetadata>, InsertionError> {
+        with_store!(&mut self.store, |store| {
+            let mut transaction = store.transaction().await.change_context(InsertionError)?;
+
+            let mut metadata = Vec::new();
+            for (entity, entity_type_id, entity_id) in entities {
+                metadata.push(
+                    transaction
+                        .store()
+                        .create_entity(
+                            entity,
+                            entity_type_id,
+                            self.account_id,
+                            entity_id,
+                            self.account_id,
+                        )
+                        .await?,
+                );
+            }
+
+            transaction.commit().await.change_context(InsertionError)?;
+            Ok(metadata)
+        })
     }
 
     async fn create_link(
@@ -353,9 +576,9 @@ impl DatabaseApi<'_> {
         link_type_id: VersionedUri,
     ) -> Result<(), InsertionError> {
         let link = Link::new(source_entity_id, target_entity_id, link_type_id, None);
-        self.store
+        with_store!(&mut self.store, |store| store
             .create_link(&link, self.account_id, self.account_id)
-            .await
+            .await)
     }
 
     async fn create_ordered_link(
@@ -371,9 +594,9 @@ impl DatabaseApi<'_> {
             link_type_id,
             Some(index),
         );
-        self.store
+        with_store!(&mut self.store, |store| store
             .create_link(&link, self.account_id, self.account_id)
-            .await
+            .await)
     }
 
     pub async fn get_link_target(
@@ -381,8 +604,7 @@ impl DatabaseApi<'_> {
         source_entity_id: EntityId,
         link_type_id: VersionedUri,
     ) -> Result<PersistedLink, QueryError> {
-        Ok(self
-            .store
+        Ok(with_store!(&self.store, |store| store
             .get_links(&StructuralQuery {
                 expression: Expression::All(vec![
                     Expression::for_link_by_source_entity_id(source_entity_id),
@@ -414,28 +636,61 @@ impl DatabaseApi<'_> {
                     ]),
                 ]),
                 graph_resolve_depths: GraphResolveDepths::zeroed(),
+                link_type_filter: None,
             })
-            .await?
-            .pop()
-            .ok_or_else(|| Report::new(QueryError).attach_printable("no link found"))?
-            .link
-            .clone())
+            .await)?
+        .pop()
+        .ok_or_else(|| Report::new(QueryError).attach_printable("no link found"))?
+        .link
+        .clone())
     }
 
     pub async fn get_entity_links(
         &self,
         source_entity_id: EntityId,
     ) -> Result<Vec<PersistedLink>, QueryError> {
-        Ok(self
-            .store
+        Ok(with_store!(&self.store, |store| store
             .get_links(&StructuralQuery {
                 expression: Expression::for_link_by_source_entity_id(source_entity_id),
                 graph_resolve_depths: GraphResolveDepths::zeroed(),
+                link_type_filter: None,
             })
-            .await?
-            .into_iter()
-            .map(|link_rooted_subgraph| link_rooted_subgraph.link)
-            .collect())
+            .await)?
+        .into_iter()
+        .map(|link_rooted_subgraph| link_rooted_subgraph.link)
+        .collect())
+    }
+
+    pub async fn export_snapshot(&self) -> Result<Snapshot, SnapshotError> {
+        with_store!(&self.store, |store| Snapshot::export(
+            store,
+            Some(self.account_id)
+        )
+        .await)
+    }
+
+    pub async fn restore_snapshot(
+        &mut self,
+        snapshot: &Snapshot,
+    ) -> Result<RestoreSummary, SnapshotError> {
+        with_store!(&mut self.store, |store| snapshot.restore(store).await)
+    }
+
+    pub async fn restore_snapshot_lines(
+        &mut self,
+        ndjson: &str,
+    ) -> Result<RestoreSummary, SnapshotError> {
+        let lines = stream::iter(
+            ndjson
+                .lines()
+                .map(|line| Ok(line.to_owned()))
+                .collect::<Vec<_>>(),
+        );
+        let (_, entries) = Snapshot::read_lines(lines).await?;
+        with_store!(&mut self.store, |store| Snapshot::restore_entries(
+            store, entries
+        )
+        .await)
     }
 
     async fn remove_link(
@@ -445,7 +700,9 @@ impl DatabaseApi<'_> {
         link_type_id: VersionedUri,
     ) -> Result<(), LinkRemovalError> {
         let link = Link::new(source_entity_id, target_entity_id, link_type_id, None);
-        self.store.remove_link(&link, self.account_id).await
+        with_store!(&mut self.store, |store| store
+            .remove_link(&link, self.account_id)
+            .await)
     }
 }
 
@@ -0,0 +1,40 @@
+use std::time::{Duration, Instant};
+
+use graph::store::{AsClient, DatabasePoolConfig, PostgresStorePool, QueryLimits, StorePool};
+use tokio_postgres::{error::SqlState, NoTls};
+
+use crate::postgres::postgres_connection_info;
+
+#[tokio::test]
+async fn statement_is_cancelled_after_query_timeout() {
+    let Some(connection_info) = postgres_connection_info() else {
+        // The timeout is enforced by Postgres
+        return;
+    };
+
+    let pool = PostgresStorePool::with_config(
+        &connection_info,
+        &DatabasePoolConfig::default(),
+        QueryLimits {
+            timeout: Some(1),
+            ..QueryLimits::default()
+        },
+        NoTls,
+    )
+    .await
+    .expect("could not connect to database");
+    let store = pool
+        .acquire()
+        .await
+        .expect("could not acquire a database connection");
+
+    let start = Instant::now();
+    let error = store
+        .as_client()
+        .query("SELECT pg_sleep(10);", &[])
+        .await
+        .expect_err("statement was not cancelled");
+
+    assert_eq!(error.code(), Some(&SqlState::QUERY_CANCELED));
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
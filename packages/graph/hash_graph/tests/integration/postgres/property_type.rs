@@ -1,7 +1,15 @@
 use std::str::FromStr;
 
-use graph_test_data::{data_type, property_type};
-use type_system::PropertyType;
+use graph::{
+    shared::identifier::GraphElementIdentifier,
+    store::QueryLimits,
+    subgraph::{EdgeKind, GraphResolveDepths, OutwardEdge},
+};
+use graph_test_data::{data_type, entity_type, link_type, property_type};
+use type_system::{
+    uri::{BaseUri, VersionedUri},
+    PropertyType,
+};
 
 use crate::postgres::DatabaseTestWrapper;
 
@@ -82,3 +90,134 @@ async fn update() {
     assert_eq!(&user_id_pt_v1, returned_user_id_pt_v1.inner());
     assert_eq!(&user_id_pt_v2, returned_user_id_pt_v2.inner());
 }
+
+#[tokio::test]
+async fn get_referencing_types() {
+    let mut database = DatabaseTestWrapper::new().await;
+    let api = database
+        .seed(
+            [data_type::TEXT_V1],
+            [property_type::NAME_V1],
+            [link_type::FRIEND_OF_V1],
+            [entity_type::PERSON_V1],
+        )
+        .await
+        .expect("could not seed database");
+
+    let name_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/property-type/name/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+
+    let person_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/entity-type/person/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+
+    let subgraph = api
+        .get_property_type_subgraph(&name_type_id, GraphResolveDepths {
+            referencing_type_resolve_depth: 1,
+            ..GraphResolveDepths::zeroed()
+        })
+        .await
+        .expect("could not get property type");
+
+    assert!(
+        subgraph
+            .vertices
+            .contains_key(&GraphElementIdentifier::OntologyElementId(
+                person_type_id.clone()
+            ))
+    );
+    assert!(subgraph.edges.into_iter().any(|(identifier, edges)| {
+        identifier == GraphElementIdentifier::OntologyElementId(name_type_id.clone())
+            && edges.contains(&OutwardEdge {
+                edge_kind: EdgeKind::References,
+                reversed: true,
+                destination: GraphElementIdentifier::OntologyElementId(person_type_id.clone()),
+            })
+    }));
+}
+
+#[tokio::test]
+async fn limit_resolve_depth() {
+    let mut database = DatabaseTestWrapper::new().await;
+    let api = database
+        .seed([data_type::TEXT_V1], [property_type::NAME_V1], [], [])
+        .await
+        .expect("could not seed database")
+        .with_query_limits(QueryLimits {
+            max_ontology_resolve_depth: 0,
+            ..QueryLimits::unlimited()
+        });
+
+    let name_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/property-type/name/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+
+    let subgraph = api
+        .get_property_type_subgraph(&name_type_id, GraphResolveDepths {
+            data_type_resolve_depth: 1,
+            ..GraphResolveDepths::zeroed()
+        })
+        .await
+        .expect("could not get property type");
+
+    assert!(subgraph.truncated);
+    assert_eq!(subgraph.depths, GraphResolveDepths::zeroed());
+    assert_eq!(subgraph.vertices.len(), 1);
+}
+
+#[tokio::test]
+async fn limit_vertices() {
+    let mut database = DatabaseTestWrapper::new().await;
+    let api = database
+        .seed([data_type::TEXT_V1], [property_type::NAME_V1], [], [])
+        .await
+        .expect("could not seed database")
+        .with_query_limits(QueryLimits {
+            max_vertices: Some(1),
+            ..QueryLimits::unlimited()
+        });
+
+    let name_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/property-type/name/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+    let graph_resolve_depths = GraphResolveDepths {
+        data_type_resolve_depth: 1,
+        ..GraphResolveDepths::zeroed()
+    };
+
+    let subgraph = api
+        .get_property_type_subgraph(&name_type_id, graph_resolve_depths)
+        .await
+        .expect("could not get property type");
+
+    assert!(subgraph.truncated);
+    assert_eq!(subgraph.vertices.len(), 1);
+    assert!(
+        subgraph
+            .vertices
+            .contains_key(&GraphElementIdentifier::OntologyElementId(
+                name_type_id.clone()
+            ))
+    );
+
+    let api = api.with_query_limits(QueryLimits {
+        max_vertices: Some(2),
+        ..QueryLimits::unlimited()
+    });
+    let subgraph = api
+        .get_property_type_subgraph(&name_type_id, graph_resolve_depths)
+        .await
+        .expect("could not get property type");
+
+    assert!(!subgraph.truncated);
+    assert_eq!(subgraph.vertices.len(), 2);
+}
@@ -0,0 +1,396 @@
+use graph::{
+    snapshot::{InvalidSnapshot, Snapshot},
+    subgraph::GraphResolveDepths,
+};
+use graph_test_data::{data_type, entity, entity_type, link_type, property_type};
+use type_system::uri::{BaseUri, VersionedUri};
+
+use crate::postgres::DatabaseTestWrapper;
+
+#[tokio::test]
+async fn export_and_restore() {
+    let person_a = serde_json::from_str(entity::PERSON_A_V1).expect("could not parse entity");
+    let person_b = serde_json::from_str(entity::PERSON_B_V1).expect("could not parse entity");
+
+    let person_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/entity-type/person/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+    let link_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/link-type/friend-of/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+
+    let mut source_database = DatabaseTestWrapper::new().await;
+    let mut source_api = source_database
+        .seed(
+            [data_type::TEXT_V1],
+            [property_type::NAME_V1],
+            [link_type::FRIEND_OF_V1],
+            [entity_type::PERSON_V1],
+        )
+        .await
+        .expect("could not seed database");
+
+    let person_a_metadata = source_api
+        .create_entity(person_a, person_type_id.clone(), None)
+        .await
+        .expect("could not create entity");
+    let person_b_metadata = source_api
+        .create_entity(person_b, person_type_id, None)
+        .await
+        .expect("could not create entity");
+    source_api
+        .create_link(
+            person_a_metadata.identifier().entity_id(),
+            person_b_metadata.identifier().entity_id(),
+            link_type_id.clone(),
+        )
+        .await
+        .expect("could not create link");
+
+    let snapshot = source_api
+        .export_snapshot()
+        .await
+        .expect("could not export snapshot");
+
+    let mut ndjson = Vec::new();
+    snapshot
+        .write(&mut ndjson)
+        .expect("could not write snapshot");
+    let snapshot = Snapshot::read(ndjson.as_slice()).expect("could not read snapshot");
+
+    let mut target_database = DatabaseTestWrapper::new().await;
+    let mut target_api = target_database
+        .seed([], [], [], [])
+        .await
+        .expect("could not seed database");
+
+    let summary = target_api
+        .restore_snapshot(&snapshot)
+        .await
+        .expect("could not restore snapshot");
+    assert_eq!(summary.ontology_types(), 4);
+    assert_eq!(summary.entities(), 2);
+    assert_eq!(summary.links(), 1);
+    assert_eq!(summary.skipped(), 0);
+
+    let source_entity = source_api
+        .get_entity(person_a_metadata.identifier().entity_id())
+        .await
+        .expect("could not get entity");
+    let restored_entity = target_api
+        .get_entity(person_a_metadata.identifier().entity_id())
+        .await
+        .expect("could not get entity");
+    assert_eq!(restored_entity, source_entity);
+
+    let link_target = target_api
+        .get_link_target(person_a_metadata.identifier().entity_id(), link_type_id)
+        .await
+        .expect("could not fetch link");
+    assert_eq!(
+        link_target.inner().target_entity(),
+        person_b_metadata.identifier().entity_id()
+    );
+
+    // Restoring the same snapshot again does not change anything
+    let summary = target_api
+        .restore_snapshot(&snapshot)
+        .await
+        .expect("could not restore snapshot");
+    assert_eq!(summary.accounts(), 0);
+    assert_eq!(summary.ontology_types(), 0);
+    assert_eq!(summary.entities(), 0);
+    assert_eq!(summary.links(), 0);
+    assert_eq!(summary.skipped(), 7);
+}
+
+#[tokio::test]
+async fn export_includes_referenced_types_of_other_accounts() {
+    let person_a = serde_json::from_str(entity::PERSON_A_V1).expect("could not parse entity");
+    let person_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/entity-type/person/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+
+    let mut source_database = DatabaseTestWrapper::new().await;
+    let mut source_api = source_database
+        .seed(
+            [data_type::TEXT_V1],
+            [property_type::NAME_V1],
+            [link_type::FRIEND_OF_V1],
+            [entity_type::PERSON_V1],
+        )
+        .await
+        .expect("could not seed database");
+
+    // The entity is owned by a different account than the types it depends on
+    source_api.switch_to_new_account().await;
+    let person_a_metadata = source_api
+        .create_entity(person_a, person_type_id, None)
+        .await
+        .expect("could not create entity");
+
+    let snapshot = source_api
+        .export_snapshot()
+        .await
+        .expect("could not export snapshot");
+
+    let mut target_database = DatabaseTestWrapper::new().await;
+    let mut target_api = target_database
+        .seed([], [], [], [])
+        .await
+        .expect("could not seed database");
+
+    let summary = target_api
+        .restore_snapshot(&snapshot)
+        .await
+        .expect("could not restore snapshot");
+    assert_eq!(summary.ontology_types(), 4);
+    assert_eq!(summary.entities(), 1);
+
+    let restored_entity = target_api
+        .get_entity(person_a_metadata.identifier().entity_id())
+        .await
+        .expect("could not get entity");
+    assert_eq!(
+        restored_entity.metadata().identifier().entity_id(),
+        person_a_metadata.identifier().entity_id()
+    );
+}
+
+#[tokio::test]
+async fn failed_restore_is_rolled_back() {
+    let person_a = serde_json::from_str(entity::PERSON_A_V1).expect("could not parse entity");
+    let person_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/entity-type/person/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+
+    let mut source_database = DatabaseTestWrapper::new().await;
+    let mut source_api = source_database
+        .seed(
+            [data_type::TEXT_V1],
+            [property_type::NAME_V1],
+            [link_type::FRIEND_OF_V1],
+            [entity_type::PERSON_V1],
+        )
+        .await
+        .expect("could not seed database");
+    let person_a_metadata = source_api
+        .create_entity(person_a, person_type_id.clone(), None)
+        .await
+        .expect("could not create entity");
+
+    let snapshot = source_api
+        .export_snapshot()
+        .await
+        .expect("could not export snapshot");
+    let mut ndjson = Vec::new();
+    snapshot
+        .write(&mut ndjson)
+        .expect("could not write snapshot");
+    let ndjson = String::from_utf8(ndjson).expect("snapshot is not valid UTF-8");
+
+    // Append an entity with a missing type, so the restore fails after the types and the first
+    // entity were inserted
+    let entity_line = ndjson
+        .lines()
+        .find(|line| line.contains(r#""type":"entity""#))
+        .expect("snapshot does not contain an entity");
+    let invalid_entity_line = entity_line.replace(
+        &person_type_id.to_string(),
+        "https://blockprotocol.org/@alice/types/entity-type/missing/v/1",
+    );
+    let ndjson = format!("{ndjson}{invalid_entity_line}\n");
+
+    let mut target_database = DatabaseTestWrapper::new().await;
+    let mut target_api = target_database
+        .seed([], [], [], [])
+        .await
+        .expect("could not seed database");
+
+    let report = target_api
+        .restore_snapshot_lines(&ndjson)
+        .await
+        .expect_err("restored entity with a missing type");
+    assert!(report.contains::<InvalidSnapshot>());
+
+    let subgraph = target_api
+        .get_entity_subgraph(
+            person_a_metadata.identifier().entity_id(),
+            GraphResolveDepths::zeroed(),
+            None,
+        )
+        .await
+        .expect("could not query entity");
+    assert!(
+        subgraph.vertices.is_empty(),
+        "entity of a failed restore was inserted"
+    );
+}
+
+#[tokio::test]
+async fn restore_line_by_line() {
+    let person_a = serde_json::from_str(entity::PERSON_A_V1).expect("could not parse entity");
+    let person_type_id = VersionedUri::new(
+        BaseUri::new("https://blockprotocol.org/@alice/types/entity-type/person/".to_owned())
+            .expect("couldn't construct Base URI"),
+        1,
+    );
+
+    let mut source_database = DatabaseTestWrapper::new().await;
+    let mut source_api = source_database
+        .seed(
+            [data_type::TEXT_V1],
+            [property_type::NAME_V1],
+            [link_type::FRIEND_OF_V1],
+            [entity_type::PERSON_V1],
+        )
+        .await
+        .expect("could not seed database");
+    let person_a_metadata = source_api
+        .create_entity(person_a, person_type_id, None)
+        .await
+        .expect("could not create entity");
+
+    let snapshot = source_api
+        .export_snapshot()
+        .await
+        .expect("could not export snapshot");
+    let mut ndjson = Vec::new();
+    snapshot
+        .write(&mut ndjson)
+        .expect("could not write snapshot");
+    let ndjson = String::from_utf8(ndjson).expect("snapshot is not valid UTF-8");
+
+    let mut target_database = DatabaseTestWrapper::new().await;
+    let mut target_api = target_database
+        .seed([], [], [], [])
+        .await
+        .expect("could not seed database");
+
+    let summary = target_api
+        .restore_snapshot_lines(&ndjson)
+        .await
+        .expect("could not restore snapshot");
+    assert_eq!(summary.ontology_types(), 4);
+    assert_eq!(summary.entities(), 1);
+    assert_eq!(summary.skipped(), 0);
+
+    let source_entity = source_api
+        .get_entity(person_a_metadata.identifier().entity_id())
+        .await
+        .expect("could not get entity");
+    let restored_entity = target_api
+        .get_entity(person_a_metadata.identifier().entity_id())
+        .await
+        .expect("could not get entity");
+    assert_eq!(restored_entity, source_entity);
+}
+
+#[tokio::test]
+async fn entities_preceding_their_types_are_rejected() {
+    let mut source_database = DatabaseTestWrapper::new().await;
+    let mut source_api = source_database
+        .seed([data_type::TEXT_V1], [property_type::NAME_V1], [], [
+            entity_type::ORGANIZATION_V1,
+        ])
+        .await
+        .expect("could not seed database");
+    source_api
+        .create_entity(
+            serde_json::from_str(entity::ORGANIZATION_V1).expect("could not parse entity"),
+            VersionedUri::new(
+                BaseUri::new(
+                    "https://blockprotocol.org/@alice/types/entity-type/organization/".to_owned(),
+                )
+                .expect("couldn't construct Base URI"),
+                1,
+            ),
+            None,
+        )
+        .await
+        .expect("could not create entity");
+
+    let snapshot = source_api
+        .export_snapshot()
+        .await
+        .expect("could not export snapshot");
+    let mut ndjson = Vec::new();
+    snapshot
+        .write(&mut ndjson)
+        .expect("could not write snapshot");
+    let ndjson = String::from_utf8(ndjson).expect("snapshot is not valid UTF-8");
+
+    // Move the entity in front of the ontology types
+    let (entities, mut lines): (Vec<_>, Vec<_>) = ndjson
+        .lines()
+        .partition(|line| line.contains(r#""type":"entity""#));
+    lines.splice(1..1, entities);
+
+    let mut target_database = DatabaseTestWrapper::new().await;
+    let mut target_api = target_database
+        .seed([], [], [], [])
+        .await
+        .expect("could not seed database");
+
+    let report = target_api
+        .restore_snapshot_lines(&lines.join("\n"))
+        .await
+        .expect_err("restored entity before its type");
+    assert!(report.contains::<InvalidSnapshot>());
+}
+
+#[tokio::test]
+async fn missing_references_are_rejected() {
+    let mut source_database = DatabaseTestWrapper::new().await;
+    let source_api = source_database
+        .seed([data_type::TEXT_V1], [property_type::NAME_V1], [], [
+            entity_type::ORGANIZATION_V1,
+        ])
+        .await
+        .expect("could not seed database");
+
+    let snapshot = source_api
+        .export_snapshot()
+        .await
+        .expect("could not export snapshot");
+
+    // Drop the data type referenced by the property type
+    let mut ndjson = Vec::new();
+    snapshot
+        .write(&mut ndjson)
+        .expect("could not write snapshot");
+    let ndjson = String::from_utf8(ndjson).expect("snapshot is not valid UTF-8");
+    let ndjson = ndjson
+        .lines()
+        .filter(|line| !line.contains(r#""type":"dataType""#))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let snapshot = Snapshot::read(ndjson.as_bytes()).expect("could not read snapshot");
+
+    let mut target_database = DatabaseTestWrapper::new().await;
+    let mut target_api = target_database
+        .seed([], [], [], [])
+        .await
+        .expect("could not seed database");
+
+    let report = target_api
+        .restore_snapshot(&snapshot)
+        .await
+        .expect_err("restored snapshot with missing references");
+    assert!(report.contains::<InvalidSnapshot>());
+}
+
+#[test]
+fn unsupported_version_is_rejected() {
+    let report = Snapshot::read(r#"{"type":"header","version":0,"accountId":null}"#.as_bytes())
+        .expect_err("read snapshot with unsupported version");
+    assert!(report.contains::<InvalidSnapshot>());
+}
@@ -0,0 +1,307 @@
+use std::{borrow::Cow, vec};
+
+use deer::{Deserialize, Error as _, Visitor};
+use error_stack::{Report, Result, ResultExt};
+use serde_json::Value;
+
+use crate::{
+    error::{Error, Path, Position, Segment},
+    node::{Kind, Node},
+    number, parse,
+};
+
+/// Deserializes the value `node` located at `path`.
+///
+/// If the deserialization fails and the error does not originate from a nested value, the [`Path`]
+/// and the [`Position`] are attached to the error. `position` is used if `node` is missing, e.g.
+/// the position of the object, which does not contain a requested key.
+pub(crate) fn deserialize_node<'de, T: Deserialize<'de>>(
+    node: Option<Node<'de>>,
+    path: Path,
+    position: Option<Position>,
+) -> Result<T, Error> {
+    let position = node.as_ref().map_or(position, |node| node.position);
+
+    T::deserialize(Deserializer {
+        node,
+        path: path.clone(),
+    })
+    .map_err(|report| {
+        if report.contains::<Path>() {
+            return report;
+        }
+
+        let report = report.attach_printable(path);
+        match position {
+            Some(position) => report.attach_printable(position),
+            None => report,
+        }
+    })
+}
+
+/// A [`deer::Deserializer`] for JSON, either parsed from a string or from a [`Value`].
+///
+/// Prefer [`from_str`] and [`from_value`], which also attach the location to errors of the root
+/// value.
+///
+/// [`from_str`]: crate::from_str
+/// [`from_value`]: crate::from_value
+pub struct Deserializer<'de> {
+    node: Option<Node<'de>>,
+    path: Path,
+}
+
+impl<'de> Deserializer<'de> {
+    /// Parses `input` and creates a `Deserializer` for the contained value.
+    ///
+    /// # Errors
+    ///
+    /// `input` is not valid JSON
+    pub fn from_json(input: &'de str) -> Result<Self, Error> {
+        parse::parse(input).map(|node| Self {
+            node: Some(node),
+            path: Path::root(),
+        })
+    }
+
+    /// Creates a `Deserializer` for `value`.
+    ///
+    /// Errors contain the [`Path`] of the value, but no [`Position`], as [`Value`] does not record
+    /// where it has been parsed from.
+    #[must_use]
+    pub fn from_value(value: &'de Value) -> Self {
+        Self {
+            node: Some(Node::from(value)),
+            path: Path::root(),
+        }
+    }
+}
+
+impl<'de> deer::Deserializer<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let result = match self.node {
+            None => visitor.visit_none(),
+            Some(Node { kind, position }) => match kind {
+                Kind::Null => visitor.visit_null(),
+                Kind::Bool(value) => visitor.visit_bool(value),
+                Kind::Number(lexeme) => visitor.visit_number(number::parse(&lexeme)?),
+                Kind::String(Cow::Borrowed(value)) => visitor.visit_borrowed_str(value),
+                Kind::String(Cow::Owned(value)) => visitor.visit_string(value),
+                Kind::Array(items) => visitor.visit_array(ArrayAccess {
+                    items: items.into_iter(),
+                    path: self.path,
+                    index: 0,
+                }),
+                Kind::Object(entries) => visitor.visit_object(ObjectAccess {
+                    entries: entries.into_iter().map(Some).collect(),
+                    path: self.path,
+                    position,
+                    cursor: 0,
+                }),
+            },
+        };
+
+        result.change_context(Error::new())
+    }
+
+    fn deserialize_none<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_null<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_optional<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let result = match &self.node {
+            None => visitor.visit_none(),
+            Some(Node {
+                kind: Kind::Null, ..
+            }) => visitor.visit_null(),
+            Some(_) => visitor.visit_some(self),
+        };
+
+        result.change_context(Error::new())
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_number<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_bytes_buffer<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_array<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_object<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+}
+
+struct ArrayAccess<'de> {
+    items: vec::IntoIter<Node<'de>>,
+    path: Path,
+    index: usize,
+}
+
+impl<'de> deer::ArrayAccess<'de> for ArrayAccess<'de> {
+    type Error = Error;
+
+    fn next<T>(&mut self) -> Result<Option<T>, Self::Error>
+    where
+        T: Deserialize<'de>,
+    {
+        self.items
+            .next()
+            .map(|item| {
+                let path = self.path.join(Segment::Index(self.index));
+                self.index += 1;
+
+                deserialize_node(Some(item), path, None)
+            })
+            .transpose()
+    }
+
+    fn finish(self) -> Result<(), Self::Error> {
+        let remaining = self.items.len();
+
+        if remaining == 0 {
+            Ok(())
+        } else {
+            Err(
+                Report::new(Error::message("unexpected items in array")).attach_printable(format!(
+                    "expected {} items, received {}",
+                    self.index,
+                    self.index + remaining
+                )),
+            )
+        }
+    }
+}
+
+struct ObjectAccess<'de> {
+    /// Entries are taken once they have been deserialized.
+    entries: Vec<Option<(Cow<'de, str>, Node<'de>)>>,
+    path: Path,
+    position: Option<Position>,
+    cursor: usize,
+}
+
+impl<'de> deer::ObjectAccess<'de> for ObjectAccess<'de> {
+    type Error = Error;
+
+    fn value<T>(&mut self, key: &str) -> Result<T, Self::Error>
+    where
+        T: Deserialize<'de>,
+    {
+        let node = self
+            .entries
+            .iter_mut()
+            .find(|entry| matches!(entry, Some((name, _)) if name == key))
+            .and_then(Option::take)
+            .map(|(_, node)| node);
+
+        let path = self.path.join(Segment::Key(key.to_owned()));
+
+        // a missing value is deserialized as well, it is up to the `Deserialize` implementation to
+        // decide whether this is an error
+        deserialize_node(node, path, self.position)
+    }
+
+    fn next<T>(&mut self) -> Result<Option<(String, T)>, Self::Error>
+    where
+        T: Deserialize<'de>,
+    {
+        while let Some(entry) = self.entries.get_mut(self.cursor) {
+            self.cursor += 1;
+
+            if let Some((key, node)) = entry.take() {
+                let path = self.path.join(Segment::Key(key.to_string()));
+                let value = deserialize_node(Some(node), path, None)?;
+
+                return Ok(Some((key.into_owned(), value)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn finish(self) -> Result<(), Self::Error> {
+        let mut remaining = self.entries.into_iter().flatten().peekable();
+
+        if remaining.peek().is_none() {
+            return Ok(());
+        }
+
+        let mut report = Report::new(Error::message("unexpected entries in object"));
+        for (key, _) in remaining {
+            report = report.attach_printable(format!("unexpected key `{key}`"));
+        }
+
+        Err(report)
+    }
+}
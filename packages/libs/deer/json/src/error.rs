@@ -0,0 +1,138 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+};
+
+use error_stack::Context;
+
+/// The error returned by the JSON [`Deserializer`].
+///
+/// The [`Report`] of every error originating from a value contains the [`Path`] and, if the value
+/// has been parsed from a string, the [`Position`] of the value as attachments, errors raised while
+/// parsing contain the [`Position`] of the invalid input.
+///
+/// [`Deserializer`]: crate::Deserializer
+/// [`Report`]: error_stack::Report
+#[derive(Debug)]
+pub struct Error {
+    message: Cow<'static, str>,
+}
+
+impl Error {
+    pub(crate) const fn syntax() -> Self {
+        Self {
+            message: Cow::Borrowed("unable to parse JSON"),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        fmt.write_str(&self.message)
+    }
+}
+
+impl Context for Error {}
+
+impl deer::Error for Error {
+    fn message(contents: &str) -> Self {
+        Self {
+            message: Cow::Owned(contents.to_owned()),
+        }
+    }
+
+    fn new() -> Self {
+        Self {
+            message: Cow::Borrowed("unable to deserialize JSON"),
+        }
+    }
+}
+
+/// A single step of a [`Path`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Segment {
+    /// The key of an entry of an object.
+    Key(String),
+    /// The index of an item of an array.
+    Index(usize),
+}
+
+/// The location of a value inside of a JSON document, e.g. `$.servers[0].port`.
+///
+/// `$` denotes the root value, `.key` (or `["key"]` if the key is not an identifier) an entry of an
+/// object and `[index]` an item of an array.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Path(Vec<Segment>);
+
+impl Path {
+    pub(crate) const fn root() -> Self {
+        Self(Vec::new())
+    }
+
+    pub(crate) fn join(&self, segment: Segment) -> Self {
+        let mut path = self.clone();
+        path.0.push(segment);
+        path
+    }
+
+    /// The segments leading from the root value to the value.
+    #[must_use]
+    pub fn segments(&self) -> &[Segment] {
+        &self.0
+    }
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+
+    matches!(chars.next(), Some(char) if char.is_alphabetic() || char == '_')
+        && chars.all(|char| char.is_alphanumeric() || char == '_')
+}
+
+impl Display for Path {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        fmt.write_str("$")?;
+
+        for segment in &self.0 {
+            match segment {
+                Segment::Key(key) if is_identifier(key) => write!(fmt, ".{key}")?,
+                Segment::Key(key) => write!(fmt, "[{key:?}]")?,
+                Segment::Index(index) => write!(fmt, "[{index}]")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The position of a value or of invalid input inside of a JSON string, both `line` and `column`
+/// start at `1`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Position {
+    line: usize,
+    column: usize,
+}
+
+impl Position {
+    pub(crate) const fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+
+    /// The line of the position, starting at `1`.
+    #[must_use]
+    pub const fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column of the position in characters, starting at `1`.
+    #[must_use]
+    pub const fn column(&self) -> usize {
+        self.column
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        write!(fmt, "line {}, column {}", self.line, self.column)
+    }
+}
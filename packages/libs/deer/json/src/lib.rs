@@ -0,0 +1,81 @@
+//! JSON backend for [`deer`].
+//!
+//! Values are deserialized either from a string with [`from_str`] or from a
+//! [`serde_json::Value`] with [`from_value`]. In contrast to [`serde_json`], the error does not
+//! stop at the message: every [`Report`] carries the [`Path`] of the value, which could not be
+//! deserialized, and, if the value has been parsed from a string, its [`Position`]:
+//!
+//! ```rust
+//! use std::collections::BTreeMap;
+//!
+//! use deer_json::{Path, Position};
+//!
+//! let input = r#"{
+//!     "primary": [8080, 8443],
+//!     "secondary": [8080, 65536]
+//! }"#;
+//!
+//! let report = deer_json::from_str::<BTreeMap<String, Vec<u16>>>(input)
+//!     .expect_err("65536 is too large for an u16");
+//!
+//! let path = report
+//!     .frames()
+//!     .find_map(|frame| frame.downcast_ref::<Path>())
+//!     .expect("the path should be attached");
+//! assert_eq!(path.to_string(), "$.secondary[1]");
+//!
+//! let position = report
+//!     .frames()
+//!     .find_map(|frame| frame.downcast_ref::<Position>())
+//!     .expect("the position should be attached");
+//! assert_eq!((position.line(), position.column()), (3, 25));
+//! ```
+//!
+//! With the `arbitrary-precision` feature enabled, integers, which do not fit into an `i64`, are
+//! deserialized as [`BigInt`] and floats as [`Decimal`], if they can be represented exactly.
+//!
+//! [`Report`]: error_stack::Report
+//! [`BigInt`]: https://docs.rs/num-bigint/latest/num_bigint/struct.BigInt.html
+//! [`Decimal`]: https://docs.rs/rust_decimal/latest/rust_decimal/struct.Decimal.html
+
+#![warn(unreachable_pub, clippy::pedantic, clippy::nursery)]
+#![allow(clippy::module_name_repetitions)]
+#![allow(clippy::redundant_pub_crate)]
+#![forbid(unsafe_code)]
+
+use deer::Deserialize;
+use error_stack::Result;
+use serde_json::Value;
+
+pub use crate::{
+    deserializer::Deserializer,
+    error::{Error, Path, Position, Segment},
+};
+
+mod deserializer;
+mod error;
+mod node;
+mod number;
+mod parse;
+
+/// Deserializes an instance of `T` from the JSON string `input`.
+///
+/// # Errors
+///
+/// - `input` is not valid JSON, the [`Position`] of the invalid input is attached
+/// - `T` could not be deserialized from the value, the [`Path`] and [`Position`] of the offending
+///   value are attached
+pub fn from_str<'de, T: Deserialize<'de>>(input: &'de str) -> Result<T, Error> {
+    let node = parse::parse(input)?;
+
+    deserializer::deserialize_node(Some(node), Path::root(), None)
+}
+
+/// Deserializes an instance of `T` from `value`.
+///
+/// # Errors
+///
+/// `T` could not be deserialized from the value, the [`Path`] of the offending value is attached
+pub fn from_value<'de, T: Deserialize<'de>>(value: &'de Value) -> Result<T, Error> {
+    deserializer::deserialize_node(Some(value.into()), Path::root(), None)
+}
@@ -0,0 +1,51 @@
+use std::borrow::Cow;
+
+use serde_json::Value;
+
+use crate::error::Position;
+
+/// A parsed JSON value, strings are borrowed from the input wherever possible.
+///
+/// Numbers are kept as they were written and only converted into a [`deer::Number`] when they are
+/// deserialized, so that conversion errors carry the location of the number.
+#[derive(Debug)]
+pub(crate) enum Kind<'de> {
+    Null,
+    Bool(bool),
+    Number(Cow<'de, str>),
+    String(Cow<'de, str>),
+    Array(Vec<Node<'de>>),
+    Object(Vec<(Cow<'de, str>, Node<'de>)>),
+}
+
+#[derive(Debug)]
+pub(crate) struct Node<'de> {
+    pub(crate) kind: Kind<'de>,
+    /// Only available if the node has been parsed from a string.
+    pub(crate) position: Option<Position>,
+}
+
+impl<'de> From<&'de Value> for Node<'de> {
+    fn from(value: &'de Value) -> Self {
+        let kind = match value {
+            Value::Null => Kind::Null,
+            Value::Bool(value) => Kind::Bool(*value),
+            // With `arbitrary_precision` enabled `serde_json` keeps the number as it was written,
+            // otherwise this is the shortest representation, which is parsed into the same value.
+            Value::Number(number) => Kind::Number(Cow::Owned(number.to_string())),
+            Value::String(value) => Kind::String(Cow::Borrowed(value)),
+            Value::Array(items) => Kind::Array(items.iter().map(Self::from).collect()),
+            Value::Object(entries) => Kind::Object(
+                entries
+                    .iter()
+                    .map(|(key, value)| (Cow::Borrowed(key.as_str()), Self::from(value)))
+                    .collect(),
+            ),
+        };
+
+        Self {
+            kind,
+            position: None,
+        }
+    }
+}
@@ -0,0 +1,56 @@
+#[cfg(feature = "arbitrary-precision")]
+use std::str::FromStr;
+
+use deer::{Error as _, Number};
+use error_stack::{Report, Result};
+#[cfg(feature = "arbitrary-precision")]
+use num_bigint::BigInt;
+use num_traits::FromPrimitive;
+#[cfg(feature = "arbitrary-precision")]
+use rust_decimal::Decimal;
+
+use crate::error::Error;
+
+/// Converts a valid JSON number into a [`Number`].
+///
+/// Integers are stored as `i64` or `u64` if possible, floats as `f64`. With `arbitrary-precision`
+/// enabled larger integers are stored as [`BigInt`] and floats as [`Decimal`], if they can be
+/// represented exactly.
+pub(crate) fn parse(lexeme: &str) -> Result<Number, Error> {
+    let is_integer = !lexeme.contains(['.', 'e', 'E']);
+
+    if is_integer {
+        if let Ok(value) = lexeme.parse::<i64>() {
+            return Ok(Number::from(value));
+        }
+
+        // only stored as `u64` without `arbitrary-precision`, otherwise as `BigInt`
+        if let Some(value) = lexeme.parse::<u64>().ok().and_then(Number::from_u64) {
+            return Ok(value);
+        }
+
+        #[cfg(feature = "arbitrary-precision")]
+        if let Ok(value) = BigInt::from_str(lexeme) {
+            return Ok(Number::from(value));
+        }
+    } else {
+        #[cfg(feature = "arbitrary-precision")]
+        {
+            let value = if lexeme.contains(['e', 'E']) {
+                Decimal::from_scientific(lexeme)
+            } else {
+                Decimal::from_str_exact(lexeme)
+            };
+
+            if let Ok(value) = value {
+                return Ok(Number::from(value));
+            }
+        }
+    }
+
+    match lexeme.parse::<f64>() {
+        Ok(value) if value.is_finite() => Ok(Number::from(value)),
+        _ => Err(Report::new(Error::message("number out of range"))
+            .attach_printable(format!("unable to represent {lexeme} as a number"))),
+    }
+}
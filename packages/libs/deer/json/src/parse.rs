@@ -0,0 +1,344 @@
+use std::borrow::Cow;
+
+use error_stack::{Report, Result};
+
+use crate::{
+    error::{Error, Position},
+    node::{Kind, Node},
+};
+
+/// Maximum nesting of arrays and objects, deeper nesting would overflow the stack.
+const RECURSION_LIMIT: usize = 128;
+
+/// Parses `input` into a [`Node`], the whole input must consist of a single JSON value.
+pub(crate) fn parse(input: &str) -> Result<Node<'_>, Error> {
+    let mut parser = Parser {
+        input,
+        offset: 0,
+        line: 1,
+        column: 1,
+    };
+
+    let node = parser.parse_value(0)?;
+
+    parser.skip_whitespace();
+    if parser.peek().is_some() {
+        return Err(parser.error("trailing characters after the JSON value"));
+    }
+
+    Ok(node)
+}
+
+fn syntax_error(reason: &'static str, position: Position) -> Report<Error> {
+    Report::new(Error::syntax())
+        .attach_printable(reason)
+        .attach_printable(position)
+}
+
+struct Parser<'de> {
+    input: &'de str,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'de> Parser<'de> {
+    const fn position(&self) -> Position {
+        Position::new(self.line, self.column)
+    }
+
+    fn error(&self, reason: &'static str) -> Report<Error> {
+        syntax_error(reason, self.position())
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.offset).copied()
+    }
+
+    fn bump(&mut self) {
+        if let Some(byte) = self.peek() {
+            self.offset += 1;
+
+            if byte == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            } else if byte & 0xC0 != 0x80 {
+                // continuation bytes of multi-byte characters do not start a new column
+                self.column += 1;
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.bump();
+        }
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<Node<'de>, Error> {
+        self.skip_whitespace();
+        let position = self.position();
+
+        let kind = match self.peek() {
+            None => return Err(self.error("unexpected end of input, expected a value")),
+            Some(b'n') => self.parse_literal("null", Kind::Null)?,
+            Some(b't') => self.parse_literal("true", Kind::Bool(true))?,
+            Some(b'f') => self.parse_literal("false", Kind::Bool(false))?,
+            Some(b'"') => Kind::String(self.parse_string()?),
+            Some(b'-' | b'0'..=b'9') => Kind::Number(self.parse_number()?),
+            Some(b'[' | b'{') if depth >= RECURSION_LIMIT => {
+                return Err(self.error("recursion limit exceeded"));
+            }
+            Some(b'[') => Kind::Array(self.parse_array(depth)?),
+            Some(b'{') => Kind::Object(self.parse_object(depth)?),
+            Some(_) => return Err(self.error("unexpected character, expected a value")),
+        };
+
+        Ok(Node {
+            kind,
+            position: Some(position),
+        })
+    }
+
+    fn parse_literal(
+        &mut self,
+        literal: &'static str,
+        kind: Kind<'de>,
+    ) -> Result<Kind<'de>, Error> {
+        if !self.input[self.offset..].starts_with(literal) {
+            return Err(self.error(match literal {
+                "null" => "expected `null`",
+                "true" => "expected `true`",
+                _ => "expected `false`",
+            }));
+        }
+
+        for _ in 0..literal.len() {
+            self.bump();
+        }
+
+        Ok(kind)
+    }
+
+    fn parse_digits(&mut self) -> Result<(), Error> {
+        if !matches!(self.peek(), Some(b'0'..=b'9')) {
+            return Err(self.error("expected a digit"));
+        }
+
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.bump();
+        }
+
+        Ok(())
+    }
+
+    fn parse_number(&mut self) -> Result<Cow<'de, str>, Error> {
+        let start = self.offset;
+
+        if self.peek() == Some(b'-') {
+            self.bump();
+        }
+
+        // leading zeros are not allowed
+        if self.peek() == Some(b'0') {
+            self.bump();
+        } else {
+            self.parse_digits()?;
+        }
+
+        if self.peek() == Some(b'.') {
+            self.bump();
+            self.parse_digits()?;
+        }
+
+        if let Some(b'e' | b'E') = self.peek() {
+            self.bump();
+
+            if let Some(b'+' | b'-') = self.peek() {
+                self.bump();
+            }
+
+            self.parse_digits()?;
+        }
+
+        Ok(Cow::Borrowed(&self.input[start..self.offset]))
+    }
+
+    fn parse_string(&mut self) -> Result<Cow<'de, str>, Error> {
+        let input = self.input;
+        let position = self.position();
+        // skip the opening quote
+        self.bump();
+
+        // strings are only copied if they contain escape sequences
+        let mut owned: Option<String> = None;
+        let mut segment = self.offset;
+
+        loop {
+            match self.peek() {
+                None => return Err(syntax_error("unterminated string", position)),
+                Some(b'"') => {
+                    let tail = &input[segment..self.offset];
+                    self.bump();
+
+                    return Ok(owned.map_or(Cow::Borrowed(tail), |mut string| {
+                        string.push_str(tail);
+                        Cow::Owned(string)
+                    }));
+                }
+                Some(b'\\') => {
+                    let string = owned.get_or_insert_with(String::new);
+                    string.push_str(&input[segment..self.offset]);
+                    string.push(self.parse_escape()?);
+                    segment = self.offset;
+                }
+                Some(0x00..=0x1F) => {
+                    return Err(self.error("control characters must be escaped in strings"));
+                }
+                Some(_) => self.bump(),
+            }
+        }
+    }
+
+    fn parse_escape(&mut self) -> Result<char, Error> {
+        let position = self.position();
+        // skip the backslash
+        self.bump();
+
+        let escaped = match self.peek() {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                self.bump();
+                return self.parse_unicode_escape(position);
+            }
+            _ => return Err(syntax_error("invalid escape sequence", position)),
+        };
+
+        self.bump();
+        Ok(escaped)
+    }
+
+    /// Parses the code point after `\u`, surrogate pairs are combined into a single character.
+    fn parse_unicode_escape(&mut self, position: Position) -> Result<char, Error> {
+        let high = self.parse_hex(position)?;
+
+        let code_point = match high {
+            0xD800..=0xDBFF => {
+                if !self.input[self.offset..].starts_with("\\u") {
+                    return Err(syntax_error(
+                        "unpaired surrogate in unicode escape",
+                        position,
+                    ));
+                }
+                self.bump();
+                self.bump();
+
+                let low = self.parse_hex(position)?;
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(syntax_error(
+                        "unpaired surrogate in unicode escape",
+                        position,
+                    ));
+                }
+
+                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+            }
+            0xDC00..=0xDFFF => {
+                return Err(syntax_error(
+                    "unpaired surrogate in unicode escape",
+                    position,
+                ));
+            }
+            _ => high,
+        };
+
+        char::from_u32(code_point).ok_or_else(|| syntax_error("invalid unicode escape", position))
+    }
+
+    fn parse_hex(&mut self, position: Position) -> Result<u32, Error> {
+        let mut value = 0;
+
+        for _ in 0..4 {
+            let digit = self
+                .peek()
+                .and_then(|byte| char::from(byte).to_digit(16))
+                .ok_or_else(|| syntax_error("invalid unicode escape", position))?;
+
+            value = value * 16 + digit;
+            self.bump();
+        }
+
+        Ok(value)
+    }
+
+    fn parse_array(&mut self, depth: usize) -> Result<Vec<Node<'de>>, Error> {
+        // skip the opening bracket
+        self.bump();
+        let mut items = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.bump();
+            return Ok(items);
+        }
+
+        loop {
+            items.push(self.parse_value(depth + 1)?);
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.bump(),
+                Some(b']') => {
+                    self.bump();
+                    return Ok(items);
+                }
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn parse_object(&mut self, depth: usize) -> Result<Vec<(Cow<'de, str>, Node<'de>)>, Error> {
+        // skip the opening brace
+        self.bump();
+        let mut entries = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.bump();
+            return Ok(entries);
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected a string as key"));
+            }
+            let key = self.parse_string()?;
+
+            self.skip_whitespace();
+            if self.peek() != Some(b':') {
+                return Err(self.error("expected `:`"));
+            }
+            self.bump();
+
+            entries.push((key, self.parse_value(depth + 1)?));
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.bump(),
+                Some(b'}') => {
+                    self.bump();
+                    return Ok(entries);
+                }
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+}
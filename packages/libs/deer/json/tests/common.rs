@@ -0,0 +1,102 @@
+#![allow(dead_code)]
+
+use std::marker::PhantomData;
+
+use deer::{Deserialize, Deserializer, ObjectAccess, Visitor};
+use deer_json::{Path, Position};
+use error_stack::{AttachmentKind, FrameKind, Report, Result, ResultExt};
+
+/// The [`Path`] attached to `report`, formatted as string.
+pub fn path<C>(report: &Report<C>) -> Option<String> {
+    report
+        .frames()
+        .find_map(|frame| frame.downcast_ref::<Path>())
+        .map(ToString::to_string)
+}
+
+/// The line and column of the [`Position`] attached to `report`.
+pub fn position<C>(report: &Report<C>) -> Option<(usize, usize)> {
+    report
+        .frames()
+        .find_map(|frame| frame.downcast_ref::<Position>())
+        .map(|position| (position.line(), position.column()))
+}
+
+/// The messages of all contexts and printable attachments of `report`.
+pub fn messages<C>(report: &Report<C>) -> Vec<String> {
+    report
+        .frames()
+        .filter_map(|frame| match frame.kind() {
+            FrameKind::Context(context) => Some(context.to_string()),
+            FrameKind::Attachment(AttachmentKind::Printable(attachment)) => {
+                Some(attachment.to_string())
+            }
+            FrameKind::Attachment(_) => None,
+        })
+        .collect()
+}
+
+/// Asserts that `report` contains `message` as context or printable attachment.
+pub fn assert_message<C>(report: &Report<C>, message: &str) {
+    let messages = messages(report);
+    assert!(
+        messages.iter().any(|candidate| candidate == message),
+        "{message:?} is not contained in {messages:?}"
+    );
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Server {
+    pub name: String,
+    pub port: u16,
+    pub tags: Option<Vec<String>>,
+}
+
+impl<'de> Deserialize<'de> for Server {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        struct ServerVisitor<E>(PhantomData<fn() -> E>);
+
+        impl<'de, E: deer::Error> Visitor<'de> for ServerVisitor<E> {
+            type Error = E;
+            type Value = Server;
+
+            fn expecting_display(&self) -> String {
+                "server".to_owned()
+            }
+
+            fn visit_object<A>(self, mut object: A) -> Result<Self::Value, Self::Error>
+            where
+                A: ObjectAccess<'de>,
+            {
+                let server = Server {
+                    name: object.value("name").change_context(E::new())?,
+                    port: object.value("port").change_context(E::new())?,
+                    tags: object.value("tags").change_context(E::new())?,
+                };
+
+                object.finish().change_context(E::new())?;
+
+                Ok(server)
+            }
+        }
+
+        de.deserialize_object(ServerVisitor::<D::Error>(PhantomData))
+    }
+}
+
+/// Arbitrarily nested arrays, e.g. `[[], [[]]]`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Nested(pub Vec<Nested>);
+
+impl Nested {
+    /// `depth` arrays nested into each other.
+    pub fn json(depth: usize) -> String {
+        "[".repeat(depth) + &"]".repeat(depth)
+    }
+}
+
+impl<'de> Deserialize<'de> for Nested {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        Vec::deserialize(de).map(Self)
+    }
+}
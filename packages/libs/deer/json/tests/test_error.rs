@@ -0,0 +1,137 @@
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::*;
+use deer::Deserialize;
+use deer_json::{from_str, from_value, Path, Segment};
+use serde_json::Value;
+
+const SERVERS: &str = r#"[
+    {"name": "primary", "port": 8080},
+    {"name": "secondary", "port": 8443, "tags": ["internal", 3]}
+]"#;
+
+#[test]
+fn nested_value() {
+    let report = from_str::<Vec<Server>>(SERVERS).expect_err("3 is not a string");
+
+    assert_message(&report, "unexpected value of type number");
+    assert_eq!(path(&report).as_deref(), Some("$[1].tags[1]"));
+    assert_eq!(position(&report), Some((3, 62)));
+
+    let path = report
+        .frames()
+        .find_map(|frame| frame.downcast_ref::<Path>())
+        .expect("the path should be attached");
+    assert_eq!(path.segments(), [
+        Segment::Index(1),
+        Segment::Key("tags".to_owned()),
+        Segment::Index(1)
+    ]);
+}
+
+#[test]
+fn path_is_attached_once() {
+    let report = from_str::<Vec<Server>>(SERVERS).expect_err("3 is not a string");
+
+    let paths = report
+        .frames()
+        .filter(|frame| frame.downcast_ref::<Path>().is_some())
+        .count();
+    assert_eq!(paths, 1);
+}
+
+#[test]
+fn root_value() {
+    let report = from_str::<u8>(" 300").expect_err("300 is too large for an u8");
+
+    assert_message(&report, "unable to represent 300 as u8");
+    assert_eq!(path(&report).as_deref(), Some("$"));
+    assert_eq!(position(&report), Some((1, 2)));
+}
+
+#[test]
+fn column_counts_characters() {
+    let report = from_str::<Server>(r#"{"name": "é", "port": 70000}"#)
+        .expect_err("70000 is too large for an u16");
+
+    assert_eq!(path(&report).as_deref(), Some("$.port"));
+    assert_eq!(position(&report), Some((1, 23)));
+}
+
+#[test]
+fn missing_key() {
+    let report = from_str::<Server>("{\n  \"port\": 1\n}").expect_err("name is missing");
+
+    assert_message(&report, "unexpected missing value");
+    assert_eq!(path(&report).as_deref(), Some("$.name"));
+    // the position of the object, which is missing the key
+    assert_eq!(position(&report), Some((1, 1)));
+}
+
+#[test]
+fn unexpected_key() {
+    let report = from_str::<Server>(r#"[{"name": "a", "port": 1, "extra": 2}]"#)
+        .expect_err("arrays are not servers");
+    assert_message(&report, "unexpected value of type array");
+
+    let report = from_str::<Server>(r#"{"name": "a", "port": 1, "extra": 2}"#)
+        .expect_err("extra is not a field of server");
+    assert_message(&report, "unexpected key `extra`");
+    assert_eq!(path(&report).as_deref(), Some("$"));
+    assert_eq!(position(&report), Some((1, 1)));
+}
+
+#[test]
+fn unexpected_item() {
+    let report = from_str::<[u8; 1]>("[1, 2]").expect_err("the array is too long");
+
+    assert_message(&report, "unexpected items in array");
+    assert_eq!(path(&report).as_deref(), Some("$"));
+}
+
+#[test]
+fn key_is_not_an_identifier() {
+    let report = from_str::<BTreeMap<String, u8>>(r#"{"a b": 300, "1": 1}"#)
+        .expect_err("300 is too large for an u8");
+    assert_eq!(path(&report).as_deref(), Some(r#"$["a b"]"#));
+
+    let report =
+        from_str::<BTreeMap<String, u8>>(r#"{"1": 300}"#).expect_err("300 is too large for an u8");
+    assert_eq!(path(&report).as_deref(), Some(r#"$["1"]"#));
+}
+
+/// Deserializes `input` with [`from_str`] and [`from_value`] and checks that both return the same
+/// value or fail at the same path.
+fn assert_parity<T>(input: &str)
+where
+    T: for<'de> Deserialize<'de> + PartialEq + std::fmt::Debug,
+{
+    let value = serde_json::from_str::<Value>(input).expect("input should be valid JSON");
+
+    match (from_str::<T>(input), from_value::<T>(&value)) {
+        (Ok(from_str), Ok(from_value)) => assert_eq!(from_str, from_value, "{input}"),
+        (Err(from_str), Err(from_value)) => {
+            assert_eq!(path(&from_str), path(&from_value), "{input}");
+            assert!(position(&from_str).is_some(), "{input}");
+            assert_eq!(position(&from_value), None, "{input}");
+        }
+        (from_str, from_value) => panic!(
+            "{input}: `from_str` returned {from_str:?}, `from_value` returned {from_value:?}"
+        ),
+    }
+}
+
+#[test]
+fn from_value_parity() {
+    assert_parity::<Vec<Server>>(SERVERS);
+    assert_parity::<Vec<Server>>(r#"[{"name": "a", "port": 1, "tags": ["b", "c"]}]"#);
+    assert_parity::<Server>(r#"{"port": 1}"#);
+    assert_parity::<Server>(r#"{"name": "a", "port": 1, "extra": 2}"#);
+    assert_parity::<BTreeMap<String, Option<u16>>>(r#"{"a": null, "b": 2, "c": 70000}"#);
+    assert_parity::<(bool, String, f64)>(r#"[true, "é😀", -1.5e2]"#);
+    assert_parity::<Vec<i64>>("[-9223372036854775808, 9223372036854775807]");
+    assert_parity::<Vec<u64>>("[18446744073709551615]");
+    assert_parity::<Vec<u8>>("[1, 2, 256]");
+}
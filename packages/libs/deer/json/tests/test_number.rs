@@ -0,0 +1,119 @@
+mod common;
+
+use common::*;
+use deer::Number;
+use deer_json::{from_str, from_value};
+use num_traits::ToPrimitive;
+use serde_json::json;
+
+#[test]
+fn integer() {
+    assert_eq!(from_str::<i64>("0").ok(), Some(0));
+    assert_eq!(from_str::<i64>("-0").ok(), Some(0));
+    assert_eq!(from_str::<i64>("-9223372036854775808").ok(), Some(i64::MIN));
+    assert_eq!(from_str::<i64>("9223372036854775807").ok(), Some(i64::MAX));
+    assert_eq!(from_str::<u8>("255").ok(), Some(255));
+}
+
+#[test]
+fn unsigned_integer() {
+    assert_eq!(from_str::<u64>("9223372036854775808").ok(), Some(1 << 63));
+    assert_eq!(from_str::<u64>("18446744073709551615").ok(), Some(u64::MAX));
+    assert_eq!(
+        from_str::<u128>("18446744073709551615").ok(),
+        Some(u128::from(u64::MAX))
+    );
+    assert_eq!(from_value::<u64>(&json!(u64::MAX)).ok(), Some(u64::MAX));
+
+    let number = from_str::<Number>("18446744073709551615").expect("should deserialize");
+    assert_eq!(number.to_string(), "18446744073709551615");
+
+    let report = from_str::<i64>("18446744073709551615").expect_err("too large for an i64");
+    assert_message(&report, "unable to represent 18446744073709551615 as i64");
+}
+
+#[test]
+#[cfg(not(feature = "arbitrary-precision"))]
+fn integer_larger_than_u64() {
+    // without `arbitrary-precision` larger integers are stored as `f64`
+    let number = from_str::<Number>("18446744073709551616").expect("should deserialize");
+    assert_eq!(number.to_f64(), Some(18_446_744_073_709_551_616.0));
+
+    let report = from_str::<u128>("18446744073709551616").expect_err("floats are not integers");
+    assert_eq!(position(&report), Some((1, 1)));
+}
+
+#[test]
+#[cfg(feature = "arbitrary-precision")]
+fn integer_larger_than_u64() {
+    assert_eq!(
+        from_str::<u128>("340282366920938463463374607431768211455").ok(),
+        Some(u128::MAX)
+    );
+    assert_eq!(
+        from_str::<i128>("-170141183460469231731687303715884105728").ok(),
+        Some(i128::MIN)
+    );
+
+    let number = from_str::<Number>("123456789012345678901234567890").expect("should deserialize");
+    assert_eq!(number.to_string(), "123456789012345678901234567890");
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn float() {
+    assert_eq!(from_str::<f64>("0.5").ok(), Some(0.5));
+    assert_eq!(from_str::<f64>("-1.5e2").ok(), Some(-150.0));
+    assert_eq!(from_str::<f64>("1E-2").ok(), Some(0.01));
+    assert_eq!(from_str::<f64>("2e+3").ok(), Some(2000.0));
+    assert_eq!(from_str::<f32>("0.25").ok(), Some(0.25));
+    assert_eq!(from_str::<f64>("42").ok(), Some(42.0));
+    assert_eq!(
+        from_str::<Number>("2.5")
+            .ok()
+            .and_then(|number| number.to_f64()),
+        Some(2.5)
+    );
+}
+
+#[test]
+#[cfg(feature = "arbitrary-precision")]
+fn decimal() {
+    let number = from_str::<Number>("0.1000000000000000000001").expect("should deserialize");
+    assert_eq!(number.to_string(), "0.1000000000000000000001");
+}
+
+#[test]
+fn float_out_of_range() {
+    let report = from_str::<Vec<f64>>("[1e400]").expect_err("1e400 is too large for an f64");
+    assert_message(&report, "number out of range");
+    assert_message(&report, "unable to represent 1e400 as a number");
+    assert_eq!(path(&report).as_deref(), Some("$[0]"));
+    assert_eq!(position(&report), Some((1, 2)));
+}
+
+#[test]
+fn float_as_integer() {
+    let report = from_str::<u8>("1.5").expect_err("floats are not integers");
+    assert_message(&report, "unable to represent 1.5 as u8");
+
+    let report = from_str::<u8>("1.0").expect_err("floats are not integers");
+    assert_message(&report, "provided value too large or too small");
+}
+
+#[test]
+fn invalid_number() {
+    for (input, expected) in [
+        ("01", (1, 2)),
+        ("1.", (1, 3)),
+        ("-", (1, 2)),
+        ("1e", (1, 3)),
+        ("1e+", (1, 4)),
+        (".5", (1, 1)),
+        ("+1", (1, 1)),
+    ] {
+        let report = from_str::<f64>(input).expect_err("input is not a valid number");
+        assert_message(&report, "unable to parse JSON");
+        assert_eq!(position(&report), Some(expected), "{input:?}");
+    }
+}
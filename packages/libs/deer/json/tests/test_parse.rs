@@ -0,0 +1,127 @@
+mod common;
+
+use common::*;
+use deer_json::from_str;
+
+#[test]
+fn literals() {
+    assert_eq!(from_str::<bool>("true").ok(), Some(true));
+    assert_eq!(from_str::<bool>(" false ").ok(), Some(false));
+    assert_eq!(from_str::<()>("null").ok(), Some(()));
+    assert_eq!(from_str::<Option<bool>>("null").ok(), Some(None));
+
+    let report = from_str::<bool>("tru").expect_err("`tru` is not a literal");
+    assert_message(&report, "expected `true`");
+    assert_eq!(position(&report), Some((1, 1)));
+}
+
+#[test]
+fn string() {
+    assert_eq!(
+        from_str::<String>(r#""deer""#).ok(),
+        Some("deer".to_owned())
+    );
+    assert_eq!(from_str::<String>(r#""""#).ok(), Some(String::new()));
+    assert_eq!(
+        from_str::<String>(r#""héllo wörld""#).ok(),
+        Some("héllo wörld".to_owned())
+    );
+}
+
+#[test]
+fn string_escapes() {
+    assert_eq!(
+        from_str::<String>(r#""\" \\ \/ \b \f \n \r \t""#).ok(),
+        Some("\" \\ / \u{8} \u{c} \n \r \t".to_owned())
+    );
+    assert_eq!(from_str::<String>(r#""éé""#).ok(), Some("éé".to_owned()));
+    assert_eq!(
+        from_str::<String>(r#""\u0000""#).ok(),
+        Some("\0".to_owned())
+    );
+    assert_eq!(from_str::<char>(r#""\n""#).ok(), Some('\n'));
+}
+
+#[test]
+fn string_invalid_escape() {
+    let report = from_str::<String>(r#""ab\x""#).expect_err("`\\x` is not an escape sequence");
+    assert_message(&report, "invalid escape sequence");
+    assert_eq!(position(&report), Some((1, 4)));
+
+    let report = from_str::<String>(r#""\u00g0""#).expect_err("`g` is not a hex digit");
+    assert_message(&report, "invalid unicode escape");
+    assert_eq!(position(&report), Some((1, 2)));
+}
+
+#[test]
+fn surrogate_pair() {
+    assert_eq!(from_str::<String>(r#""😀""#).ok(), Some("😀".to_owned()));
+    assert_eq!(from_str::<String>(r#""𝄞!""#).ok(), Some("𝄞!".to_owned()));
+    assert_eq!(from_str::<char>(r#""😀""#).ok(), Some('😀'));
+}
+
+#[test]
+fn unpaired_surrogate() {
+    for input in [r#""\ud800""#, r#""\ud800x""#, r#""\ud800A""#, r#""\udc00""#] {
+        let report = from_str::<String>(input).expect_err("surrogates have to be paired");
+        assert_message(&report, "unpaired surrogate in unicode escape");
+        assert_eq!(position(&report), Some((1, 2)));
+    }
+}
+
+#[test]
+fn string_unterminated() {
+    let report = from_str::<String>(r#""deer"#).expect_err("the string is not terminated");
+    assert_message(&report, "unterminated string");
+    assert_eq!(position(&report), Some((1, 1)));
+}
+
+#[test]
+fn string_control_character() {
+    let report = from_str::<String>("\"a\nb\"").expect_err("newlines have to be escaped");
+    assert_message(&report, "control characters must be escaped in strings");
+}
+
+#[test]
+fn recursion_limit() {
+    let value = from_str::<Nested>(&Nested::json(128)).expect("128 levels should be allowed");
+    assert_eq!(value.0.len(), 1);
+
+    let report = from_str::<Nested>(&Nested::json(129)).expect_err("recursion limit is 128");
+    assert_message(&report, "recursion limit exceeded");
+    assert_eq!(position(&report), Some((1, 129)));
+
+    let object = r#"{"a":"#.repeat(200) + "1" + &"}".repeat(200);
+    let report = from_str::<Nested>(&object).expect_err("recursion limit is 128");
+    assert_message(&report, "recursion limit exceeded");
+}
+
+#[test]
+fn syntax_error_position() {
+    for (input, reason, expected) in [
+        ("", "unexpected end of input, expected a value", (1, 1)),
+        ("   ", "unexpected end of input, expected a value", (1, 4)),
+        ("[1,]", "unexpected character, expected a value", (1, 4)),
+        ("[1 2]", "expected `,` or `]`", (1, 4)),
+        (r#"{"a" 1}"#, "expected `:`", (1, 6)),
+        (r#"{"a": 1 "b": 2}"#, "expected `,` or `}`", (1, 9)),
+        ("{1: 2}", "expected a string as key", (1, 2)),
+        ("[1] x", "trailing characters after the JSON value", (1, 5)),
+        (
+            "[\n  1,\n  ]",
+            "unexpected character, expected a value",
+            (3, 3),
+        ),
+        (
+            "[\"é\", x]",
+            "unexpected character, expected a value",
+            (1, 7),
+        ),
+    ] {
+        let report = from_str::<Vec<u8>>(input).expect_err("input is not valid JSON");
+        assert_message(&report, "unable to parse JSON");
+        assert_message(&report, reason);
+        assert_eq!(position(&report), Some(expected), "{input:?}");
+        assert_eq!(path(&report), None);
+    }
+}
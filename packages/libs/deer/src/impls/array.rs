@@ -0,0 +1,190 @@
+use alloc::{format, string::String, vec::Vec};
+use core::marker::PhantomData;
+
+use error_stack::{Result, ResultExt};
+
+use crate::{
+    impls::unexpected_end_of_array, ArrayAccess, Deserialize, Deserializer, Error, Visitor,
+};
+
+struct ArrayVisitor<T, E: Error, const N: usize>(PhantomData<fn() -> (T, E)>);
+
+impl<'de, T: Deserialize<'de>, E: Error, const N: usize> Visitor<'de> for ArrayVisitor<T, E, N> {
+    type Error = E;
+    type Value = [T; N];
+
+    fn expecting_display(&self) -> String {
+        format!("array of length {N}")
+    }
+
+    fn visit_array<A>(self, mut array: A) -> Result<Self::Value, Self::Error>
+    where
+        A: ArrayAccess<'de>,
+    {
+        let mut items = Vec::with_capacity(N);
+
+        while items.len() < N {
+            match array.next().change_context(E::new())? {
+                Some(item) => items.push(item),
+                None => return Err(unexpected_end_of_array(N, items.len())),
+            }
+        }
+
+        array.finish().change_context(E::new())?;
+
+        Ok(items
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("exactly {N} items have been deserialized")))
+    }
+}
+
+impl<'de, T: Deserialize<'de>, const N: usize> Deserialize<'de> for [T; N] {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_array(ArrayVisitor::<T, D::Error, N>(PhantomData))
+    }
+}
+
+macro_rules! impl_tuple {
+    ($len:literal: $($index:tt $ty:ident),+) => {
+        impl<'de, $($ty: Deserialize<'de>),+> Deserialize<'de> for ($($ty,)+) {
+            fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+                struct TupleVisitor<E: Error, $($ty),+>(PhantomData<fn() -> (E, $($ty),+)>);
+
+                impl<'de, E: Error, $($ty: Deserialize<'de>),+> Visitor<'de>
+                    for TupleVisitor<E, $($ty),+>
+                {
+                    type Error = E;
+                    type Value = ($($ty,)+);
+
+                    fn expecting_display(&self) -> String {
+                        format!("array of length {}", $len)
+                    }
+
+                    fn visit_array<A>(self, mut array: A) -> Result<Self::Value, Self::Error>
+                    where
+                        A: ArrayAccess<'de>,
+                    {
+                        let value = ($(
+                            array
+                                .next::<$ty>()
+                                .change_context(E::new())?
+                                .ok_or_else(|| unexpected_end_of_array($len, $index))?,
+                        )+);
+
+                        array.finish().change_context(E::new())?;
+
+                        Ok(value)
+                    }
+                }
+
+                de.deserialize_array(TupleVisitor::<D::Error, $($ty),+>(PhantomData))
+            }
+        }
+    };
+}
+
+impl_tuple!(1: 0 T0);
+impl_tuple!(2: 0 T0, 1 T1);
+impl_tuple!(3: 0 T0, 1 T1, 2 T2);
+impl_tuple!(4: 0 T0, 1 T1, 2 T2, 3 T3);
+impl_tuple!(5: 0 T0, 1 T1, 2 T2, 3 T3, 4 T4);
+impl_tuple!(6: 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5);
+impl_tuple!(7: 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6);
+impl_tuple!(8: 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7);
+impl_tuple!(9: 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8);
+impl_tuple!(10: 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9);
+impl_tuple!(11: 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10);
+impl_tuple!(12: 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11);
+impl_tuple!(13: 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12);
+impl_tuple!(
+    14: 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13
+);
+impl_tuple!(
+    15: 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13,
+    14 T14
+);
+impl_tuple!(
+    16: 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13,
+    14 T14, 15 T15
+);
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec, vec::Vec};
+
+    use crate::test::{deserialize, deserialize_err, Value};
+
+    fn numbers(numbers: &[i32]) -> Value {
+        Value::Array(numbers.iter().copied().map(Value::number).collect())
+    }
+
+    #[test]
+    fn array() {
+        assert_eq!(
+            deserialize::<[u8; 3]>(numbers(&[1, 2, 3])).ok(),
+            Some([1, 2, 3])
+        );
+        assert_eq!(deserialize::<[u8; 0]>(numbers(&[])).ok(), Some([]));
+    }
+
+    #[test]
+    fn array_too_short() {
+        let messages = deserialize_err::<[u8; 3]>(numbers(&[1, 2]));
+        assert!(messages.contains(&String::from("unexpected end of array")));
+        assert!(messages.contains(&String::from("expected 3 items, received 2")));
+    }
+
+    #[test]
+    fn array_too_long() {
+        let messages = deserialize_err::<[u8; 2]>(numbers(&[1, 2, 3]));
+        assert!(messages.contains(&String::from("unexpected trailing values")));
+    }
+
+    #[test]
+    fn array_invalid_item() {
+        let messages = deserialize_err::<[u8; 2]>(numbers(&[1, 256]));
+        assert!(messages.contains(&String::from("unable to represent 256 as u8")));
+    }
+
+    #[test]
+    fn array_of_other_type() {
+        let messages = deserialize_err::<[u8; 2]>(Value::Object(Vec::new()));
+        assert!(messages.contains(&String::from("unexpected value of type object")));
+    }
+
+    #[test]
+    fn tuple() {
+        let value = Value::Array(vec![
+            Value::number(1),
+            Value::string("two"),
+            Value::Bool(true),
+        ]);
+
+        assert_eq!(
+            deserialize::<(u8, String, bool)>(value).ok(),
+            Some((1, String::from("two"), true))
+        );
+        assert_eq!(deserialize::<(u8,)>(numbers(&[1])).ok(), Some((1,)));
+    }
+
+    #[test]
+    fn tuple_too_short() {
+        let messages = deserialize_err::<(u8, u8, u8)>(numbers(&[1]));
+        assert!(messages.contains(&String::from("unexpected end of array")));
+        assert!(messages.contains(&String::from("expected 3 items, received 1")));
+    }
+
+    #[test]
+    fn tuple_too_long() {
+        let messages = deserialize_err::<(u8, u8)>(numbers(&[1, 2, 3]));
+        assert!(messages.contains(&String::from("unexpected trailing values")));
+    }
+
+    #[test]
+    fn tuple_invalid_item() {
+        let value = Value::Array(vec![Value::number(1), Value::number(2)]);
+
+        let messages = deserialize_err::<(u8, String)>(value);
+        assert!(messages.contains(&String::from("unexpected value of type number")));
+    }
+}
@@ -0,0 +1,277 @@
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque},
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{iter, marker::PhantomData};
+#[cfg(feature = "std")]
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{BuildHasher, Hash},
+};
+
+use error_stack::{Result, ResultExt};
+
+use crate::{ArrayAccess, Deserialize, Deserializer, Error, ObjectAccess, Visitor};
+
+/// Visitor for any collection, which is deserialized from an array and can be built by extending
+/// it with one item at a time.
+struct SequenceVisitor<C, T, E: Error>(PhantomData<fn() -> C>, PhantomData<fn() -> (T, E)>);
+
+impl<C, T, E: Error> SequenceVisitor<C, T, E> {
+    const fn new() -> Self {
+        Self(PhantomData, PhantomData)
+    }
+}
+
+impl<'de, C, T, E> Visitor<'de> for SequenceVisitor<C, T, E>
+where
+    C: Default + Extend<T>,
+    T: Deserialize<'de>,
+    E: Error,
+{
+    type Error = E;
+    type Value = C;
+
+    fn expecting_display(&self) -> String {
+        "array".to_string()
+    }
+
+    fn visit_array<A>(self, mut array: A) -> Result<Self::Value, Self::Error>
+    where
+        A: ArrayAccess<'de>,
+    {
+        let mut collection = C::default();
+
+        while let Some(item) = array.next().change_context(E::new())? {
+            collection.extend(iter::once(item));
+        }
+
+        array.finish().change_context(E::new())?;
+
+        Ok(collection)
+    }
+}
+
+/// Visitor for any map, which is deserialized from an object and can be built by extending it with
+/// one entry at a time.
+struct MapVisitor<C, T, E: Error>(PhantomData<fn() -> C>, PhantomData<fn() -> (T, E)>);
+
+impl<C, T, E: Error> MapVisitor<C, T, E> {
+    const fn new() -> Self {
+        Self(PhantomData, PhantomData)
+    }
+}
+
+impl<'de, C, T, E> Visitor<'de> for MapVisitor<C, T, E>
+where
+    C: Default + Extend<(String, T)>,
+    T: Deserialize<'de>,
+    E: Error,
+{
+    type Error = E;
+    type Value = C;
+
+    fn expecting_display(&self) -> String {
+        "object".to_string()
+    }
+
+    fn visit_object<A>(self, mut object: A) -> Result<Self::Value, Self::Error>
+    where
+        A: ObjectAccess<'de>,
+    {
+        let mut collection = C::default();
+
+        while let Some(entry) = object.next().change_context(E::new())? {
+            collection.extend(iter::once(entry));
+        }
+
+        object.finish().change_context(E::new())?;
+
+        Ok(collection)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Vec<T> {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_array(SequenceVisitor::<Self, T, D::Error>::new())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Box<[T]> {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        Vec::deserialize(de).map(Vec::into_boxed_slice)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for VecDeque<T> {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_array(SequenceVisitor::<Self, T, D::Error>::new())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for LinkedList<T> {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_array(SequenceVisitor::<Self, T, D::Error>::new())
+    }
+}
+
+impl<'de, T: Deserialize<'de> + Ord> Deserialize<'de> for BinaryHeap<T> {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_array(SequenceVisitor::<Self, T, D::Error>::new())
+    }
+}
+
+impl<'de, T: Deserialize<'de> + Ord> Deserialize<'de> for BTreeSet<T> {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_array(SequenceVisitor::<Self, T, D::Error>::new())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for BTreeMap<String, T> {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_object(MapVisitor::<Self, T, D::Error>::new())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de, T, S> Deserialize<'de> for HashSet<T, S>
+where
+    T: Deserialize<'de> + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_array(SequenceVisitor::<Self, T, D::Error>::new())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de, T, S> Deserialize<'de> for HashMap<String, T, S>
+where
+    T: Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_object(MapVisitor::<Self, T, D::Error>::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        boxed::Box,
+        collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque},
+        string::String,
+        vec,
+        vec::Vec,
+    };
+    #[cfg(feature = "std")]
+    use std::collections::{HashMap, HashSet};
+
+    use crate::test::{deserialize, deserialize_err, Value};
+
+    fn numbers(numbers: &[i32]) -> Value {
+        Value::Array(numbers.iter().copied().map(Value::number).collect())
+    }
+
+    fn object() -> Value {
+        Value::Object(vec![
+            (String::from("b"), Value::number(2)),
+            (String::from("a"), Value::number(1)),
+        ])
+    }
+
+    #[test]
+    fn sequence() {
+        assert_eq!(
+            deserialize::<Vec<u8>>(numbers(&[3, 1, 2])).ok(),
+            Some(vec![3, 1, 2])
+        );
+        assert_eq!(
+            deserialize::<Box<[u8]>>(numbers(&[3, 1, 2])).ok(),
+            Some(vec![3, 1, 2].into_boxed_slice())
+        );
+        assert_eq!(
+            deserialize::<VecDeque<u8>>(numbers(&[3, 1, 2])).ok(),
+            Some(VecDeque::from(vec![3, 1, 2]))
+        );
+        assert_eq!(
+            deserialize::<LinkedList<u8>>(numbers(&[3, 1, 2])).ok(),
+            Some(LinkedList::from_iter([3, 1, 2]))
+        );
+        assert_eq!(
+            deserialize::<BinaryHeap<u8>>(numbers(&[3, 1, 2]))
+                .map(BinaryHeap::into_sorted_vec)
+                .ok(),
+            Some(vec![1, 2, 3])
+        );
+        assert_eq!(
+            deserialize::<BTreeSet<u8>>(numbers(&[3, 1, 2, 1])).ok(),
+            Some(BTreeSet::from([1, 2, 3]))
+        );
+        assert_eq!(deserialize::<Vec<u8>>(numbers(&[])).ok(), Some(Vec::new()));
+    }
+
+    #[test]
+    fn sequence_invalid_item() {
+        let messages = deserialize_err::<Vec<u8>>(numbers(&[1, 256]));
+        assert!(messages.contains(&String::from("unable to represent 256 as u8")));
+    }
+
+    #[test]
+    fn sequence_of_other_type() {
+        let messages = deserialize_err::<Vec<u8>>(object());
+        assert!(messages.contains(&String::from("unexpected value of type object")));
+    }
+
+    #[test]
+    fn map() {
+        assert_eq!(
+            deserialize::<BTreeMap<String, u8>>(object()).ok(),
+            Some(BTreeMap::from([
+                (String::from("a"), 1),
+                (String::from("b"), 2)
+            ]))
+        );
+        assert_eq!(
+            deserialize::<BTreeMap<String, u8>>(Value::Object(Vec::new())).ok(),
+            Some(BTreeMap::new())
+        );
+    }
+
+    #[test]
+    fn map_invalid_value() {
+        let value = Value::Object(vec![(String::from("a"), Value::number(-1))]);
+
+        let messages = deserialize_err::<BTreeMap<String, u8>>(value);
+        assert!(messages.contains(&String::from("unable to represent -1 as u8")));
+    }
+
+    #[test]
+    fn map_of_other_type() {
+        let messages = deserialize_err::<BTreeMap<String, u8>>(numbers(&[1]));
+        assert!(messages.contains(&String::from("unexpected value of type array")));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hash_set() {
+        assert_eq!(
+            deserialize::<HashSet<u8>>(numbers(&[3, 1, 2, 1])).ok(),
+            Some(HashSet::from([1, 2, 3]))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hash_map() {
+        assert_eq!(
+            deserialize::<HashMap<String, u8>>(object()).ok(),
+            Some(HashMap::from([
+                (String::from("a"), 1),
+                (String::from("b"), 2)
+            ]))
+        );
+    }
+}
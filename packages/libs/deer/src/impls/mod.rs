@@ -0,0 +1,18 @@
+use alloc::format;
+
+use error_stack::Report;
+
+use crate::Error;
+
+mod array;
+mod collection;
+mod option;
+mod primitive;
+mod string;
+mod wrapper;
+
+/// Error returned if an array ended before `expected` items have been deserialized.
+pub(crate) fn unexpected_end_of_array<E: Error>(expected: usize, received: usize) -> Report<E> {
+    Report::new(E::message("unexpected end of array"))
+        .attach_printable(format!("expected {expected} items, received {received}"))
+}
@@ -0,0 +1,323 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::marker::PhantomData;
+
+use error_stack::{Result, ResultExt};
+
+use crate::{ArrayAccess, Deserialize, Deserializer, Error, Number, ObjectAccess, Visitor};
+
+/// A present value, which has already been visited by [`OptionVisitor`].
+///
+/// Deserializers, which do not override [`Deserializer::deserialize_optional`], visit the value
+/// directly instead of calling [`Visitor::visit_some`], the value is then passed on to `T` through
+/// a [`VisitedDeserializer`].
+trait Visited<'de> {
+    fn visit<V>(self, visitor: V) -> Result<V::Value, V::Error>
+    where
+        V: Visitor<'de>;
+}
+
+macro_rules! impl_visited {
+    [$($ty:ty => $visit:ident,)*] => {
+        $(
+            impl<'de> Visited<'de> for $ty {
+                fn visit<V>(self, visitor: V) -> Result<V::Value, V::Error>
+                where
+                    V: Visitor<'de>,
+                {
+                    visitor.$visit(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_visited![
+    bool => visit_bool,
+    Number => visit_number,
+    String => visit_string,
+    Vec<u8> => visit_bytes_buffer,
+    i8 => visit_i8,
+    i16 => visit_i16,
+    i32 => visit_i32,
+    i64 => visit_i64,
+    i128 => visit_i128,
+    isize => visit_isize,
+    u8 => visit_u8,
+    u16 => visit_u16,
+    u32 => visit_u32,
+    u64 => visit_u64,
+    u128 => visit_u128,
+    usize => visit_usize,
+    f32 => visit_f32,
+    f64 => visit_f64,
+];
+
+impl<'de> Visited<'de> for &'de str {
+    fn visit<V>(self, visitor: V) -> Result<V::Value, V::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_borrowed_str(self)
+    }
+}
+
+impl<'de> Visited<'de> for &'de [u8] {
+    fn visit<V>(self, visitor: V) -> Result<V::Value, V::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_borrowed_bytes(self)
+    }
+}
+
+struct Char(String);
+
+impl<'de> Visited<'de> for Char {
+    fn visit<V>(self, visitor: V) -> Result<V::Value, V::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_char(self.0)
+    }
+}
+
+struct Array<A>(A);
+
+impl<'de, A: ArrayAccess<'de>> Visited<'de> for Array<A> {
+    fn visit<V>(self, visitor: V) -> Result<V::Value, V::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_array(self.0)
+    }
+}
+
+struct Object<O>(O);
+
+impl<'de, O: ObjectAccess<'de>> Visited<'de> for Object<O> {
+    fn visit<V>(self, visitor: V) -> Result<V::Value, V::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_object(self.0)
+    }
+}
+
+macro_rules! forward_to_deserialize_any {
+    [$($method:ident,)*] => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.deserialize_any(visitor)
+            }
+        )*
+    };
+}
+
+/// Deserializer over a single [`Visited`] value, which is always present.
+struct VisitedDeserializer<T, E: Error>(T, PhantomData<fn() -> E>);
+
+impl<'de, T: Visited<'de>, E: Error> Deserializer<'de> for VisitedDeserializer<T, E> {
+    type Error = E;
+
+    forward_to_deserialize_any![
+        deserialize_none,
+        deserialize_null,
+        deserialize_bool,
+        deserialize_number,
+        deserialize_char,
+        deserialize_string,
+        deserialize_str,
+        deserialize_bytes,
+        deserialize_bytes_buffer,
+        deserialize_array,
+        deserialize_object,
+    ];
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.visit(visitor).change_context(E::new())
+    }
+
+    fn deserialize_optional<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self).change_context(E::new())
+    }
+}
+
+struct OptionVisitor<T, E: Error>(PhantomData<fn() -> (T, E)>);
+
+impl<'de, T: Deserialize<'de>, E: Error> OptionVisitor<T, E> {
+    fn visit_present(value: impl Visited<'de>) -> Result<Option<T>, E> {
+        T::deserialize(VisitedDeserializer(value, PhantomData)).map(Some)
+    }
+}
+
+macro_rules! forward_to_present {
+    [$($visit:ident($ty:ty),)*] => {
+        $(
+            fn $visit(self, v: $ty) -> Result<Self::Value, Self::Error> {
+                Self::visit_present(v)
+            }
+        )*
+    };
+}
+
+impl<'de, T: Deserialize<'de>, E: Error> Visitor<'de> for OptionVisitor<T, E> {
+    type Error = E;
+    type Value = Option<T>;
+
+    forward_to_present![
+        visit_bool(bool),
+        visit_number(Number),
+        visit_string(String),
+        visit_borrowed_str(&'de str),
+        visit_bytes_buffer(Vec<u8>),
+        visit_borrowed_bytes(&'de [u8]),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_isize(isize),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_usize(usize),
+        visit_f32(f32),
+        visit_f64(f64),
+    ];
+
+    fn expecting_display(&self) -> String {
+        "optional value".to_string()
+    }
+
+    fn visit_none(self) -> Result<Self::Value, Self::Error> {
+        Ok(None)
+    }
+
+    fn visit_null(self) -> Result<Self::Value, Self::Error> {
+        Ok(None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, Self::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer)
+            .map(Some)
+            .change_context(E::new())
+    }
+
+    fn visit_char(self, v: String) -> Result<Self::Value, Self::Error> {
+        Self::visit_present(Char(v))
+    }
+
+    fn visit_str(self, v: &str) -> Result<Self::Value, Self::Error> {
+        Self::visit_present(v.to_string())
+    }
+
+    fn visit_bytes(self, v: &[u8]) -> Result<Self::Value, Self::Error> {
+        Self::visit_present(v.to_vec())
+    }
+
+    fn visit_array<A>(self, v: A) -> Result<Self::Value, Self::Error>
+    where
+        A: ArrayAccess<'de>,
+    {
+        Self::visit_present(Array(v))
+    }
+
+    fn visit_object<O>(self, v: O) -> Result<Self::Value, Self::Error>
+    where
+        O: ObjectAccess<'de>,
+    {
+        Self::visit_present(Object(v))
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Option<T> {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_optional(OptionVisitor::<T, D::Error>(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec, vec::Vec};
+
+    use crate::test::{deserialize, deserialize_err, deserialize_without_optional, Value};
+
+    #[test]
+    fn none() {
+        assert_eq!(deserialize::<Option<u8>>(Value::None).ok(), Some(None));
+        assert_eq!(deserialize::<Option<u8>>(Value::Null).ok(), Some(None));
+    }
+
+    #[test]
+    fn some() {
+        assert_eq!(
+            deserialize::<Option<u8>>(Value::number(1)).ok(),
+            Some(Some(1))
+        );
+        assert_eq!(
+            deserialize::<Option<Option<bool>>>(Value::Bool(true)).ok(),
+            Some(Some(Some(true)))
+        );
+    }
+
+    #[test]
+    fn some_invalid() {
+        let messages = deserialize_err::<Option<u8>>(Value::number(256));
+        assert!(messages.contains(&String::from("unable to represent 256 as u8")));
+
+        let messages = deserialize_err::<Option<u8>>(Value::Bool(true));
+        assert!(messages.contains(&String::from("unexpected value of type bool")));
+    }
+
+    #[test]
+    fn without_deserialize_optional() {
+        assert_eq!(
+            deserialize_without_optional::<Option<u8>>(Value::None).ok(),
+            Some(None)
+        );
+        assert_eq!(
+            deserialize_without_optional::<Option<u8>>(Value::Null).ok(),
+            Some(None)
+        );
+        assert_eq!(
+            deserialize_without_optional::<Option<u8>>(Value::number(1)).ok(),
+            Some(Some(1))
+        );
+        assert_eq!(
+            deserialize_without_optional::<Option<Option<bool>>>(Value::Bool(true)).ok(),
+            Some(Some(Some(true)))
+        );
+        assert_eq!(
+            deserialize_without_optional::<Option<String>>(Value::string("value")).ok(),
+            Some(Some(String::from("value")))
+        );
+        assert_eq!(
+            deserialize_without_optional::<Option<Vec<u8>>>(Value::Array(vec![
+                Value::number(1),
+                Value::number(2)
+            ]))
+            .ok(),
+            Some(Some(vec![1, 2]))
+        );
+
+        assert!(deserialize_without_optional::<Option<u8>>(Value::number(256)).is_err());
+        assert!(deserialize_without_optional::<Option<u8>>(Value::Bool(true)).is_err());
+    }
+}
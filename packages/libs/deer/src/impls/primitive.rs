@@ -0,0 +1,281 @@
+use alloc::string::{String, ToString};
+use core::{
+    marker::PhantomData,
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+    },
+};
+
+use error_stack::{Report, Result};
+use num_traits::ToPrimitive;
+
+use crate::{number, Deserialize, Deserializer, Error, Number, NumberVisitor, Visitor};
+
+struct BoolVisitor<E: Error>(PhantomData<fn() -> E>);
+
+impl<E: Error> Visitor<'_> for BoolVisitor<E> {
+    type Error = E;
+    type Value = bool;
+
+    fn expecting_display(&self) -> String {
+        "bool".to_string()
+    }
+
+    fn visit_bool(self, v: bool) -> Result<Self::Value, Self::Error> {
+        Ok(v)
+    }
+}
+
+impl<'de> Deserialize<'de> for bool {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_bool(BoolVisitor::<D::Error>(PhantomData))
+    }
+}
+
+impl<'de> Deserialize<'de> for Number {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_number(NumberVisitor::<D::Error>::new())
+    }
+}
+
+// Deserializers are free to either call the visit method of the primitive directly or to call
+// `visit_number`, in which case the number is converted through `ToPrimitive`.
+macro_rules! impl_primitive {
+    [$($primitive:ident: $deserialize:ident($visit:ident, $to:ident),)*] => {
+        $(impl_primitive!(#internal, $primitive; $deserialize, $visit, $to);)*
+    };
+
+    (#internal, $primitive:ident; $deserialize:ident, $visit:ident, $to:ident) => {
+        impl<'de> Deserialize<'de> for $primitive {
+            fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+                struct PrimitiveVisitor<E: Error>(PhantomData<fn() -> E>);
+
+                impl<E: Error> Visitor<'_> for PrimitiveVisitor<E> {
+                    type Error = E;
+                    type Value = $primitive;
+
+                    fn expecting_display(&self) -> String {
+                        stringify!($primitive).to_string()
+                    }
+
+                    fn visit_number(self, v: Number) -> Result<Self::Value, Self::Error> {
+                        v.$to()
+                            .ok_or_else(|| number::out_of_range(&v, stringify!($primitive)))
+                    }
+
+                    fn $visit(self, v: $primitive) -> Result<Self::Value, Self::Error> {
+                        Ok(v)
+                    }
+                }
+
+                de.$deserialize(PrimitiveVisitor::<D::Error>(PhantomData))
+            }
+        }
+    };
+}
+
+impl_primitive![
+    i8: deserialize_i8(visit_i8, to_i8),
+    i16: deserialize_i16(visit_i16, to_i16),
+    i32: deserialize_i32(visit_i32, to_i32),
+    i64: deserialize_i64(visit_i64, to_i64),
+    i128: deserialize_i128(visit_i128, to_i128),
+    isize: deserialize_isize(visit_isize, to_isize),
+    u8: deserialize_u8(visit_u8, to_u8),
+    u16: deserialize_u16(visit_u16, to_u16),
+    u32: deserialize_u32(visit_u32, to_u32),
+    u64: deserialize_u64(visit_u64, to_u64),
+    u128: deserialize_u128(visit_u128, to_u128),
+    usize: deserialize_usize(visit_usize, to_usize),
+    f32: deserialize_f32(visit_f32, to_f32),
+    f64: deserialize_f64(visit_f64, to_f64),
+];
+
+macro_rules! impl_non_zero {
+    [$($non_zero:ident($primitive:ident),)*] => {
+        $(
+            impl<'de> Deserialize<'de> for $non_zero {
+                fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+                    let value = $primitive::deserialize(de)?;
+
+                    Self::new(value).ok_or_else(|| {
+                        Report::new(D::Error::message("unexpected value of zero"))
+                            .attach_printable(concat!(
+                                stringify!($non_zero),
+                                " must not be zero"
+                            ))
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_non_zero![
+    NonZeroI8(i8),
+    NonZeroI16(i16),
+    NonZeroI32(i32),
+    NonZeroI64(i64),
+    NonZeroI128(i128),
+    NonZeroIsize(isize),
+    NonZeroU8(u8),
+    NonZeroU16(u16),
+    NonZeroU32(u32),
+    NonZeroU64(u64),
+    NonZeroU128(u128),
+    NonZeroUsize(usize),
+];
+
+struct UnitVisitor<E: Error>(PhantomData<fn() -> E>);
+
+impl<E: Error> Visitor<'_> for UnitVisitor<E> {
+    type Error = E;
+    type Value = ();
+
+    fn expecting_display(&self) -> String {
+        "null".to_string()
+    }
+
+    fn visit_null(self) -> Result<Self::Value, Self::Error> {
+        Ok(())
+    }
+}
+
+impl<'de> Deserialize<'de> for () {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_null(UnitVisitor::<D::Error>(PhantomData))
+    }
+}
+
+impl<'de, T: ?Sized> Deserialize<'de> for PhantomData<T> {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        <()>::deserialize(de).map(|()| Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+    use core::{
+        marker::PhantomData,
+        num::{NonZeroI8, NonZeroU64, NonZeroU8},
+    };
+
+    use num_traits::ToPrimitive;
+
+    use crate::{
+        test::{deserialize, deserialize_err, Value},
+        Number,
+    };
+
+    #[test]
+    fn bool() {
+        assert!(deserialize::<bool>(Value::Bool(true)).expect("should deserialize"));
+        assert!(!deserialize::<bool>(Value::Bool(false)).expect("should deserialize"));
+
+        let messages = deserialize_err::<bool>(Value::number(1));
+        assert!(messages.contains(&String::from("unexpected value of type number")));
+    }
+
+    #[test]
+    fn number() {
+        let number = deserialize::<Number>(Value::number(-12)).expect("should deserialize");
+        assert_eq!(number.to_i64(), Some(-12));
+
+        let messages = deserialize_err::<Number>(Value::string("12"));
+        assert!(messages.contains(&String::from("unexpected value of type string")));
+    }
+
+    #[test]
+    fn integer() {
+        assert_eq!(deserialize::<u8>(Value::number(255)).ok(), Some(255));
+        assert_eq!(deserialize::<i8>(Value::number(-128)).ok(), Some(-128));
+        assert_eq!(
+            deserialize::<i64>(Value::number(i64::MIN)).ok(),
+            Some(i64::MIN)
+        );
+        assert_eq!(
+            deserialize::<u64>(Value::number(i64::MAX)).ok(),
+            Some(9_223_372_036_854_775_807)
+        );
+        assert_eq!(deserialize::<i128>(Value::number(-1)).ok(), Some(-1));
+        assert_eq!(deserialize::<u128>(Value::number(1)).ok(), Some(1));
+        assert_eq!(deserialize::<isize>(Value::number(-1)).ok(), Some(-1));
+        assert_eq!(deserialize::<usize>(Value::number(1)).ok(), Some(1));
+    }
+
+    #[test]
+    fn integer_out_of_range() {
+        let messages = deserialize_err::<u8>(Value::number(256));
+        assert!(messages.contains(&String::from("provided value too large or too small")));
+        assert!(messages.contains(&String::from("unable to represent 256 as u8")));
+
+        let messages = deserialize_err::<i8>(Value::number(-129));
+        assert!(messages.contains(&String::from("unable to represent -129 as i8")));
+
+        let messages = deserialize_err::<u32>(Value::number(-1));
+        assert!(messages.contains(&String::from("unable to represent -1 as u32")));
+
+        let messages = deserialize_err::<usize>(Value::number(-1));
+        assert!(messages.contains(&String::from("unable to represent -1 as usize")));
+    }
+
+    #[test]
+    fn integer_from_float() {
+        let messages = deserialize_err::<u8>(Value::number(1.5));
+        assert!(messages.contains(&String::from("unable to represent 1.5 as u8")));
+
+        // Floats are never converted to integers, even if they don't have a fractional part
+        let messages = deserialize_err::<i32>(Value::number(2.0));
+        assert!(messages.contains(&String::from("unable to represent 2 as i32")));
+    }
+
+    #[test]
+    fn integer_from_other_type() {
+        let messages = deserialize_err::<u8>(Value::Bool(true));
+        assert!(messages.contains(&String::from("unexpected value of type bool")));
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn float() {
+        assert_eq!(deserialize::<f64>(Value::number(0.5)).ok(), Some(0.5));
+        assert_eq!(deserialize::<f64>(Value::number(-3)).ok(), Some(-3.0));
+        assert_eq!(deserialize::<f32>(Value::number(0.25)).ok(), Some(0.25));
+    }
+
+    #[test]
+    fn non_zero() {
+        assert_eq!(
+            deserialize::<NonZeroU8>(Value::number(1)).ok(),
+            Some(NonZeroU8::new(1).expect("1 is not zero"))
+        );
+        assert_eq!(
+            deserialize::<NonZeroI8>(Value::number(-1)).ok(),
+            Some(NonZeroI8::new(-1).expect("-1 is not zero"))
+        );
+
+        let messages = deserialize_err::<NonZeroU64>(Value::number(0));
+        assert!(messages.contains(&String::from("unexpected value of zero")));
+        assert!(messages.contains(&String::from("NonZeroU64 must not be zero")));
+    }
+
+    #[test]
+    fn non_zero_out_of_range() {
+        let messages = deserialize_err::<NonZeroU8>(Value::number(256));
+        assert!(messages.contains(&String::from("unable to represent 256 as u8")));
+    }
+
+    #[test]
+    fn unit() {
+        assert_eq!(deserialize::<()>(Value::Null).ok(), Some(()));
+        assert_eq!(
+            deserialize::<PhantomData<String>>(Value::Null).ok(),
+            Some(PhantomData)
+        );
+
+        let messages = deserialize_err::<()>(Value::None);
+        assert!(messages.contains(&String::from("unexpected missing value")));
+    }
+}
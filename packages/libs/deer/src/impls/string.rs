@@ -0,0 +1,135 @@
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+};
+use core::marker::PhantomData;
+
+use error_stack::{Report, Result};
+
+use crate::{Deserialize, Deserializer, Error, Visitor};
+
+struct CharVisitor<E: Error>(PhantomData<fn() -> E>);
+
+impl<E: Error> CharVisitor<E> {
+    fn from_str(v: &str) -> Result<char, E> {
+        let mut chars = v.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(char), None) => Ok(char),
+            _ => Err(Report::new(E::message("expected a single character"))
+                .attach_printable(format!("received string of length {}", v.chars().count()))),
+        }
+    }
+}
+
+impl<'de, E: Error> Visitor<'de> for CharVisitor<E> {
+    type Error = E;
+    type Value = char;
+
+    fn expecting_display(&self) -> String {
+        "char".to_string()
+    }
+
+    fn visit_char(self, v: String) -> Result<Self::Value, Self::Error> {
+        Self::from_str(&v)
+    }
+
+    fn visit_string(self, v: String) -> Result<Self::Value, Self::Error> {
+        Self::from_str(&v)
+    }
+
+    fn visit_str(self, v: &str) -> Result<Self::Value, Self::Error> {
+        Self::from_str(v)
+    }
+
+    fn visit_borrowed_str(self, v: &'de str) -> Result<Self::Value, Self::Error> {
+        Self::from_str(v)
+    }
+}
+
+impl<'de> Deserialize<'de> for char {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_char(CharVisitor::<D::Error>(PhantomData))
+    }
+}
+
+struct StringVisitor<E: Error>(PhantomData<fn() -> E>);
+
+impl<'de, E: Error> Visitor<'de> for StringVisitor<E> {
+    type Error = E;
+    type Value = String;
+
+    fn expecting_display(&self) -> String {
+        "string".to_string()
+    }
+
+    fn visit_char(self, v: String) -> Result<Self::Value, Self::Error> {
+        Ok(v)
+    }
+
+    fn visit_string(self, v: String) -> Result<Self::Value, Self::Error> {
+        Ok(v)
+    }
+
+    fn visit_str(self, v: &str) -> Result<Self::Value, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn visit_borrowed_str(self, v: &'de str) -> Result<Self::Value, Self::Error> {
+        Ok(v.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for String {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        de.deserialize_string(StringVisitor::<D::Error>(PhantomData))
+    }
+}
+
+impl<'de> Deserialize<'de> for Box<str> {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        String::deserialize(de).map(String::into_boxed_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{boxed::Box, string::String};
+
+    use crate::test::{deserialize, deserialize_err, Value};
+
+    #[test]
+    fn char() {
+        assert_eq!(deserialize::<char>(Value::string("a")).ok(), Some('a'));
+        assert_eq!(deserialize::<char>(Value::string("é")).ok(), Some('é'));
+    }
+
+    #[test]
+    fn char_of_invalid_length() {
+        let messages = deserialize_err::<char>(Value::string("ab"));
+        assert!(messages.contains(&String::from("expected a single character")));
+        assert!(messages.contains(&String::from("received string of length 2")));
+
+        let messages = deserialize_err::<char>(Value::string(""));
+        assert!(messages.contains(&String::from("received string of length 0")));
+    }
+
+    #[test]
+    fn string() {
+        assert_eq!(
+            deserialize::<String>(Value::string("deer")).ok(),
+            Some(String::from("deer"))
+        );
+        assert_eq!(
+            deserialize::<Box<str>>(Value::string("deer")).ok(),
+            Some(Box::from("deer"))
+        );
+    }
+
+    #[test]
+    fn string_of_other_type() {
+        let messages = deserialize_err::<String>(Value::number(1));
+        assert!(messages.contains(&String::from("unexpected value of type number")));
+    }
+}
@@ -0,0 +1,103 @@
+use alloc::{boxed::Box, rc::Rc, sync::Arc};
+use core::{
+    cell::{Cell, RefCell},
+    cmp::Reverse,
+    num::Wrapping,
+};
+#[cfg(feature = "std")]
+use std::sync::{Mutex, RwLock};
+
+use error_stack::Result;
+
+use crate::{Deserialize, Deserializer};
+
+macro_rules! impl_wrapper {
+    [$($wrapper:ident),* $(,)?] => {
+        $(
+            impl<'de, T: Deserialize<'de>> Deserialize<'de> for $wrapper<T> {
+                fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+                    T::deserialize(de).map($wrapper::new)
+                }
+            }
+        )*
+    };
+}
+
+impl_wrapper![Box, Rc, Arc, Cell, RefCell];
+
+#[cfg(feature = "std")]
+impl_wrapper![Mutex, RwLock];
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Wrapping<T> {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        T::deserialize(de).map(Wrapping)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Reverse<T> {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        T::deserialize(de).map(Reverse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{boxed::Box, rc::Rc, string::String, sync::Arc};
+    use core::{
+        cell::{Cell, RefCell},
+        cmp::Reverse,
+        num::Wrapping,
+    };
+    #[cfg(feature = "std")]
+    use std::sync::{Mutex, RwLock};
+
+    use crate::test::{deserialize, deserialize_err, Value};
+
+    #[test]
+    fn wrapper() {
+        assert_eq!(
+            deserialize::<Box<u8>>(Value::number(1)).ok(),
+            Some(Box::new(1))
+        );
+        assert_eq!(
+            deserialize::<Rc<u8>>(Value::number(1)).ok(),
+            Some(Rc::new(1))
+        );
+        assert_eq!(
+            deserialize::<Arc<u8>>(Value::number(1)).ok(),
+            Some(Arc::new(1))
+        );
+        assert_eq!(
+            deserialize::<Cell<u8>>(Value::number(1)).ok(),
+            Some(Cell::new(1))
+        );
+        assert_eq!(
+            deserialize::<RefCell<u8>>(Value::number(1)).ok(),
+            Some(RefCell::new(1))
+        );
+        assert_eq!(
+            deserialize::<Wrapping<u8>>(Value::number(1)).ok(),
+            Some(Wrapping(1))
+        );
+        assert_eq!(
+            deserialize::<Reverse<u8>>(Value::number(1)).ok(),
+            Some(Reverse(1))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn lock() {
+        let mutex = deserialize::<Mutex<u8>>(Value::number(1)).expect("should deserialize");
+        assert_eq!(mutex.into_inner().ok(), Some(1));
+
+        let lock = deserialize::<RwLock<u8>>(Value::number(1)).expect("should deserialize");
+        assert_eq!(lock.into_inner().ok(), Some(1));
+    }
+
+    #[test]
+    fn wrapper_invalid() {
+        let messages = deserialize_err::<Box<u8>>(Value::number(256));
+        assert!(messages.contains(&String::from("unable to represent 256 as u8")));
+    }
+}
@@ -13,37 +13,55 @@ use alloc::{
 };
 use core::marker::PhantomData;
 
-use error_stack::{IntoReport, Report, Result, ResultExt};
+use error_stack::{Report, Result, ResultExt};
 use num_traits::ToPrimitive;
 
 pub use crate::{error::Error, number::Number};
 
 mod error;
+mod impls;
 mod number;
+#[cfg(test)]
+mod test;
 
 extern crate alloc;
 
+/// Provides access to the entries of an object, passed to [`Visitor::visit_object`].
 pub trait ObjectAccess<'de> {
     type Error: Error;
 
+    /// Deserialize the value of the entry with the key `key`.
     fn value<T>(&mut self, key: &str) -> Result<T, Self::Error>
     where
         T: Deserialize<'de>;
 
+    /// Deserialize the next entry, returns `None` if all entries have been consumed.
     fn next<T>(&mut self) -> Result<Option<(String, T)>, Self::Error>
     where
         T: Deserialize<'de>;
 
+    /// Finish the deserialization of the object.
+    ///
+    /// # Errors
+    ///
+    /// Entries have not been consumed
     fn finish(self) -> Result<(), Self::Error>;
 }
 
+/// Provides access to the items of an array, passed to [`Visitor::visit_array`].
 pub trait ArrayAccess<'de> {
     type Error: Error;
 
+    /// Deserialize the next item, returns `None` if all items have been consumed.
     fn next<T>(&mut self) -> Result<Option<T>, Self::Error>
     where
         T: Deserialize<'de>;
 
+    /// Finish the deserialization of the array.
+    ///
+    /// # Errors
+    ///
+    /// Items have not been consumed
     fn finish(self) -> Result<(), Self::Error>;
 }
 
@@ -78,6 +96,15 @@ pub trait Visitor<'de>: Sized {
         )))
     }
 
+    /// Called by [`Deserializer::deserialize_optional`] if a value is present, the value can be
+    /// deserialized from the [`Deserializer`] passed to this method.
+    fn visit_some<D>(self, _: D) -> Result<Self::Value, Self::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Err(Report::new(Self::Error::message("unexpected value")))
+    }
+
     fn visit_bool(self, v: bool) -> Result<Self::Value, Self::Error> {
         Err(Report::new(Self::Error::message(
             "unexpected value of type bool",
@@ -259,11 +286,11 @@ impl<E: Error> Visitor<'_> for NumberVisitor<E> {
 }
 
 macro_rules! derive_from_number {
-    [$($method:ident ($to:ident) -> $visit:ident,)*] => {
-        $(derive_from_number!(#internal, $method; $to, $visit);)*
+    [$($method:ident ($primitive:ident: $to:ident) -> $visit:ident,)*] => {
+        $(derive_from_number!(#internal, $method; $primitive, $to, $visit);)*
     };
 
-    (#internal, $method:ident; $to:ident, $visit:ident) => {
+    (#internal, $method:ident; $primitive:ident, $to:ident, $visit:ident) => {
         /// Automatically implemented convenience method, which uses [`Self::deserialize_number`]
         /// to extract a value of the primitive type, will otherwise error out.
         ///
@@ -277,8 +304,7 @@ macro_rules! derive_from_number {
             let n = self.deserialize_number(NumberVisitor::<Self::Error>::new())?;
             let v = n
                 .$to()
-                .ok_or_else(|| Self::Error::message("provided value too large or too small"))
-                .into_report()?;
+                .ok_or_else(|| number::out_of_range::<Self::Error>(&n, stringify!($primitive)))?;
 
             visitor.$visit(v).change_context(Self::Error::new())
         }
@@ -339,14 +365,34 @@ pub trait Deserializer<'de>: Sized {
     /// Deserialize a `null` (or equivalent type) value
     ///
     /// This type should signal the explicit absence of a value, not to be confused with the
+    /// missing of a value (`none`).
     ///
     /// # Errors
     ///
     /// Current value is not of type null
-    fn deserialize_null<V>(self, visitor: V) -> Result<V, Self::Error>
+    fn deserialize_null<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>;
 
+    /// Deserialize a value, which might be missing or `null`.
+    ///
+    /// Calls [`Visitor::visit_none`] if the value is missing, [`Visitor::visit_null`] if the value
+    /// is `null`, otherwise [`Visitor::visit_some`] is called with the [`Deserializer`] itself.
+    ///
+    /// The default implementation delegates to [`Self::deserialize_any`], which calls the visit
+    /// method of the present value instead of [`Visitor::visit_some`]. Formats, which are able to
+    /// tell whether a value is present, should override this method.
+    ///
+    /// # Errors
+    ///
+    /// The visitor was unable to deserialize the value
+    fn deserialize_optional<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
     /// Deserialize a [`bool`] value.
     ///
     /// Some formats might not have this concept and should always error out if that is the case.
@@ -440,22 +486,22 @@ pub trait Deserializer<'de>: Sized {
         V: Visitor<'de>;
 
     derive_from_number![
-        deserialize_i8(to_i8) -> visit_i8,
-        deserialize_i16(to_i16) -> visit_i16,
-        deserialize_i32(to_i32) -> visit_i32,
-        deserialize_i64(to_i64) -> visit_i64,
-        deserialize_i128(to_i128) -> visit_i128,
-        deserialize_isize(to_isize) -> visit_isize,
-
-        deserialize_u8(to_u8) -> visit_u8,
-        deserialize_u16(to_u16) -> visit_u16,
-        deserialize_u32(to_u32) -> visit_u32,
-        deserialize_u64(to_u64) -> visit_u64,
-        deserialize_u128(to_u128) -> visit_u128,
-        deserialize_usize(to_usize) -> visit_usize,
-
-        deserialize_f32(to_f32) -> visit_f32,
-        deserialize_f64(to_f64) -> visit_f64,
+        deserialize_i8(i8: to_i8) -> visit_i8,
+        deserialize_i16(i16: to_i16) -> visit_i16,
+        deserialize_i32(i32: to_i32) -> visit_i32,
+        deserialize_i64(i64: to_i64) -> visit_i64,
+        deserialize_i128(i128: to_i128) -> visit_i128,
+        deserialize_isize(isize: to_isize) -> visit_isize,
+
+        deserialize_u8(u8: to_u8) -> visit_u8,
+        deserialize_u16(u16: to_u16) -> visit_u16,
+        deserialize_u32(u32: to_u32) -> visit_u32,
+        deserialize_u64(u64: to_u64) -> visit_u64,
+        deserialize_u128(u128: to_u128) -> visit_u128,
+        deserialize_usize(usize: to_usize) -> visit_usize,
+
+        deserialize_f32(f32: to_f32) -> visit_f32,
+        deserialize_f64(f64: to_f64) -> visit_f64,
     ];
 }
 
@@ -1,14 +1,23 @@
+use alloc::format;
+use core::fmt::{Display, Formatter};
+
+use error_stack::Report;
 #[cfg(feature = "arbitrary-precision")]
 use num_bigint::{BigInt, BigUint, ToBigInt, ToBigUint};
 use num_traits::{FromPrimitive, ToPrimitive};
 #[cfg(feature = "arbitrary-precision")]
 use rust_decimal::Decimal;
 
+use crate::Error;
+
 // This indirection helps us to "disguise" the underlying storage, enabling us to seamlessly convert
 // and change the underlying storage at a later point in time, if required.
 #[derive(Debug, Clone)]
 enum OpaqueNumber {
     Int(i64),
+    /// Only used for integers, which are larger than `i64::MAX`.
+    #[cfg(not(feature = "arbitrary-precision"))]
+    UInt(u64),
     #[cfg(feature = "arbitrary-precision")]
     BigInt(BigInt),
     Float(f64),
@@ -24,10 +33,10 @@ enum OpaqueNumber {
 ///
 /// This type also enables easy coercion of values at deserialization time.
 ///
-/// Without the `arbitrary-precision` feature enabled, integers are limited to `i64`, while floats
-/// are stored as `f64`, larger values are only supported using the aforementioned feature and are
-/// stored as [`BigInt`], [`Decimal`] respectively, there is no guarantee that the storage of
-/// arbitrarily sized values will be the same across breaking revisions.
+/// Without the `arbitrary-precision` feature enabled, integers are limited to `i64` and `u64`,
+/// while floats are stored as `f64`, larger values are only supported using the aforementioned
+/// feature and are stored as [`BigInt`], [`Decimal`] respectively, there is no guarantee that the
+/// storage of arbitrarily sized values will be the same across breaking revisions.
 ///
 /// Even with `arbitrary-precision` enabled, this type will try to fit the converted value into a
 /// `i64`, if that isn't possible it will fallback to a [`BigInt`].
@@ -36,6 +45,28 @@ enum OpaqueNumber {
 #[derive(Debug, Clone)]
 pub struct Number(OpaqueNumber);
 
+impl Display for Number {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match &self.0 {
+            OpaqueNumber::Int(x) => Display::fmt(x, f),
+            #[cfg(not(feature = "arbitrary-precision"))]
+            OpaqueNumber::UInt(x) => Display::fmt(x, f),
+            #[cfg(feature = "arbitrary-precision")]
+            OpaqueNumber::BigInt(x) => Display::fmt(x, f),
+            OpaqueNumber::Float(x) => Display::fmt(x, f),
+            #[cfg(feature = "arbitrary-precision")]
+            OpaqueNumber::Decimal(x) => Display::fmt(x, f),
+        }
+    }
+}
+
+/// Error returned if `number` cannot be converted into the primitive type `primitive`, either
+/// because it is out of range or because it has a fractional part.
+pub(crate) fn out_of_range<E: Error>(number: &Number, primitive: &str) -> Report<E> {
+    Report::new(E::message("provided value too large or too small"))
+        .attach_printable(format!("unable to represent {number} as {primitive}"))
+}
+
 impl FromPrimitive for Number {
     fn from_isize(n: isize) -> Option<Self> {
         if let Ok(ok) = i64::try_from(n) {
@@ -78,15 +109,7 @@ impl FromPrimitive for Number {
     }
 
     fn from_usize(n: usize) -> Option<Self> {
-        if let Ok(ok) = i64::try_from(n) {
-            return Some(Self(OpaqueNumber::Int(ok)));
-        }
-
-        #[cfg(feature = "arbitrary-precision")]
-        return Some(Self(OpaqueNumber::BigInt(n.into())));
-
-        #[cfg(not(feature = "arbitrary-precision"))]
-        return None;
+        u64::try_from(n).ok().and_then(Self::from_u64)
     }
 
     fn from_u8(n: u8) -> Option<Self> {
@@ -110,7 +133,7 @@ impl FromPrimitive for Number {
         return Some(Self(OpaqueNumber::BigInt(n.into())));
 
         #[cfg(not(feature = "arbitrary-precision"))]
-        return None;
+        return Some(Self(OpaqueNumber::UInt(n)));
     }
 
     fn from_u128(n: u128) -> Option<Self> {
@@ -122,7 +145,7 @@ impl FromPrimitive for Number {
         return Some(Self(OpaqueNumber::BigInt(n.into())));
 
         #[cfg(not(feature = "arbitrary-precision"))]
-        return None;
+        return u64::try_from(n).ok().map(|n| Self(OpaqueNumber::UInt(n)));
     }
 
     fn from_f32(n: f32) -> Option<Self> {
@@ -139,6 +162,8 @@ macro_rules! to_int {
         fn $method(&self) -> Option<$ty> {
             match &self.0 {
                 OpaqueNumber::Int(x) => x.$method(),
+                #[cfg(not(feature = "arbitrary-precision"))]
+                OpaqueNumber::UInt(x) => x.$method(),
                 #[cfg(feature = "arbitrary-precision")]
                 OpaqueNumber::BigInt(x) => x.$method(),
 
@@ -173,6 +198,8 @@ impl ToPrimitive for Number {
     fn to_f32(&self) -> Option<f32> {
         match &self.0 {
             OpaqueNumber::Int(x) => x.to_f32(),
+            #[cfg(not(feature = "arbitrary-precision"))]
+            OpaqueNumber::UInt(x) => x.to_f32(),
             #[cfg(feature = "arbitrary-precision")]
             OpaqueNumber::BigInt(x) => x.to_f32(),
 
@@ -185,6 +212,8 @@ impl ToPrimitive for Number {
     fn to_f64(&self) -> Option<f64> {
         match &self.0 {
             OpaqueNumber::Int(x) => x.to_f64(),
+            #[cfg(not(feature = "arbitrary-precision"))]
+            OpaqueNumber::UInt(x) => x.to_f64(),
             #[cfg(feature = "arbitrary-precision")]
             OpaqueNumber::BigInt(x) => x.to_f64(),
 
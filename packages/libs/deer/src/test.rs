@@ -0,0 +1,359 @@
+//! A minimal, self-describing [`Deserializer`] for testing the [`Deserialize`] implementations.
+
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec::{self, Vec},
+};
+use core::fmt::{self, Display, Formatter};
+
+use error_stack::{AttachmentKind, Context, FrameKind, Report, Result, ResultExt};
+
+use crate::{Deserialize, Deserializer, Error, Number, Visitor};
+
+/// A value of the data model of `deer`.
+#[derive(Debug, Clone)]
+pub(crate) enum Value {
+    None,
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<Self>),
+    Object(Vec<(String, Self)>),
+}
+
+impl Value {
+    pub(crate) fn number(value: impl Into<Number>) -> Self {
+        Self::Number(value.into())
+    }
+
+    pub(crate) fn string(value: &str) -> Self {
+        Self::String(value.to_owned())
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TestError(String);
+
+impl Display for TestError {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        fmt.write_str(&self.0)
+    }
+}
+
+impl Context for TestError {}
+
+impl Error for TestError {
+    fn message(contents: &str) -> Self {
+        Self(contents.to_owned())
+    }
+
+    fn new() -> Self {
+        Self("unable to deserialize value".to_owned())
+    }
+}
+
+/// Deserializes `T` from `value`.
+pub(crate) fn deserialize<'de, T: Deserialize<'de>>(value: Value) -> Result<T, TestError> {
+    T::deserialize(ValueDeserializer(value))
+}
+
+/// Deserializes `T` from `value` with a [`Deserializer`], which relies on the default
+/// implementation of [`Deserializer::deserialize_optional`].
+pub(crate) fn deserialize_without_optional<'de, T: Deserialize<'de>>(
+    value: Value,
+) -> Result<T, TestError> {
+    T::deserialize(AnyDeserializer(value))
+}
+
+/// Deserializes `T` from `value` and returns the messages of all contexts and printable
+/// attachments of the error.
+///
+/// # Panics
+///
+/// if `T` could be deserialized from `value`
+pub(crate) fn deserialize_err<'de, T: Deserialize<'de>>(value: Value) -> Vec<String> {
+    deserialize::<T>(value)
+        .err()
+        .expect("value should not be deserializable")
+        .frames()
+        .filter_map(|frame| match frame.kind() {
+            FrameKind::Context(context) => Some(context.to_string()),
+            FrameKind::Attachment(AttachmentKind::Printable(attachment)) => {
+                Some(attachment.to_string())
+            }
+            FrameKind::Attachment(_) => None,
+        })
+        .collect()
+}
+
+/// Returns an error if not all items or entries have been consumed.
+fn ensure_consumed(remaining: usize) -> Result<(), TestError> {
+    if remaining == 0 {
+        Ok(())
+    } else {
+        Err(
+            Report::new(TestError::message("unexpected trailing values"))
+                .attach_printable(format!("{remaining} values remaining")),
+        )
+    }
+}
+
+struct ValueDeserializer(Value);
+
+impl<'de> Deserializer<'de> for ValueDeserializer {
+    type Error = TestError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let result = match self.0 {
+            Value::None => visitor.visit_none(),
+            Value::Null => visitor.visit_null(),
+            Value::Bool(value) => visitor.visit_bool(value),
+            Value::Number(value) => visitor.visit_number(value),
+            Value::String(value) => visitor.visit_string(value),
+            Value::Array(items) => visitor.visit_array(ArrayAccess(items.into_iter())),
+            Value::Object(entries) => visitor.visit_object(ObjectAccess(entries)),
+        };
+
+        result.change_context(TestError::new())
+    }
+
+    fn deserialize_none<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_null<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_optional<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let result = match self.0 {
+            Value::None => visitor.visit_none(),
+            Value::Null => visitor.visit_null(),
+            _ => visitor.visit_some(self),
+        };
+
+        result.change_context(TestError::new())
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_number<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_bytes_buffer<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_array<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_object<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+}
+
+/// Drives every visitor through [`ValueDeserializer::deserialize_any`], without overriding
+/// [`Deserializer::deserialize_optional`].
+struct AnyDeserializer(Value);
+
+impl<'de> Deserializer<'de> for AnyDeserializer {
+    type Error = TestError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        ValueDeserializer(self.0).deserialize_any(visitor)
+    }
+
+    fn deserialize_none<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_null<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_number<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_bytes_buffer<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_array<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_object<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+}
+
+struct ArrayAccess(vec::IntoIter<Value>);
+
+impl<'de> crate::ArrayAccess<'de> for ArrayAccess {
+    type Error = TestError;
+
+    fn next<T>(&mut self) -> Result<Option<T>, Self::Error>
+    where
+        T: Deserialize<'de>,
+    {
+        self.0.next().map(deserialize).transpose()
+    }
+
+    fn finish(self) -> Result<(), Self::Error> {
+        ensure_consumed(self.0.len())
+    }
+}
+
+struct ObjectAccess(Vec<(String, Value)>);
+
+impl<'de> crate::ObjectAccess<'de> for ObjectAccess {
+    type Error = TestError;
+
+    fn value<T>(&mut self, key: &str) -> Result<T, Self::Error>
+    where
+        T: Deserialize<'de>,
+    {
+        let value = self
+            .0
+            .iter()
+            .position(|(entry, _)| entry == key)
+            .map_or(Value::None, |index| self.0.remove(index).1);
+
+        deserialize(value)
+    }
+
+    fn next<T>(&mut self) -> Result<Option<(String, T)>, Self::Error>
+    where
+        T: Deserialize<'de>,
+    {
+        if self.0.is_empty() {
+            return Ok(None);
+        }
+
+        let (key, value) = self.0.remove(0);
+        deserialize(value).map(|value| Some((key, value)))
+    }
+
+    fn finish(self) -> Result<(), Self::Error> {
+        ensure_consumed(self.0.len())
+    }
+}
@@ -1,6 +1,6 @@
-//! Example of using `attach` to set a custom exit code. Requires nightly and std feature.
+//! Example of using `attach` to set a custom exit code. Requires the std feature.
 
-use std::process::{ExitCode, Termination};
+use std::process::ExitCode;
 
 use error_stack::{Context, Report};
 
@@ -20,5 +20,5 @@ fn main() -> ExitCode {
         .attach(ExitCode::from(100))
         .attach_printable("this error has an exit code of 100!");
 
-    report.report()
+    report.exit_code()
 }
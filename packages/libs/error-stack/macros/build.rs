@@ -0,0 +1,11 @@
+use rustc_version::{version_meta, Channel};
+
+fn main() {
+    let version_meta = version_meta().unwrap();
+
+    // The derived `Context::provide` is only available on nightly, as the macro runs with the same
+    // toolchain as the crate using it, we detect the channel here.
+    if version_meta.channel == Channel::Nightly {
+        println!("cargo:rustc-cfg=nightly")
+    }
+}
@@ -0,0 +1,301 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    parse::ParseStream, punctuated::Punctuated, Attribute, Data, DataEnum, DeriveInput, Error,
+    Expr, Field, Fields, Ident, LitStr, Result, Token,
+};
+
+/// The arguments of a `#[display("…", args…)]` attribute.
+#[derive(Clone)]
+struct Display {
+    format: LitStr,
+    args: Punctuated<Expr, Token![,]>,
+}
+
+impl Display {
+    fn from_attributes(attributes: &[Attribute]) -> Result<Option<Self>> {
+        let mut display = None;
+
+        for attribute in attributes {
+            if !attribute.path.is_ident("display") {
+                continue;
+            }
+            if display.is_some() {
+                return Err(Error::new_spanned(
+                    attribute,
+                    "duplicate `#[display(\"…\")]` attribute",
+                ));
+            }
+            display = Some(attribute.parse_args_with(Self::parse)?);
+        }
+
+        Ok(display)
+    }
+
+    fn parse(input: ParseStream) -> Result<Self> {
+        let format: LitStr = input.parse()?;
+        let args: Punctuated<Expr, Token![,]> = if input.is_empty() {
+            Punctuated::new()
+        } else {
+            input.parse::<Token![,]>()?;
+            Punctuated::parse_terminated(input)?
+        };
+
+        let (format_string, has_positional) = positional_to_named(&format.value());
+        // `{0}` would be ambiguous: It refers to the first field, but `write!` would use the first
+        // positional argument
+        if has_positional && args.iter().any(|arg| !matches!(arg, Expr::Assign(_))) {
+            return Err(Error::new_spanned(
+                format,
+                "positional placeholders like `{0}` refer to the fields and can't be combined \
+                 with positional arguments, use named arguments instead",
+            ));
+        }
+
+        Ok(Self {
+            format: LitStr::new(&format_string, format.span()),
+            args,
+        })
+    }
+
+    fn write(&self, formatter: &Ident) -> TokenStream {
+        let Self { format, args } = self;
+        let args = args.iter();
+
+        quote!(::core::write!(#formatter, #format #(, #args)*))
+    }
+}
+
+/// Replaces positional arguments in a format string by the bindings of the tuple fields, e.g.
+/// `{0}` becomes `{_0}`.
+///
+/// Returns the rewritten format string and whether it contained a positional argument.
+fn positional_to_named(format: &str) -> (String, bool) {
+    let mut output = String::with_capacity(format.len());
+    let mut has_positional = false;
+    let mut chars = format.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        output.push(char);
+        if char != '{' {
+            continue;
+        }
+
+        match chars.peek() {
+            // escaped brace
+            Some('{') => output.extend(chars.next()),
+            Some(char) if char.is_ascii_digit() => {
+                output.push('_');
+                has_positional = true;
+            }
+            _ => {}
+        }
+    }
+
+    (output, has_positional)
+}
+
+/// The values passed to `#[provide(…)]` on a type or a variant and the fields marked with
+/// `#[provide]`.
+#[derive(Default)]
+struct Provide {
+    values: Vec<Expr>,
+    fields: Vec<Ident>,
+}
+
+impl Provide {
+    fn from_attributes(attributes: &[Attribute], fields: &Fields) -> Result<Self> {
+        let mut provide = Self::default();
+
+        for attribute in attributes {
+            if !attribute.path.is_ident("provide") {
+                continue;
+            }
+            if attribute.tokens.is_empty() {
+                return Err(Error::new_spanned(
+                    attribute,
+                    "expected values to provide, e.g. `#[provide(ExitCode::FAILURE)]`",
+                ));
+            }
+            provide.values.extend(
+                attribute.parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated)?,
+            );
+        }
+
+        for (index, field) in fields.iter().enumerate() {
+            for attribute in &field.attrs {
+                if !attribute.path.is_ident("provide") {
+                    continue;
+                }
+                if !attribute.tokens.is_empty() {
+                    return Err(Error::new_spanned(
+                        attribute,
+                        "`#[provide]` on a field does not take any arguments",
+                    ));
+                }
+                provide.fields.push(binding(index, field));
+            }
+        }
+
+        Ok(provide)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.values.is_empty() && self.fields.is_empty()
+    }
+
+    fn provide(&self, demand: &Ident) -> TokenStream {
+        let Self { values, fields } = self;
+
+        quote! {
+            #( #demand.provide_ref(#fields); )*
+            #( #demand.provide_value(#values); )*
+        }
+    }
+}
+
+/// Returns the identifier, which a field is bound to in a pattern.
+///
+/// Named fields are bound to their name, tuple fields are bound to `_0`, `_1`, ….
+fn binding(index: usize, field: &Field) -> Ident {
+    field
+        .ident
+        .clone()
+        .unwrap_or_else(|| format_ident!("_{}", index))
+}
+
+/// Returns a pattern, which destructures `path` and binds all fields.
+fn pattern(path: &TokenStream, fields: &Fields) -> TokenStream {
+    let bindings = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| binding(index, field));
+
+    match fields {
+        Fields::Named(_) => quote!(#path { #(#bindings),* }),
+        Fields::Unnamed(_) => quote!(#path ( #(#bindings),* )),
+        Fields::Unit => quote!(#path),
+    }
+}
+
+/// A struct or a single variant of an enum.
+struct Variant {
+    pattern: TokenStream,
+    display: Display,
+    provide: Provide,
+}
+
+fn variants(input: &DeriveInput) -> Result<(Vec<Variant>, Provide)> {
+    let display = Display::from_attributes(&input.attrs)?;
+
+    match &input.data {
+        Data::Struct(data) => {
+            let display = display.ok_or_else(|| {
+                Error::new_spanned(&input.ident, "missing `#[display(\"…\")]` attribute")
+            })?;
+            let variant = Variant {
+                pattern: pattern(&quote!(Self), &data.fields),
+                display,
+                provide: Provide::from_attributes(&input.attrs, &data.fields)?,
+            };
+
+            Ok((vec![variant], Provide::default()))
+        }
+        Data::Enum(DataEnum { variants, .. }) => {
+            let variants = variants
+                .iter()
+                .map(|variant| {
+                    let ident = &variant.ident;
+                    let display = match Display::from_attributes(&variant.attrs)? {
+                        Some(display) => display,
+                        None => display.clone().ok_or_else(|| {
+                            Error::new_spanned(
+                                ident,
+                                "missing `#[display(\"…\")]` attribute on the variant or on the \
+                                 enum",
+                            )
+                        })?,
+                    };
+
+                    Ok(Variant {
+                        pattern: pattern(&quote!(Self::#ident), &variant.fields),
+                        display,
+                        provide: Provide::from_attributes(&variant.attrs, &variant.fields)?,
+                    })
+                })
+                .collect::<Result<_>>()?;
+
+            Ok((
+                variants,
+                Provide::from_attributes(&input.attrs, &Fields::Unit)?,
+            ))
+        }
+        Data::Union(data) => Err(Error::new_spanned(
+            data.union_token,
+            "`Context` cannot be derived for unions",
+        )),
+    }
+}
+
+pub(crate) fn derive(input: &DeriveInput) -> Result<TokenStream> {
+    let (variants, common) = variants(input)?;
+
+    // These identifiers are not visible to the format strings and the provided values, so they
+    // cannot clash with the field bindings.
+    let formatter = Ident::new("formatter", Span::mixed_site());
+    let demand = Ident::new("demand", Span::mixed_site());
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // An empty enum cannot be matched by reference
+    let scrutinee = if variants.is_empty() {
+        quote!(*self)
+    } else {
+        quote!(self)
+    };
+
+    let display = variants.iter().map(|variant| {
+        let pattern = &variant.pattern;
+        let write = variant.display.write(&formatter);
+        quote!(#pattern => #write,)
+    });
+
+    let provide = if cfg!(nightly)
+        && !(common.is_empty() && variants.iter().all(|variant| variant.provide.is_empty()))
+    {
+        let common = common.provide(&demand);
+        let variants = variants.iter().map(|variant| {
+            let pattern = &variant.pattern;
+            let provide = variant.provide.provide(&demand);
+            quote!(#pattern => { #provide })
+        });
+
+        quote! {
+            #[allow(unused_variables)]
+            fn provide<'a>(&'a self, #demand: &mut ::core::any::Demand<'a>) {
+                #common
+                match #scrutinee {
+                    #(#variants)*
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn fmt(&self, #formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match #scrutinee {
+                    #(#display)*
+                }
+            }
+        }
+
+        impl #impl_generics ::error_stack::Context for #ident #ty_generics #where_clause {
+            #provide
+        }
+    })
+}
@@ -1 +1,130 @@
-//! Placeholder for future support of macros.
+//! Derive macros for the [`error-stack`] crate.
+//!
+//! [`error-stack`]: https://docs.rs/error-stack
+#![warn(
+    missing_docs,
+    unreachable_pub,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::undocumented_unsafe_blocks
+)]
+#![allow(clippy::redundant_pub_crate)] // This would otherwise clash with `unreachable_pub`
+
+mod context;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Derives [`Display`] and [`Context`] for a struct or an enum.
+///
+/// # Display
+///
+/// The message is set with the `#[display("…")]` attribute, which accepts the same arguments as
+/// [`write!`]. The fields can be referred to in the format string by their name, or, for tuple
+/// structs and tuple variants, by their index. As the index refers to a field, it can't be combined
+/// with positional arguments, named arguments are used instead:
+///
+/// ```rust
+/// use error_stack::Report;
+/// use error_stack_macros::Context;
+///
+/// #[derive(Debug, Context)]
+/// #[display("could not parse {path:?} at line {line}")]
+/// struct ParseError {
+///     path: String,
+///     line: usize,
+/// }
+///
+/// #[derive(Debug, Context)]
+/// #[display("invalid port {0}, expected a value up to {max}", max = u16::MAX)]
+/// struct InvalidPort(u32);
+///
+/// let report = Report::new(ParseError {
+///     path: "config.toml".to_owned(),
+///     line: 4,
+/// });
+/// assert_eq!(
+///     report.to_string(),
+///     r#"could not parse "config.toml" at line 4"#
+/// );
+/// assert_eq!(
+///     InvalidPort(70000).to_string(),
+///     "invalid port 70000, expected a value up to 65535"
+/// );
+/// ```
+///
+/// For enums, every variant has its own message. A `#[display("…")]` attribute on the enum is
+/// used for all variants without an attribute:
+///
+/// ```rust
+/// use error_stack_macros::Context;
+///
+/// #[derive(Debug, Context)]
+/// #[display("the configuration is invalid")]
+/// enum ConfigError {
+///     #[display("the configuration file {0:?} does not exist")]
+///     NotFound(String),
+///     #[display("the key {key} is set multiple times")]
+///     DuplicateKey {
+///         key: String,
+///     },
+///     Invalid,
+/// }
+///
+/// assert_eq!(
+///     ConfigError::NotFound("config.toml".to_owned()).to_string(),
+///     r#"the configuration file "config.toml" does not exist"#
+/// );
+/// assert_eq!(
+///     ConfigError::DuplicateKey {
+///         key: "port".to_owned()
+///     }
+///     .to_string(),
+///     "the key port is set multiple times"
+/// );
+/// assert_eq!(
+///     ConfigError::Invalid.to_string(),
+///     "the configuration is invalid"
+/// );
+/// ```
+///
+/// # Provided values
+///
+/// Values can be provided to [`Report::request_ref`] and [`Report::request_value`]:
+///
+/// - `#[provide(value, …)]` on the type or on a variant provides the values, which may refer to
+///   `self`. On an enum, values on the type are provided for every variant.
+/// - `#[provide]` on a field provides a reference to the field.
+///
+/// ```rust
+/// # #![cfg_attr(nightly, feature(provide_any))]
+/// use std::process::ExitCode;
+///
+/// use error_stack_macros::Context;
+///
+/// #[derive(Debug, Context)]
+/// #[display("the request failed with status {status}")]
+/// #[provide(ExitCode::from(2))]
+/// struct RequestError {
+///     #[provide]
+///     status: u16,
+/// }
+/// ```
+///
+/// As [`Context::provide`] is only available on a nightly toolchain, provided values are ignored
+/// on stable. On nightly, the crate deriving `Context` with provided values has to enable the
+/// `provide_any` feature.
+///
+/// [`Display`]: core::fmt::Display
+/// [`Context`]: https://docs.rs/error-stack/latest/error_stack/trait.Context.html
+/// [`Context::provide`]: https://docs.rs/error-stack/latest/error_stack/trait.Context.html#method.provide
+/// [`Report::request_ref`]: https://docs.rs/error-stack/latest/error_stack/struct.Report.html#method.request_ref
+/// [`Report::request_value`]: https://docs.rs/error-stack/latest/error_stack/struct.Report.html#method.request_value
+#[proc_macro_derive(Context, attributes(display, provide))]
+pub fn derive_context(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    context::derive(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,178 @@
+#![cfg_attr(nightly, feature(provide_any))]
+
+use core::fmt;
+
+use error_stack::Report;
+use error_stack_macros::Context;
+
+#[derive(Debug, Context)]
+#[display("root error")]
+struct RootError;
+
+#[derive(Debug, Context)]
+#[display("could not parse {path:?} at line {line}")]
+struct NamedError {
+    path: &'static str,
+    line: usize,
+}
+
+#[derive(Debug, Context)]
+#[display("expected a value between {min} and {max}, got {0}", min = 1, max = u8::MAX)]
+struct TupleError(u32);
+
+#[derive(Debug, Context)]
+#[display("{{0}} is not interpolated, but {0} is")]
+struct EscapedError(&'static str);
+
+#[derive(Debug, Context)]
+#[display("the formatter is named {formatter}")]
+struct ShadowingError {
+    formatter: &'static str,
+}
+
+#[derive(Debug, Context)]
+#[display("generic error: {0}")]
+struct GenericError<T: fmt::Display + fmt::Debug + Send + Sync + 'static>(T);
+
+#[derive(Debug, Context)]
+#[display("configuration error")]
+enum ConfigError {
+    #[display("the file {0:?} does not exist")]
+    NotFound(&'static str),
+    #[display("the key {key} is set {count} times")]
+    DuplicateKey {
+        key: &'static str,
+        count: usize,
+    },
+    Invalid,
+}
+
+#[derive(Debug)]
+struct ExitCode(u8);
+
+#[derive(Debug, Context)]
+#[display("request failed with status {status}")]
+#[provide(ExitCode(2))]
+struct ProvideError {
+    #[provide]
+    status: u16,
+}
+
+#[derive(Debug, Context)]
+#[display("command failed")]
+#[provide(ExitCode(1))]
+enum ProvideEnum {
+    #[provide(u64::from(*code))]
+    Exit {
+        code: u32,
+    },
+    Signal(#[provide] i32),
+}
+
+#[test]
+fn unit_struct() {
+    assert_eq!(Report::new(RootError).to_string(), "root error");
+}
+
+#[test]
+fn named_fields() {
+    let error = NamedError {
+        path: "config.toml",
+        line: 4,
+    };
+    assert_eq!(
+        error.to_string(),
+        r#"could not parse "config.toml" at line 4"#
+    );
+}
+
+#[test]
+fn tuple_fields() {
+    assert_eq!(
+        TupleError(300).to_string(),
+        "expected a value between 1 and 255, got 300"
+    );
+}
+
+#[test]
+fn escaped() {
+    assert_eq!(
+        EscapedError("zero").to_string(),
+        "{0} is not interpolated, but zero is"
+    );
+}
+
+#[test]
+fn shadowing() {
+    let error = ShadowingError { formatter: "test" };
+    assert_eq!(error.to_string(), "the formatter is named test");
+}
+
+#[test]
+fn generics() {
+    assert_eq!(GenericError(5).to_string(), "generic error: 5");
+    assert_eq!(
+        Report::new(GenericError("a")).to_string(),
+        "generic error: a"
+    );
+}
+
+#[test]
+fn enum_variants() {
+    assert_eq!(
+        ConfigError::NotFound("config.toml").to_string(),
+        r#"the file "config.toml" does not exist"#
+    );
+    assert_eq!(
+        ConfigError::DuplicateKey {
+            key: "port",
+            count: 2
+        }
+        .to_string(),
+        "the key port is set 2 times"
+    );
+    assert_eq!(ConfigError::Invalid.to_string(), "configuration error");
+}
+
+#[test]
+#[cfg(nightly)]
+fn provide_struct() {
+    let report = Report::new(ProvideError { status: 404 });
+
+    assert_eq!(report.request_ref::<u16>().next(), Some(&404));
+    assert_eq!(
+        report
+            .request_value::<ExitCode>()
+            .map(|code| code.0)
+            .collect::<Vec<_>>(),
+        [2]
+    );
+}
+
+#[test]
+#[cfg(nightly)]
+fn provide_enum() {
+    let report = Report::new(ProvideEnum::Exit { code: 3 });
+    assert_eq!(report.request_value::<u64>().collect::<Vec<_>>(), [3]);
+    assert_eq!(report.request_ref::<i32>().count(), 0);
+    assert_eq!(
+        report
+            .request_value::<ExitCode>()
+            .map(|code| code.0)
+            .collect::<Vec<_>>(),
+        [1]
+    );
+
+    let report = Report::new(ProvideEnum::Signal(9));
+    assert_eq!(report.request_ref::<i32>().next(), Some(&9));
+    assert_eq!(report.request_value::<u64>().count(), 0);
+}
+
+#[test]
+fn display_without_provide() {
+    assert_eq!(ProvideEnum::Exit { code: 3 }.to_string(), "command failed");
+    assert_eq!(
+        ProvideError { status: 500 }.to_string(),
+        "request failed with status 500"
+    );
+}
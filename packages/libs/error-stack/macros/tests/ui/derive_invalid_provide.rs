@@ -0,0 +1,21 @@
+use error_stack_macros::Context;
+
+#[derive(Debug, Context)]
+#[display("provide without values")]
+#[provide]
+struct ProvideWithoutValues;
+
+#[derive(Debug, Context)]
+#[display("provide with arguments on a field")]
+struct ProvideFieldWithArguments {
+    #[provide(0)]
+    code: u8,
+}
+
+#[derive(Context)]
+#[display("union")]
+union Union {
+    code: u8,
+}
+
+fn main() {}
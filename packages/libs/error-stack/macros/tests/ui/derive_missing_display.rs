@@ -0,0 +1,26 @@
+use error_stack_macros::Context;
+
+#[derive(Debug, Context)]
+struct MissingDisplay;
+
+#[derive(Debug, Context)]
+enum MissingVariantDisplay {
+    #[display("variant with display")]
+    WithDisplay,
+    WithoutDisplay,
+}
+
+#[derive(Debug, Context)]
+#[display("first")]
+#[display("second")]
+struct DuplicateDisplay;
+
+#[derive(Debug, Context)]
+#[display(missing_format_string)]
+struct InvalidDisplay;
+
+#[derive(Debug, Context)]
+#[display("{0} is not between {} and {}", 1, 10)]
+struct PositionalDisplay(u8);
+
+fn main() {}
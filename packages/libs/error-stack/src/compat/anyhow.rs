@@ -37,3 +37,13 @@ impl<T> IntoReportCompat for core::result::Result<T, AnyhowError> {
         }
     }
 }
+
+/// Converts the [`Report`] into an [`anyhow::Error`] by wrapping it into a [`ReportError`].
+///
+/// [`ReportError`]: crate::ReportError
+#[cfg(feature = "std")]
+impl<C: 'static> From<Report<C>> for AnyhowError {
+    fn from(report: Report<C>) -> Self {
+        Self::new(report.into_error())
+    }
+}
@@ -32,3 +32,12 @@ impl<T> IntoReportCompat for core::result::Result<T, EyreReport> {
         }
     }
 }
+
+/// Converts the [`Report`] into an [`eyre::Report`] by wrapping it into a [`ReportError`].
+///
+/// [`ReportError`]: crate::ReportError
+impl<C: 'static> From<Report<C>> for EyreReport {
+    fn from(report: Report<C>) -> Self {
+        Self::new(report.into_error())
+    }
+}
@@ -0,0 +1,158 @@
+use alloc::boxed::Box;
+#[cfg(nightly)]
+use core::error::Error;
+use core::fmt;
+#[cfg(all(not(nightly), feature = "std"))]
+use std::error::Error;
+
+use crate::{Frame, FrameKind, Report};
+
+/// A [`Report`] converted into an [`Error`].
+///
+/// `Report` itself does not implement [`Error`], as every [`Error`] is a valid [`Context`], which
+/// would allow to nest `Report`s. `ReportError` is the adaptor to pass a `Report` to APIs
+/// expecting an [`Error`], e.g. a `Box<dyn Error + Send + Sync>`. It is created by
+/// [`Report::into_error`] or by converting the `Report` into a boxed [`Error`] directly.
+///
+/// The [`Display`] and [`Debug`] output are the same as the output of the `Report`.
+/// [`Error::source`] returns the contexts of the `Report`, following the first source if a
+/// `Report` has multiple sources. Attachments are only visible in the [`Debug`] output.
+///
+/// ## Example
+///
+/// ```rust
+/// use std::{error::Error, fmt, io};
+///
+/// use error_stack::{Context, Report, ReportError};
+///
+/// #[derive(Debug)]
+/// struct ConfigError;
+///
+/// impl fmt::Display for ConfigError {
+///     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         fmt.write_str("could not load the configuration")
+///     }
+/// }
+///
+/// impl Context for ConfigError {}
+///
+/// let report = Report::new(io::Error::from(io::ErrorKind::NotFound)).change_context(ConfigError);
+///
+/// let error: Box<dyn Error + Send + Sync> = report.into();
+/// assert_eq!(error.to_string(), "could not load the configuration");
+/// assert_eq!(error.source().unwrap().to_string(), "entity not found");
+///
+/// // The `Report` can be recovered by downcasting the error
+/// let report = error
+///     .downcast::<ReportError<ConfigError>>()
+///     .unwrap()
+///     .into_report();
+/// assert!(report.contains::<io::Error>());
+/// ```
+///
+/// [`Context`]: crate::Context
+/// [`Display`]: core::fmt::Display
+/// [`Debug`]: core::fmt::Debug
+pub struct ReportError<C> {
+    report: Report<C>,
+}
+
+impl<C> ReportError<C> {
+    /// Returns a reference to the wrapped [`Report`].
+    pub const fn report(&self) -> &Report<C> {
+        &self.report
+    }
+
+    /// Converts the `ReportError` back into the wrapped [`Report`].
+    #[allow(clippy::missing_const_for_fn)] // `ReportError` can't be destructured in a `const fn`
+    pub fn into_report(self) -> Report<C> {
+        self.report
+    }
+}
+
+impl<C> Report<C> {
+    /// Converts the `Report` into a [`ReportError`], which implements [`Error`].
+    ///
+    /// See [`ReportError`] for further information.
+    #[must_use]
+    pub const fn into_error(self) -> ReportError<C> {
+        ReportError { report: self }
+    }
+}
+
+impl<C> fmt::Debug for ReportError<C> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.report, fmt)
+    }
+}
+
+impl<C> fmt::Display for ReportError<C> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.report, fmt)
+    }
+}
+
+impl<C> Error for ReportError<C> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        let current = first_context(self.report.current_frames())?;
+        first_context(current.sources()).map(|frame| FrameError::new(frame) as &dyn Error)
+    }
+}
+
+impl<C: 'static> From<Report<C>> for Box<dyn Error + Send + Sync> {
+    fn from(report: Report<C>) -> Self {
+        Box::new(report.into_error())
+    }
+}
+
+impl<C: 'static> From<Report<C>> for Box<dyn Error> {
+    fn from(report: Report<C>) -> Self {
+        Box::new(report.into_error())
+    }
+}
+
+/// Returns the first context in `frames`, following the first source of every frame.
+fn first_context(frames: &[Frame]) -> Option<&Frame> {
+    let mut frame = frames.first()?;
+
+    while !matches!(frame.kind(), FrameKind::Context(_)) {
+        frame = frame.sources().first()?;
+    }
+
+    Some(frame)
+}
+
+/// A context inside of a [`ReportError`], which is returned by [`Error::source`].
+#[repr(transparent)]
+struct FrameError(Frame);
+
+impl FrameError {
+    const fn new(frame: &Frame) -> &Self {
+        // SAFETY: `FrameError` is `#[repr(transparent)]` over `Frame`
+        unsafe { &*(frame as *const Frame).cast::<Self>() }
+    }
+}
+
+impl fmt::Debug for FrameError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.kind() {
+            FrameKind::Context(context) => fmt::Debug::fmt(context, fmt),
+            FrameKind::Attachment(_) => unreachable!("only contexts are converted into errors"),
+        }
+    }
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.kind() {
+            FrameKind::Context(context) => fmt::Display::fmt(context, fmt),
+            FrameKind::Attachment(_) => unreachable!("only contexts are converted into errors"),
+        }
+    }
+}
+
+impl Error for FrameError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        first_context(self.0.sources()).map(|frame| Self::new(frame) as &dyn Error)
+    }
+}
@@ -0,0 +1,305 @@
+// We allow `unreachable_pub` on no-std, because in that case we do not export (`pub`) the
+// structures contained in here, but still use them.
+#![cfg_attr(not(feature = "std"), allow(unreachable_pub, dead_code))]
+
+use core::{
+    fmt,
+    fmt::{Display, Write},
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+#[cfg(feature = "pretty-print")]
+use owo_colors::{OwoColorize, Style as OwOStyle};
+
+#[cfg(feature = "std")]
+use crate::Report;
+
+static CHARSET: AtomicU8 = AtomicU8::new(Charset::DEFAULT as u8);
+static COLOR_MODE: AtomicU8 = AtomicU8::new(ColorMode::DEFAULT as u8);
+static COLOR_STREAM: AtomicU8 = AtomicU8::new(ColorStream::DEFAULT as u8);
+
+/// The set of characters used to render the tree of the [`Debug`] output.
+///
+/// Set through [`Report::set_charset`].
+///
+/// [`Debug`]: core::fmt::Debug
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Charset {
+    /// Box-drawing characters like `├`, `─` and `▶`.
+    ///
+    /// This is the default if the `pretty-print` feature is enabled.
+    Utf8,
+    /// Only ASCII characters like `|`, `-` and `>`.
+    ///
+    /// This is the default if the `pretty-print` feature is disabled.
+    Ascii,
+}
+
+impl Charset {
+    #[cfg(feature = "pretty-print")]
+    const DEFAULT: Self = Self::Utf8;
+    #[cfg(not(feature = "pretty-print"))]
+    const DEFAULT: Self = Self::Ascii;
+
+    /// Returns the currently configured `Charset`.
+    pub(crate) fn load() -> Self {
+        match CHARSET.load(Ordering::Relaxed) {
+            0 => Self::Utf8,
+            _ => Self::Ascii,
+        }
+    }
+}
+
+/// Whether colors are used in the [`Debug`] output.
+///
+/// Set through [`Report::set_color_mode`]. Colors are only ever emitted if the `pretty-print`
+/// feature is enabled.
+///
+/// [`Debug`]: core::fmt::Debug
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ColorMode {
+    /// Use colors if the [`ColorStream`] is a terminal which supports them.
+    ///
+    /// This honours the [`NO_COLOR`](http://no-color.org/) and `FORCE_COLOR` environment
+    /// variables as well as [`owo_colors::set_override`].
+    ///
+    /// This is the default.
+    ///
+    /// [`owo_colors::set_override`]: https://docs.rs/owo-colors/latest/owo_colors/fn.set_override.html
+    Auto,
+    /// Always use colors.
+    Always,
+    /// Never use colors.
+    Never,
+}
+
+impl ColorMode {
+    const DEFAULT: Self = Self::Auto;
+
+    /// Returns the currently configured `ColorMode`.
+    pub(crate) fn load() -> Self {
+        match COLOR_MODE.load(Ordering::Relaxed) {
+            0 => Self::Auto,
+            1 => Self::Always,
+            _ => Self::Never,
+        }
+    }
+}
+
+/// The stream, which is probed for color support if the [`ColorMode`] is [`ColorMode::Auto`].
+///
+/// Set through [`Report::set_color_stream`]. This should be the stream the [`Debug`] output is
+/// written to.
+///
+/// [`Debug`]: core::fmt::Debug
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ColorStream {
+    /// The standard output, this is the default.
+    Stdout,
+    /// The standard error.
+    Stderr,
+}
+
+impl ColorStream {
+    const DEFAULT: Self = Self::Stdout;
+
+    /// Returns the currently configured `ColorStream`.
+    pub(crate) fn load() -> Self {
+        match COLOR_STREAM.load(Ordering::Relaxed) {
+            0 => Self::Stdout,
+            _ => Self::Stderr,
+        }
+    }
+}
+
+#[cfg(feature = "pretty-print")]
+impl From<ColorStream> for owo_colors::Stream {
+    fn from(stream: ColorStream) -> Self {
+        match stream {
+            ColorStream::Stdout => Self::Stdout,
+            ColorStream::Stderr => Self::Stderr,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Report<()> {
+    /// Globally sets the [`Charset`] used to render the [`Debug`] output of a [`Report`].
+    ///
+    /// Use [`Charset::Ascii`] if the output is displayed by a system, which is unable to render
+    /// box-drawing characters, like some CI consoles or log aggregators.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{Error, ErrorKind};
+    ///
+    /// use error_stack::{
+    ///     fmt::{Charset, ColorMode},
+    ///     Report,
+    /// };
+    ///
+    /// Report::set_charset(Charset::Ascii);
+    /// Report::set_color_mode(ColorMode::Never);
+    ///
+    /// let report = Report::new(Error::from(ErrorKind::InvalidInput))
+    ///     .attach_printable("unable to parse the configuration");
+    ///
+    /// let output = format!("{report:?}");
+    /// assert!(output.is_ascii());
+    /// assert!(output.contains("|- unable to parse the configuration"));
+    /// ```
+    ///
+    /// [`Debug`]: core::fmt::Debug
+    pub fn set_charset(charset: Charset) {
+        CHARSET.store(charset as u8, Ordering::Relaxed);
+    }
+
+    /// Globally sets the [`ColorMode`] used to render the [`Debug`] output of a [`Report`].
+    ///
+    /// Use [`ColorMode::Never`] if the output is written to a file while the probed
+    /// [`ColorStream`] is a terminal, otherwise the file will contain ANSI escape codes.
+    ///
+    /// [`Debug`]: core::fmt::Debug
+    pub fn set_color_mode(mode: ColorMode) {
+        COLOR_MODE.store(mode as u8, Ordering::Relaxed);
+    }
+
+    /// Globally sets the [`ColorStream`], which is probed for color support if the [`ColorMode`]
+    /// is [`ColorMode::Auto`].
+    ///
+    /// By default, the standard output is probed, call this with [`ColorStream::Stderr`] if
+    /// [`Report`]s are printed to the standard error instead.
+    ///
+    /// [`Debug`]: core::fmt::Debug
+    pub fn set_color_stream(stream: ColorStream) {
+        COLOR_STREAM.store(stream as u8, Ordering::Relaxed);
+    }
+}
+
+/// Foreground colors used in the [`Debug`] output.
+///
+/// [`Debug`]: core::fmt::Debug
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(not(feature = "pretty-print"), allow(dead_code))]
+pub(crate) enum Color {
+    Red,
+    BrightBlack,
+}
+
+/// Small compatability layer between owocolors regardless if we use pretty-print or not
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Style {
+    bold: bool,
+    color: Option<Color>,
+}
+
+impl Style {
+    pub(crate) const fn new() -> Self {
+        Self {
+            bold: false,
+            color: None,
+        }
+    }
+
+    pub(crate) const fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    pub(crate) const fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+#[cfg(feature = "pretty-print")]
+impl From<Style> for OwOStyle {
+    fn from(value: Style) -> Self {
+        let mut this = Self::new();
+
+        if value.bold {
+            this = this.bold();
+        }
+
+        match value.color {
+            Some(Color::Red) => this = this.red(),
+            Some(Color::BrightBlack) => this = this.bright_black(),
+            None => {}
+        }
+
+        this
+    }
+}
+
+/// Snapshot of the global configuration, taken once per rendered [`Report`].
+///
+/// [`Report`]: crate::Report
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Config {
+    charset: Charset,
+    #[cfg_attr(not(feature = "pretty-print"), allow(dead_code))]
+    color_mode: ColorMode,
+    #[cfg_attr(not(feature = "pretty-print"), allow(dead_code))]
+    color_stream: ColorStream,
+}
+
+impl Config {
+    pub(crate) fn load() -> Self {
+        Self {
+            charset: Charset::load(),
+            color_mode: ColorMode::load(),
+            color_stream: ColorStream::load(),
+        }
+    }
+
+    /// Configuration independent of the global settings and the enabled features, used for
+    /// snapshots.
+    #[cfg(feature = "testing")]
+    pub(crate) const fn normalized() -> Self {
+        Self {
+            charset: Charset::Utf8,
+            color_mode: ColorMode::Never,
+            color_stream: ColorStream::Stdout,
+        }
+    }
+
+    pub(crate) const fn charset(self) -> Charset {
+        self.charset
+    }
+
+    /// Writes `value` with the `style` applied, if colors are enabled.
+    #[cfg(feature = "pretty-print")]
+    pub(crate) fn write_styled(
+        self,
+        output: &mut impl Write,
+        value: &impl Display,
+        style: Style,
+    ) -> fmt::Result {
+        match self.color_mode {
+            ColorMode::Auto => write!(
+                output,
+                "{}",
+                value
+                    .if_supports_color(self.color_stream.into(), |value| value.style(style.into()))
+            ),
+            ColorMode::Always => write!(output, "{}", value.style(style.into())),
+            ColorMode::Never => write!(output, "{value}"),
+        }
+    }
+
+    /// Writes `value`, styles are ignored without the `pretty-print` feature.
+    #[cfg(not(feature = "pretty-print"))]
+    #[allow(clippy::unused_self)] // Mirrors the signature with `pretty-print` enabled
+    pub(crate) fn write_styled(
+        self,
+        output: &mut impl Write,
+        value: &impl Display,
+        _: Style,
+    ) -> fmt::Result {
+        write!(output, "{value}")
+    }
+}
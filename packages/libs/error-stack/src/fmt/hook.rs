@@ -49,6 +49,7 @@ pub(crate) struct HookContextInner {
     storage: Storage,
 
     alternate: bool,
+    normalized: bool,
 
     body: Vec<String>,
     appendix: Vec<String>,
@@ -79,6 +80,7 @@ impl HookContextInner {
             body: Vec::new(),
             appendix: Vec::new(),
             alternate,
+            normalized: false,
         }
     }
 }
@@ -278,6 +280,17 @@ impl<T> HookContext<T> {
         }
     }
 
+    /// Omits locations, backtraces and span traces from the output.
+    #[cfg(feature = "testing")]
+    pub(crate) const fn normalized(mut self) -> Self {
+        self.inner.normalized = true;
+        self
+    }
+
+    pub(crate) const fn is_normalized(&self) -> bool {
+        self.inner.normalized
+    }
+
     pub(crate) fn appendix(&self) -> &[String] {
         &self.inner.appendix
     }
@@ -763,7 +776,9 @@ impl Hooks {
     }
 
     pub(crate) fn call(&self, frame: &Frame, context: &mut HookContext<Frame>) {
-        self.call_location(frame, context);
+        if !context.is_normalized() {
+            self.call_location(frame, context);
+        }
 
         for (_, hook) in &self.inner {
             hook(frame, context);
@@ -776,7 +791,7 @@ mod default {
 
     #[cfg(any(rust_1_65, feature = "spantrace"))]
     use alloc::format;
-    use alloc::{vec, vec::Vec};
+    use alloc::{string::String, vec, vec::Vec};
     use core::any::TypeId;
     #[cfg(rust_1_65)]
     use std::backtrace::Backtrace;
@@ -788,11 +803,11 @@ mod default {
         },
     };
 
-    #[cfg(feature = "pretty-print")]
-    use owo_colors::{OwoColorize, Stream};
     #[cfg(feature = "spantrace")]
     use tracing_error::SpanTrace;
 
+    #[cfg(feature = "pretty-print")]
+    use crate::fmt::{Color, Config, Style};
     use crate::{
         fmt::hook::{into_boxed_hook, BoxedHook, HookContext},
         Frame, Report,
@@ -831,10 +846,13 @@ mod default {
 
     fn location(location: &Location<'static>, context: &mut HookContext<Location<'static>>) {
         #[cfg(feature = "pretty-print")]
-        context.push_body(format!(
-            "{}",
-            location.if_supports_color(Stream::Stdout, OwoColorize::bright_black)
-        ));
+        {
+            let mut body = String::new();
+            Config::load()
+                .write_styled(&mut body, location, Style::new().color(Color::BrightBlack))
+                .expect("writing to a `String` is infallible");
+            context.push_body(body);
+        }
 
         #[cfg(not(feature = "pretty-print"))]
         context.push_body(format!("at {location}"));
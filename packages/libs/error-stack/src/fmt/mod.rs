@@ -1,6 +1,11 @@
 //! Implementation of formatting, to enable colors and the use of box-drawing characters use the
 //! `pretty-print` feature.
 //!
+//! The characters used to render the tree and whether colors are used can be set globally through
+//! [`Report::set_charset`] and [`Report::set_color_mode`]. By default, colors are used if the
+//! standard output supports them, [`Report::set_color_stream`] changes the stream to probe, e.g.
+//! if [`Report`]s are printed to the standard error.
+//!
 //! # Hooks
 //!
 //! The [`Debug`] implementation can be easily extended using hooks. Hooks are functions of the
@@ -140,6 +145,7 @@
 //! [`atomic`]: std::sync::atomic
 //! [`Error::provide`]: core::error::Error::provide
 
+mod config;
 #[cfg(feature = "std")]
 mod hook;
 
@@ -158,12 +164,15 @@ use core::{
     mem,
 };
 
+#[cfg(not(feature = "std"))]
+use config::Charset;
+#[cfg(feature = "std")]
+pub use config::{Charset, ColorMode, ColorStream};
+pub(crate) use config::{Color, Config, Style};
 #[cfg(feature = "std")]
 pub use hook::HookContext;
 #[cfg(feature = "std")]
 pub(crate) use hook::{install_builtin_hooks, Hooks};
-#[cfg(feature = "pretty-print")]
-use owo_colors::{OwoColorize, Stream, Style as OwOStyle};
 
 use crate::{AttachmentKind, Context, Frame, FrameKind, Report};
 
@@ -184,7 +193,7 @@ enum Symbol {
 /// between [`Instruction`] to [`Symbol`] harder to comprehend for a user.
 ///
 /// This macro fixes this by creating a compile-time lookup table to easily map every character in
-/// the [`Display`] of [`Symbol`] to it's corresponding symbol.
+/// [`Symbol::as_str`] to it's corresponding symbol.
 ///
 /// # Example
 ///
@@ -236,61 +245,26 @@ macro_rules! sym {
     };
 }
 
-#[cfg(feature = "pretty-print")]
-impl Display for Symbol {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Vertical => f.write_str("│"),
-            Self::VerticalRight => f.write_str("├"),
-            Self::Horizontal => f.write_str("─"),
-            Self::HorizontalLeft => f.write_str("╴"),
-            Self::HorizontalDown => f.write_str("┬"),
-            Self::ArrowRight => f.write_str("▶"),
-            Self::CurveRight => f.write_str("╰"),
-            Self::Space => f.write_str(" "),
-        }
-    }
-}
-
-#[cfg(not(feature = "pretty-print"))]
-impl Display for Symbol {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Vertical | Self::VerticalRight | Self::CurveRight => f.write_str("|"),
-            Self::Horizontal | Self::HorizontalDown | Self::HorizontalLeft => f.write_str("-"),
-            Self::ArrowRight => f.write_str(">"),
-            Self::Space => f.write_str(" "),
-        }
-    }
-}
-
-/// Small compatability layer between owocolors regardless if we use pretty-print or not
-#[derive(Debug, Copy, Clone)]
-struct Style {
-    bold: bool,
-}
-
-impl Style {
-    const fn new() -> Self {
-        Self { bold: false }
-    }
-
-    const fn bold(mut self) -> Self {
-        self.bold = true;
-        self
-    }
-}
-
-#[cfg(feature = "pretty-print")]
-impl From<Style> for OwOStyle {
-    fn from(value: Style) -> Self {
-        let mut this = Self::new();
-
-        if value.bold {
-            this = this.bold();
+impl Symbol {
+    const fn as_str(self, charset: Charset) -> &'static str {
+        match charset {
+            Charset::Utf8 => match self {
+                Self::Vertical => "│",
+                Self::VerticalRight => "├",
+                Self::Horizontal => "─",
+                Self::HorizontalLeft => "╴",
+                Self::HorizontalDown => "┬",
+                Self::ArrowRight => "▶",
+                Self::CurveRight => "╰",
+                Self::Space => " ",
+            },
+            Charset::Ascii => match self {
+                Self::Vertical | Self::VerticalRight | Self::CurveRight => "|",
+                Self::Horizontal | Self::HorizontalDown | Self::HorizontalLeft => "-",
+                Self::ArrowRight => ">",
+                Self::Space => " ",
+            },
         }
-
-        this
     }
 }
 
@@ -467,38 +441,21 @@ impl Instruction {
     }
 }
 
-#[cfg(feature = "pretty-print")]
-impl Display for Instruction {
-    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+impl Instruction {
+    fn render(&self, config: Config, output: &mut String) -> fmt::Result {
         match self.prepare() {
             PreparedInstruction::Symbols(symbols) => {
                 for symbol in symbols {
-                    Display::fmt(
-                        &symbol.if_supports_color(Stream::Stdout, OwoColorize::red),
-                        fmt,
+                    config.write_styled(
+                        output,
+                        &symbol.as_str(config.charset()),
+                        Style::new().color(Color::Red),
                     )?;
                 }
             }
-            PreparedInstruction::Content(value, &style) => Display::fmt(
-                &value.if_supports_color(Stream::Stdout, |value| value.style(style.into())),
-                fmt,
-            )?,
-        }
-
-        Ok(())
-    }
-}
-
-#[cfg(not(feature = "pretty-print"))]
-impl Display for Instruction {
-    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
-        match self.prepare() {
-            PreparedInstruction::Symbols(symbols) => {
-                for symbol in symbols {
-                    Display::fmt(symbol, fmt)?;
-                }
+            PreparedInstruction::Content(value, &style) => {
+                config.write_styled(output, &value, style)?;
             }
-            PreparedInstruction::Content(value, _) => fmt.write_str(value)?,
         }
 
         Ok(())
@@ -523,13 +480,17 @@ impl Line {
     }
 }
 
-impl Display for Line {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+impl Line {
+    fn render(&self, config: Config) -> String {
+        let mut output = String::new();
+
         for instruction in self.0.iter().rev() {
-            Display::fmt(instruction, f)?;
+            instruction
+                .render(config, &mut output)
+                .expect("writing to a `String` is infallible");
         }
 
-        Ok(())
+        output
     }
 }
 
@@ -667,12 +628,39 @@ impl Opaque {
     }
 }
 
+/// Returns if `frame` holds information, which was automatically captured from the environment.
+#[cfg(feature = "testing")]
+fn is_captured(frame: &Frame) -> bool {
+    #[cfg(rust_1_65)]
+    if frame.is::<std::backtrace::Backtrace>() {
+        return true;
+    }
+
+    #[cfg(feature = "spantrace")]
+    if frame.is::<tracing_error::SpanTrace>() {
+        return true;
+    }
+
+    false
+}
+
 fn debug_attachments_invoke(
     frames: Vec<&Frame>,
     #[cfg(feature = "std")] context: &mut HookContext<Frame>,
 ) -> (Opaque, Vec<String>) {
     let mut opaque = Opaque::new();
 
+    // Backtraces and span traces depend on the environment and are omitted from snapshots
+    #[cfg(feature = "testing")]
+    let frames: Vec<_> = if context.is_normalized() {
+        frames
+            .into_iter()
+            .filter(|frame| !is_captured(frame))
+            .collect()
+    } else {
+        frames
+    };
+
     let body = frames
         .into_iter()
         .map(|frame| match frame.kind() {
@@ -683,11 +671,16 @@ fn debug_attachments_invoke(
             }
             #[cfg(all(not(feature = "std"), feature = "pretty-print"))]
             FrameKind::Context(_) => {
-                let location = frame
-                    .location()
-                    .if_supports_color(Stream::Stdout, OwoColorize::bright_black);
+                let mut location = String::new();
+                Config::load()
+                    .write_styled(
+                        &mut location,
+                        frame.location(),
+                        Style::new().color(Color::BrightBlack),
+                    )
+                    .expect("writing to a `String` is infallible");
 
-                vec![format!("{location}")]
+                vec![location]
             }
             #[cfg(all(not(feature = "std"), not(feature = "pretty-print")))]
             FrameKind::Context(_) => {
@@ -950,71 +943,77 @@ impl<C> Debug for Report<C> {
             return result;
         }
 
-        #[cfg(feature = "std")]
-        let mut context = HookContext::new(fmt.alternate());
+        fmt.write_str(&debug_report(
+            self.current_frames(),
+            Config::load(),
+            #[cfg(feature = "std")]
+            HookContext::new(fmt.alternate()),
+        ))
+    }
+}
 
-        #[cfg_attr(not(feature = "std"), allow(unused_mut))]
-        let mut lines = self
-            .current_frames()
+/// Renders the tree of `frames` followed by the appendix, which is the default [`Debug`] output of
+/// a [`Report`].
+pub(crate) fn debug_report(
+    frames: &[Frame],
+    config: Config,
+    #[cfg(feature = "std")] mut context: HookContext<Frame>,
+) -> String {
+    #[cfg_attr(not(feature = "std"), allow(unused_mut))]
+    let mut lines = frames
+        .iter()
+        .flat_map(|frame| {
+            debug_frame(
+                frame,
+                &[],
+                #[cfg(feature = "std")]
+                &mut context,
+            )
+        })
+        .enumerate()
+        .flat_map(|(idx, lines)| {
+            if idx == 0 {
+                lines.into_vec()
+            } else {
+                lines
+                    .before(
+                        Line::new().push(Indent::no_group().visible(false).spacing(None).into()),
+                    )
+                    .into_vec()
+            }
+        })
+        .map(|line| line.render(config))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    #[cfg(feature = "std")]
+    {
+        let appendix = context
+            .appendix()
             .iter()
-            .flat_map(|frame| {
-                debug_frame(
-                    frame,
-                    &[],
-                    #[cfg(feature = "std")]
-                    &mut context,
-                )
-            })
-            .enumerate()
-            .flat_map(|(idx, lines)| {
-                if idx == 0 {
-                    lines.into_vec()
-                } else {
-                    lines
-                        .before(
-                            Line::new()
-                                .push(Indent::no_group().visible(false).spacing(None).into()),
-                        )
-                        .into_vec()
-                }
-            })
-            .map(|line| line.to_string())
+            .map(
+                // remove all trailing newlines for a more uniform look
+                |snippet| snippet.trim_end_matches('\n').to_owned(),
+            )
             .collect::<Vec<_>>()
-            .join("\n");
+            .join("\n\n");
 
-        #[cfg(feature = "std")]
-        {
-            let appendix = context
-                .appendix()
-                .iter()
-                .map(
-                    // remove all trailing newlines for a more uniform look
-                    |snippet| snippet.trim_end_matches('\n').to_owned(),
-                )
-                .collect::<Vec<_>>()
-                .join("\n\n");
-
-            if !appendix.is_empty() {
-                // 44 is the size for the separation.
-                lines.reserve(44 + appendix.len());
-
-                lines.push_str("\n\n");
-                #[cfg(feature = "pretty-print")]
-                {
-                    lines.push_str(&"━".repeat(40));
-                }
-                #[cfg(not(feature = "pretty-print"))]
-                {
-                    lines.push_str(&"=".repeat(40));
-                }
+        if !appendix.is_empty() {
+            // 44 is the size for the separation.
+            lines.reserve(44 + appendix.len());
 
-                lines.push_str("\n\n");
-                lines.push_str(&appendix);
+            lines.push_str("\n\n");
+            match config.charset() {
+                Charset::Utf8 => lines.push_str(&"━".repeat(40)),
+                Charset::Ascii => lines.push_str(&"=".repeat(40)),
             }
-        }
 
-        fmt.write_str(&lines)
+            lines.push_str("\n\n");
+            lines.push_str(&appendix);
+        }
     }
+
+    lines
 }
 
 impl<Context> Display for Report<Context> {
@@ -4,7 +4,7 @@ use core::any::Demand;
 #[cfg(any(feature = "anyhow", feature = "eyre"))]
 use core::fmt;
 use core::{
-    any::TypeId,
+    any::{self, TypeId},
     fmt::{Debug, Display},
     panic::Location,
 };
@@ -31,6 +31,11 @@ pub(super) unsafe trait FrameImpl: Send + Sync + 'static {
     /// `type_id`.
     fn type_id(&self) -> TypeId;
 
+    /// Returns the name of the type returned by [`type_id`].
+    ///
+    /// [`type_id`]: Self::type_id
+    fn type_name(&self) -> &'static str;
+
     /// Provide values which can then be requested.
     #[cfg(nightly)]
     fn provide<'a>(&'a self, demand: &mut Demand<'a>);
@@ -51,6 +56,10 @@ unsafe impl<C: Context> FrameImpl for ContextFrame<C> {
         TypeId::of::<C>()
     }
 
+    fn type_name(&self) -> &'static str {
+        any::type_name::<C>()
+    }
+
     #[cfg(nightly)]
     fn provide<'a>(&'a self, demand: &mut Demand<'a>) {
         Context::provide(&self.context, demand);
@@ -72,6 +81,10 @@ unsafe impl<A: 'static + Send + Sync> FrameImpl for AttachmentFrame<A> {
         TypeId::of::<A>()
     }
 
+    fn type_name(&self) -> &'static str {
+        any::type_name::<A>()
+    }
+
     #[cfg(nightly)]
     fn provide<'a>(&'a self, demand: &mut Demand<'a>) {
         demand.provide_ref(&self.attachment);
@@ -93,6 +106,10 @@ unsafe impl<A: 'static + Debug + Display + Send + Sync> FrameImpl for PrintableA
         TypeId::of::<A>()
     }
 
+    fn type_name(&self) -> &'static str {
+        any::type_name::<A>()
+    }
+
     #[cfg(nightly)]
     fn provide<'a>(&'a self, demand: &mut Demand<'a>) {
         demand.provide_ref(&self.attachment);
@@ -138,6 +155,10 @@ unsafe impl FrameImpl for AnyhowContext {
         TypeId::of::<anyhow::Error>()
     }
 
+    fn type_name(&self) -> &'static str {
+        any::type_name::<anyhow::Error>()
+    }
+
     #[cfg(nightly)]
     #[inline]
     fn provide<'a>(&'a self, demand: &mut Demand<'a>) {
@@ -183,6 +204,10 @@ unsafe impl FrameImpl for EyreContext {
         TypeId::of::<eyre::Report>()
     }
 
+    fn type_name(&self) -> &'static str {
+        any::type_name::<eyre::Report>()
+    }
+
     #[cfg(nightly)]
     #[inline]
     fn provide<'a>(&'a self, demand: &mut Demand<'a>) {
@@ -2,6 +2,8 @@ mod frame_impl;
 mod kind;
 
 use alloc::boxed::Box;
+#[cfg(any(feature = "serde", feature = "tracing"))]
+use alloc::vec::Vec;
 #[cfg(nightly)]
 use core::any::{self, Demand, Provider};
 use core::{any::TypeId, fmt, panic::Location};
@@ -125,6 +127,49 @@ impl Frame {
     pub(crate) fn type_id(&self) -> TypeId {
         FrameImpl::type_id(&*self.frame)
     }
+
+    /// Returns the name of the type of the held context or attachment.
+    ///
+    /// The name is obtained from [`core::any::type_name`] and shares its caveats: it is meant for
+    /// diagnostics, and its exact contents are not guaranteed to be stable between compiler
+    /// versions.
+    #[must_use]
+    pub fn type_name(&self) -> &'static str {
+        FrameImpl::type_name(&*self.frame)
+    }
+}
+
+/// Walks down every frame in `frames` until a context is found and pushes the context together with
+/// the attachments found on the way to `contexts`.
+///
+/// If an attachment has multiple sources, its attachments are assigned to the first context of
+/// every branch.
+#[cfg(any(feature = "serde", feature = "tracing"))]
+pub(crate) fn collect_contexts<'a>(
+    frames: &'a [Frame],
+    prefix: &[&'a Frame],
+    contexts: &mut Vec<(&'a Frame, Vec<&'a Frame>)>,
+) {
+    for frame in frames {
+        let mut attachments = prefix.to_vec();
+        let mut current = frame;
+
+        loop {
+            if matches!(current.kind(), FrameKind::Context(_)) {
+                contexts.push((current, attachments));
+                break;
+            }
+
+            attachments.push(current);
+            match current.sources() {
+                [source] => current = source,
+                sources => {
+                    collect_contexts(sources, &attachments, contexts);
+                    break;
+                }
+            }
+        }
+    }
 }
 
 #[cfg(nightly)]
@@ -2,17 +2,23 @@
 //!
 //! Extends [`Future`] with the same methods as [`ResultExt`] but calls the methods on [`poll`]ing.
 //!
+//! Additionally, [`try_collect_reports`] and [`partition_results`] wait for multiple [`Future`]s
+//! and collect all of their errors, like [`IteratorExt`] does for iterators.
+//!
 //! [`Report`]: crate::Report
+//! [`IteratorExt`]: crate::IteratorExt
 //! [`poll`]: Future::poll
 
+use alloc::{boxed::Box, vec::Vec};
 use core::{
     fmt::{Debug, Display},
     future::Future,
+    mem,
     pin::Pin,
     task::{Context as TaskContext, Poll},
 };
 
-use crate::{Context, Result, ResultExt};
+use crate::{Context, IteratorExt, Report, Result, ResultExt};
 
 macro_rules! implement_future_adaptor {
     ($future:ident, $method:ident, $bound:ident $(+ $bounds:ident)* $(+ $lifetime:lifetime)*, $output:ty) => {
@@ -305,3 +311,158 @@ where
         }
     }
 }
+
+enum MaybeDone<Fut: Future> {
+    Pending(Pin<Box<Fut>>),
+    Done(Fut::Output),
+}
+
+/// Polls all futures concurrently and resolves to their outputs in the original order.
+struct JoinAll<Fut: Future> {
+    futures: Vec<MaybeDone<Fut>>,
+}
+
+// The futures are pinned on the heap and the outputs are never pinned, so `JoinAll` can be moved.
+impl<Fut: Future> Unpin for JoinAll<Fut> {}
+
+impl<Fut: Future> JoinAll<Fut> {
+    fn new(futures: impl IntoIterator<Item = Fut>) -> Self {
+        Self {
+            futures: futures
+                .into_iter()
+                .map(|future| MaybeDone::Pending(Box::pin(future)))
+                .collect(),
+        }
+    }
+
+    fn poll(&mut self, cx: &mut TaskContext) -> Poll<Vec<Fut::Output>> {
+        let mut done = true;
+        for future in &mut self.futures {
+            if let MaybeDone::Pending(pending) = future {
+                match pending.as_mut().poll(cx) {
+                    Poll::Ready(output) => *future = MaybeDone::Done(output),
+                    Poll::Pending => done = false,
+                }
+            }
+        }
+
+        if !done {
+            return Poll::Pending;
+        }
+
+        Poll::Ready(
+            mem::take(&mut self.futures)
+                .into_iter()
+                .map(|future| match future {
+                    MaybeDone::Done(output) => output,
+                    MaybeDone::Pending(_) => unreachable!("all futures have been resolved"),
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Future returned by [`try_collect_reports`].
+pub struct TryCollectReports<Fut: Future> {
+    inner: JoinAll<Fut>,
+}
+
+impl<Fut, T, C> Future for TryCollectReports<Fut>
+where
+    Fut: Future<Output = Result<T, C>>,
+{
+    type Output = Result<Vec<T>, C>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext) -> Poll<Self::Output> {
+        self.get_mut()
+            .inner
+            .poll(cx)
+            .map(|results| results.into_iter().try_collect_reports())
+    }
+}
+
+/// Future returned by [`partition_results`].
+pub struct PartitionResults<Fut: Future> {
+    inner: JoinAll<Fut>,
+}
+
+impl<Fut, T, C> Future for PartitionResults<Fut>
+where
+    Fut: Future<Output = Result<T, C>>,
+{
+    type Output = (Vec<T>, Option<Report<C>>);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext) -> Poll<Self::Output> {
+        self.get_mut()
+            .inner
+            .poll(cx)
+            .map(|results| results.into_iter().partition_results())
+    }
+}
+
+/// Waits for all `futures` concurrently and collects their successful values, or all errors if at
+/// least one of the futures resolves to an [`Err`].
+///
+/// This is the asynchronous equivalent of [`IteratorExt::try_collect_reports`]. Unlike joining the
+/// futures with `?`, this does not stop at the first error.
+///
+/// # Example
+///
+/// ```rust
+/// # use core::fmt;
+/// use error_stack::{future::try_collect_reports, report, Context, Result};
+///
+/// #[derive(Debug)]
+/// struct FetchError;
+///
+/// impl fmt::Display for FetchError {
+///     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         fmt.write_str("could not fetch the resource")
+///     }
+/// }
+///
+/// impl Context for FetchError {}
+///
+/// async fn fetch(id: u32) -> Result<u32, FetchError> {
+///     if id % 2 == 0 {
+///         Ok(id)
+///     } else {
+///         Err(report!(FetchError))
+///     }
+/// }
+///
+/// # futures::executor::block_on(async {
+/// let values = try_collect_reports([2, 4, 6].map(fetch)).await;
+/// assert_eq!(values.unwrap(), [2, 4, 6]);
+///
+/// let values = try_collect_reports([1, 2, 3].map(fetch)).await;
+/// assert_eq!(values.unwrap_err().current_frames().len(), 2);
+/// # });
+/// ```
+///
+/// [`IteratorExt::try_collect_reports`]: crate::IteratorExt::try_collect_reports
+pub fn try_collect_reports<I>(futures: I) -> TryCollectReports<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Future,
+{
+    TryCollectReports {
+        inner: JoinAll::new(futures),
+    }
+}
+
+/// Waits for all `futures` concurrently and collects their successful values, and all errors if at
+/// least one of the futures resolves to an [`Err`].
+///
+/// This is the asynchronous equivalent of [`IteratorExt::partition_results`].
+///
+/// [`IteratorExt::partition_results`]: crate::IteratorExt::partition_results
+pub fn partition_results<I>(futures: I) -> PartitionResults<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Future,
+{
+    PartitionResults {
+        inner: JoinAll::new(futures),
+    }
+}
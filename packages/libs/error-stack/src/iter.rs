@@ -1,4 +1,6 @@
-//! Iterators over [`Frame`]s.
+//! Iterators over [`Frame`]s and an extension for iterators over [`Result`]s.
+//!
+//! [`Result`]: crate::Result
 
 use alloc::{vec, vec::Vec};
 #[cfg(nightly)]
@@ -10,7 +12,7 @@ use core::{
     slice::{Iter, IterMut},
 };
 
-use crate::Frame;
+use crate::{Frame, Report, Result};
 
 /// Helper function, which is used in both [`Frames`] and [`FramesMut`].
 ///
@@ -267,3 +269,100 @@ where
         fmt.debug_list().entries(self.clone()).finish()
     }
 }
+
+/// Extension trait for [`Iterator`]s over [`Result`]s to collect all errors instead of stopping at
+/// the first one.
+///
+/// All errors are combined into a single [`Report`] by [`Report::extend_one`], so every error is
+/// a sibling of the others in [`Report::current_frames`].
+pub trait IteratorExt<T, C>: Iterator<Item = Result<T, C>> + Sized {
+    /// Collects all successful values, or all errors if at least one of the items is an [`Err`].
+    ///
+    /// Unlike collecting into `Result<Vec<T>, Report<C>>`, this consumes the whole iterator, even
+    /// after the first error has been encountered.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use core::fmt;
+    /// use error_stack::{report, Context, IteratorExt, Result};
+    ///
+    /// #[derive(Debug)]
+    /// struct ParseError;
+    ///
+    /// impl fmt::Display for ParseError {
+    ///     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         fmt.write_str("could not parse number")
+    ///     }
+    /// }
+    ///
+    /// impl Context for ParseError {}
+    ///
+    /// fn parse(value: &str) -> Result<u32, ParseError> {
+    ///     value.parse().map_err(|_| report!(ParseError))
+    /// }
+    ///
+    /// let numbers = ["1", "2", "3"].into_iter().map(parse).try_collect_reports();
+    /// assert_eq!(numbers.unwrap(), [1, 2, 3]);
+    ///
+    /// let numbers = ["1", "a", "b"].into_iter().map(parse).try_collect_reports();
+    /// assert_eq!(numbers.unwrap_err().current_frames().len(), 2);
+    /// ```
+    fn try_collect_reports(self) -> Result<Vec<T>, C>;
+
+    /// Collects all successful values, and all errors if at least one of the items is an [`Err`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use core::fmt;
+    /// use error_stack::{report, Context, IteratorExt, Result};
+    ///
+    /// #[derive(Debug)]
+    /// struct ParseError;
+    ///
+    /// impl fmt::Display for ParseError {
+    ///     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         fmt.write_str("could not parse number")
+    ///     }
+    /// }
+    ///
+    /// impl Context for ParseError {}
+    ///
+    /// fn parse(value: &str) -> Result<u32, ParseError> {
+    ///     value.parse().map_err(|_| report!(ParseError))
+    /// }
+    ///
+    /// let (numbers, report) = ["1", "a", "3"].into_iter().map(parse).partition_results();
+    /// assert_eq!(numbers, [1, 3]);
+    /// assert_eq!(report.unwrap().current_frames().len(), 1);
+    /// ```
+    fn partition_results(self) -> (Vec<T>, Option<Report<C>>);
+}
+
+impl<I, T, C> IteratorExt<T, C> for I
+where
+    I: Iterator<Item = Result<T, C>>,
+{
+    fn try_collect_reports(self) -> Result<Vec<T>, C> {
+        match self.partition_results() {
+            (values, None) => Ok(values),
+            (_, Some(report)) => Err(report),
+        }
+    }
+
+    fn partition_results(self) -> (Vec<T>, Option<Report<C>>) {
+        let mut values = Vec::new();
+        let mut report: Option<Report<C>> = None;
+
+        for result in self {
+            match (result, &mut report) {
+                (Ok(value), _) => values.push(value),
+                (Err(error), Some(report)) => report.extend_one(error),
+                (Err(error), report @ None) => *report = Some(error),
+            }
+        }
+
+        (values, report)
+    }
+}
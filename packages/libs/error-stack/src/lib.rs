@@ -420,6 +420,9 @@
 //! `spantrace`    | Enables automatic capturing of [`SpanTrace`]s                      | disabled
 //! `anyhow`       | Provides `into_report` to convert [`anyhow::Error`] to [`Report`]  | disabled
 //! `eyre`         | Provides `into_report` to convert [`eyre::Report`] to [`Report`]   | disabled
+//! `serde`        | Implements [`Serialize`] for [`Report`]                            | disabled
+//! `tracing`      | Records [`Report`]s as `tracing` events                            | disabled
+//! `testing`      | Provides assertions and snapshots for [`Report`]s in [`testing`]   | disabled
 //!
 //! [^color]: error-stack supports the [`NO_COLOR`](http://no-color.org/)
 //!     and `FORCE_COLOR` environment variables through the [owo-colors crate](https://crates.io/crates/owo-colors)
@@ -433,6 +436,7 @@
 //! [`Display`]: core::fmt::Display
 //! [`Debug`]: core::fmt::Debug
 //! [`SpanTrace`]: tracing_error::SpanTrace
+//! [`Serialize`]: ::serde::Serialize
 #![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(
     nightly,
@@ -468,13 +472,23 @@ mod report;
 mod result;
 
 mod context;
+#[cfg(any(nightly, feature = "std"))]
+mod error;
 #[cfg(feature = "std")]
 pub mod fmt;
 #[cfg(not(feature = "std"))]
 mod fmt;
 #[cfg(feature = "std")]
 mod hook;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "tracing")]
+mod tracing;
 
+#[cfg(any(nightly, feature = "std"))]
+pub use self::error::ReportError;
 #[cfg(feature = "std")]
 #[allow(deprecated, unreachable_pub)]
 pub use self::hook::HookAlreadySet;
@@ -489,6 +503,7 @@ pub use self::{
 #[doc(inline)]
 pub use self::{
     future::FutureExt,
+    iter::IteratorExt,
     result::{IntoReport, ResultExt},
 };
 
@@ -12,7 +12,7 @@ use tracing_error::{SpanTrace, SpanTraceStatus};
 use crate::iter::{RequestRef, RequestValue};
 use crate::{
     iter::{Frames, FramesMut},
-    Context, Frame,
+    Context, Frame, FrameKind,
 };
 
 /// Contains a [`Frame`] stack consisting of [`Context`]s and attachments.
@@ -511,6 +511,120 @@ impl<C> Report<C> {
         RequestValue::new(&self.frames)
     }
 
+    /// Returns the most specific value of type `T` provided by the frames of the report.
+    ///
+    /// Values are provided by [`attach`](Self::attach)ing them, or, on nightly, by
+    /// [`provide`](core::any::Provider::provide)ing them from a [`Context`]. The most specific
+    /// value is the one closest to the cause of the error, i.e. the value provided below the most
+    /// contexts. Attachments don't count towards this distance, so of multiple values attached to
+    /// the same context the first attached one is returned. If values of multiple sources are
+    /// equally far away, the value of the first source is returned.
+    ///
+    /// This is useful to map a report to an error code, e.g. an HTTP status code. The outermost
+    /// value can be requested by calling [`downcast_ref()`](Self::downcast_ref) instead.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use core::fmt;
+    /// use error_stack::{Context, Report};
+    ///
+    /// #[derive(Debug)]
+    /// struct RequestError;
+    ///
+    /// impl fmt::Display for RequestError {
+    ///     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         fmt.write_str("could not handle the request")
+    ///     }
+    /// }
+    ///
+    /// impl Context for RequestError {}
+    ///
+    /// #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    /// struct StatusCode(u16);
+    ///
+    /// let report = Report::new(RequestError)
+    ///     .attach(StatusCode(404))
+    ///     .attach_printable("the user does not exist")
+    ///     .attach(StatusCode(500));
+    ///
+    /// assert_eq!(report.most_specific::<StatusCode>(), Some(StatusCode(404)));
+    /// assert_eq!(report.most_specific::<u32>(), None);
+    /// ```
+    #[must_use]
+    pub fn most_specific<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+        /// Returns the most specific value of `frames` and the number of contexts above it.
+        fn search<T: Clone + Send + Sync + 'static>(
+            frames: &[Frame],
+            depth: usize,
+        ) -> Option<(usize, T)> {
+            let mut found: Option<(usize, T)> = None;
+
+            for frame in frames {
+                let depth = match frame.kind() {
+                    FrameKind::Context(_) => depth + 1,
+                    FrameKind::Attachment(_) => depth,
+                };
+
+                // The sources are never above the frame, so a value of the sources is at least as
+                // specific as the value of the frame
+                let candidate = search(frame.sources(), depth)
+                    .or_else(|| provided_value(frame).map(|value| (depth, value)));
+
+                if let Some((candidate_depth, value)) = candidate {
+                    if found
+                        .as_ref()
+                        .map_or(true, |(found_depth, _)| candidate_depth > *found_depth)
+                    {
+                        found = Some((candidate_depth, value));
+                    }
+                }
+            }
+
+            found
+        }
+
+        search(&self.frames, 0).map(|(_, value)| value)
+    }
+
+    /// Returns the [`ExitCode`] of the report.
+    ///
+    /// This is the [`most_specific`](Self::most_specific) [`ExitCode`] provided by the frames of
+    /// the report, or [`ExitCode::FAILURE`] if no frame provides one. The exit code is also
+    /// used, when the report is returned from `main`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # use core::fmt;
+    /// use std::process::ExitCode;
+    ///
+    /// use error_stack::{Context, Report};
+    ///
+    /// #[derive(Debug)]
+    /// struct ConfigError;
+    ///
+    /// impl fmt::Display for ConfigError {
+    ///     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         fmt.write_str("could not load the configuration")
+    ///     }
+    /// }
+    ///
+    /// impl Context for ConfigError {}
+    ///
+    /// fn main() -> ExitCode {
+    ///     let report = Report::new(ConfigError).attach(ExitCode::from(78));
+    ///
+    ///     eprintln!("{report:?}");
+    ///     report.exit_code()
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn exit_code(&self) -> ExitCode {
+        self.most_specific().unwrap_or(ExitCode::FAILURE)
+    }
+
     /// Returns if `T` is the type held by any frame inside of the report.
     ///
     /// `T` could either be an attachment or a [`Context`].
@@ -626,17 +740,21 @@ impl<C> Report<C> {
 #[cfg(feature = "std")]
 impl<Context> std::process::Termination for Report<Context> {
     fn report(self) -> ExitCode {
-        #[cfg(not(nightly))]
-        return ExitCode::FAILURE;
-
-        #[cfg(nightly)]
-        self.request_ref::<ExitCode>()
-            .next()
-            .copied()
-            .unwrap_or(ExitCode::FAILURE)
+        self.exit_code()
     }
 }
 
+/// Returns the value of type `T` provided by `frame`.
+fn provided_value<T: Clone + Send + Sync + 'static>(frame: &Frame) -> Option<T> {
+    #[cfg(nightly)]
+    return frame
+        .request_value::<T>()
+        .or_else(|| frame.request_ref::<T>().cloned());
+
+    #[cfg(not(nightly))]
+    frame.downcast_ref::<T>().cloned()
+}
+
 impl<Context> FromIterator<Report<Context>> for Option<Report<Context>> {
     fn from_iter<T: IntoIterator<Item = Report<Context>>>(iter: T) -> Self {
         let mut iter = iter.into_iter();
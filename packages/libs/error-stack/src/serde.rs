@@ -0,0 +1,290 @@
+//! Implementation of [`Serialize`] for [`Report`].
+//!
+//! A [`Report`] is serialized as the list of its current contexts. Each context is serialized as
+//! an object containing the [`Display`] output of the context, the name of its type, the
+//! [`Location`] where it was created, the attachments added on top of it, structured fields
+//! contributed by hooks, and its sources, which are serialized the same way:
+//!
+//! ```json
+//! [
+//!   {
+//!     "context": "could not parse the configuration",
+//!     "type": "parse_config::ParseConfigError",
+//!     "location": { "file": "src/main.rs", "line": 32, "column": 10 },
+//!     "attachments": [
+//!       {
+//!         "attachment": "could not read \"config.json\"",
+//!         "type": "alloc::string::String",
+//!         "location": { "file": "src/main.rs", "line": 31, "column": 10 },
+//!         "fields": {}
+//!       }
+//!     ],
+//!     "fields": {},
+//!     "sources": [
+//!       {
+//!         "context": "No such file or directory (os error 2)",
+//!         "type": "std::io::error::Error",
+//!         "location": { "file": "src/main.rs", "line": 30, "column": 10 },
+//!         "attachments": [],
+//!         "fields": {},
+//!         "sources": []
+//!       }
+//!     ]
+//!   }
+//! ]
+//! ```
+//!
+//! Attachments are listed from the most recently added to the oldest one. Opaque attachments have
+//! no printable representation, their `"attachment"` is `null`. Attachments which were added on
+//! top of a [`Report`] with multiple sources are listed on every context below them.
+//!
+//! The `"type"` is obtained from [`core::any::type_name`], it is meant for diagnostics and its
+//! exact contents are not guaranteed to be stable between compiler versions.
+//!
+//! ## Hooks
+//!
+//! With the `std` feature enabled, [`Report::install_serialize_hook`] adds structured fields to
+//! the `"fields"` object of contexts and attachments. Hooks are invoked with the same rules as
+//! [`Report::install_debug_hook`].
+//!
+//! [`Display`]: core::fmt::Display
+
+#[cfg(feature = "std")]
+use alloc::sync::Arc;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use core::any::TypeId;
+use core::panic::Location;
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::{frame::collect_contexts, AttachmentKind, Frame, FrameKind, Report};
+
+#[cfg(feature = "std")]
+type SharedSerializeHook = Arc<dyn Fn(&Frame, &mut Fields) + Send + Sync>;
+
+#[cfg(feature = "std")]
+static SERIALIZE_HOOK: RwLock<Vec<(TypeId, SharedSerializeHook)>> = RwLock::new(Vec::new());
+
+/// Structured fields of a context or an attachment, which are filled by serialize hooks.
+///
+/// See [`Report::install_serialize_hook`] for more information.
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct Fields {
+    inner: Map<String, Value>,
+}
+
+#[cfg(feature = "std")]
+impl Fields {
+    /// Inserts a field, replacing any previous value of the same `key`.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Value>) {
+        self.inner.insert(key.into(), value.into());
+    }
+
+    /// Returns the value of the field `key`, if a hook has inserted it.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.inner.get(key)
+    }
+
+    /// Removes the field `key` and returns its value, if a hook has inserted it.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        self.inner.remove(key)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Report<()> {
+    /// Can be used to globally set a serialize hook, for a specific type `T`.
+    ///
+    /// This hook will be called for every context and attachment providing `T` when a [`Report`]
+    /// is serialized. The hook can add structured fields, which are serialized alongside the
+    /// context or attachment. Installing a second hook for the same type replaces the first one.
+    ///
+    /// Just like [`install_debug_hook`], on a stable toolchain only attachments of type `T` are
+    /// passed to the hook, on nightly every value of type `T` provided by a context or an
+    /// attachment is passed to the hook.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::fmt::{Display, Formatter};
+    ///
+    /// use error_stack::{Context, Report};
+    ///
+    /// #[derive(Debug)]
+    /// struct ErrorCode(u16);
+    ///
+    /// #[derive(Debug)]
+    /// struct UserError;
+    ///
+    /// impl Display for UserError {
+    ///     fn fmt(&self, fmt: &mut Formatter<'_>) -> std::fmt::Result {
+    ///         fmt.write_str("invalid user input")
+    ///     }
+    /// }
+    ///
+    /// impl Context for UserError {}
+    ///
+    /// Report::install_serialize_hook::<ErrorCode>(|code, fields| {
+    ///     fields.insert("code", code.0);
+    /// });
+    ///
+    /// let report = Report::new(UserError).attach(ErrorCode(404));
+    /// let value = serde_json::to_value(&report).expect("could not serialize report");
+    ///
+    /// assert_eq!(value[0]["attachments"][0]["fields"]["code"], 404);
+    /// ```
+    ///
+    /// [`install_debug_hook`]: Self::install_debug_hook
+    pub fn install_serialize_hook<T: Send + Sync + 'static>(
+        hook: impl Fn(&T, &mut Fields) + Send + Sync + 'static,
+    ) {
+        let type_id = TypeId::of::<T>();
+
+        let mut hooks = SERIALIZE_HOOK.write().expect("should not be poisoned");
+        // make sure that previous hooks of the same TypeId are deleted.
+        hooks.retain(|(id, _)| *id != type_id);
+        hooks.push((type_id, into_shared_hook(hook)));
+    }
+}
+
+#[cfg(feature = "std")]
+fn into_shared_hook<T: Send + Sync + 'static>(
+    hook: impl Fn(&T, &mut Fields) + Send + Sync + 'static,
+) -> SharedSerializeHook {
+    Arc::new(move |frame: &Frame, fields: &mut Fields| {
+        #[cfg(nightly)]
+        {
+            if let Some(value) = frame.request_ref::<T>() {
+                hook(value, fields);
+            } else if let Some(ref value) = frame.request_value::<T>() {
+                hook(value, fields);
+            }
+        }
+
+        // emulate the behavior from nightly, see `fmt::hook::into_boxed_hook`
+        #[cfg(not(nightly))]
+        if let Some(value) = matches!(frame.kind(), FrameKind::Attachment(_))
+            .then_some(frame)
+            .and_then(Frame::downcast_ref::<T>)
+        {
+            hook(value, fields);
+        }
+    })
+}
+
+/// Collects the fields of `frame` by invoking all installed serialize hooks.
+#[cfg(feature = "std")]
+fn fields(frame: &Frame) -> Map<String, Value> {
+    // The hooks are copied out of the lock, so a hook may install another hook without deadlocking
+    let hooks: Vec<_> = SERIALIZE_HOOK
+        .read()
+        .expect("should not be poisoned")
+        .iter()
+        .map(|(_, hook)| Arc::clone(hook))
+        .collect();
+
+    let mut fields = Fields::default();
+    for hook in hooks {
+        hook(frame, &mut fields);
+    }
+
+    fields.inner
+}
+
+#[cfg(not(feature = "std"))]
+fn fields(_: &Frame) -> Map<String, Value> {
+    Map::new()
+}
+
+struct SerializeLocation(&'static Location<'static>);
+
+impl Serialize for SerializeLocation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut location = serializer.serialize_struct("Location", 3)?;
+        location.serialize_field("file", self.0.file())?;
+        location.serialize_field("line", &self.0.line())?;
+        location.serialize_field("column", &self.0.column())?;
+        location.end()
+    }
+}
+
+struct SerializeAttachment<'a>(&'a Frame);
+
+impl Serialize for SerializeAttachment<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let frame = self.0;
+        let attachment = match frame.kind() {
+            FrameKind::Attachment(AttachmentKind::Printable(attachment)) => {
+                Some(attachment.to_string())
+            }
+            FrameKind::Attachment(AttachmentKind::Opaque(_)) | FrameKind::Context(_) => None,
+        };
+
+        let mut state = serializer.serialize_struct("Attachment", 4)?;
+        state.serialize_field("attachment", &attachment)?;
+        state.serialize_field("type", frame.type_name())?;
+        state.serialize_field("location", &SerializeLocation(frame.location()))?;
+        state.serialize_field("fields", &fields(frame))?;
+        state.end()
+    }
+}
+
+/// A context together with all attachments, which were added on top of it.
+struct SerializeContext<'a> {
+    context: &'a Frame,
+    attachments: Vec<&'a Frame>,
+}
+
+impl Serialize for SerializeContext<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let frame = self.context;
+        let context = match frame.kind() {
+            FrameKind::Context(context) => context.to_string(),
+            FrameKind::Attachment(_) => unreachable!("only contexts are collected as contexts"),
+        };
+        let attachments: Vec<_> = self
+            .attachments
+            .iter()
+            .map(|&attachment| SerializeAttachment(attachment))
+            .collect();
+
+        let mut state = serializer.serialize_struct("Context", 6)?;
+        state.serialize_field("context", &context)?;
+        state.serialize_field("type", frame.type_name())?;
+        state.serialize_field("location", &SerializeLocation(frame.location()))?;
+        state.serialize_field("attachments", &attachments)?;
+        state.serialize_field("fields", &fields(frame))?;
+        state.serialize_field("sources", &SerializeSources(frame.sources()))?;
+        state.end()
+    }
+}
+
+struct SerializeSources<'a>(&'a [Frame]);
+
+impl Serialize for SerializeSources<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut contexts = Vec::new();
+        collect_contexts(self.0, &[], &mut contexts);
+        serializer.collect_seq(contexts.into_iter().map(|(context, attachments)| {
+            SerializeContext {
+                context,
+                attachments,
+            }
+        }))
+    }
+}
+
+impl<C> Serialize for Report<C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeSources(self.current_frames()).serialize(serializer)
+    }
+}
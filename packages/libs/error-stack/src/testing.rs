@@ -0,0 +1,262 @@
+//! Helpers to assert the structure and the output of a [`Report`] in tests.
+//!
+//! [`assert_report`] returns a [`ReportAssertion`], which checks the frames of a [`Report`] and
+//! panics with a readable message, including the [`snapshot`] of the [`Report`], if a check fails:
+//!
+//! ```rust
+//! # use core::fmt;
+//! use error_stack::{testing::assert_report, Context, Report};
+//!
+//! #[derive(Debug)]
+//! struct ParseError;
+//!
+//! impl fmt::Display for ParseError {
+//!     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!         fmt.write_str("could not parse the configuration")
+//!     }
+//! }
+//!
+//! impl Context for ParseError {}
+//!
+//! #[derive(Debug)]
+//! struct ConfigError;
+//!
+//! impl fmt::Display for ConfigError {
+//!     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!         fmt.write_str("could not load the configuration")
+//!     }
+//! }
+//!
+//! impl Context for ConfigError {}
+//!
+//! let report = Report::new(ParseError)
+//!     .attach_printable("unexpected token at line 4")
+//!     .change_context(ConfigError);
+//!
+//! assert_report(&report)
+//!     .contains_context_beneath::<ParseError, ConfigError>()
+//!     .has_printable_attachment("line 4")
+//!     .has_sources(1);
+//! ```
+//!
+//! [`snapshot`] renders the [`Debug`] output of a [`Report`] without the information, which
+//! depends on the environment, so it can be compared to a stored snapshot, e.g. with [`insta`]:
+//!
+//! ```rust
+//! # use core::fmt;
+//! # use error_stack::{Context, Report};
+//! use error_stack::testing::snapshot;
+//! # #[derive(Debug)]
+//! # struct ParseError;
+//! # impl fmt::Display for ParseError {
+//! #     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+//! #         fmt.write_str("could not parse the configuration")
+//! #     }
+//! # }
+//! # impl Context for ParseError {}
+//!
+//! let report = Report::new(ParseError).attach_printable("unexpected token at line 4");
+//!
+//! assert_eq!(
+//!     snapshot(&report),
+//!     "could not parse the configuration\n╰╴ unexpected token at line 4"
+//! );
+//! ```
+//!
+//! [`Debug`]: core::fmt::Debug
+//! [`insta`]: https://docs.rs/insta
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::any::type_name;
+
+use crate::{
+    fmt::{debug_report, Config, HookContext},
+    AttachmentKind, Frame, FrameKind, Report,
+};
+
+/// Renders the [`Debug`] output of `report` in a normalized form.
+///
+/// Compared to the regular [`Debug`] output, the snapshot
+///
+/// - does not contain the locations of contexts,
+/// - does not contain backtraces and span traces,
+/// - never contains colors and always uses [`Charset::Utf8`], regardless of the global settings and
+///   the enabled features,
+/// - ignores a hook installed with [`Report::set_debug_hook`].
+///
+/// Hooks installed with [`Report::install_debug_hook`] are still called.
+///
+/// [`Debug`]: core::fmt::Debug
+/// [`Charset::Utf8`]: crate::fmt::Charset::Utf8
+#[must_use]
+pub fn snapshot<C>(report: &Report<C>) -> String {
+    debug_report(
+        report.current_frames(),
+        Config::normalized(),
+        HookContext::new(false).normalized(),
+    )
+}
+
+/// Starts assertions on the frames of `report`.
+///
+/// See the [module-level documentation](self) for an example.
+#[must_use]
+pub const fn assert_report<C>(report: &Report<C>) -> ReportAssertion<'_, C> {
+    ReportAssertion { report }
+}
+
+/// Assertions on the frames of a [`Report`], created by [`assert_report`].
+///
+/// Every assertion panics if it fails and returns the `ReportAssertion` otherwise, so assertions
+/// can be chained.
+pub struct ReportAssertion<'r, C> {
+    report: &'r Report<C>,
+}
+
+// Assertions are called for their side effect, the `ReportAssertion` returned by the last
+// assertion in a chain is discarded.
+#[allow(clippy::must_use_candidate, clippy::return_self_not_must_use)]
+impl<C> ReportAssertion<'_, C> {
+    #[track_caller]
+    fn fail(&self, message: &str) -> ! {
+        panic!(
+            "assertion failed: {message}\n\nreport:\n{}",
+            snapshot(self.report)
+        )
+    }
+
+    /// Asserts that the [`Report`] contains a context of type `T`.
+    #[track_caller]
+    pub fn contains_context<T: Send + Sync + 'static>(self) -> Self {
+        if !self.report.frames().any(is_context::<T>) {
+            self.fail(&format!(
+                "the report does not contain a context of type `{}`",
+                type_name::<T>()
+            ));
+        }
+        self
+    }
+
+    /// Asserts that the [`Report`] contains an attachment of type `T`.
+    #[track_caller]
+    pub fn contains_attachment<T: Send + Sync + 'static>(self) -> Self {
+        let found = self
+            .report
+            .frames()
+            .any(|frame| matches!(frame.kind(), FrameKind::Attachment(_)) && frame.is::<T>());
+
+        if !found {
+            self.fail(&format!(
+                "the report does not contain an attachment of type `{}`",
+                type_name::<T>()
+            ));
+        }
+        self
+    }
+
+    /// Asserts that the [`Report`] contains a context of type `T` beneath a context of type `U`,
+    /// i.e. `T` is a direct or indirect source of `U`.
+    #[track_caller]
+    pub fn contains_context_beneath<T, U>(self) -> Self
+    where
+        T: Send + Sync + 'static,
+        U: Send + Sync + 'static,
+    {
+        let found = self
+            .report
+            .frames()
+            .filter(|frame| is_context::<U>(frame))
+            .any(|frame| any_frame(frame.sources(), &is_context::<T>));
+
+        if !found {
+            self.fail(&format!(
+                "the report does not contain a context of type `{}` beneath a context of type `{}`",
+                type_name::<T>(),
+                type_name::<U>()
+            ));
+        }
+        self
+    }
+
+    /// Asserts that the [`Report`] contains a printable attachment, which contains `pattern`.
+    #[track_caller]
+    pub fn has_printable_attachment(self, pattern: &str) -> Self {
+        if !self.has_printable_attachment_by(|attachment| attachment.contains(pattern)) {
+            self.fail(&format!(
+                "the report does not contain a printable attachment containing {pattern:?}"
+            ));
+        }
+        self
+    }
+
+    /// Asserts that the [`Report`] contains a printable attachment, for which `predicate` returns
+    /// `true` when called with its [`Display`] output.
+    ///
+    /// [`Display`]: core::fmt::Display
+    #[track_caller]
+    pub fn has_printable_attachment_matching(self, predicate: impl Fn(&str) -> bool) -> Self {
+        if !self.has_printable_attachment_by(predicate) {
+            self.fail("the report does not contain a printable attachment matching the predicate");
+        }
+        self
+    }
+
+    fn has_printable_attachment_by(&self, predicate: impl Fn(&str) -> bool) -> bool {
+        self.report.frames().any(|frame| match frame.kind() {
+            FrameKind::Attachment(AttachmentKind::Printable(attachment)) => {
+                predicate(&attachment.to_string())
+            }
+            FrameKind::Attachment(AttachmentKind::Opaque(_)) | FrameKind::Context(_) => false,
+        })
+    }
+
+    /// Asserts that the current contexts of the [`Report`] have `expected` sources in total.
+    ///
+    /// The sources are the contexts directly beneath the current contexts, e.g. a [`Report`]
+    /// created by [`Report::new`] has no sources, after calling [`Report::change_context`] it has
+    /// one source.
+    #[track_caller]
+    pub fn has_sources(self, expected: usize) -> Self {
+        let actual: usize = first_contexts(self.report.current_frames())
+            .into_iter()
+            .map(|context| first_contexts(context.sources()).len())
+            .sum();
+
+        if actual != expected {
+            self.fail(&format!(
+                "the report has {actual} sources, but {expected} were expected"
+            ));
+        }
+        self
+    }
+}
+
+fn is_context<T: Send + Sync + 'static>(frame: &Frame) -> bool {
+    matches!(frame.kind(), FrameKind::Context(_)) && frame.is::<T>()
+}
+
+/// Returns if `predicate` returns `true` for any frame in `frames` or their sources.
+fn any_frame(frames: &[Frame], predicate: &impl Fn(&Frame) -> bool) -> bool {
+    frames
+        .iter()
+        .any(|frame| predicate(frame) || any_frame(frame.sources(), predicate))
+}
+
+/// Returns the first context in every branch of `frames`.
+fn first_contexts(frames: &[Frame]) -> Vec<&Frame> {
+    frames
+        .iter()
+        .flat_map(|frame| {
+            if matches!(frame.kind(), FrameKind::Context(_)) {
+                vec![frame]
+            } else {
+                first_contexts(frame.sources())
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,144 @@
+//! Integration of [`Report`] with [`tracing`].
+
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use tracing::Level;
+
+use crate::{frame::collect_contexts, AttachmentKind, Frame, FrameKind, Report};
+
+/// The identifier of the next `Report` recorded by [`Report::emit_event`].
+static NEXT_REPORT_ID: AtomicUsize = AtomicUsize::new(0);
+
+impl<C> Report<C> {
+    /// Records the `Report` as [`tracing`] events at the specified `level`.
+    ///
+    /// One event is recorded for every context in the `Report`, starting with the current
+    /// context, followed by its sources in depth-first order. The message of an event is the
+    /// [`Display`] output of the context, its other fields are
+    ///
+    /// - `error.report`: an identifier of the `Report`, which is shared by all of its events and
+    ///   unique within the process,
+    /// - `error.index`: the position of the context in the order the contexts are recorded,
+    /// - `error.parent`: the `error.index` of the context, which the context is a source of. It's
+    ///   not recorded for the current contexts,
+    /// - `error.type_name`: the name of the type of the context,
+    /// - `error.location`: the [`Location`] where the context was created,
+    /// - `error.depth`: how many contexts are above the context, `0` for the current context.
+    ///
+    /// Each printable attachment is recorded as a separate event directly after the event of the
+    /// context it was added to, starting with the most recent attachment. The message of an
+    /// attachment event is the [`Display`] output of the attachment, its other fields are
+    /// `error.report`, `error.location` and `error.attachment_of`, the `error.index` of the
+    /// context.
+    ///
+    /// All events are recorded with the target `error_stack`. Structured formatters like the JSON
+    /// formatter of `tracing-subscriber` emit every field separately, so unlike logging the
+    /// [`Debug`] output of the `Report`, the fields can be queried individually and the `Report`
+    /// can be reassembled from the events.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use core::fmt;
+    /// use error_stack::{Context, Report};
+    /// use tracing::Level;
+    ///
+    /// #[derive(Debug)]
+    /// struct ConfigError;
+    ///
+    /// impl fmt::Display for ConfigError {
+    ///     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         fmt.write_str("could not load the configuration")
+    ///     }
+    /// }
+    ///
+    /// impl Context for ConfigError {}
+    ///
+    /// let report = Report::new(ConfigError).attach_printable("config.toml does not exist");
+    /// report.emit_event(Level::ERROR);
+    /// ```
+    ///
+    /// [`Display`]: core::fmt::Display
+    /// [`Debug`]: core::fmt::Debug
+    /// [`Location`]: core::panic::Location
+    pub fn emit_event(&self, level: Level) {
+        let mut emitter = Emitter {
+            report: NEXT_REPORT_ID.fetch_add(1, Ordering::Relaxed),
+            next_index: 0,
+            level,
+        };
+        emitter.emit_contexts(self.current_frames(), None, 0);
+    }
+}
+
+/// Records the events of a single [`Report`].
+struct Emitter {
+    report: usize,
+    next_index: usize,
+    level: Level,
+}
+
+/// Records an event at the runtime `$level`.
+///
+/// The level of an event has to be known at compile time, so the event is recorded in a separate
+/// branch for every level.
+macro_rules! event {
+    ($level:expr, $($arguments:tt)+) => {
+        if $level == Level::ERROR {
+            tracing::event!(target: "error_stack", Level::ERROR, $($arguments)+);
+        } else if $level == Level::WARN {
+            tracing::event!(target: "error_stack", Level::WARN, $($arguments)+);
+        } else if $level == Level::INFO {
+            tracing::event!(target: "error_stack", Level::INFO, $($arguments)+);
+        } else if $level == Level::DEBUG {
+            tracing::event!(target: "error_stack", Level::DEBUG, $($arguments)+);
+        } else {
+            tracing::event!(target: "error_stack", Level::TRACE, $($arguments)+);
+        }
+    };
+}
+
+impl Emitter {
+    fn emit_contexts(&mut self, frames: &[Frame], parent: Option<usize>, depth: usize) {
+        let mut contexts = Vec::new();
+        collect_contexts(frames, &[], &mut contexts);
+
+        for (frame, attachments) in contexts {
+            let context = match frame.kind() {
+                FrameKind::Context(context) => context,
+                FrameKind::Attachment(_) => unreachable!("only contexts are collected as contexts"),
+            };
+
+            let index = self.next_index;
+            self.next_index += 1;
+
+            event!(
+                self.level,
+                error.report = self.report,
+                error.index = index,
+                error.parent = parent,
+                error.type_name = frame.type_name(),
+                error.location = %frame.location(),
+                error.depth = depth,
+                "{context}"
+            );
+
+            for attachment in attachments {
+                if let FrameKind::Attachment(AttachmentKind::Printable(printable)) =
+                    attachment.kind()
+                {
+                    event!(
+                        self.level,
+                        error.report = self.report,
+                        error.attachment_of = index,
+                        error.location = %attachment.location(),
+                        "{printable}"
+                    );
+                }
+            }
+
+            self.emit_contexts(frame.sources(), Some(index), depth + 1);
+        }
+    }
+}
@@ -11,6 +11,8 @@ use common::*;
 use error_stack::IntoReportCompat;
 #[cfg(feature = "std")]
 use error_stack::Report;
+#[cfg(feature = "std")]
+use error_stack::ReportError;
 
 // All frames except backtraces in this file are printable (either a printable attachment or a
 // context), so only backtraces are "opaque". Depending on how the backtrace is generated, it will
@@ -115,6 +117,24 @@ fn anyhow_output() {
     assert_eq!(anyhow_display_extended, context_display_extended);
 }
 
+#[test]
+#[cfg(all(feature = "std", feature = "anyhow"))]
+fn report_into_anyhow() {
+    let report = create_report().change_context(ContextA(0));
+
+    let anyhow = anyhow::Error::from(report);
+    assert_eq!(
+        anyhow.chain().map(ToString::to_string).collect::<Vec<_>>(),
+        ["context A", "root error"]
+    );
+
+    let report = anyhow
+        .downcast::<ReportError<ContextA>>()
+        .expect("should be a `ReportError<ContextA>`")
+        .into_report();
+    assert!(report.contains::<RootError>());
+}
+
 #[cfg(feature = "eyre")]
 fn install_eyre_hook() {
     use std::sync::Once;
@@ -215,3 +235,27 @@ fn eyre_output() {
     assert_eq!(eyre_display_normal, context_display_normal);
     assert_eq!(eyre_display_extended, context_display_extended);
 }
+
+#[test]
+#[cfg(feature = "eyre")]
+#[cfg_attr(
+    miri,
+    ignore = "bug: miri is failing for `eyre`, this is unrelated to our implementation"
+)]
+fn report_into_eyre() {
+    install_eyre_hook();
+
+    let report = create_report().change_context(ContextA(0));
+
+    let eyre = eyre::Report::from(report);
+    assert_eq!(eyre.chain().map(ToString::to_string).collect::<Vec<_>>(), [
+        "context A",
+        "root error"
+    ]);
+
+    let report = eyre
+        .downcast::<ReportError<ContextA>>()
+        .expect("should be a `ReportError<ContextA>`")
+        .into_report();
+    assert!(report.contains::<RootError>());
+}
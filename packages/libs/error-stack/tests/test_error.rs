@@ -0,0 +1,107 @@
+#![cfg(feature = "std")]
+#![cfg_attr(nightly, feature(provide_any))]
+#![cfg_attr(all(nightly, feature = "std"), feature(error_generic_member_access))]
+
+mod common;
+
+use std::error::Error;
+
+use common::*;
+use error_stack::{Report, ReportError};
+
+fn sources(error: &(dyn Error + 'static)) -> Vec<String> {
+    let mut sources = Vec::new();
+    let mut source = error.source();
+    while let Some(error) = source {
+        sources.push(error.to_string());
+        source = error.source();
+    }
+    sources
+}
+
+#[test]
+fn display() {
+    let report = create_report().change_context(ContextA(0));
+    let expected = (report.to_string(), format!("{report:#}"));
+
+    let error = report.into_error();
+    assert_eq!((error.to_string(), format!("{error:#}")), expected);
+}
+
+#[test]
+fn debug() {
+    let report = create_report().attach_printable(PrintableA(0));
+    let expected = format!("{report:?}");
+
+    assert_eq!(format!("{:?}", report.into_error()), expected);
+}
+
+#[test]
+fn source() {
+    let error = create_report()
+        .attach_printable(PrintableA(0))
+        .change_context(ContextA(0))
+        .attach(AttachmentB(0))
+        .change_context(ContextB(0))
+        .into_error();
+
+    assert_eq!(error.to_string(), "context B");
+    assert_eq!(sources(&error), ["context A", "root error"]);
+}
+
+#[test]
+fn source_without_sources() {
+    let error = create_report().attach_printable(PrintableA(0)).into_error();
+
+    assert!(error.source().is_none());
+}
+
+#[test]
+fn source_follows_first_branch() {
+    let mut report = create_report().change_context(ContextB(0));
+    report.extend_one(Report::new(ContextB(1)));
+    let error = report.change_context(ContextA(0)).into_error();
+
+    assert_eq!(sources(&error), ["context B", "root error"]);
+}
+
+#[test]
+fn into_report() {
+    let error = create_report().change_context(ContextA(0)).into_error();
+    assert!(error.report().contains::<RootError>());
+
+    let report = error.into_report();
+    assert_eq!(report.current_context(), &ContextA(0));
+}
+
+#[test]
+fn boxed() {
+    let error: Box<dyn Error> = create_report().change_context(ContextA(0)).into();
+    assert_eq!(error.to_string(), "context A");
+    assert_eq!(sources(error.as_ref()), ["root error"]);
+
+    let report = error
+        .downcast::<ReportError<ContextA>>()
+        .expect("should be a `ReportError<ContextA>`")
+        .into_report();
+    assert!(report.contains::<RootError>());
+}
+
+#[test]
+fn boxed_send_sync() {
+    fn fallible() -> Result<(), Box<dyn Error + Send + Sync>> {
+        Err(create_report().change_context(ContextA(0)))?;
+        Ok(())
+    }
+
+    let error = fallible().expect_err("should be an error");
+    assert_eq!(error.to_string(), "context A");
+    assert_eq!(sources(error.as_ref()), ["root error"]);
+
+    assert!(error.downcast_ref::<ReportError<ContextB>>().is_none());
+    let report = error
+        .downcast::<ReportError<ContextA>>()
+        .expect("should be a `ReportError<ContextA>`")
+        .into_report();
+    assert!(report.contains::<RootError>());
+}
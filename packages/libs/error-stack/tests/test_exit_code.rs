@@ -0,0 +1,77 @@
+#![cfg_attr(nightly, feature(provide_any))]
+#![cfg_attr(all(nightly, feature = "std"), feature(error_generic_member_access))]
+
+mod common;
+
+use common::*;
+
+#[test]
+fn most_specific() {
+    let report = create_report();
+    assert_eq!(report.most_specific::<PrintableA>(), None);
+
+    let report = report
+        .attach(PrintableA(1))
+        .attach(PrintableA(2))
+        .change_context(ContextA(0))
+        .attach(PrintableA(3));
+    assert_eq!(report.most_specific::<PrintableA>(), Some(PrintableA(1)));
+}
+
+#[test]
+fn most_specific_sources() {
+    let mut report = create_report().attach(PrintableA(1));
+    report.extend_one(create_report().attach(PrintableA(2)));
+    assert_eq!(report.most_specific::<PrintableA>(), Some(PrintableA(1)));
+
+    // Attachments don't make a value more specific
+    let mut report = create_report()
+        .attach(PrintableA(1))
+        .change_context(ContextA(0));
+    report.extend_one(
+        create_report()
+            .attach(PrintableA(2))
+            .attach_printable("attachment")
+            .change_context(ContextA(1)),
+    );
+    assert_eq!(report.most_specific::<PrintableA>(), Some(PrintableA(1)));
+
+    let mut report = create_report()
+        .attach(PrintableA(1))
+        .change_context(ContextA(0));
+    report.extend_one(
+        create_report()
+            .attach(PrintableA(2))
+            .change_context(ContextA(1))
+            .change_context(ContextA(2)),
+    );
+    assert_eq!(report.most_specific::<PrintableA>(), Some(PrintableA(2)));
+}
+
+#[test]
+#[cfg(nightly)]
+fn most_specific_provided() {
+    let report = create_report().change_context(ContextA(10));
+    assert_eq!(report.most_specific::<u64>(), Some(10));
+    assert_eq!(report.most_specific::<u32>(), Some(10));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn exit_code() {
+    use std::process::{ExitCode, Termination};
+
+    // `ExitCode` does not implement `PartialEq` on all supported compiler versions
+    fn assert_exit_code(actual: ExitCode, expected: ExitCode) {
+        assert_eq!(format!("{actual:?}"), format!("{expected:?}"));
+    }
+
+    assert_exit_code(create_report().exit_code(), ExitCode::FAILURE);
+
+    let report = create_report()
+        .attach(ExitCode::from(100))
+        .change_context(ContextA(0))
+        .attach(ExitCode::from(2));
+    assert_exit_code(report.exit_code(), ExitCode::from(100));
+    assert_exit_code(report.report(), ExitCode::from(100));
+}
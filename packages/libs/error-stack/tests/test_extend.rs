@@ -5,7 +5,10 @@ mod common;
 use core::fmt::{Display, Formatter};
 
 use common::*;
-use error_stack::{report, Context, Report};
+use error_stack::{
+    future::{partition_results, try_collect_reports},
+    report, Context, IteratorExt, Report, Result,
+};
 
 #[derive(Debug)]
 struct Error;
@@ -76,3 +79,73 @@ fn collect_none() {
 
     assert!(report.is_none());
 }
+
+fn results() -> [Result<u32, Error>; 4] {
+    [
+        Ok(1),
+        Err(report!(Error).attach_printable("Not Supported")),
+        Ok(2),
+        Err(report!(Error).attach_printable("Not Supported")),
+    ]
+}
+
+#[test]
+fn try_collect_reports_ok() {
+    let results: [Result<u32, Error>; 3] = [Ok(1), Ok(2), Ok(3)];
+    let values = results
+        .into_iter()
+        .try_collect_reports()
+        .expect("should be ok");
+
+    assert_eq!(values, [1, 2, 3]);
+}
+
+#[test]
+fn try_collect_reports_err() {
+    let report = results()
+        .into_iter()
+        .try_collect_reports()
+        .expect_err("should be err");
+
+    assert_eq!(report.current_frames().len(), 2);
+    assert_eq!(report.frames().count(), expect_count(2) * 2);
+}
+
+#[test]
+fn partition_results_mixed() {
+    let (values, report) = results().into_iter().partition_results();
+
+    assert_eq!(values, [1, 2]);
+    let report = report.expect("should be some");
+    assert_eq!(report.current_frames().len(), 2);
+}
+
+#[test]
+fn partition_results_ok() {
+    let results: [Result<u32, Error>; 2] = [Ok(1), Ok(2)];
+    let (values, report) = results.into_iter().partition_results();
+
+    assert_eq!(values, [1, 2]);
+    assert!(report.is_none());
+}
+
+#[test]
+fn try_collect_reports_future() {
+    let futures = results().map(futures::future::ready);
+    let report =
+        futures::executor::block_on(try_collect_reports(futures)).expect_err("should be err");
+    assert_eq!(report.current_frames().len(), 2);
+
+    let futures = [Ok(1), Ok(2)].map(futures::future::ready::<Result<u32, Error>>);
+    let values = futures::executor::block_on(try_collect_reports(futures)).expect("should be ok");
+    assert_eq!(values, [1, 2]);
+}
+
+#[test]
+fn partition_results_future() {
+    let futures = results().map(futures::future::ready);
+    let (values, report) = futures::executor::block_on(partition_results(futures));
+
+    assert_eq!(values, [1, 2]);
+    assert_eq!(report.expect("should be some").current_frames().len(), 2);
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "std")]
+#![cfg_attr(nightly, feature(provide_any))]
+#![cfg_attr(all(nightly, feature = "std"), feature(error_generic_member_access))]
+
+mod common;
+
+use std::sync::{Mutex, MutexGuard};
+
+use common::*;
+use error_stack::{
+    fmt::{Charset, ColorMode, ColorStream},
+    Report,
+};
+
+/// The configuration is global, tests changing it must not run concurrently.
+fn lock() -> MutexGuard<'static, ()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+
+    let guard = LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    std::env::set_var("RUST_LIB_BACKTRACE", "0");
+    Report::set_charset(Charset::Utf8);
+    Report::set_color_mode(ColorMode::Never);
+    Report::set_color_stream(ColorStream::Stdout);
+    guard
+}
+
+fn create_nested_report() -> Report<ContextA> {
+    create_report()
+        .attach_printable("printable attachment")
+        .change_context(ContextA(0))
+}
+
+/// Renders the report without locations, which depend on the line numbers of the test files.
+fn render(report: &Report<ContextA>) -> String {
+    format!("{report:?}")
+        .lines()
+        .filter(|line| !line.contains(".rs:"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn ascii() {
+    let _guard = lock();
+    Report::set_charset(Charset::Ascii);
+
+    assert_eq!(
+        render(&create_nested_report()),
+        "context A\n|\n|-> root error\n    |- printable attachment"
+    );
+}
+
+#[test]
+#[cfg(feature = "pretty-print")]
+fn utf8() {
+    let _guard = lock();
+
+    assert_eq!(
+        render(&create_nested_report()),
+        "context A\n│\n╰─▶ root error\n    ╰╴ printable attachment"
+    );
+}
+
+#[test]
+#[cfg(feature = "pretty-print")]
+fn color_never() {
+    let _guard = lock();
+    owo_colors::set_override(true);
+
+    let output = format!("{:?}", create_nested_report());
+    owo_colors::unset_override();
+
+    assert!(!output.contains('\u{1b}'), "{output}");
+}
+
+#[test]
+#[cfg(feature = "pretty-print")]
+fn color_always() {
+    let _guard = lock();
+    Report::set_color_mode(ColorMode::Always);
+    owo_colors::set_override(false);
+
+    let output = format!("{:?}", create_nested_report());
+    owo_colors::unset_override();
+
+    assert!(output.contains("\u{1b}[1mcontext A\u{1b}[0m"), "{output}");
+}
+
+#[test]
+#[cfg(feature = "pretty-print")]
+fn color_auto() {
+    let _guard = lock();
+    Report::set_color_mode(ColorMode::Auto);
+    Report::set_color_stream(ColorStream::Stderr);
+
+    owo_colors::set_override(true);
+    let colored = format!("{:?}", create_nested_report());
+    owo_colors::set_override(false);
+    let plain = format!("{:?}", create_nested_report());
+    owo_colors::unset_override();
+
+    assert!(colored.contains('\u{1b}'), "{colored}");
+    assert!(!plain.contains('\u{1b}'), "{plain}");
+}
+
+#[test]
+#[cfg(not(feature = "pretty-print"))]
+fn color_without_pretty_print() {
+    let _guard = lock();
+    Report::set_color_mode(ColorMode::Always);
+
+    let output = format!("{:?}", create_nested_report());
+    assert!(!output.contains('\u{1b}'), "{output}");
+}
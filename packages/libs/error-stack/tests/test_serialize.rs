@@ -0,0 +1,238 @@
+#![cfg(feature = "serde")]
+#![cfg_attr(nightly, feature(provide_any))]
+#![cfg_attr(all(nightly, feature = "std"), feature(error_generic_member_access))]
+
+mod common;
+
+use common::*;
+use error_stack::Report;
+use serde_json::{json, Value};
+
+/// Removes all `"location"` entries, which depend on the line numbers of the test files, and all
+/// backtraces and span traces, which depend on the environment.
+fn normalize(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            object.remove("location");
+            if let Some(Value::Array(attachments)) = object.get_mut("attachments") {
+                attachments.retain(|attachment| {
+                    !matches!(
+                        attachment["type"].as_str(),
+                        Some("std::backtrace::Backtrace" | "tracing_error::backtrace::SpanTrace")
+                    )
+                });
+            }
+            object.values_mut().for_each(normalize);
+        }
+        Value::Array(array) => array.iter_mut().for_each(normalize),
+        _ => {}
+    }
+}
+
+fn serialize<C>(report: &Report<C>) -> Value {
+    let mut value = serde_json::to_value(report).expect("could not serialize report");
+    normalize(&mut value);
+    value
+}
+
+struct Opaque;
+
+#[test]
+fn context() {
+    let report = create_report();
+
+    assert_eq!(
+        serialize(&report),
+        json!([
+            {
+                "context": "root error",
+                "type": "test_serialize::common::RootError",
+                "attachments": [],
+                "fields": {},
+                "sources": []
+            }
+        ])
+    );
+}
+
+#[test]
+fn location() {
+    let report = create_report();
+    let value = serde_json::to_value(&report).expect("could not serialize report");
+    let location = report.frames().next().expect("no frames").location();
+
+    assert_eq!(
+        value[0]["location"],
+        json!({
+            "file": location.file(),
+            "line": location.line(),
+            "column": location.column(),
+        })
+    );
+}
+
+#[test]
+fn attachments() {
+    let report = create_report()
+        .attach(Opaque)
+        .attach_printable("printable attachment");
+
+    assert_eq!(
+        serialize(&report),
+        json!([
+            {
+                "context": "root error",
+                "type": "test_serialize::common::RootError",
+                "attachments": [
+                    {
+                        "attachment": "printable attachment",
+                        "type": "&str",
+                        "fields": {}
+                    },
+                    {
+                        "attachment": null,
+                        "type": "test_serialize::Opaque",
+                        "fields": {}
+                    }
+                ],
+                "fields": {},
+                "sources": []
+            }
+        ])
+    );
+}
+
+#[test]
+fn sources() {
+    let report = create_report()
+        .attach_printable("attachment of root")
+        .change_context(ContextA(0))
+        .attach_printable("attachment of context A");
+
+    assert_eq!(
+        serialize(&report),
+        json!([
+            {
+                "context": "context A",
+                "type": "test_serialize::common::ContextA",
+                "attachments": [
+                    {
+                        "attachment": "attachment of context A",
+                        "type": "&str",
+                        "fields": {}
+                    }
+                ],
+                "fields": {},
+                "sources": [
+                    {
+                        "context": "root error",
+                        "type": "test_serialize::common::RootError",
+                        "attachments": [
+                            {
+                                "attachment": "attachment of root",
+                                "type": "&str",
+                                "fields": {}
+                            }
+                        ],
+                        "fields": {},
+                        "sources": []
+                    }
+                ]
+            }
+        ])
+    );
+}
+
+#[test]
+fn multiple_sources() {
+    let mut report = create_report().change_context(ContextA(0));
+    report.extend_one(create_report().change_context(ContextA(1)));
+    let report = report
+        .attach_printable("shared attachment")
+        .change_context(ContextB(0));
+
+    let root = json!({
+        "context": "root error",
+        "type": "test_serialize::common::RootError",
+        "attachments": [],
+        "fields": {},
+        "sources": []
+    });
+    let context_a = json!({
+        "context": "context A",
+        "type": "test_serialize::common::ContextA",
+        "attachments": [
+            {
+                "attachment": "shared attachment",
+                "type": "&str",
+                "fields": {}
+            }
+        ],
+        "fields": {},
+        "sources": [root]
+    });
+
+    assert_eq!(
+        serialize(&report),
+        json!([
+            {
+                "context": "context B",
+                "type": "test_serialize::common::ContextB",
+                "attachments": [],
+                "fields": {},
+                "sources": [context_a, context_a]
+            }
+        ])
+    );
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn hook() {
+    struct ErrorCode(u16);
+
+    Report::install_serialize_hook::<ErrorCode>(|code, fields| {
+        fields.insert("code", code.0);
+        fields.insert("retry", json!({ "allowed": code.0 >= 500 }));
+    });
+
+    let report = create_report().attach(ErrorCode(503));
+
+    assert_eq!(
+        serialize(&report)[0]["attachments"][0],
+        json!({
+            "attachment": null,
+            "type": "test_serialize::hook::ErrorCode",
+            "fields": {
+                "code": 503,
+                "retry": { "allowed": true }
+            }
+        })
+    );
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn hook_installing_hook() {
+    struct Outer;
+    struct Inner;
+
+    Report::install_serialize_hook::<Outer>(|_, fields| {
+        // Installing a hook while serializing must not deadlock
+        Report::install_serialize_hook::<Inner>(|_, fields| {
+            fields.insert("inner", true);
+        });
+        fields.insert("outer", true);
+    });
+
+    let report = create_report().attach(Inner).attach(Outer);
+    let value = serialize(&report);
+    assert_eq!(
+        value[0]["attachments"][0]["fields"],
+        json!({ "outer": true })
+    );
+    assert_eq!(
+        value[0]["attachments"][1]["fields"],
+        json!({ "inner": true })
+    );
+}
@@ -0,0 +1,122 @@
+#![cfg(feature = "testing")]
+#![cfg_attr(nightly, feature(provide_any))]
+#![cfg_attr(all(nightly, feature = "std"), feature(error_generic_member_access))]
+
+mod common;
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use common::*;
+use error_stack::{
+    testing::{assert_report, snapshot},
+    Report,
+};
+
+struct Opaque;
+
+/// Returns the panic message of `closure`.
+fn failure(closure: impl FnOnce()) -> String {
+    let payload = catch_unwind(AssertUnwindSafe(closure)).expect_err("assertion did not fail");
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => (*payload.downcast::<&str>().expect("unexpected payload")).to_owned(),
+    }
+}
+
+fn create_nested_report() -> Report<ContextB> {
+    let mut report = create_report()
+        .attach_printable("printable attachment")
+        .change_context(ContextA(0));
+    report.extend_one(create_report().change_context(ContextA(1)));
+    report.attach(Opaque).change_context(ContextB(0))
+}
+
+#[test]
+fn contains() {
+    assert_report(&create_nested_report())
+        .contains_context::<RootError>()
+        .contains_context::<ContextA>()
+        .contains_attachment::<Opaque>()
+        .contains_context_beneath::<RootError, ContextB>()
+        .contains_context_beneath::<ContextA, ContextB>()
+        .contains_context_beneath::<RootError, ContextA>();
+}
+
+#[test]
+fn contains_failure() {
+    let message = failure(|| {
+        assert_report(&create_nested_report()).contains_context_beneath::<ContextB, RootError>();
+    });
+
+    assert!(
+        message.starts_with(
+            "assertion failed: the report does not contain a context of type \
+             `test_testing::common::ContextB` beneath a context of type \
+             `test_testing::common::RootError`\n\nreport:\ncontext B\n"
+        ),
+        "{message}"
+    );
+
+    let message = failure(|| {
+        assert_report(&create_report()).contains_attachment::<Opaque>();
+    });
+    assert!(
+        message.starts_with(
+            "assertion failed: the report does not contain an attachment of type \
+             `test_testing::Opaque`"
+        ),
+        "{message}"
+    );
+}
+
+#[test]
+fn printable_attachment() {
+    assert_report(&create_nested_report())
+        .has_printable_attachment("printable")
+        .has_printable_attachment_matching(|attachment| attachment.ends_with("attachment"));
+
+    let message = failure(|| {
+        assert_report(&create_nested_report()).has_printable_attachment("missing");
+    });
+    assert!(
+        message.starts_with(
+            "assertion failed: the report does not contain a printable attachment containing \
+             \"missing\""
+        ),
+        "{message}"
+    );
+}
+
+#[test]
+fn sources() {
+    assert_report(&create_report()).has_sources(0);
+    assert_report(&create_report().change_context(ContextA(0))).has_sources(1);
+    assert_report(&create_nested_report()).has_sources(2);
+
+    let message = failure(|| {
+        assert_report(&create_nested_report()).has_sources(1);
+    });
+    assert!(
+        message.starts_with("assertion failed: the report has 2 sources, but 1 were expected"),
+        "{message}"
+    );
+}
+
+#[test]
+fn normalized_snapshot() {
+    assert_eq!(
+        snapshot(&create_nested_report()),
+        "context B
+│
+╰┬▶ context A
+ │  ├╴ 1 additional opaque attachment
+ │  │
+ │  ╰─▶ root error
+ │      ╰╴ printable attachment
+ │
+ ╰▶ context A
+    ├╴ 1 additional opaque attachment
+    │
+    ╰─▶ root error"
+    );
+}
@@ -0,0 +1,199 @@
+#![cfg(feature = "tracing")]
+#![cfg_attr(nightly, feature(provide_any))]
+#![cfg_attr(all(nightly, feature = "std"), feature(error_generic_member_access))]
+
+mod common;
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use common::*;
+use error_stack::Report;
+use tracing::{
+    field::{Field, Visit},
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, prelude::*, Layer, Registry};
+
+type Fields = BTreeMap<&'static str, String>;
+
+#[derive(Default)]
+struct FieldVisitor(Fields);
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name(), value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name(), format!("{value:?}"));
+    }
+}
+
+#[derive(Clone, Default)]
+struct CaptureLayer(Arc<Mutex<Vec<(Level, Fields)>>>);
+
+impl<S: Subscriber> Layer<S> for CaptureLayer {
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        assert_eq!(event.metadata().target(), "error_stack");
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        self.0
+            .lock()
+            .expect("should not be poisoned")
+            .push((*event.metadata().level(), visitor.0));
+    }
+}
+
+fn capture<C>(report: &Report<C>, level: Level) -> Vec<(Level, Fields)> {
+    let layer = CaptureLayer::default();
+    tracing::subscriber::with_default(Registry::default().with(layer.clone()), || {
+        report.emit_event(level);
+    });
+
+    let events = layer.0.lock().expect("should not be poisoned").clone();
+    events
+}
+
+fn field<'a>(event: &'a (Level, Fields), name: &str) -> &'a str {
+    event.1.get(name).map_or("", String::as_str)
+}
+
+#[test]
+fn single_context() {
+    let report = create_report().attach_printable("printable attachment");
+    let events = capture(&report, Level::ERROR);
+
+    assert_eq!(events.len(), 2);
+    let event = &events[0];
+    assert_eq!(event.0, Level::ERROR);
+    assert_eq!(field(event, "message"), "root error");
+    assert_eq!(
+        field(event, "error.type_name"),
+        "test_tracing::common::RootError"
+    );
+    assert_eq!(field(event, "error.index"), "0");
+    assert_eq!(field(event, "error.parent"), "");
+    assert_eq!(field(event, "error.depth"), "0");
+
+    let location = report.current_frames()[0]
+        .sources()
+        .first()
+        .expect("should have a source")
+        .location();
+    assert_eq!(field(event, "error.location"), location.to_string());
+
+    let attachment = &events[1];
+    assert_eq!(attachment.0, Level::ERROR);
+    assert_eq!(field(attachment, "message"), "printable attachment");
+    assert_eq!(field(attachment, "error.attachment_of"), "0");
+    assert_eq!(
+        field(attachment, "error.report"),
+        field(event, "error.report")
+    );
+    assert_eq!(
+        field(attachment, "error.location"),
+        report.current_frames()[0].location().to_string()
+    );
+}
+
+#[test]
+fn sources() {
+    let report = create_report()
+        .attach_printable("attachment of root")
+        .change_context(ContextA(0))
+        .attach(0_u32)
+        .change_context(ContextB(0));
+    let events = capture(&report, Level::WARN);
+
+    let messages: Vec<_> = events
+        .iter()
+        .map(|event| {
+            (
+                event.0,
+                field(event, "message"),
+                field(event, "error.index"),
+                field(event, "error.parent"),
+                field(event, "error.depth"),
+                field(event, "error.attachment_of"),
+            )
+        })
+        .collect();
+    assert_eq!(messages, [
+        (Level::WARN, "context B", "0", "", "0", ""),
+        (Level::WARN, "context A", "1", "0", "1", ""),
+        (Level::WARN, "root error", "2", "1", "2", ""),
+        (Level::WARN, "attachment of root", "", "", "", "2"),
+    ]);
+}
+
+#[test]
+fn multiple_sources() {
+    let mut report = create_report().change_context(ContextA(0));
+    report.extend_one(create_report().change_context(ContextA(1)));
+    let events = capture(&report, Level::INFO);
+
+    let messages: Vec<_> = events
+        .iter()
+        .map(|event| {
+            (
+                field(event, "message"),
+                field(event, "error.index"),
+                field(event, "error.parent"),
+                field(event, "error.depth"),
+            )
+        })
+        .collect();
+    assert_eq!(messages, [
+        ("context A", "0", "", "0"),
+        ("root error", "1", "0", "1"),
+        ("context A", "2", "", "0"),
+        ("root error", "3", "2", "1"),
+    ]);
+}
+
+#[test]
+fn branching_sources() {
+    let mut report = create_report();
+    report.extend_one(create_report().attach_printable("attachment of second root"));
+    let report = report.change_context(ContextA(0));
+    let events = capture(&report, Level::DEBUG);
+
+    let messages: Vec<_> = events
+        .iter()
+        .map(|event| {
+            (
+                field(event, "message"),
+                field(event, "error.index"),
+                field(event, "error.parent"),
+                field(event, "error.attachment_of"),
+            )
+        })
+        .collect();
+    assert_eq!(messages, [
+        ("context A", "0", "", ""),
+        ("root error", "1", "0", ""),
+        ("root error", "2", "0", ""),
+        ("attachment of second root", "", "", "2"),
+    ]);
+}
+
+#[test]
+fn report_identifier() {
+    let report = create_report().change_context(ContextA(0));
+    let first = capture(&report, Level::TRACE);
+    let second = capture(&report, Level::TRACE);
+
+    assert_eq!(
+        field(&first[0], "error.report"),
+        field(&first[1], "error.report")
+    );
+    assert_ne!(
+        field(&first[0], "error.report"),
+        field(&second[0], "error.report")
+    );
+}